@@ -0,0 +1,124 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::Rule;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(rename_all = "snake_case"))]
+pub enum CellState {
+    Dead,
+    Alive
+}
+
+// Row and column offsets of the neighbours, in mask bit order
+const NEIGHBORS: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1)
+];
+
+/// One bit per live neighbour of (x, y) in a `width` by `height` grid of
+/// `cells`, row by row, in the order documented on
+/// [`NeighborhoodRule`](crate::NeighborhoodRule).  Cells past the edges
+/// count as dead.
+pub fn neighbor_mask(cells: &[CellState], width: usize, height: usize, x: usize, y: usize) -> u8 {
+    let mut mask = 0;
+    for (bit, (j, i)) in NEIGHBORS.into_iter().enumerate() {
+        let (Some(ny), Some(nx)) = (y.checked_add_signed(j), x.checked_add_signed(i)) else {
+            continue;
+        };
+        if ny < height && nx < width && cells[ny * width + nx] == CellState::Alive {
+            mask |= 1 << bit;
+        }
+    }
+    mask
+}
+
+/// Like [`neighbor_mask`], but with the edges joined up as on a torus:
+/// past one edge are the cells along the opposite one.
+pub fn neighbor_mask_torus(cells: &[CellState], width: usize, height: usize, x: usize, y: usize) -> u8 {
+    let mut mask = 0;
+    for (bit, (j, i)) in NEIGHBORS.into_iter().enumerate() {
+        let ny = (y + height).wrapping_add_signed(j) % height;
+        let nx = (x + width).wrapping_add_signed(i) % width;
+        if cells[ny * width + nx] == CellState::Alive {
+            mask |= 1 << bit;
+        }
+    }
+    mask
+}
+
+/// A universe small enough for one core: a grid following a [`Rule`],
+/// stepped a generation at a time.
+///
+/// ```
+/// use rustlife_core::{CellState, Grid};
+///
+/// let mut blinker = Grid::new(5, 5);
+/// for x in 1..4 {
+///     blinker.set(x, 2, CellState::Alive);
+/// }
+/// blinker.step();
+/// assert_eq!(blinker.get(2, 1), CellState::Alive);
+/// assert_eq!(blinker.population(), 3);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Grid {
+    width: usize,
+    height: usize,
+    cells: Vec<CellState>,
+    // The next generation is written here, then swapped in
+    scratch: Vec<CellState>,
+    pub rule: Rule,
+}
+
+impl Grid {
+    /// An empty grid following Conway's rule.
+    pub fn new(width: usize, height: usize) -> Self {
+        let cells = vec![CellState::Dead; width * height];
+        Grid { width, height, scratch: cells.clone(), cells, rule: Rule::default() }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Every cell, row by row.
+    pub fn cells(&self) -> &[CellState] {
+        &self.cells
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> CellState {
+        self.cells[y * self.width + x]
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, state: CellState) {
+        self.cells[y * self.width + x] = state;
+    }
+
+    pub fn population(&self) -> usize {
+        self.cells.iter().filter(|c| **c == CellState::Alive).count()
+    }
+
+    pub fn step(&mut self) {
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let neighbors = neighbor_mask(&self.cells, self.width, self.height, x, y);
+                self.scratch[y * self.width + x] = self.rule.next(self.cells[y * self.width + x], neighbors.count_ones() as usize);
+            }
+        }
+        core::mem::swap(&mut self.cells, &mut self.scratch);
+    }
+}
@@ -0,0 +1,17 @@
+//! The rules of Life and the stepping at the heart of it, without the
+//! standard library, so the same logic runs on microcontrollers and
+//! LED-matrix badges.  Only an allocator is needed.  The `rustlife` crate's
+//! backends are built on this.
+//!
+//! Turn on the `serde` feature for `Serialize` and `Deserialize` on the
+//! rule and cell types.
+
+#![no_std]
+
+extern crate alloc;
+
+mod grid;
+mod rule;
+
+pub use grid::{neighbor_mask, neighbor_mask_torus, CellState, Grid};
+pub use rule::{NeighborhoodRule, Rule};
@@ -0,0 +1,121 @@
+use alloc::format;
+use alloc::string::String;
+use core::fmt;
+use core::str::FromStr;
+
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::CellState;
+
+/// Outer-totalistic rule in birth/survival form.  Index `n` of each table says
+/// whether a cell with `n` live neighbours is born or survives.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Rule {
+    pub birth: [bool; 9],
+    pub survive: [bool; 9],
+}
+
+impl Rule {
+    /// B3/S23
+    pub fn conway() -> Self {
+        let mut birth = [false; 9];
+        let mut survive = [false; 9];
+        birth[3] = true;
+        survive[2] = true;
+        survive[3] = true;
+        Rule { birth, survive }
+    }
+
+    pub fn next(&self, cell_state: CellState, live_count: usize) -> CellState {
+        let lives = match cell_state {
+            CellState::Dead => self.birth[live_count],
+            CellState::Alive => self.survive[live_count],
+        };
+        if lives { CellState::Alive } else { CellState::Dead }
+    }
+}
+
+/// Rule logic that needs more than a neighbour count, e.g. rules loaded from
+/// plugins.  `neighbors` has one bit per neighbour, bit 0 being the top left
+/// and then row by row (top, top right, left, right, bottom left, bottom,
+/// bottom right).
+pub trait NeighborhoodRule: Send + Sync {
+    fn name(&self) -> &str;
+    fn next(&self, cell_state: CellState, neighbors: u8) -> CellState;
+}
+
+impl Default for Rule {
+    fn default() -> Self {
+        Rule::conway()
+    }
+}
+
+/// Accepts `B3/S23` notation (case-insensitive, either half may come first)
+/// and the older `S/B` form `23/3`.
+impl FromStr for Rule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        fn digits(part: &str, table: &mut [bool; 9]) -> Result<(), String> {
+            for c in part.chars() {
+                let n = c.to_digit(10)
+                    .filter(|n| *n <= 8)
+                    .ok_or_else(|| format!("invalid neighbour count '{}'", c))?;
+                table[n as usize] = true;
+            }
+            Ok(())
+        }
+
+        let mut birth = [false; 9];
+        let mut survive = [false; 9];
+        let s = s.trim();
+        let (first, second) = s.split_once('/')
+            .ok_or_else(|| format!("rule '{}' is missing '/'", s))?;
+
+        let lower = s.to_ascii_lowercase();
+        if lower.starts_with('b') || lower.starts_with('s') {
+            for part in [first, second] {
+                let mut chars = part.chars();
+                match chars.next() {
+                    Some('B' | 'b') => digits(chars.as_str(), &mut birth)?,
+                    Some('S' | 's') => digits(chars.as_str(), &mut survive)?,
+                    _ => return Err(format!("rule part '{}' must start with B or S", part))
+                }
+            }
+        } else {
+            digits(first, &mut survive)?;
+            digits(second, &mut birth)?;
+        }
+        Ok(Rule { birth, survive })
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "B")?;
+        for n in (0..9).filter(|n| self.birth[*n]) {
+            write!(f, "{}", n)?;
+        }
+        write!(f, "/S")?;
+        for n in (0..9).filter(|n| self.survive[*n]) {
+            write!(f, "{}", n)?;
+        }
+        Ok(())
+    }
+}
+
+/// Written the same way as on the command line, e.g. `"B3/S23"`.
+#[cfg(feature = "serde")]
+impl Serialize for Rule {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Rule {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?.parse().map_err(de::Error::custom)
+    }
+}
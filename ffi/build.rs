@@ -0,0 +1,20 @@
+//! Generates `rustlife.h` in `OUT_DIR` from the functions `src/lib.rs`
+//! exports.  The checked in `include/rustlife.h` is tested against it, so
+//! the header can't fall behind them.
+
+use std::env;
+use std::path::Path;
+
+fn main() {
+    println!("cargo:rerun-if-changed=src/lib.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let out = env::var("OUT_DIR").expect("cargo sets OUT_DIR");
+    let config = cbindgen::Config::from_file(dir.join("cbindgen.toml")).expect("cbindgen.toml");
+    cbindgen::Builder::new()
+        .with_config(config)
+        .with_src(dir.join("src/lib.rs"))
+        .generate()
+        .expect("generating rustlife.h")
+        .write_to_file(Path::new(&out).join("rustlife.h"));
+}
@@ -0,0 +1,122 @@
+/* C interface to the rustlife engine, generated from ffi/src/lib.rs by
+ * cbindgen when the crate is built; edit the doc comments there rather
+ * than this file.
+ *
+ * Link against librustlife_c (built by `cargo build --release -p
+ * rustlife-ffi`).  Cells are bytes, 1 alive and 0 dead; cells outside the
+ * grid read as dead and ignore writes.  An engine must only be used from
+ * one thread at a time. */
+
+#ifndef RUSTLIFE_H
+#define RUSTLIFE_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+// Bumped whenever a function's signature or meaning changes.
+#define RUSTLIFE_ABI_VERSION 1
+
+typedef struct RustlifeEngine RustlifeEngine;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+uint32_t rustlife_abi_version(void);
+
+// A `width` by `height` universe on `backend`, e.g. `"dense"`, or the dense
+// backend if `backend` is NULL.  Returns NULL for an unknown backend.
+struct RustlifeEngine *rustlife_create(const char *backend, size_t width, size_t height);
+
+// Frees an engine.  NULL is ignored.
+//
+// # Safety
+//
+// `engine` must have come from [`rustlife_create`] and not been freed.
+void rustlife_destroy(struct RustlifeEngine *engine);
+
+// Sets the rule from `B3/S23` notation.  Returns 0, or -1 if the rule
+// doesn't parse.
+//
+// # Safety
+//
+// `engine` must be a live engine and `rule` a NUL terminated string.
+int rustlife_set_rule(struct RustlifeEngine *engine, const char *rule);
+
+// Fills the grid with a soup, each cell alive with chance `density`.
+//
+// # Safety
+//
+// `engine` must be a live engine.
+void rustlife_randomize(struct RustlifeEngine *engine, double density, uint64_t seed);
+
+// # Safety
+//
+// `engine` must be a live engine.
+size_t rustlife_width(const struct RustlifeEngine *engine);
+
+// # Safety
+//
+// `engine` must be a live engine.
+size_t rustlife_height(const struct RustlifeEngine *engine);
+
+// # Safety
+//
+// `engine` must be a live engine.
+uint8_t rustlife_get(const struct RustlifeEngine *engine, size_t x, size_t y);
+
+// # Safety
+//
+// `engine` must be a live engine.
+void rustlife_set(struct RustlifeEngine *engine, size_t x, size_t y, uint8_t alive);
+
+// Steps `n` generations.
+//
+// # Safety
+//
+// `engine` must be a live engine.
+void rustlife_step(struct RustlifeEngine *engine, uint64_t n);
+
+// Steps until the universe repeats, giving up after `max_generations`.
+// Returns the period, 1 for a still life, or 0 if it didn't settle.  An
+// empty universe is a still life.
+//
+// # Safety
+//
+// `engine` must be a live engine.
+uint64_t rustlife_run_until_settled(struct RustlifeEngine *engine, uint64_t max_generations);
+
+// Generations stepped since the engine was created.
+//
+// # Safety
+//
+// `engine` must be a live engine.
+uint64_t rustlife_generation(const struct RustlifeEngine *engine);
+
+// # Safety
+//
+// `engine` must be a live engine.
+size_t rustlife_population(const struct RustlifeEngine *engine);
+
+// Copies the cells row by row into `out`, one byte each, stopping after
+// `len` bytes.  Returns the number of cells in the grid, so a call with a
+// NULL `out` and 0 `len` gives the size of buffer needed.
+//
+// # Safety
+//
+// `engine` must be a live engine and `out` valid for `len` bytes.
+size_t rustlife_copy_cells(const struct RustlifeEngine *engine, uint8_t *out, size_t len);
+
+// Sets the cells row by row from `cells`, one byte each, non-zero being
+// alive, stopping after `len` bytes or at the end of the grid.
+//
+// # Safety
+//
+// `engine` must be a live engine and `cells` valid for `len` bytes.
+void rustlife_load_cells(struct RustlifeEngine *engine, const uint8_t *cells, size_t len);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif // __cplusplus
+
+#endif /* RUSTLIFE_H */
@@ -0,0 +1,65 @@
+use std::ptr;
+
+use rustlife_c::*;
+
+#[test]
+fn steps_a_blinker_through_the_c_interface() {
+    unsafe {
+        assert!(rustlife_create(c"quantum".as_ptr(), 8, 8).is_null());
+        let engine = rustlife_create(ptr::null(), 5, 5);
+        for x in 1..4 {
+            rustlife_set(engine, x, 2, 1);
+        }
+        rustlife_set(engine, 99, 99, 1);
+        assert_eq!(rustlife_set_rule(engine, c"B3/S23".as_ptr()), 0);
+        assert_eq!(rustlife_set_rule(engine, c"nonsense".as_ptr()), -1);
+        rustlife_step(engine, 3);
+        assert_eq!((rustlife_generation(engine), rustlife_population(engine)), (3, 3));
+
+        let size = rustlife_copy_cells(engine, ptr::null_mut(), 0);
+        let mut cells = vec![0u8; size];
+        rustlife_copy_cells(engine, cells.as_mut_ptr(), cells.len());
+        let column: Vec<_> = (0..5).map(|y| cells[y * 5 + 2]).collect();
+        assert_eq!(column, [0, 1, 1, 1, 0]);
+        assert_eq!(rustlife_get(engine, 99, 99), 0);
+        rustlife_destroy(engine);
+    }
+}
+
+#[test]
+fn header_declares_every_function() {
+    let header = include_str!("../include/rustlife.h");
+    let source = include_str!("../src/lib.rs");
+    let exported: Vec<_> = source.lines()
+        .filter_map(|l| l.split("extern \"C\" fn ").nth(1))
+        .map(|l| &l[..l.find('(').unwrap()])
+        .collect();
+    assert!(exported.len() > 10);
+    for name in exported {
+        assert!(header.contains(&format!(" {}(", name)) || header.contains(&format!("*{}(", name)), "{} missing from rustlife.h", name);
+    }
+    assert!(header.contains(&format!("#define RUSTLIFE_ABI_VERSION {}", RUSTLIFE_ABI_VERSION)));
+}
+
+#[test]
+fn checked_in_header_is_the_generated_one() {
+    let generated = include_str!(concat!(env!("OUT_DIR"), "/rustlife.h"));
+    assert!(include_str!("../include/rustlife.h") == generated, "include/rustlife.h is out of date; copy it from {}", env!("OUT_DIR"));
+}
+
+#[test]
+fn loads_cells_and_runs_until_settled() {
+    unsafe {
+        let engine = rustlife_create(ptr::null(), 6, 6);
+        let mut cells = [0u8; 36];
+        // A block and a blinker
+        for i in [0, 1, 6, 7, 27, 28, 29] {
+            cells[i] = 1;
+        }
+        rustlife_load_cells(engine, cells.as_ptr(), cells.len());
+        assert_eq!(rustlife_population(engine), 7);
+        assert_eq!(rustlife_run_until_settled(engine, 100), 2);
+        assert_eq!(rustlife_generation(engine), 2);
+        rustlife_destroy(engine);
+    }
+}
@@ -0,0 +1,24 @@
+//! Feeds arbitrary bytes to every pattern parser; none may panic, however
+//! broken the file.  Run with `cargo fuzz run parse_pattern` from the
+//! repository root.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rustlife::{apgcode, io, Rule};
+
+fuzz_target!(|data: &[u8]| {
+    let input = String::from_utf8_lossy(data);
+    let _ = io::parse_pattern(&input);
+    let _ = io::parse_rle(&input);
+    io::parse_plaintext(&input);
+    let _ = io::parse_macrocell(&input);
+    let _ = apgcode::decode(&input);
+    let _ = input.parse::<Rule>();
+
+    // What parses as RLE comes back the same once written out again
+    if let Ok(live) = io::parse_rle(&input) {
+        let rle = io::cells_to_rle(&live);
+        assert_eq!(io::parse_rle(&rle).as_ref(), Ok(&live), "{}", rle);
+    }
+});
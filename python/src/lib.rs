@@ -0,0 +1,150 @@
+//! Python module for the engine, built with PyO3 and maturin:
+//!
+//!     pip install ./python
+//!
+//! ```python
+//! from rustlife import Engine
+//!
+//! life = Engine(256, 256, rule="B3/S23")
+//! life.randomize(0.5, seed=42)
+//! period = life.run_until_settled(10000)
+//! print(life.generation, period, life.cells().sum())
+//! ```
+//!
+//! `cells` returns a numpy array of shape (height, width) and `load` takes
+//! anything numpy can turn into one, so experiments can set up and read
+//! back universes from notebooks while the stepping runs at native speed
+//! across every core.  Stepping lets go of the GIL, so other Python threads
+//! carry on meanwhile.
+
+use numpy::ndarray::Array2;
+use numpy::{AllowTypeChange, IntoPyArray, PyArray2, PyArrayLike2};
+use pyo3::exceptions::{PyIndexError, PyValueError};
+use pyo3::prelude::*;
+
+use rustlife::period::{self, PeriodDetector};
+use rustlife::{CellState, LifeEngine, Rule, SimulationBuilder};
+
+/// A universe of `width` by `height` cells on `backend`.
+#[pyclass(module = "rustlife")]
+struct Engine {
+    life: Box<dyn LifeEngine>,
+    generation: u64,
+}
+
+impl Engine {
+    fn cell(&self, (x, y): (usize, usize)) -> PyResult<(usize, usize)> {
+        if x < self.life.width() && y < self.life.height() {
+            Ok((x, y))
+        } else {
+            Err(PyIndexError::new_err(format!("{}, {} is outside the {}x{} grid", x, y, self.life.width(), self.life.height())))
+        }
+    }
+}
+
+#[pymethods]
+impl Engine {
+    #[new]
+    #[pyo3(signature = (width, height, backend = "dense", rule = None))]
+    fn new(width: usize, height: usize, backend: &str, rule: Option<&str>) -> PyResult<Self> {
+        let mut builder = SimulationBuilder::new(width, height).backend(backend);
+        if let Some(rule) = rule {
+            builder = builder.rule(rule.parse::<Rule>().map_err(PyValueError::new_err)?);
+        }
+        let life = builder.build().map_err(PyValueError::new_err)?;
+        Ok(Engine { life, generation: 0 })
+    }
+
+    #[getter]
+    fn width(&self) -> usize {
+        self.life.width()
+    }
+
+    #[getter]
+    fn height(&self) -> usize {
+        self.life.height()
+    }
+
+    /// Generations stepped since the engine was created.
+    #[getter]
+    fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    #[getter]
+    fn population(&self) -> usize {
+        self.life.population()
+    }
+
+    /// Sets the rule from B3/S23 notation.
+    fn set_rule(&mut self, rule: &str) -> PyResult<()> {
+        self.life.set_rule(rule.parse::<Rule>().map_err(PyValueError::new_err)?);
+        Ok(())
+    }
+
+    #[pyo3(signature = (density = 0.5, seed = 0))]
+    fn randomize(&mut self, density: f64, seed: u64) {
+        self.life.randomize(density, seed);
+    }
+
+    fn __getitem__(&self, xy: (usize, usize)) -> PyResult<bool> {
+        let (x, y) = self.cell(xy)?;
+        Ok(self.life.get(x, y) == CellState::Alive)
+    }
+
+    fn __setitem__(&mut self, xy: (usize, usize), alive: bool) -> PyResult<()> {
+        let (x, y) = self.cell(xy)?;
+        self.life.set(x, y, if alive { CellState::Alive } else { CellState::Dead });
+        Ok(())
+    }
+
+    #[pyo3(signature = (n = 1))]
+    fn step(&mut self, py: Python<'_>, n: u64) {
+        let life = &mut self.life;
+        py.detach(|| life.step_n(n));
+        self.generation += n;
+    }
+
+    /// Steps until the universe repeats and returns the period, 1 for a
+    /// still life, or None if it hadn't settled after `max_generations`.
+    fn run_until_settled(&mut self, py: Python<'_>, max_generations: u64) -> Option<u64> {
+        let Engine { life, generation } = self;
+        py.detach(|| {
+            let mut detector = PeriodDetector::default();
+            detector.observe(*generation, period::hash(life.as_ref(), None));
+            for _ in 0..max_generations {
+                life.step();
+                *generation += 1;
+                if let Some(period) = detector.observe(*generation, period::hash(life.as_ref(), None)) {
+                    return Some(period);
+                }
+            }
+            None
+        })
+    }
+
+    /// Every cell, 1 alive and 0 dead, as a (height, width) uint8 array.
+    fn cells<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray2<u8>> {
+        let (width, height) = (self.life.width(), self.life.height());
+        Array2::from_shape_fn((height, width), |(y, x)| (self.life.get(x, y) == CellState::Alive) as u8).into_pyarray(py)
+    }
+
+    /// Sets every cell from a (height, width) array, anything non-zero being
+    /// alive.
+    fn load(&mut self, cells: PyArrayLike2<'_, u8, AllowTypeChange>) -> PyResult<()> {
+        let cells = cells.as_array();
+        let expected = (self.life.height(), self.life.width());
+        if cells.dim() != expected {
+            return Err(PyValueError::new_err(format!("expected shape {:?}, got {:?}", expected, cells.dim())));
+        }
+        for ((y, x), &cell) in cells.indexed_iter() {
+            self.life.set(x, y, if cell != 0 { CellState::Alive } else { CellState::Dead });
+        }
+        Ok(())
+    }
+}
+
+#[pymodule(name = "rustlife")]
+fn rustlife_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<Engine>()
+}
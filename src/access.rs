@@ -0,0 +1,73 @@
+//! The status for screen readers, with the `accessibility` feature: the
+//! window gets an AccessKit tree holding the same status line as the title
+//! and the latest announcement, the second in a polite live region so it is
+//! read out when it changes.  Nothing in the tree can be acted on; the keys
+//! do that.
+
+use std::num::NonZeroU64;
+
+use accesskit::{ActionHandler, ActionRequest, Live, NodeBuilder, NodeClassSet, NodeId, Role, Tree, TreeUpdate};
+use accesskit_winit::Adapter;
+use winit::event::WindowEvent;
+use winit::window::Window;
+
+const WINDOW: u64 = 1;
+const STATUS: u64 = 2;
+const ANNOUNCEMENT: u64 = 3;
+
+fn id(n: u64) -> NodeId {
+    NodeId::from(NonZeroU64::new(n).expect("ids start at 1"))
+}
+
+// Screen readers asking to click or focus something get nothing
+struct Ignore;
+
+impl ActionHandler for Ignore {
+    fn do_action(&self, _: ActionRequest) {}
+}
+
+pub struct Access {
+    adapter: Adapter,
+    classes: NodeClassSet,
+}
+
+impl Access {
+    /// The tree for `window`, which mustn't have been shown yet.
+    pub fn new(window: &Window, title: &str) -> Access {
+        let mut classes = NodeClassSet::new();
+        let first = tree(&mut classes, title, None);
+        let adapter = Adapter::with_action_handler(window, move || first, Box::new(Ignore));
+        Access { adapter, classes }
+    }
+
+    /// Keeps the tree's idea of where the window is up to date.
+    pub fn on_event(&self, window: &Window, event: &WindowEvent) {
+        let _ = self.adapter.on_event(window, event);
+    }
+
+    /// Shows `status`, and `said` as the latest announcement, if a screen
+    /// reader is listening.
+    pub fn update(&mut self, status: &str, said: Option<&str>) {
+        self.adapter.update_if_active(|| tree(&mut self.classes, status, said));
+    }
+}
+
+fn tree(classes: &mut NodeClassSet, status: &str, said: Option<&str>) -> TreeUpdate {
+    let mut root = NodeBuilder::new(Role::Window);
+    root.set_name("Conway's Game of Life");
+    root.set_children(vec![id(STATUS), id(ANNOUNCEMENT)]);
+    let mut line = NodeBuilder::new(Role::StaticText);
+    line.set_name(status);
+    let mut announcement = NodeBuilder::new(Role::StaticText);
+    announcement.set_name(said.unwrap_or_default());
+    announcement.set_live(Live::Polite);
+    TreeUpdate {
+        nodes: vec![
+            (id(WINDOW), root.build(classes)),
+            (id(STATUS), line.build(classes)),
+            (id(ANNOUNCEMENT), announcement.build(classes)),
+        ],
+        tree: Some(Tree::new(id(WINDOW))),
+        focus: Some(id(WINDOW)),
+    }
+}
@@ -0,0 +1,41 @@
+//! Pinning the stepping threads to cores, for `--pin-threads`.
+
+/// The cores this process may run on, in order.
+#[cfg(target_os = "linux")]
+pub fn allowed_cores() -> Result<Vec<usize>, String> {
+    // SAFETY: the set is plain data, zeroed is a valid empty set, and the
+    // kernel writes at most its size
+    let set = unsafe {
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        if libc::sched_getaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &mut set) != 0 {
+            return Err(std::io::Error::last_os_error().to_string());
+        }
+        set
+    };
+    // SAFETY: CPU_ISSET only reads the set
+    Ok((0..libc::CPU_SETSIZE as usize).filter(|&cpu| unsafe { libc::CPU_ISSET(cpu, &set) }).collect())
+}
+
+/// Keeps the calling thread on `core` from now on.
+#[cfg(target_os = "linux")]
+pub fn pin(core: usize) -> Result<(), String> {
+    // SAFETY: as above, and the set outlives the call
+    unsafe {
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        libc::CPU_SET(core, &mut set);
+        if libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &set) != 0 {
+            return Err(std::io::Error::last_os_error().to_string());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn allowed_cores() -> Result<Vec<usize>, String> {
+    Err("pinning threads is only supported on Linux".to_string())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn pin(_core: usize) -> Result<(), String> {
+    Err("pinning threads is only supported on Linux".to_string())
+}
@@ -0,0 +1,128 @@
+//! Recording a headless run as an animated GIF, built with `--features gif`
+//! and started with `--gif <file>`.  A frame is drawn every so many
+//! generations, of the whole grid or, given a camera path, of wherever the
+//! path says the camera is looking at that generation.
+
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use gif::{Encoder, Frame, Repeat};
+
+use rustlife::camera::SharedCamera;
+use rustlife::script::SharedLife;
+use rustlife::{GenerationEvent, LifeEngine};
+
+use crate::render::{self, PixelMap, Theme, View};
+
+// Quality against speed of cutting frames down to 256 colours, when they
+// have more, 1 to 30
+const QUANTIZE_SPEED: i32 = 10;
+
+/// A GIF being written, a frame at a time.
+pub struct Animation {
+    path: PathBuf,
+    /// None once finished, or after an error
+    encoder: Option<Encoder<BufWriter<File>>>,
+    size: (u16, u16),
+    every: u64,
+    /// Hundredths of a second a frame
+    delay: u16,
+    theme: Theme,
+    camera: Option<SharedCamera>,
+    screen: Vec<u8>,
+    pixel_map: PixelMap,
+    frames: usize,
+}
+
+pub type SharedAnimation = Arc<Mutex<Animation>>;
+
+impl Animation {
+    /// Starts a `width` x `height` GIF at `path` playing `fps` frames a
+    /// second and looping for ever.
+    pub fn create(path: &Path, (width, height): (u32, u32), fps: u32, theme: Theme) -> Result<Self, String> {
+        let error = |e: &dyn std::fmt::Display| format!("{}: {}", path.display(), e);
+        let size = (u16::try_from(width).map_err(|e| error(&e))?, u16::try_from(height).map_err(|e| error(&e))?);
+        let file = File::create(path).map_err(|e| error(&e))?;
+        let mut encoder = Encoder::new(BufWriter::new(file), size.0, size.1, &[]).map_err(|e| error(&e))?;
+        encoder.set_repeat(Repeat::Infinite).map_err(|e| error(&e))?;
+        Ok(Animation {
+            path: path.to_path_buf(),
+            encoder: Some(encoder),
+            size,
+            every: 1,
+            delay: (100 / fps.max(1)).max(1) as u16,
+            theme,
+            camera: None,
+            screen: vec![0; width as usize * height as usize * 4],
+            pixel_map: PixelMap::default(),
+            frames: 0,
+        })
+    }
+
+    /// Draws a frame every `every` generations rather than every one.
+    pub fn every(mut self, every: u64) -> Self {
+        self.every = every.max(1);
+        self
+    }
+
+    /// Follows `camera` rather than showing the whole grid.
+    pub fn camera(mut self, camera: SharedCamera) -> Self {
+        self.camera = Some(camera);
+        self
+    }
+
+    /// Where to look at `generation`: the camera's shot, cells kept square,
+    /// or the whole grid without one.
+    fn view(&self, generation: u64, grid: (usize, usize)) -> View {
+        let shot = self.camera.as_ref().and_then(|c| c.lock().ok()?.at(generation));
+        match shot {
+            Some(shot) => {
+                let (width, height) = (self.size.0 as f64 / shot.zoom, self.size.1 as f64 / shot.zoom);
+                View { x: shot.x - width / 2.0, y: shot.y - height / 2.0, width, height }
+            }
+            None => View::whole(grid)
+        }
+    }
+
+    /// Adds `life` as generation `generation`'s frame, if one is due.
+    pub fn record(&mut self, generation: u64, life: &dyn LifeEngine) -> Result<(), String> {
+        if self.encoder.is_none() || !generation.is_multiple_of(self.every) {
+            return Ok(());
+        }
+        let view = self.view(generation, (life.width(), life.height()));
+        let (width, height) = self.size;
+        self.pixel_map.update(width as u32, height as u32, &view);
+        render::draw(&mut self.screen, life, &self.theme, &self.pixel_map);
+        let mut frame = Frame::from_rgba_speed(width, height, &mut self.screen, QUANTIZE_SPEED);
+        frame.delay = self.delay;
+        let written = self.encoder.as_mut().map_or(Ok(()), |e| e.write_frame(&frame));
+        if let Err(e) = written {
+            self.encoder = None;
+            return Err(format!("{}: {}", self.path.display(), e));
+        }
+        self.frames += 1;
+        Ok(())
+    }
+
+    /// Ends the GIF and returns how many frames it has.
+    pub fn finish(&mut self) -> Result<usize, String> {
+        if let Some(encoder) = self.encoder.take() {
+            encoder.into_inner().map_err(|e| format!("{}: {}", self.path.display(), e))?;
+        }
+        Ok(self.frames)
+    }
+}
+
+/// The event listener that adds a frame to `animation` whenever one is
+/// due, giving up on the recording after the first error.
+pub fn recorder(animation: SharedAnimation, life: SharedLife) -> impl FnMut(&GenerationEvent) + Send {
+    move |event| {
+        if let (Ok(mut a), Ok(l)) = (animation.lock(), life.read()) {
+            if let Err(e) = a.record(event.generation, l.as_ref()) {
+                log::error!("Stopped recording the GIF: {}", e);
+            }
+        }
+    }
+}
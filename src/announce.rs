@@ -0,0 +1,62 @@
+//! Status in words, for anyone who can't see the grid: pausing, running,
+//! stop conditions and the like are put in the window title, where screen
+//! readers find it, and with the `accessibility` feature in `access`'s
+//! tree.  With `--speak` they are also read aloud by the system's speech
+//! command: `spd-say` on Linux and the BSDs, `say` on macOS and the speech
+//! synthesizer through PowerShell on Windows.
+
+use std::process::{Child, Command, Stdio};
+
+pub struct Announcer {
+    speak: bool,
+    latest: Option<String>,
+    // Speech still going, collected once it finishes
+    speaking: Vec<Child>,
+}
+
+impl Announcer {
+    pub fn new(speak: bool) -> Self {
+        Announcer { speak, latest: None, speaking: Vec::new() }
+    }
+
+    /// The last thing said, for the window title.
+    pub fn latest(&self) -> Option<&str> {
+        self.latest.as_deref()
+    }
+
+    pub fn say(&mut self, text: String) {
+        // Not waited for; a newer announcement just talks over it
+        self.speaking.retain_mut(|child| matches!(child.try_wait(), Ok(None)));
+        if self.speak {
+            match speech(&text).stdin(Stdio::null()).stdout(Stdio::null()).spawn() {
+                Ok(child) => self.speaking.push(child),
+                Err(e) => {
+                    log::warn!("Could not speak, speech is off: {}", e);
+                    self.speak = false;
+                }
+            }
+        }
+        self.latest = Some(text);
+    }
+}
+
+fn speech(text: &str) -> Command {
+    let mut command;
+    if cfg!(target_os = "macos") {
+        command = Command::new("say");
+        command.arg(text);
+    } else if cfg!(windows) {
+        command = Command::new("powershell");
+        // Handed over in the environment rather than pasted into the
+        // script, so quotes in it can't break out
+        command.env("RUSTLIFE_SAY", text).args([
+            "-NoProfile",
+            "-Command",
+            "Add-Type -AssemblyName System.Speech; (New-Object System.Speech.Synthesis.SpeechSynthesizer).Speak($env:RUSTLIFE_SAY)",
+        ]);
+    } else {
+        command = Command::new("spd-say");
+        command.arg("--").arg(text);
+    }
+    command
+}
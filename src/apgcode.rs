@@ -0,0 +1,275 @@
+//! Catagolue's apgcodes: the extended Wechsler format plus a prefix saying
+//! what kind of object it is, e.g. `xs4_33` for the block, `xp2_7` for the
+//! blinker or `xq4_153` for the glider.
+//!
+//! The pattern is cut into strips five rows tall.  Each column of a strip is
+//! one character, `0`-`9` then `a`-`v`, with bit 0 the top row.  Runs of
+//! empty columns are shortened to `w` (two), `x` (three) or `y` plus one
+//! character (four to 39), and `z` starts the next strip.
+
+use crate::engine::{CellState, ConwayState};
+use crate::io;
+use crate::rule::Rule;
+
+const DIGITS: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+// Names of the objects a soup usually leaves behind, and a few more well
+// known ones, the dictionary Identify pattern names objects from
+const NAMES: &[(&str, &str)] = &[
+    ("xs4_33", "block"),
+    ("xs6_696", "beehive"),
+    ("xs7_2596", "loaf"),
+    ("xs5_253", "boat"),
+    ("xs4_252", "tub"),
+    ("xs6_356", "ship"),
+    ("xs8_6996", "pond"),
+    ("xs7_25ac", "long boat"),
+    ("xs6_25a4", "barge"),
+    ("xs8_25ak8", "long barge"),
+    ("xs8_35ac", "long ship"),
+    ("xs8_69ic", "mango"),
+    ("xs7_178c", "eater 1"),
+    ("xs6_39c", "aircraft carrier"),
+    ("xs6_bd", "snake"),
+    ("xp2_7", "blinker"),
+    ("xp2_7e", "toad"),
+    ("xp2_318c", "beacon"),
+    ("xp2_2a54", "clock"),
+    ("xp3_co9nas0san9oczgoldlo0oldlogz1047210127401", "pulsar"),
+    ("xp15_4r4z4r4", "pentadecathlon"),
+    ("xq4_153", "glider"),
+    ("xq4_6frc", "lightweight spaceship"),
+    ("xq4_27dee6", "middleweight spaceship"),
+    ("xq4_27deee6", "heavyweight spaceship"),
+];
+
+/// Longest period [`identify`] looks for.
+pub const MAX_PERIOD: usize = 64;
+
+/// The common name of a Conway's Life object, e.g. `block` for `xs4_33`.
+pub fn name(code: &str) -> Option<&'static str> {
+    NAMES.iter().find(|(c, _)| *c == code).map(|(_, name)| *name)
+}
+
+/// The apgcode of a commonly named object, ignoring case.
+pub fn by_name(name: &str) -> Option<&'static str> {
+    NAMES.iter().find(|(_, n)| n.eq_ignore_ascii_case(name)).map(|(code, _)| *code)
+}
+
+/// Encodes `live` cells as the body of an apgcode, without a prefix.  The
+/// pattern is used as given, see [`identify`] for the canonical code.
+pub fn encode(live: &[(usize, usize)]) -> String {
+    let live = normalize(live);
+    let (w, h) = io::extent(&live);
+    let mut grid = vec![false; w * h];
+    for &(x, y) in &live {
+        grid[y * w + x] = true;
+    }
+
+    let mut out = String::new();
+    for strip in 0..h.div_ceil(5) {
+        if strip > 0 {
+            out.push('z');
+        }
+        let mut zeros = 0;
+        for x in 0..w {
+            let mut column = 0;
+            for bit in 0..5 {
+                let y = strip * 5 + bit;
+                if y < h && grid[y * w + x] {
+                    column |= 1 << bit;
+                }
+            }
+            if column == 0 {
+                zeros += 1;
+                continue;
+            }
+            push_zeros(&mut out, zeros);
+            zeros = 0;
+            out.push(DIGITS[column] as char);
+        }
+        // Trailing empty columns are left out
+    }
+    out
+}
+
+fn push_zeros(out: &mut String, mut zeros: usize) {
+    while zeros > 0 {
+        match zeros {
+            1 => out.push('0'),
+            2 => out.push('w'),
+            3 => out.push('x'),
+            _ => {
+                let run = zeros.min(39);
+                out.push('y');
+                out.push(DIGITS[run - 4] as char);
+                zeros -= run;
+                continue;
+            }
+        }
+        return;
+    }
+}
+
+/// Decodes an apgcode, with or without its `xs`/`xp`/`xq` prefix, to live
+/// cells relative to the top left corner.
+pub fn decode(code: &str) -> Result<Vec<(usize, usize)>, String> {
+    let code = code.trim();
+    let body = match code.split_once('_') {
+        Some((prefix, body)) if prefix.starts_with('x') => body,
+        Some((prefix, _)) => return Err(format!("unknown apgcode prefix '{}'", prefix)),
+        None => code
+    };
+
+    let mut live = Vec::new();
+    let (mut x, mut y) = (0, 0);
+    let mut chars = body.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            'w' => x += 2,
+            'x' => x += 3,
+            'y' => {
+                let n = chars.next()
+                    .and_then(|c| c.to_digit(36))
+                    .ok_or("apgcode ends in the middle of a 'y' run")?;
+                x += 4 + n as usize;
+            }
+            'z' => {
+                x = 0;
+                y += 5;
+            }
+            c => {
+                let column = c.to_digit(36)
+                    .filter(|n| *n < 32)
+                    .ok_or_else(|| format!("unexpected '{}' in apgcode", c))?;
+                live.extend((0..5).filter(|bit| column & (1 << bit) != 0).map(|bit| (x, y + bit)));
+                x += 1;
+            }
+        }
+    }
+    Ok(live)
+}
+
+/// Finds the canonical apgcode of a single still life, oscillator or
+/// spaceship: the shortest code (then the first alphabetically) over every
+/// phase and orientation.  Returns `None` for an empty pattern or one that
+/// does not repeat within [`MAX_PERIOD`] generations.
+pub fn identify(live: &[(usize, usize)], rule: Rule) -> Option<String> {
+    let (phases, motion) = evolve(live, rule)?;
+    let kind = match motion {
+        Motion { period: 1, dx: 0, dy: 0 } => "xs",
+        Motion { dx: 0, dy: 0, .. } => "xp",
+        _ => "xq"
+    };
+    let tag = if kind == "xs" { phases[0].len() } else { motion.period };
+    let body = phases.iter()
+        .flat_map(|phase| orientations(phase))
+        .map(|cells| encode(&cells))
+        .min_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)))?;
+    Some(format!("{}{}_{}", kind, tag, body))
+}
+
+/// How far a pattern has moved when it first repeats its shape.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Motion {
+    pub period: usize,
+    pub dx: isize,
+    pub dy: isize,
+}
+
+/// The period and displacement of a single object, found by running it on
+/// its own until its shape repeats.  `None` if it does not within
+/// [`MAX_PERIOD`] generations.
+pub fn motion(live: &[(usize, usize)], rule: Rule) -> Option<Motion> {
+    evolve(live, rule).map(|(_, motion)| motion)
+}
+
+// Each phase normalized, as live cells
+type Phases = Vec<Vec<(usize, usize)>>;
+
+/// Every phase of `live` up to the first repeat, and how it moved.
+fn evolve(live: &[(usize, usize)], rule: Rule) -> Option<(Phases, Motion)> {
+    let start = normalize(live);
+    if start.is_empty() {
+        return None;
+    }
+
+    // Room for a spaceship to travel at up to c for the whole search
+    let (w, h) = io::extent(&start);
+    let margin = MAX_PERIOD + 2;
+    let mut state = ConwayState::empty(w + 2 * margin, h + 2 * margin);
+    state.rule = rule;
+    for &(x, y) in &start {
+        state.set(x + margin, y + margin, CellState::Alive);
+    }
+
+    let origin = corner(&state)?;
+    let mut phases = vec![start.clone()];
+    for period in 1..=MAX_PERIOD {
+        state = state.step();
+        let cells = live_cells(&state);
+        if cells.is_empty() {
+            return None;
+        }
+        let shape = normalize(&cells);
+        if shape == start {
+            let now = corner(&state)?;
+            let (dx, dy) = (now.0 as isize - origin.0 as isize, now.1 as isize - origin.1 as isize);
+            return Some((phases, Motion { period, dx, dy }));
+        }
+        phases.push(shape);
+    }
+    None
+}
+
+/// Top left corner of the live cells' bounding box.
+fn corner(state: &ConwayState) -> Option<(usize, usize)> {
+    let cells = live_cells(state);
+    Some((cells.iter().map(|c| c.0).min()?, cells.iter().map(|c| c.1).min()?))
+}
+
+fn live_cells(state: &ConwayState) -> Vec<(usize, usize)> {
+    (0..state.height)
+        .flat_map(|y| (0..state.width).map(move |x| (x, y)))
+        .filter(|&(x, y)| state.get(x, y) == CellState::Alive)
+        .collect()
+}
+
+/// Shifts `live` so its bounding box starts at (0, 0), sorted row by row.
+fn normalize(live: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    let min_x = live.iter().map(|c| c.0).min().unwrap_or(0);
+    let min_y = live.iter().map(|c| c.1).min().unwrap_or(0);
+    let mut cells: Vec<_> = live.iter().map(|&(x, y)| (x - min_x, y - min_y)).collect();
+    cells.sort_by_key(|&(x, y)| (y, x));
+    cells.dedup();
+    cells
+}
+
+/// The eight rotations and reflections of a pattern, each normalized.
+/// Symmetric patterns give repeats.
+pub fn orientations(live: &[(usize, usize)]) -> Vec<Vec<(usize, usize)>> {
+    let live = normalize(live);
+    let (w, h) = io::extent(&live);
+    let mut all = Vec::with_capacity(8);
+    for transpose in [false, true] {
+        for flip_x in [false, true] {
+            for flip_y in [false, true] {
+                let cells: Vec<_> = live.iter().map(|&(x, y)| {
+                    let x = if flip_x { w - 1 - x } else { x };
+                    let y = if flip_y { h - 1 - y } else { y };
+                    if transpose { (y, x) } else { (x, y) }
+                }).collect();
+                all.push(normalize(&cells));
+            }
+        }
+    }
+    all
+}
+
+/// The rotation or reflection of a pattern whose cells, read row by row,
+/// come first, which is the same for every orientation of it.
+pub fn canonical(live: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    orientations(live).into_iter()
+        .min_by_key(|cells| cells.iter().map(|&(x, y)| (y, x)).collect::<Vec<_>>())
+        .unwrap_or_default()
+}
@@ -0,0 +1,234 @@
+//! HTTP API for headless runs, built with `--features api` and started with
+//! `--headless --api <addr>`.  Once the `--generations` requested on the
+//! command line have run, the universe is only stepped when asked to:
+//!
+//! | request                                   | reply                                          |
+//! |-------------------------------------------|------------------------------------------------|
+//! | `GET /stats`                              | `{"generation": .., "population": .., "entropy": .., "width": .., "height": .., "rule": ..}` |
+//! | `GET /state?x=0&y=0&width=64&height=64`   | the region as RLE, the live cells' bounding box without a region |
+//! | `GET /state?format=png&...`               | the region as a PNG, one pixel per cell, the whole grid without a region |
+//! | `POST /pattern?x=10&y=10`                 | stamps the RLE body there, centred without x/y; `format=cells` for plaintext |
+//! | `POST /step?count=100`                    | steps, at most 100000 a request, then replies like `/stats` |
+//!
+//! Requests are handled one at a time, in order, on the calling thread.
+//! Errors reply with a 4xx status and a plain text message.
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+
+use serde_json::json;
+
+use rustlife::script::SharedLife;
+use rustlife::{io, CellState, EventBus, GenerationEvent, LifeEngine};
+
+use crate::render::Theme;
+
+// Largest request body and PNG side accepted, and most generations one
+// request steps, so a single request can't hold the universe for hours
+const MAX_BODY: usize = 16 << 20;
+const MAX_PNG: usize = 8192;
+const MAX_STEPS: usize = 100_000;
+
+struct Request {
+    method: String,
+    path: String,
+    query: HashMap<String, String>,
+    body: String,
+}
+
+struct Response {
+    status: &'static str,
+    content_type: &'static str,
+    body: Vec<u8>,
+}
+
+impl Response {
+    fn ok(content_type: &'static str, body: impl Into<Vec<u8>>) -> Self {
+        Response { status: "200 OK", content_type, body: body.into() }
+    }
+
+    fn error(status: &'static str, message: impl Into<String>) -> Self {
+        Response { status, content_type: "text/plain", body: message.into().into_bytes() }
+    }
+}
+
+/// Serves the API on `addr` until the process is stopped.  `generation` is
+/// how many generations have already run; steps taken through the API are
+/// published on `events` like any others.
+pub fn serve(addr: &str, life: &SharedLife, events: &mut EventBus, mut generation: u64, theme: Theme) -> Result<(), String> {
+    let listener = TcpListener::bind(addr).map_err(|e| format!("{}: {}", addr, e))?;
+    log::info!("API listening on http://{}/", addr);
+    for stream in listener.incoming().flatten() {
+        let peer = stream.peer_addr().map(|a| a.to_string()).unwrap_or_default();
+        if let Err(e) = client(stream, life, events, &mut generation, &theme) {
+            log::warn!("API client {}: {}", peer, e);
+        }
+    }
+    Ok(())
+}
+
+fn client(stream: TcpStream, life: &SharedLife, events: &mut EventBus, generation: &mut u64, theme: &Theme) -> std::io::Result<()> {
+    let mut reader = BufReader::new(stream);
+    let response = match read_request(&mut reader)? {
+        Ok(request) => handle(&request, life, events, generation, theme).unwrap_or_else(|e| Response::error("400 Bad Request", e)),
+        Err(e) => Response::error("400 Bad Request", e)
+    };
+    let mut stream = reader.into_inner();
+    write!(stream, "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        response.status, response.content_type, response.body.len())?;
+    stream.write_all(&response.body)
+}
+
+fn read_request(reader: &mut BufReader<TcpStream>) -> std::io::Result<Result<Request, String>> {
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let mut parts = line.split_whitespace();
+    let (method, target) = match (parts.next(), parts.next()) {
+        (Some(m), Some(t)) => (m.to_string(), t.to_string()),
+        _ => return Ok(Err("malformed request line".to_string()))
+    };
+
+    let mut length = 0;
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 || line.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+    if length > MAX_BODY {
+        return Ok(Err(format!("body larger than {} bytes", MAX_BODY)));
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+
+    let (path, query) = target.split_once('?').unwrap_or((&target, ""));
+    let query = query.split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    Ok(Ok(Request { method, path: path.to_string(), query, body: String::from_utf8_lossy(&body).into_owned() }))
+}
+
+impl Request {
+    fn number(&self, name: &str) -> Result<Option<usize>, String> {
+        self.query.get(name)
+            .map(|v| v.parse().map_err(|_| format!("{} must be a number, got '{}'", name, v)))
+            .transpose()
+    }
+}
+
+fn stats(generation: u64, life: &dyn LifeEngine) -> Response {
+    let stats = json!({
+        "generation": generation,
+        "population": life.population(),
+        "entropy": rustlife::stats::entropy(life),
+        "width": life.width(),
+        "height": life.height(),
+        "rule": life.rule().to_string(),
+    });
+    Response::ok("application/json", stats.to_string())
+}
+
+fn handle(request: &Request, life: &SharedLife, events: &mut EventBus, generation: &mut u64, theme: &Theme) -> Result<Response, String> {
+    match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/stats") => {
+            let l = life.read().map_err(|_| "universe lock poisoned")?;
+            Ok(stats(*generation, l.as_ref()))
+        }
+        ("GET", "/state") => {
+            let l = life.read().map_err(|_| "universe lock poisoned")?;
+            let png = request.query.get("format").is_some_and(|f| f == "png");
+            let region = match (request.number("x")?, request.number("y")?, request.number("width")?, request.number("height")?) {
+                (Some(x), Some(y), Some(w), Some(h)) => (x, y, w, h),
+                (None, None, None, None) if png => (0, 0, l.width(), l.height()),
+                (None, None, None, None) => match l.bounding_box() {
+                    Some(bb) => (bb.min_x, bb.min_y, bb.width(), bb.height()),
+                    None => (0, 0, 0, 0)
+                },
+                _ => return Err("give all of x, y, width and height, or none".to_string())
+            };
+            if png {
+                encode_png(l.as_ref(), region, theme).map(|png| Response::ok("image/png", png))
+            } else {
+                Ok(Response::ok("text/plain", io::cells_to_rle(&region_cells(l.as_ref(), region))))
+            }
+        }
+        ("POST", "/pattern") => {
+            let live = match request.query.get("format").map(String::as_str) {
+                Some("cells") => io::parse_plaintext(&request.body),
+                Some("rle") | None => io::parse_rle(&request.body)?,
+                Some(other) => return Err(format!("unknown pattern format '{}'", other))
+            };
+            let mut l = life.write().map_err(|_| "universe lock poisoned")?;
+            let (x, y) = match (request.number("x")?, request.number("y")?) {
+                (Some(x), Some(y)) => (x, y),
+                _ => {
+                    let (w, h) = io::extent(&live);
+                    (l.width().saturating_sub(w) / 2, l.height().saturating_sub(h) / 2)
+                }
+            };
+            io::place(l.as_mut(), &live, x, y);
+            Ok(stats(*generation, l.as_ref()))
+        }
+        ("POST", "/step") => {
+            let count = request.number("count")?.unwrap_or(1).min(MAX_STEPS);
+            for _ in 0..count {
+                let event = {
+                    let mut l = life.write().map_err(|_| "universe lock poisoned")?;
+                    l.step();
+                    *generation += 1;
+                    GenerationEvent::from_engine(*generation, l.as_ref())
+                };
+                // With no locks held, as for any other stepping loop
+                events.publish(&event);
+            }
+            let l = life.read().map_err(|_| "universe lock poisoned")?;
+            Ok(stats(*generation, l.as_ref()))
+        }
+        _ => Ok(Response::error("404 Not Found", format!("no such endpoint {} {}", request.method, request.path)))
+    }
+}
+
+/// Live cells inside `(x, y, width, height)`, relative to its corner.
+fn region_cells(life: &dyn LifeEngine, (x, y, w, h): (usize, usize, usize, usize)) -> Vec<(usize, usize)> {
+    let mut live = Vec::new();
+    for cy in y..y.saturating_add(h).min(life.height()) {
+        for cx in x..x.saturating_add(w).min(life.width()) {
+            if life.get(cx, cy) == CellState::Alive {
+                live.push((cx - x, cy - y));
+            }
+        }
+    }
+    live
+}
+
+fn encode_png(life: &dyn LifeEngine, (x, y, w, h): (usize, usize, usize, usize), theme: &Theme) -> Result<Vec<u8>, String> {
+    let (w, h) = (w.min(life.width().saturating_sub(x)), h.min(life.height().saturating_sub(y)));
+    if w == 0 || h == 0 || w > MAX_PNG || h > MAX_PNG {
+        return Err(format!("PNG region must be 1 to {} cells on each side and inside the grid", MAX_PNG));
+    }
+    let mut pixels = Vec::with_capacity(w * h * 3);
+    for cy in y..y + h {
+        for cx in x..x + w {
+            pixels.extend_from_slice(if life.get(cx, cy) == CellState::Alive { &theme.alive } else { &theme.dead });
+        }
+    }
+
+    let mut out = Vec::new();
+    let mut encoder = png::Encoder::new(&mut out, w as u32, h as u32);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.write_header()
+        .and_then(|mut writer| {
+            writer.write_image_data(&pixels)?;
+            writer.finish()
+        })
+        .map_err(|e| e.to_string())?;
+    Ok(out)
+}
@@ -0,0 +1,127 @@
+//! Sonification, built with `--features audio` and switched on with
+//! `--audio`.  The universe is split into [`VOICES`] vertical bands, left to
+//! right across the stereo field, each a sine tone on a pentatonic scale
+//! that gets louder the more live cells the band holds.  Population moves
+//! every tone up or down together, and a burst of change in one generation
+//! (many more cells flipping than usual) sounds a click.
+
+use std::f32::consts::TAU;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Arc;
+
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+
+use rustlife::{CellState, GenerationEvent};
+
+use crate::slots::ActiveSlot;
+
+pub const VOICES: usize = 4;
+
+// Semitones above the base pitch, a major pentatonic
+const SCALE: [f32; VOICES] = [0.0, 4.0, 7.0, 9.0];
+const BASE_HZ: f32 = 220.0;
+// Cells sampled per band and generation
+const SAMPLES: usize = 1024;
+const CLICK_SECONDS: f32 = 0.05;
+
+/// Parameters shared with the audio callback.  Floats are stored as their
+/// bits so the callback never has to take a lock.
+#[derive(Default)]
+struct Controls {
+    amplitude: [AtomicU32; VOICES],
+    pitch: AtomicU32,
+    click: AtomicBool,
+}
+
+fn store(a: &AtomicU32, v: f32) {
+    a.store(v.to_bits(), Ordering::Relaxed);
+}
+
+fn load(a: &AtomicU32) -> f32 {
+    f32::from_bits(a.load(Ordering::Relaxed))
+}
+
+/// Opens the default output device.  The returned stream plays for as long
+/// as it is kept; the listener feeds it from generation events.
+pub fn start(active: ActiveSlot) -> Result<(cpal::Stream, impl FnMut(&GenerationEvent) + Send), String> {
+    let device = cpal::default_host().default_output_device().ok_or("no audio output device")?;
+    let config = device.default_output_config().map_err(|e| e.to_string())?;
+    if config.sample_format() != cpal::SampleFormat::F32 {
+        return Err(format!("audio output wants {} samples, only f32 is supported", config.sample_format()));
+    }
+    let rate = config.sample_rate().0 as f32;
+    let channels = config.channels() as usize;
+
+    let controls = Arc::new(Controls::default());
+    store(&controls.pitch, 1.0);
+    let c_controls = Arc::clone(&controls);
+    let mut phases = [0.0f32; VOICES];
+    // Smoothed amplitudes, so tones fade rather than jump between generations
+    let mut levels = [0.0f32; VOICES];
+    let mut click_left = 0.0f32;
+    let mut noise = 0x2545_f491u32;
+
+    let stream = device.build_output_stream(&config.into(), move |data: &mut [f32], _: &cpal::OutputCallbackInfo| {
+        let pitch = load(&c_controls.pitch);
+        if c_controls.click.swap(false, Ordering::Relaxed) {
+            click_left = CLICK_SECONDS;
+        }
+        for frame in data.chunks_mut(channels) {
+            let (mut left, mut right) = (0.0, 0.0);
+            for v in 0..VOICES {
+                levels[v] += (load(&c_controls.amplitude[v]) - levels[v]) * 0.001;
+                let hz = BASE_HZ * pitch * 2f32.powf(SCALE[v] / 12.0);
+                phases[v] = (phases[v] + hz / rate) % 1.0;
+                let sample = (phases[v] * TAU).sin() * levels[v] / VOICES as f32;
+                let pan = v as f32 / (VOICES - 1) as f32;
+                left += sample * (1.0 - pan);
+                right += sample * pan;
+            }
+            if click_left > 0.0 {
+                // xorshift white noise, fading out
+                noise ^= noise << 13;
+                noise ^= noise >> 17;
+                noise ^= noise << 5;
+                let hit = (noise as f32 / u32::MAX as f32 * 2.0 - 1.0) * 0.3 * click_left / CLICK_SECONDS;
+                left += hit;
+                right += hit;
+                click_left -= 1.0 / rate;
+            }
+            for (c, out) in frame.iter_mut().enumerate() {
+                *out = if channels == 1 { (left + right) / 2.0 } else if c % 2 == 0 { left } else { right };
+            }
+        }
+    }, |e| log::error!("Audio output: {}", e), None).map_err(|e| e.to_string())?;
+    stream.play().map_err(|e| e.to_string())?;
+
+    let mut usual_change = 0.0f32;
+    let listener = move |event: &GenerationEvent| {
+        let life = match active.read() {
+            Ok(active) => Arc::clone(&active.0),
+            Err(_) => return
+        };
+        if let Ok(l) = life.read() {
+            let band_width = (l.width() / VOICES).max(1);
+            // Spread the samples over the band on a fixed lattice
+            let stride = ((band_width * l.height()) / SAMPLES).max(1);
+            for v in 0..VOICES {
+                let live = (0..SAMPLES)
+                    .map(|i| i * stride)
+                    .map(|i| (v * band_width + i % band_width, i / band_width))
+                    .filter(|&(x, y)| x < l.width() && y < l.height() && l.get(x, y) == CellState::Alive)
+                    .count();
+                store(&controls.amplitude[v], (live as f32 / SAMPLES as f32 * 4.0).min(1.0));
+            }
+            let cells = (l.width() * l.height()).max(1) as f32;
+            // One octave up or down across the whole range of densities
+            store(&controls.pitch, 2f32.powf(event.population as f32 / cells * 4.0 - 1.0));
+        }
+
+        let change = event.changed as f32;
+        if usual_change > 0.0 && change > usual_change * 2.0 {
+            controls.click.store(true, Ordering::Relaxed);
+        }
+        usual_change += (change - usual_change) * 0.1;
+    };
+    Ok((stream, listener))
+}
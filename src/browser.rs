@@ -0,0 +1,235 @@
+//! The pattern browser: thumbnails of the recently used patterns, the built
+//! in ones and those in the user's patterns directory.  Clicking one picks
+//! it up, and each left click on the grid then places a copy of it centred
+//! on the cell under the mouse.  Escape or the right button puts it back.
+
+use std::path::Path;
+
+use winit::event::VirtualKeyCode;
+use winit_input_helper::WinitInputHelper;
+
+use rustlife::io;
+use rustlife::library::{self, LibraryPattern};
+
+use crate::commands::Command;
+use crate::overlay::{self, GLYPH_SIZE};
+use crate::recent::RecentItem;
+use crate::render::View;
+
+// Side of a thumbnail, in pixels, and most pixels a cell gets in one
+const THUMB: u32 = 56;
+const MAX_CELL: f64 = 8.0;
+const LINE: u32 = GLYPH_SIZE + 4;
+// A tile holds a thumbnail with its name underneath
+const TILE_WIDTH: u32 = THUMB + 16;
+const TILE_HEIGHT: u32 = THUMB + 8 + LINE;
+
+struct Entry {
+    pattern: LibraryPattern,
+    /// How to find it again, if not by its cells
+    source: Option<RecentItem>,
+    /// `THUMB` pixels square, true where a live cell is drawn
+    thumbnail: Vec<bool>,
+}
+
+#[derive(Default)]
+pub struct Browser {
+    open: bool,
+    entries: Vec<Entry>,
+    /// How many of the entries, at the start, were used recently
+    recent: usize,
+    /// First row of tiles shown
+    scroll: usize,
+    /// Picked up and waiting to be placed
+    held: Option<usize>,
+}
+
+/// Draws `cells` scaled to fit a thumbnail, centred.
+fn thumbnail(cells: &[(usize, usize)]) -> Vec<bool> {
+    let (w, h) = io::extent(cells);
+    let scale = (THUMB as f64 / w.max(h).max(1) as f64).min(MAX_CELL);
+    let (left, top) = ((THUMB as f64 - w as f64 * scale) / 2.0, (THUMB as f64 - h as f64 * scale) / 2.0);
+    // A gap between cells when there is room for one
+    let size = if scale >= 3.0 { scale - 1.0 } else { scale };
+    let mut thumb = vec![false; (THUMB * THUMB) as usize];
+    for &(x, y) in cells {
+        let (x0, y0) = (left + x as f64 * scale, top + y as f64 * scale);
+        for py in y0 as u32..((y0 + size).ceil() as u32).min(THUMB) {
+            for px in x0 as u32..((x0 + size).ceil() as u32).min(THUMB) {
+                thumb[(py * THUMB + px) as usize] = true;
+            }
+        }
+    }
+    thumb
+}
+
+/// Top left corner for `cells` to be centred on `cell`.
+fn corner(cells: &[(usize, usize)], (x, y): (usize, usize)) -> (usize, usize) {
+    let (w, h) = io::extent(cells);
+    (x.saturating_sub(w / 2), y.saturating_sub(h / 2))
+}
+
+impl Browser {
+    pub fn new() -> Self {
+        Browser::default()
+    }
+
+    /// Open, or holding a pattern to place, either way taking the input.
+    pub fn is_active(&self) -> bool {
+        self.open || self.held.is_some()
+    }
+
+    /// Opens the browser, reading `dir` again so new files show up, with
+    /// the `recent` patterns first.  Files that can't be read are reported
+    /// and left out.
+    pub fn open(&mut self, dir: Option<&Path>, recent: &[RecentItem]) {
+        let mut patterns = Vec::new();
+        for item in recent {
+            match item.resolve() {
+                Ok(pattern) => patterns.push((pattern, Some(item.clone()))),
+                Err(e) => log::warn!("Skipping recent pattern: {}", e)
+            }
+        }
+        let recent_count = patterns.len();
+        patterns.extend(library::builtin().into_iter().map(|pattern| (pattern, None)));
+        if let Some(dir) = dir {
+            match library::pattern_files(dir) {
+                Ok(files) => {
+                    for file in files {
+                        match library::load_file(&file) {
+                            Ok(pattern) => patterns.push((pattern, Some(RecentItem::File { path: file.display().to_string() }))),
+                            Err(e) => log::error!("Could not read pattern: {}", e)
+                        }
+                    }
+                }
+                // No directory is the same as an empty one
+                Err(e) if dir.exists() => log::error!("Could not list patterns: {}", e),
+                Err(_) => {}
+            }
+        }
+        let entries = patterns.into_iter().map(|(pattern, source)| Entry { thumbnail: thumbnail(&pattern.cells), pattern, source }).collect();
+        *self = Browser { open: true, entries, recent: recent_count, scroll: 0, held: None };
+    }
+
+    /// The pattern picked up to place, as it goes in the recent list.
+    pub fn held(&self) -> Option<RecentItem> {
+        let entry = &self.entries[self.held?];
+        Some(entry.source.clone().unwrap_or_else(|| RecentItem::pattern(&entry.pattern)))
+    }
+
+    /// Picks up `pattern` to place, as if it had been clicked.  `source` is
+    /// how to find it again, if not by its cells.
+    pub fn hold(&mut self, pattern: LibraryPattern, source: Option<RecentItem>) {
+        let entry = Entry { thumbnail: thumbnail(&pattern.cells), pattern, source };
+        *self = Browser { open: false, entries: vec![entry], recent: 0, scroll: 0, held: Some(0) };
+    }
+
+    pub fn close(&mut self) {
+        *self = Browser::default();
+    }
+
+    /// Where the tiles go on a `width` by `height` screen: the top left of
+    /// the first, and how many columns and rows fit.
+    fn layout(width: u32, height: u32) -> ((i32, i32), u32, u32) {
+        let columns = (width.saturating_sub(16) / TILE_WIDTH).max(1);
+        let rows = (height.saturating_sub(16 + LINE) / TILE_HEIGHT).max(1);
+        let x = width.saturating_sub(columns * TILE_WIDTH) as i32 / 2;
+        ((x, 8 + LINE as i32 + 4), columns, rows)
+    }
+
+    /// The entry whose tile is under the screen pixel.
+    fn tile_at(&self, (px, py): (usize, usize), width: u32, height: u32) -> Option<usize> {
+        let ((x, y), columns, rows) = Browser::layout(width, height);
+        let (dx, dy) = (px as i32 - x, py as i32 - y);
+        if dx < 0 || dy < 0 {
+            return None;
+        }
+        let (column, row) = (dx as u32 / TILE_WIDTH, dy as u32 / TILE_HEIGHT);
+        let i = (self.scroll as u32 + row) * columns + column;
+        (column < columns && row < rows && (i as usize) < self.entries.len()).then_some(i as usize)
+    }
+
+    /// Handles this frame's input while the browser is active.  `pointer`
+    /// is the screen pixel under the mouse and `cell` the grid cell there.
+    /// Returns the paste to make when a held pattern is placed.
+    pub fn update(&mut self, input: &WinitInputHelper, width: u32, height: u32, pointer: Option<(usize, usize)>, cell: Option<(usize, usize)>) -> Option<Command> {
+        if input.key_pressed(VirtualKeyCode::Escape) {
+            self.close();
+            return None;
+        }
+        if let Some(held) = self.held {
+            if input.mouse_pressed(1) {
+                self.close();
+            } else if let Some(cell) = cell.filter(|_| input.mouse_pressed(0)) {
+                let cells = &self.entries[held].pattern.cells;
+                return Some(Command::Paste { pattern: io::cells_to_plaintext(cells), at: Some(corner(cells, cell)) });
+            }
+            return None;
+        }
+
+        let (_, columns, rows) = Browser::layout(width, height);
+        let last = (self.entries.len() as u32).div_ceil(columns).saturating_sub(rows) as usize;
+        let scroll = input.scroll_diff();
+        if (scroll > 0.0 || input.key_pressed_os(VirtualKeyCode::Up)) && self.scroll > 0 {
+            self.scroll -= 1;
+        }
+        if (scroll < 0.0 || input.key_pressed_os(VirtualKeyCode::Down)) && self.scroll < last {
+            self.scroll += 1;
+        }
+        if input.mouse_pressed(0) {
+            if let Some(i) = pointer.and_then(|p| self.tile_at(p, width, height)) {
+                self.open = false;
+                self.held = Some(i);
+            }
+        }
+        None
+    }
+
+    /// Draws the open browser, or the held pattern where it would go with
+    /// the mouse over `cell`.
+    pub fn draw(&self, width: u32, height: u32, screen: &mut [u8], view: &View, pointer: Option<(usize, usize)>, cell: Option<(usize, usize)>) {
+        if let Some(held) = self.held {
+            let pattern = &self.entries[held].pattern;
+            if let Some(cell) = cell {
+                let (x, y) = corner(&pattern.cells, cell);
+                let size = view.scale(width).max(1.0) as u32;
+                for &(cx, cy) in &pattern.cells {
+                    let (sx, sy) = view.screen_pos(((x + cx) as f64, (y + cy) as f64), (width, height));
+                    overlay::fill_rect(screen, width, height, (sx, sy, size, size), [64, 255, 64, 160]);
+                }
+            }
+            let hint = format!("Click to place {}, Esc to put it back", pattern.name);
+            overlay::fill_rect(screen, width, height, (0, height as i32 - LINE as i32 - 4, width, LINE + 4), [0, 0, 0, 200]);
+            overlay::draw_text(screen, width, height, 4, height as i32 - LINE as i32, &hint, [255, 255, 255]);
+            return;
+        }
+        if !self.open {
+            return;
+        }
+
+        let ((x, y), columns, rows) = Browser::layout(width, height);
+        let panel = (x - 4, 4, columns * TILE_WIDTH + 8, LINE + 8 + rows * TILE_HEIGHT);
+        overlay::fill_rect(screen, width, height, panel, [0, 0, 0, 220]);
+        let title = if self.recent > 0 { "Patterns, recent first: click one to pick it up" } else { "Patterns: click one to pick it up" };
+        overlay::draw_text(screen, width, height, x, 8, title, [255, 255, 255]);
+        let hovered = pointer.and_then(|p| self.tile_at(p, width, height));
+        let first = self.scroll * columns as usize;
+        for (i, entry) in self.entries.iter().enumerate().skip(first).take((columns * rows) as usize) {
+            let n = (i - first) as u32;
+            let (tx, ty) = (x + (n % columns * TILE_WIDTH) as i32, y + (n / columns * TILE_HEIGHT) as i32);
+            if hovered == Some(i) {
+                overlay::fill_rect(screen, width, height, (tx, ty, TILE_WIDTH, TILE_HEIGHT), [80, 80, 160, 255]);
+            }
+            let (left, top) = (tx + 8, ty + 4);
+            // Recently used ones stand out from the rest
+            let background = if i < self.recent { [24, 24, 64, 255] } else { [24, 24, 24, 255] };
+            overlay::fill_rect(screen, width, height, (left, top, THUMB, THUMB), background);
+            for (p, _) in entry.thumbnail.iter().enumerate().filter(|(_, &on)| on) {
+                let (px, py) = (left + (p as u32 % THUMB) as i32, top + (p as u32 / THUMB) as i32);
+                overlay::fill_rect(screen, width, height, (px, py, 1, 1), [230, 230, 230, 255]);
+            }
+            let name: String = entry.pattern.name.chars().take((TILE_WIDTH / GLYPH_SIZE) as usize).collect();
+            overlay::draw_text(screen, width, height, tx + 4, top + THUMB as i32 + 4, &name, [200, 200, 200]);
+        }
+    }
+}
@@ -0,0 +1,232 @@
+//! Putting a universe together: its size, backend, rule, the settings of
+//! the backend, what lies past its edges and what it starts with.  The
+//! command line, replays and programs using the library all build theirs
+//! this way.
+//!
+//!     use rustlife::{Boundary, Rule, SimulationBuilder};
+//!
+//!     let life = SimulationBuilder::new(256, 256)
+//!         .rule(Rule::conway())
+//!         .boundary(Boundary::Torus)
+//!         .soup(0.5, 42)
+//!         .build()
+//!         .unwrap();
+//!
+//! The edges of the grid are dead unless the boundary is a torus, which
+//! only the dense backend can be.
+
+use std::sync::Arc;
+
+use crate::cyclic::CyclicEngine;
+use crate::elementary::ElementaryEngine;
+use crate::engine::{self, Boundary, DenseEngine, LifeEngine};
+use crate::forest::ForestEngine;
+use crate::gas::{GasEngine, GasModel};
+use crate::io;
+use crate::ising::IsingEngine;
+use crate::memory::Estimate;
+use crate::reaction::ReactionEngine;
+use crate::replay::Start;
+use crate::rule::{NeighborhoodRule, Rule};
+use crate::soup::{self, Layout, Symmetry};
+
+#[derive(Clone)]
+pub struct SimulationBuilder {
+    backend: String,
+    width: usize,
+    height: usize,
+    rule: Rule,
+    custom_rule: Option<Arc<dyn NeighborhoodRule>>,
+    reaction: Option<(f64, f64)>,
+    forest: Option<(f64, f64)>,
+    cyclic: Option<(u8, usize)>,
+    ising: Option<(f64, f64)>,
+    gas: Option<GasModel>,
+    elementary: Option<u8>,
+    boundary: Boundary,
+    start: Option<Start>,
+    threads: usize,
+    memory_limit: u64,
+}
+
+impl SimulationBuilder {
+    /// An empty `width` by `height` grid on the dense backend, following
+    /// Conway's rule.
+    pub fn new(width: usize, height: usize) -> Self {
+        SimulationBuilder {
+            backend: "dense".to_string(),
+            width,
+            height,
+            rule: Rule::default(),
+            custom_rule: None,
+            reaction: None,
+            forest: None,
+            cyclic: None,
+            ising: None,
+            gas: None,
+            elementary: None,
+            boundary: Boundary::Dead,
+            start: None,
+            threads: 0,
+            memory_limit: 0,
+        }
+    }
+
+    /// One of [`engine::BACKENDS`].
+    pub fn backend(mut self, name: &str) -> Self {
+        self.backend = name.to_string();
+        self
+    }
+
+    pub fn rule(mut self, rule: Rule) -> Self {
+        self.rule = rule;
+        self
+    }
+
+    /// A rule from a plugin, used instead of [`rule`](Self::rule).  Only the
+    /// dense backend takes one.
+    pub fn custom_rule(mut self, rule: Arc<dyn NeighborhoodRule>) -> Self {
+        self.custom_rule = Some(rule);
+        self
+    }
+
+    /// Feed and kill rates, on the reaction backend.
+    pub fn reaction(mut self, feed: f64, kill: f64) -> Self {
+        self.reaction = Some((feed, kill));
+        self
+    }
+
+    /// Growth and lightning chances, on the forest backend.
+    pub fn forest(mut self, growth: f64, lightning: f64) -> Self {
+        self.forest = Some((growth, lightning));
+        self
+    }
+
+    /// States and threshold, on the cyclic backend.
+    pub fn cyclic(mut self, states: u8, threshold: usize) -> Self {
+        self.cyclic = Some((states, threshold));
+        self
+    }
+
+    /// Temperature and external field, on the ising backend.
+    pub fn ising(mut self, temperature: f64, field: f64) -> Self {
+        self.ising = Some((temperature, field));
+        self
+    }
+
+    /// Lattice gas model, on the gas backend.
+    pub fn gas(mut self, model: GasModel) -> Self {
+        self.gas = Some(model);
+        self
+    }
+
+    /// Wolfram code, on the elementary backend.
+    pub fn elementary(mut self, rule: u8) -> Self {
+        self.elementary = Some(rule);
+        self
+    }
+
+    /// What lies past the edges.  Only the dense backend can be a torus.
+    pub fn boundary(mut self, boundary: Boundary) -> Self {
+        self.boundary = boundary;
+        self
+    }
+
+    /// Fills the whole grid with a soup, each cell alive with chance
+    /// `density`.
+    pub fn soup(self, density: f64, seed: u64) -> Self {
+        self.start(Start::Soup { density, seed, symmetry: Symmetry::C1, size: 0, layout: Layout::Uniform })
+    }
+
+    /// Puts the live cells of a pattern in the middle of the grid.
+    pub fn pattern(self, live: &[(usize, usize)]) -> Self {
+        let (w, h) = io::extent(live);
+        let (x, y) = (self.width.saturating_sub(w) / 2, self.height.saturating_sub(h) / 2);
+        self.start(Start::Pattern { x, y, pattern: io::cells_to_plaintext(live) })
+    }
+
+    /// Starts from a soup or pattern as a replay records it.  Without one
+    /// the grid starts empty.
+    pub fn start(mut self, start: Start) -> Self {
+        self.start = Some(start);
+        self
+    }
+
+    /// Threads stepping uses, 0 for one per core.  There is only one pool of
+    /// them, so this can't be changed once anything has stepped.
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads;
+        self
+    }
+
+    /// Most bytes the grid and its scratch buffer may take, 0 for no limit.
+    /// A universe that would need more isn't built.
+    pub fn memory_limit(mut self, bytes: u64) -> Self {
+        self.memory_limit = bytes;
+        self
+    }
+
+    /// What the universe starts with, for recording a replay of it.
+    pub fn starting(&self) -> Option<&Start> {
+        self.start.as_ref()
+    }
+
+    pub fn build(&self) -> Result<Box<dyn LifeEngine>, String> {
+        #[cfg(not(target_arch = "wasm32"))]
+        if self.threads > 0 {
+            set_threads(self.threads)?;
+        }
+        if let Some(estimate) = Estimate::new(&self.backend, self.width, self.height) {
+            estimate.check(self.memory_limit).map_err(|e| format!("{}x{} on the {} backend: {}", self.width, self.height, self.backend, e))?;
+        }
+        let mut life = engine::create(&self.backend, self.width, self.height)
+            .ok_or_else(|| format!("unknown backend '{}', expected one of: {}", self.backend, engine::BACKENDS.join(", ")))?;
+        life.set_rule(self.rule);
+        if let Some(rule) = &self.custom_rule {
+            if !life.set_custom_rule(Some(rule.clone())) {
+                return Err(format!("backend '{}' does not support plugin rules", life.name()));
+            }
+        }
+        if let (Some((feed, kill)), Some(r)) = (self.reaction, life.downcast_mut::<ReactionEngine>()) {
+            r.set_rates(feed, kill);
+        }
+        if let (Some((growth, lightning)), Some(f)) = (self.forest, life.downcast_mut::<ForestEngine>()) {
+            f.set_chances(growth, lightning);
+        }
+        if let (Some((states, threshold)), Some(c)) = (self.cyclic, life.downcast_mut::<CyclicEngine>()) {
+            c.set_params(states, threshold);
+        }
+        if let (Some((temperature, field)), Some(i)) = (self.ising, life.downcast_mut::<IsingEngine>()) {
+            i.set_params(temperature, field);
+        }
+        if let (Some(model), Some(g)) = (self.gas, life.downcast_mut::<GasEngine>()) {
+            g.set_model(model);
+        }
+        if let (Some(rule), Some(e)) = (self.elementary, life.downcast_mut::<ElementaryEngine>()) {
+            e.set_code(rule);
+        }
+        if self.boundary != Boundary::Dead {
+            let name = life.name();
+            life.downcast_mut::<DenseEngine>()
+                .ok_or_else(|| format!("backend '{}' has dead edges only, not a {} boundary", name, self.boundary))?
+                .set_boundary(self.boundary);
+        }
+        match &self.start {
+            Some(Start::Soup { density, seed, symmetry, size, layout }) => soup::fill(life.as_mut(), layout, *symmetry, *size, *density, *seed),
+            Some(Start::Pattern { x, y, pattern }) => io::place(life.as_mut(), &io::parse_plaintext(pattern), *x, *y),
+            None => {}
+        }
+        Ok(life)
+    }
+}
+
+/// Starts the pool of threads stepping uses with `threads` of them.  Fine
+/// to call again with the same number, but the pool can't be resized.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn set_threads(threads: usize) -> Result<(), String> {
+    match rayon::ThreadPoolBuilder::new().num_threads(threads).build_global() {
+        Ok(()) => Ok(()),
+        Err(_) if rayon::current_num_threads() == threads => Ok(()),
+        Err(e) => Err(format!("could not use {} threads, stepping already uses {}: {}", threads, rayon::current_num_threads(), e))
+    }
+}
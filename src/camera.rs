@@ -0,0 +1,136 @@
+//! Camera paths for recordings: keyframes saying where the camera looks and
+//! how close at a given generation, with the generations between filled in
+//! smoothly.  A path is written one keyframe a line,
+//!
+//! ```text
+//! # generation  x    y    zoom
+//! 0             256  256  1
+//! 300           120  200  4
+//! 600           400  380  0.5
+//! ```
+//!
+//! where (x, y) is the cell in the middle of the picture and zoom is pixels
+//! a cell, so 4 shows each cell as a 4x4 square and 0.5 fits two cells to a
+//! pixel.  The camera moves along a Catmull-Rom spline through the keyframes,
+//! so it keeps going through each one rather than stopping, and zooms at a
+//! steady rate, so going from 1 to 16 takes as long as from 16 to 256.
+//! Before the first keyframe it waits at the first, after the last at the
+//! last.
+
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// Where the camera looks at one generation.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Shot {
+    pub x: f64,
+    pub y: f64,
+    /// Pixels a cell
+    pub zoom: f64,
+}
+
+/// A shot the camera passes through.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Keyframe {
+    pub generation: u64,
+    pub shot: Shot,
+}
+
+/// Keyframes in order of generation, at most one a generation.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CameraPath {
+    keyframes: Vec<Keyframe>,
+}
+
+/// A camera path a script can add keyframes to while it is recorded.
+pub type SharedCamera = Arc<Mutex<CameraPath>>;
+
+impl CameraPath {
+    pub fn new() -> Self {
+        CameraPath::default()
+    }
+
+    pub fn keyframes(&self) -> &[Keyframe] {
+        &self.keyframes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keyframes.is_empty()
+    }
+
+    /// Adds a keyframe, replacing any already at its generation.  The zoom
+    /// must be above 0.
+    pub fn add(&mut self, keyframe: Keyframe) -> Result<(), String> {
+        let Shot { x, y, zoom } = keyframe.shot;
+        if !(x.is_finite() && y.is_finite() && zoom.is_finite() && zoom > 0.0) {
+            return Err(format!("generation {}: expected a position and a zoom above 0, got {}, {} and {}", keyframe.generation, x, y, zoom));
+        }
+        match self.keyframes.binary_search_by_key(&keyframe.generation, |k| k.generation) {
+            Ok(i) => self.keyframes[i] = keyframe,
+            Err(i) => self.keyframes.insert(i, keyframe)
+        }
+        Ok(())
+    }
+
+    /// Reads a camera path written as in the module documentation.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        CameraPath::parse(&text).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// A camera path, one `generation x y zoom` keyframe a line.  Blank lines
+    /// and anything after a `#` are left out.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut path = CameraPath::new();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            parse_keyframe(line).and_then(|k| path.add(k)).map_err(|e| format!("line {}: {}", number + 1, e))?;
+        }
+        if path.is_empty() {
+            return Err("no keyframes, expected lines of generation x y zoom".to_string());
+        }
+        Ok(path)
+    }
+
+    /// Where the camera looks at `generation`, or None without keyframes.
+    pub fn at(&self, generation: u64) -> Option<Shot> {
+        let keys = &self.keyframes;
+        let after = keys.partition_point(|k| k.generation <= generation);
+        if after == 0 || after == keys.len() {
+            return keys.get(after.saturating_sub(1)).map(|k| k.shot);
+        }
+        let (from, to) = (&keys[after - 1], &keys[after]);
+        let t = (generation - from.generation) as f64 / (to.generation - from.generation) as f64;
+        // The keyframes either side of this stretch, the ends standing in
+        // for themselves
+        let before = &keys[(after - 1).saturating_sub(1)];
+        let next = &keys[(after + 1).min(keys.len() - 1)];
+        let spline = |of: fn(&Shot) -> f64| catmull_rom(of(&before.shot), of(&from.shot), of(&to.shot), of(&next.shot), t);
+        Some(Shot {
+            x: spline(|s| s.x),
+            y: spline(|s| s.y),
+            zoom: spline(|s| s.zoom.ln()).exp(),
+        })
+    }
+}
+
+fn parse_keyframe(line: &str) -> Result<Keyframe, String> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    let [generation, x, y, zoom] = fields[..] else {
+        return Err(format!("expected generation x y zoom, got '{}'", line));
+    };
+    let number = |s: &str| s.parse::<f64>().map_err(|_| format!("'{}' is not a number", s));
+    let generation = generation.parse().map_err(|_| format!("'{}' is not a generation", generation))?;
+    Ok(Keyframe { generation, shot: Shot { x: number(x)?, y: number(y)?, zoom: number(zoom)? } })
+}
+
+/// The point `t` (0 to 1) of the way from `p1` to `p2` on the Catmull-Rom
+/// spline through `p0` to `p3`.
+fn catmull_rom(p0: f64, p1: f64, p2: f64, p3: f64, t: f64) -> f64 {
+    let (t2, t3) = (t * t, t * t * t);
+    0.5 * (2.0 * p1 + (p2 - p0) * t + (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * t2 + (3.0 * p1 - p0 - 3.0 * p2 + p3) * t3)
+}
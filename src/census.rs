@@ -0,0 +1,301 @@
+//! Soup searching in the spirit of apgsearch: random 16x16 soups are run
+//! until they settle, then the debris is split into objects and each one is
+//! identified by its apgcode.
+//!
+//! Objects are separated by distance alone (live cells within two cells of
+//! each other belong together), so objects that happen to sit close
+//! together are counted as one.  Anything heading for the edge of the
+//! search area, usually a glider, is identified and removed on the way.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use crate::apgcode;
+use crate::engine::{BoundingBox, CellState, ConwayState, LifeEngine};
+use crate::quadtree;
+use crate::rule::Rule;
+use crate::soup::{self, Symmetry};
+
+pub const SOUP_SIZE: usize = 16;
+
+// The soup starts in the middle of a GRID x GRID universe
+const GRID: usize = 128;
+// Objects this close to the edge are taken out before it affects them
+const BORDER: usize = 12;
+const CHECK_EVERY: usize = 16;
+const MAX_GENERATIONS: usize = 20_000;
+// Settled means the population has repeated with a period up to MAX_PERIOD
+// for SETTLED_SPAN generations
+const MAX_PERIOD: usize = 30;
+const SETTLED_SPAN: usize = 90;
+
+// Anything bigger is left unidentified rather than run for MAX_PERIOD
+// generations
+const MAX_OBJECT: usize = 64;
+
+/// Code given to objects [`apgcode::identify`] cannot make sense of.
+pub const UNIDENTIFIED: &str = "unidentified";
+
+/// The live cells of soup number `seed`, each alive with probability 1/2.
+pub fn soup(seed: u64, symmetry: Symmetry) -> Vec<(usize, usize)> {
+    soup::generate(symmetry, SOUP_SIZE, 0.5, seed)
+}
+
+/// Groups live cells into objects, in grid coordinates.
+pub fn objects(state: &ConwayState) -> Vec<Vec<(usize, usize)>> {
+    clusters(state.width, state.height, |i| state.cells[i] == CellState::Alive)
+}
+
+/// [`objects`] for any engine, going through its live cells alone.
+pub fn objects_in(life: &dyn LifeEngine) -> Vec<Vec<(usize, usize)>> {
+    let whole = BoundingBox { min_x: 0, min_y: 0, max_x: life.width().saturating_sub(1), max_y: life.height().saturating_sub(1) };
+    clusters_of(&quadtree::index(life).live_cells_in(whole))
+}
+
+fn clusters(w: usize, h: usize, alive: impl Fn(usize) -> bool) -> Vec<Vec<(usize, usize)>> {
+    let mut seen = vec![false; w * h];
+    let mut found = Vec::new();
+    for start in 0..w * h {
+        if seen[start] || !alive(start) {
+            continue;
+        }
+        seen[start] = true;
+        let mut object = Vec::new();
+        let mut todo = vec![start];
+        while let Some(i) = todo.pop() {
+            let (x, y) = (i % w, i / w);
+            object.push((x, y));
+            for ny in y.saturating_sub(2)..(y + 3).min(h) {
+                for nx in x.saturating_sub(2)..(x + 3).min(w) {
+                    let n = ny * w + nx;
+                    if !seen[n] && alive(n) {
+                        seen[n] = true;
+                        todo.push(n);
+                    }
+                }
+            }
+        }
+        found.push(object);
+    }
+    found
+}
+
+/// [`clusters`] of just `cells`, in reading order, for grids too big or
+/// too empty to go through cell by cell.
+fn clusters_of(cells: &[(usize, usize)]) -> Vec<Vec<(usize, usize)>> {
+    let mut left: HashSet<(usize, usize)> = cells.iter().copied().collect();
+    let mut found = Vec::new();
+    for &start in cells {
+        if !left.remove(&start) {
+            continue;
+        }
+        let mut object = Vec::new();
+        let mut todo = vec![start];
+        while let Some((x, y)) = todo.pop() {
+            object.push((x, y));
+            for ny in y.saturating_sub(2)..y + 3 {
+                for nx in x.saturating_sub(2)..x + 3 {
+                    if left.remove(&(nx, ny)) {
+                        todo.push((nx, ny));
+                    }
+                }
+            }
+        }
+        found.push(object);
+    }
+    found
+}
+
+/// An object found by [`identify_region`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Object {
+    pub bounds: BoundingBox,
+    pub cells: usize,
+    /// Its apgcode, or [`UNIDENTIFIED`]
+    pub code: String,
+}
+
+impl Object {
+    pub fn name(&self) -> Option<&'static str> {
+        apgcode::name(&self.code)
+    }
+}
+
+impl fmt::Display for Object {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.name() {
+            Some(name) => write!(f, "{} ({})", name, self.code)?,
+            None if self.code == UNIDENTIFIED => write!(f, "{} object of {} cell{}", UNIDENTIFIED, self.cells, if self.cells == 1 { "" } else { "s" })?,
+            None => write!(f, "{}", self.code)?,
+        }
+        write!(f, " at {},{}", self.bounds.min_x, self.bounds.min_y)
+    }
+}
+
+/// Splits the live cells of `life` inside `region`, or the whole grid, into
+/// objects and identifies each on its own, whatever its phase, rotation or
+/// reflection.  Objects reaching out of the region are cut off at its edge.
+/// In reading order of their top left corners.
+pub fn identify_region(life: &dyn LifeEngine, region: Option<BoundingBox>) -> Vec<Object> {
+    let whole = BoundingBox { min_x: 0, min_y: 0, max_x: life.width().saturating_sub(1), max_y: life.height().saturating_sub(1) };
+    let Some(region) = region.unwrap_or(whole).intersection(whole) else { return Vec::new() };
+    let mut found: Vec<Object> = clusters_of(&quadtree::index(life).live_cells_in(region))
+        .into_iter()
+        .map(|object| {
+            let bounds = BoundingBox::of(&object).expect("objects have cells");
+            Object { bounds, cells: object.len(), code: identify(&object, life.rule()) }
+        })
+        .collect();
+    found.sort_by_key(|o| (o.bounds.min_y, o.bounds.min_x));
+    found
+}
+
+fn identify(object: &[(usize, usize)], rule: Rule) -> String {
+    if BoundingBox::of(object).is_some_and(|bb| bb.width() > MAX_OBJECT || bb.height() > MAX_OBJECT) {
+        return UNIDENTIFIED.to_string();
+    }
+    apgcode::identify(object, rule).unwrap_or_else(|| UNIDENTIFIED.to_string())
+}
+
+/// The generation the last [`SETTLED_SPAN`] populations started repeating
+/// with some period at, if they do.
+pub(crate) fn settled(populations: &[usize]) -> Option<usize> {
+    if populations.len() < SETTLED_SPAN + MAX_PERIOD {
+        return None;
+    }
+    let recent = &populations[populations.len() - SETTLED_SPAN - MAX_PERIOD..];
+    let period = (1..=MAX_PERIOD).find(|&p| (p..recent.len()).all(|i| recent[i] == recent[i - p]))?;
+    let mut since = populations.len() - SETTLED_SPAN - MAX_PERIOD + period;
+    while since > period && populations[since - 1] == populations[since - 1 - period] {
+        since -= 1;
+    }
+    // populations[0] is generation 1
+    Some(since - period + 1)
+}
+
+/// Runs `state` until its population settles into a repeating cycle,
+/// taking out any object that comes near the edge and identifying it on
+/// the way.  Returns the generation it settled at and the codes of what
+/// was taken out, or `None` if it was still going after the generation
+/// limit.
+pub fn settle(state: &mut ConwayState) -> Option<(usize, Vec<String>)> {
+    let (w, h) = (state.width, state.height);
+    let mut scratch = state.clone();
+    let mut codes = Vec::new();
+    // Cells taken out still count, so an escaping glider's removal does not
+    // look like the population changing
+    let mut removed = 0;
+    let mut populations = Vec::new();
+    for generation in 1..=MAX_GENERATIONS {
+        state.next_state_serial(&mut scratch);
+        state.swap_state(&mut scratch);
+        populations.push(state.population() + removed);
+
+        if generation % CHECK_EVERY == 0 {
+            for object in objects(state) {
+                let near_edge = object.iter().any(|&(x, y)| x < BORDER || y < BORDER || x >= w - BORDER || y >= h - BORDER);
+                if near_edge {
+                    codes.push(identify(&object, state.rule));
+                    removed += object.len();
+                    for &(x, y) in &object {
+                        state.set(x, y, CellState::Dead);
+                    }
+                }
+            }
+            if let Some(at) = settled(&populations) {
+                return Some((at, codes));
+            }
+        }
+    }
+    None
+}
+
+/// Runs soup `seed` until it settles and returns the apgcode of every object
+/// it left behind, or `None` if it was still going after the generation
+/// limit.
+pub fn search(seed: u64, symmetry: Symmetry, rule: Rule) -> Option<Vec<String>> {
+    let mut state = ConwayState::empty(GRID, GRID);
+    state.rule = rule;
+    let corner = (GRID - SOUP_SIZE) / 2;
+    for (x, y) in soup(seed, symmetry) {
+        state.set(corner + x, corner + y, CellState::Alive);
+    }
+    let (_, mut codes) = settle(&mut state)?;
+    codes.extend(objects(&state).iter().map(|object| identify(object, rule)));
+    Some(codes)
+}
+
+/// Counts the objects in a running universe.  `soups` stays at zero.
+pub fn take(life: &dyn LifeEngine) -> Census {
+    let mut census = Census::new();
+    for object in objects_in(life) {
+        *census.counts.entry(identify(&object, life.rule())).or_default() += 1;
+    }
+    census
+}
+
+/// Object counts over many soups.
+#[derive(Default, Clone, Debug)]
+pub struct Census {
+    pub soups: u64,
+    /// Soups that had not settled within the generation limit
+    pub unsettled: u64,
+    pub counts: HashMap<String, u64>,
+}
+
+impl Census {
+    pub fn new() -> Self {
+        Census::default()
+    }
+
+    pub fn add(&mut self, result: Option<Vec<String>>) {
+        self.soups += 1;
+        match result {
+            Some(codes) => {
+                for code in codes {
+                    *self.counts.entry(code).or_default() += 1;
+                }
+            }
+            None => self.unsettled += 1
+        }
+    }
+
+    pub fn merge(&mut self, other: Census) {
+        self.soups += other.soups;
+        self.unsettled += other.unsettled;
+        for (code, n) in other.counts {
+            *self.counts.entry(code).or_default() += n;
+        }
+    }
+
+    /// Most common first, ties by code.
+    pub fn sorted(&self) -> Vec<(&str, u64)> {
+        let mut rows: Vec<_> = self.counts.iter().map(|(c, n)| (c.as_str(), *n)).collect();
+        rows.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        rows
+    }
+
+    /// One line per object, most common first, with the common name where
+    /// there is one.
+    pub fn report(&self) -> String {
+        let mut out = String::new();
+        for (code, n) in self.sorted() {
+            match apgcode::name(code) {
+                Some(name) => out.push_str(&format!("{:>6} {} ({})\n", n, name, code)),
+                None => out.push_str(&format!("{:>6} {}\n", n, code))
+            }
+        }
+        out
+    }
+
+    /// The census in the layout of an apgsearch haul.
+    pub fn haul(&self, rule: Rule, symmetry: Symmetry) -> String {
+        let rule = rule.to_string().to_ascii_lowercase().replace('/', "");
+        let mut out = format!("@RULE {}\n@SYMMETRY {}\n@NUM_SOUPS {}\n@NUM_OBJECTS {}\n\n@CENSUS TABLE\n",
+            rule, symmetry.apgsearch(SOUP_SIZE), self.soups, self.counts.values().sum::<u64>());
+        for (code, n) in self.sorted() {
+            out.push_str(&format!("{} {}\n", code, n));
+        }
+        out
+    }
+}
@@ -0,0 +1,138 @@
+//! Checkpoints of a long run, written every so often to a run directory so
+//! it can pick up where it left off after a crash or a reboot.  Each one is
+//! a [`Snapshot`] in a file named after the generation.  Checkpoints from
+//! before snapshots, gzipped JSON with the live cells packed as in
+//! [`crate::packed`], are still read.  Like the timeline's keyframes, only
+//! whether cells are alive is kept.
+
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use flate2::read::GzDecoder;
+use serde::Deserialize;
+
+use crate::engine::LifeEngine;
+use crate::events::GenerationEvent;
+use crate::packed::PackedGrid;
+use crate::rule::Rule;
+use crate::script::SharedLife;
+use crate::snapshot::Snapshot;
+
+/// Checkpoints kept in the run directory; older ones are removed.
+pub const KEEP: usize = 3;
+
+const PREFIX: &str = "checkpoint-";
+const SUFFIX: &str = ".snap";
+// What checkpoints were written as before snapshots
+const OLD_SUFFIX: &str = ".json.gz";
+
+#[derive(Deserialize)]
+struct OldCheckpoint {
+    generation: u64,
+    backend: String,
+    rule: Rule,
+    #[serde(flatten)]
+    grid: PackedGrid,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Checkpoint {
+    pub snapshot: Snapshot,
+}
+
+impl Checkpoint {
+    pub fn of(generation: u64, life: &dyn LifeEngine) -> Self {
+        Checkpoint { snapshot: Snapshot::of(generation, life) }
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.snapshot.generation
+    }
+
+    /// Writes it to `dir`, made if need be, and removes all but the latest
+    /// [`KEEP`] there.  Written to a temporary file first, so a run stopped
+    /// part way through leaves the last checkpoint whole.
+    pub fn save(&self, dir: &Path) -> Result<PathBuf, String> {
+        let err = |path: &Path, e: std::io::Error| format!("{}: {}", path.display(), e);
+        fs::create_dir_all(dir).map_err(|e| err(dir, e))?;
+        let path = dir.join(format!("{}{:012}{}", PREFIX, self.generation(), SUFFIX));
+        let partial = path.with_extension("partial");
+        self.snapshot.save(&partial)?;
+        fs::rename(&partial, &path).map_err(|e| err(&path, e))?;
+
+        let saved = saved(dir)?;
+        for (_, old) in saved.iter().rev().skip(KEEP) {
+            if let Err(e) = fs::remove_file(old) {
+                log::warn!("Could not remove old checkpoint {}: {}", old.display(), e);
+            }
+        }
+        Ok(path)
+    }
+
+    pub fn load(path: &Path) -> Result<Checkpoint, String> {
+        if !path.to_string_lossy().ends_with(OLD_SUFFIX) {
+            return Snapshot::load(path).map(|snapshot| Checkpoint { snapshot });
+        }
+        let file = File::open(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        let old: OldCheckpoint = serde_json::from_reader(GzDecoder::new(BufReader::new(file))).map_err(|e| format!("{}: {}", path.display(), e))?;
+        let size = (old.grid.width, old.grid.height);
+        Ok(Checkpoint { snapshot: Snapshot::new(old.generation, &old.backend, old.rule, size, &old.grid.alive()) })
+    }
+
+    /// The latest checkpoint in `dir`, or None if there are none yet.
+    pub fn latest(dir: &Path) -> Result<Option<Checkpoint>, String> {
+        if !dir.exists() {
+            return Ok(None);
+        }
+        saved(dir)?.last().map(|(_, path)| Checkpoint::load(path)).transpose()
+    }
+
+    /// Sets the cells of `life`, which must be the same size and on the same
+    /// backend, and its rule to the checkpoint's.
+    pub fn restore(&self, life: &mut dyn LifeEngine) -> Result<(), String> {
+        let s = &self.snapshot;
+        if (s.width, s.height) != (life.width(), life.height()) || s.backend != life.name() {
+            return Err(format!(
+                "the checkpoint is of a {}x{} grid on the {} backend, not {}x{} on {}",
+                s.width, s.height, s.backend, life.width(), life.height(), life.name()
+            ));
+        }
+        s.restore(life)
+    }
+}
+
+/// Event listener saving a checkpoint of `life` to `dir` every `every`
+/// generations, 0 for never, and after any generation `interval` or more
+/// since the last one.
+pub fn writer(life: SharedLife, dir: PathBuf, every: u64, interval: Option<Duration>) -> impl FnMut(&GenerationEvent) + Send {
+    let mut last = Instant::now();
+    move |event| {
+        let due = (every > 0 && event.generation.is_multiple_of(every)) || interval.is_some_and(|i| last.elapsed() >= i);
+        if !due {
+            return;
+        }
+        last = Instant::now();
+        let Ok(l) = life.read() else { return };
+        match Checkpoint::of(event.generation, l.as_ref()).save(&dir) {
+            Ok(path) => log::info!("Wrote checkpoint {}", path.display()),
+            Err(e) => log::error!("Could not write checkpoint: {}", e)
+        }
+    }
+}
+
+/// The checkpoints in `dir` with their generations, earliest first.
+fn saved(dir: &Path) -> Result<Vec<(u64, PathBuf)>, String> {
+    let entries = fs::read_dir(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+    let mut saved: Vec<(u64, PathBuf)> = entries.flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            let rest = name.strip_prefix(PREFIX)?;
+            let generation = rest.strip_suffix(SUFFIX).or_else(|| rest.strip_suffix(OLD_SUFFIX))?.parse().ok()?;
+            Some((generation, entry.path()))
+        })
+        .collect();
+    saved.sort();
+    Ok(saved)
+}
@@ -0,0 +1,579 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+
+use rustlife::evolve::Fitness;
+use rustlife::gas::GasModel;
+use rustlife::io::{self, Format};
+use rustlife::soup::{Layout, Symmetry};
+use rustlife::text::Glyphs;
+use rustlife::{Boundary, BoundingBox, Rule};
+
+/// Options left unset here fall back to the config file, then to built-in
+/// defaults.
+#[derive(Parser, Debug, Clone)]
+#[command(name = "conway", about = "Conway's Game of Life")]
+pub struct Args {
+    /// Config file to load and save settings to
+    #[arg(long)]
+    pub config: Option<PathBuf>,
+
+    /// Start fresh instead of restoring the last session's window and view
+    #[arg(long)]
+    pub no_session: bool,
+
+    /// Universe width in cells [default: 2048]
+    #[arg(long)]
+    pub grid_width: Option<usize>,
+
+    /// Universe height in cells [default: 2048]
+    #[arg(long)]
+    pub grid_height: Option<usize>,
+
+    /// Window (framebuffer) width in pixels [default: 512]
+    #[arg(long)]
+    pub window_width: Option<u32>,
+
+    /// Window (framebuffer) height in pixels [default: 512]
+    #[arg(long)]
+    pub window_height: Option<u32>,
+
+    /// Rule in B/S notation, e.g. B3/S23 or B36/S23 [default: B3/S23].  With
+    /// --convert, the rule to write in place of the one in the file
+    #[arg(long)]
+    pub rule: Option<Rule>,
+
+    /// Fraction of cells alive in the initial random soup [default: 0.5]
+    #[arg(long, value_parser = parse_fraction)]
+    pub density: Option<f64>,
+
+    /// Symmetry of random soups and --census soups: C1, C2, C4, D2, D4 or
+    /// D8 [default: C1]
+    #[arg(long)]
+    pub symmetry: Option<Symmetry>,
+
+    /// Side of the random soup, placed in the middle of an empty grid;
+    /// fills the grid if not given
+    #[arg(long)]
+    pub soup_size: Option<usize>,
+
+    /// How the random soup is laid out: uniform, gradient, stripes[:width],
+    /// rings[:width], blobs[:count[:radius]] or tiles:<pattern file>[:gap]
+    /// [default: uniform]
+    #[arg(long, value_parser = parse_layout)]
+    pub layout: Option<Layout>,
+
+    /// Seed for the initial random soup; picked at random if not given
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    /// Plaintext (.cells) or RLE pattern to start from instead of a random soup
+    #[arg(long)]
+    pub pattern: Option<String>,
+
+    /// Start the --pattern file over whenever it is saved, for editing it
+    /// alongside
+    #[cfg(feature = "gui")]
+    #[arg(long, requires = "pattern", conflicts_with_all = ["headless", "join"])]
+    pub watch: bool,
+
+    /// Read the starting pattern, plaintext or RLE, from standard input
+    #[arg(long, conflicts_with = "pattern")]
+    pub stdin: bool,
+
+    /// Simulation backend: dense, species for predators and prey, reaction
+    /// for Gray-Scott reaction-diffusion, sand for falling sand, forest for
+    /// the forest-fire model, cyclic for the cyclic automaton, ising for the
+    /// Ising model, gas for a lattice gas, or elementary for one-dimensional
+    /// Wolfram rules [default: dense]
+    #[arg(long)]
+    pub backend: Option<String>,
+
+    /// Maximum generations per second, 0 for unlimited [default: 0]
+    #[arg(long)]
+    pub speed: Option<u32>,
+
+    /// Maximum frames drawn per second, however fast the generations go,
+    /// 0 for the display's rate [default: 0]
+    #[cfg(feature = "gui")]
+    #[arg(long)]
+    pub max_fps: Option<u32>,
+
+    /// Save power: at most 15 generations and frames a second on 2 threads,
+    /// and stopped while the window is minimized or covered.  On by default
+    /// on battery; `--power-save false` turns it off
+    #[cfg(feature = "gui")]
+    #[arg(long, num_args = 0..=1, default_missing_value = "true")]
+    pub power_save: Option<bool>,
+
+    /// Worker threads for stepping, 0 for one per core [default: 0]
+    #[arg(long)]
+    pub threads: Option<usize>,
+
+    /// Pin each stepping thread to its own core and give it the same strip
+    /// of the grid every generation, in memory near that core.  Helps on
+    /// machines with several sockets or core complexes; Linux only
+    #[arg(long)]
+    pub pin_threads: bool,
+
+    /// Most memory the universe may take, in MiB.  Grids that would need
+    /// more are refused before anything is allocated [default: the
+    /// machine's memory]
+    #[arg(long, value_name = "MIB")]
+    pub max_memory: Option<u64>,
+
+    /// Colour theme, or a .toml theme file that is reloaded when saved
+    /// [default: classic]
+    #[arg(long)]
+    pub theme: Option<String>,
+
+    /// Feed and kill rates for the reaction backend, e.g. "0.055 0.062"
+    #[arg(long, value_parser = parse_reaction)]
+    pub reaction: Option<(f64, f64)>,
+
+    /// Growth and lightning chances for the forest backend, e.g. "0.01 0.00001"
+    #[arg(long, value_parser = parse_forest)]
+    pub forest: Option<(f64, f64)>,
+
+    /// States and threshold for the cyclic backend, e.g. "14 1"
+    #[arg(long, value_parser = parse_cyclic)]
+    pub cyclic: Option<(u8, usize)>,
+
+    /// Temperature and external field for the ising backend, e.g. "2.269 0"
+    #[arg(long, value_parser = parse_ising)]
+    pub ising: Option<(f64, f64)>,
+
+    /// Lattice gas model for the gas backend: hpp or fhp [default: hpp]
+    #[arg(long)]
+    pub gas: Option<GasModel>,
+
+    /// Wolfram code for the elementary backend, 0 to 255 [default: 110]
+    #[arg(long)]
+    pub elementary: Option<u8>,
+
+    /// What lies past the edges of the grid: dead, or torus to join them up
+    /// so what leaves one side comes back on the other, on the dense
+    /// backend only [default: dead]
+    #[arg(long)]
+    pub boundary: Option<Boundary>,
+
+    /// Start the elementary backend from a rule 110 tape of 0s and 1s set in
+    /// the ether, e.g. 1101
+    #[arg(long, conflicts_with_all = ["pattern", "stdin"])]
+    pub tape: Option<String>,
+
+    /// Chance of each cell flipping every generation, e.g. 0.0001 [default: 0]
+    #[arg(long, value_parser = parse_fraction)]
+    pub noise: Option<f64>,
+
+    /// Pause when the population dies out
+    #[arg(long)]
+    pub stop_on_extinction: bool,
+
+    /// Pause when the grid settles into a still life or oscillator
+    #[arg(long)]
+    pub stop_on_repeat: bool,
+
+    /// Pause on reaching this generation
+    #[arg(long)]
+    pub stop_at: Option<u64>,
+
+    /// Report when the whole universe enters a cycle, and when, without
+    /// pausing; headless runs add it to the report
+    #[arg(long)]
+    pub detect_cycles: bool,
+
+    /// Check for cycles, and for --stop-on-repeat, only every this many
+    /// generations, which spares time on big grids [default: 1]
+    #[arg(long, value_name = "GENERATIONS")]
+    pub cycle_every: Option<u64>,
+
+    /// Rule plugin (shared library) to load; may be repeated
+    #[arg(long = "plugin")]
+    pub plugins: Vec<PathBuf>,
+
+    /// Use the named plugin rule instead of --rule
+    #[arg(long)]
+    pub rule_plugin: Option<String>,
+
+    /// Rhai script run at startup and after every generation
+    #[arg(long)]
+    pub script: Option<PathBuf>,
+
+    /// Record the starting state and every edit to this replay file
+    #[arg(long)]
+    pub record: Option<PathBuf>,
+
+    /// Play back a replay file instead of starting from --pattern or a soup
+    #[arg(long)]
+    pub replay: Option<PathBuf>,
+
+    /// Record tracing spans for stepping, locking and drawing to this file,
+    /// viewable in chrome://tracing or Perfetto
+    #[arg(long)]
+    pub trace_chrome: Option<PathBuf>,
+
+    /// Accept remote control commands over WebSocket on this address,
+    /// e.g. 127.0.0.1:9001
+    #[cfg(feature = "remote")]
+    #[arg(long)]
+    pub remote: Option<String>,
+
+    /// Accept text commands on a Unix domain socket at this path
+    #[cfg(unix)]
+    #[arg(long)]
+    pub ipc: Option<String>,
+
+    /// Serve a live MJPEG view of the first slot over HTTP on this address,
+    /// e.g. 0.0.0.0:8080.  With --headless, --speed paces the run
+    #[cfg(feature = "stream")]
+    #[arg(long)]
+    pub stream: Option<String>,
+
+    /// Frames per second to stream
+    #[cfg(feature = "stream")]
+    #[arg(long, default_value_t = 15)]
+    pub stream_fps: u32,
+
+    /// Record the headless run as an animated GIF the size of the window
+    #[cfg(feature = "gif")]
+    #[arg(long, requires = "headless")]
+    pub gif: Option<PathBuf>,
+
+    /// Generations between the GIF's frames
+    #[cfg(feature = "gif")]
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u64).range(1..))]
+    pub gif_every: u64,
+
+    /// Frames per second the GIF plays at
+    #[cfg(feature = "gif")]
+    #[arg(long, default_value_t = 20, value_parser = clap::value_parser!(u32).range(1..=100))]
+    pub gif_fps: u32,
+
+    /// Camera path for the GIF to follow, lines of `generation x y zoom`
+    /// with (x, y) the cell in the middle and zoom in pixels a cell; a
+    /// script's camera(gen, x, y, zoom) keyframes are added to it
+    #[cfg(feature = "gif")]
+    #[arg(long, requires = "gif")]
+    pub camera: Option<PathBuf>,
+
+    /// With --headless, serve an HTTP API on this address after the
+    /// requested generations have run, e.g. 127.0.0.1:8000
+    #[cfg(feature = "api")]
+    #[arg(long)]
+    pub api: Option<String>,
+
+    /// Send Open Sound Control messages to this address, e.g. 127.0.0.1:9000
+    #[arg(long)]
+    pub osc: Option<String>,
+
+    /// OSC updates per second
+    #[arg(long, default_value_t = 30)]
+    pub osc_rate: u32,
+
+    /// Play the simulation as sound on the default audio output
+    #[cfg(feature = "audio")]
+    #[arg(long)]
+    pub audio: bool,
+
+    /// Share the shown universe with anyone who joins on this address,
+    /// e.g. 0.0.0.0:9100
+    #[arg(long)]
+    pub host: Option<String>,
+
+    /// Join a universe shared with --host at this address instead of
+    /// starting one
+    #[arg(long, conflicts_with_all = ["host", "replay", "pattern"])]
+    pub join: Option<String>,
+
+    /// Instead of running a universe, search this many random 16x16 soups
+    /// and print a census of the objects they settle into.  --seed picks
+    /// the first soup
+    #[arg(long)]
+    pub census: Option<u64>,
+
+    /// Instead of running a universe, hunt for methuselahs: run this many
+    /// small random seeds to stabilisation and print the longest lived.
+    /// --seed picks the first
+    #[arg(long, conflicts_with = "census")]
+    pub hunt: Option<u64>,
+
+    /// Instead of running a universe, breed small seed patterns for this
+    /// many generations of a genetic algorithm toward --fitness, and print
+    /// the best.  --seed picks the first population
+    #[arg(long, conflicts_with_all = ["census", "hunt"])]
+    pub evolve: Option<u64>,
+
+    /// What --evolve breeds for: lifespan, the generation a seed settles
+    /// at; population, its live cells once settled; or distance, how far
+    /// its bounding box travels in 256 generations
+    #[arg(long, default_value_t, requires = "evolve")]
+    pub fitness: Fitness,
+
+    /// Seed patterns in each --evolve generation
+    #[arg(long, default_value_t = 50, value_parser = clap::value_parser!(u64).range(3..), requires = "evolve")]
+    pub evolve_size: u64,
+
+    /// How many of the best --evolve patterns to report
+    #[arg(long, default_value_t = 5, requires = "evolve")]
+    pub evolve_keep: usize,
+
+    /// Instead of running a universe, try this many random B/S rules on a
+    /// soup each and print the most interesting: those that keep changing
+    /// without dying out, filling the grid or settling.  --seed picks the
+    /// first
+    #[arg(long, conflicts_with_all = ["census", "hunt"])]
+    pub explore: Option<u64>,
+
+    /// How many of the best --explore rules to report
+    #[arg(long, default_value_t = 10, requires = "explore")]
+    pub explore_keep: usize,
+
+    /// Instead of running a universe, step a soup --generations times on
+    /// this engine and on --verify-against in lockstep and report the
+    /// first cell they disagree on: dense, parallel, strips, core or
+    /// reference.  Fails if they disagree
+    #[arg(long, value_name = "ENGINE", conflicts_with_all = ["census", "hunt", "evolve", "explore"])]
+    pub verify: Option<String>,
+
+    /// The engine --verify checks against
+    #[arg(long, value_name = "ENGINE", default_value = "reference", requires = "verify")]
+    pub verify_against: String,
+
+    /// Compare the --verify engines only every this many generations,
+    /// going back a generation at a time when they disagree
+    #[arg(long, value_name = "GENERATIONS", default_value_t = 1, requires = "verify")]
+    pub verify_every: u64,
+
+    /// Instead of running a universe, convert the pattern file IN to OUT,
+    /// each in the format its extension names: .cells, .rle, .lif (Life
+    /// 1.06) or .mc (macrocell).  Life 1.05 is read but only written with
+    /// --format
+    #[arg(long, num_args = 2, value_names = ["IN", "OUT"], conflicts_with_all = ["census", "hunt"])]
+    pub convert: Option<Vec<String>>,
+
+    /// Format for --convert to write, instead of going by OUT's extension:
+    /// cells, rle, life105, life106 or mc
+    #[arg(long, requires = "convert")]
+    pub format: Option<Format>,
+
+    /// Crop what --convert writes to the live cells
+    #[arg(long, requires = "convert")]
+    pub trim: bool,
+
+    /// Crop what --convert writes to the live cells and turn it to its
+    /// canonical orientation, the rotation or reflection whose cells come
+    /// first read row by row
+    #[arg(long, requires = "convert")]
+    pub canonical: bool,
+
+    /// Instead of running a universe, time this many generations of a soup
+    /// on square grids from 512 cells across up to --scaling-max, each on 1,
+    /// 2, 4 and so on up to every core, and report generations a second and
+    /// memory for each
+    #[arg(long, conflicts_with_all = ["census", "hunt", "convert"])]
+    pub scaling: Option<u64>,
+
+    /// Widest grid --scaling tries
+    #[arg(long, default_value_t = 16384, requires = "scaling")]
+    pub scaling_max: usize,
+
+    /// Instead of running a universe, look for a predecessor of the pattern
+    /// in this file, at most 8x8: cells that step to it within its bounding
+    /// box.  Prints one as RLE, or says there is none within one cell more
+    /// each way
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["census", "hunt", "convert"])]
+    pub predecessor: Option<String>,
+
+    /// Write the --scaling report as JSON
+    #[arg(long, requires = "scaling")]
+    pub json: bool,
+
+    /// Live cells in each --hunt seed, placed in an 8x8 box
+    #[arg(long, default_value_t = 20, value_parser = clap::value_parser!(u64).range(1..=64))]
+    pub hunt_cells: u64,
+
+    /// How many of the longest lived --hunt seeds to report
+    #[arg(long, default_value_t = 10)]
+    pub hunt_keep: usize,
+
+    /// Fill the screen without a cursor, start again whenever the universe
+    /// settles or dies out, and quit on any key, click or mouse movement
+    #[cfg(feature = "gui")]
+    #[arg(long, conflicts_with_all = ["headless", "join", "replay"])]
+    pub screensaver: bool,
+
+    /// Open the window fullscreen
+    #[cfg(feature = "gui")]
+    #[arg(long, conflicts_with_all = ["headless", "wallpaper"])]
+    pub fullscreen: bool,
+
+    /// Monitor to go fullscreen on, or to be the wallpaper or screensaver
+    /// of, counting from 1.  With it the monitors are logged at info level
+    #[cfg(feature = "gui")]
+    #[arg(long)]
+    pub monitor: Option<usize>,
+
+    /// Go fullscreen on every monitor, the universe spread across them as
+    /// though they were one screen, the main window on --monitor
+    #[cfg(feature = "gui")]
+    #[arg(long, conflicts_with_all = ["headless", "wallpaper"])]
+    pub span: bool,
+
+    /// Run as a live wallpaper: a borderless window over the whole screen,
+    /// behind every other window, that clicks go through.  Steps 4
+    /// generations a second unless --speed is given
+    #[cfg(feature = "gui")]
+    #[arg(long, conflicts_with_all = ["headless", "join", "screensaver"])]
+    pub wallpaper: bool,
+
+    /// Show famous patterns one after another with captions, each until
+    /// it settles or has had its time.  Steps 30 generations a second
+    /// unless --speed is given
+    #[cfg(feature = "gui")]
+    #[arg(long, conflicts_with_all = ["headless", "join", "replay", "screensaver", "pattern"])]
+    pub demo: bool,
+
+    /// Read out pausing, stop conditions and other changes with the
+    /// system's speech command, as well as putting them in the window title
+    #[cfg(feature = "gui")]
+    #[arg(long)]
+    pub speak: bool,
+
+    /// Show the tutorial, which otherwise only comes up the first time
+    #[cfg(feature = "gui")]
+    #[arg(long)]
+    pub tutorial: bool,
+
+    /// Steer with a gamepad
+    #[cfg(feature = "gamepad")]
+    #[arg(long)]
+    pub gamepad: bool,
+
+    /// Run without a window and print the result
+    #[arg(long)]
+    pub headless: bool,
+
+    /// Run in the terminal instead of a window, drawn in half blocks or
+    /// Braille
+    #[cfg(feature = "tui")]
+    #[arg(long, conflicts_with = "headless")]
+    pub tui: bool,
+
+    /// Number of generations to run in headless mode
+    #[arg(long, default_value_t = 100)]
+    pub generations: u64,
+
+    /// Write the headless report to this file instead of stdout
+    #[arg(long)]
+    pub output: Option<String>,
+
+    /// Write checkpoints of a headless run to this directory every so often
+    /// and at the end, keeping the latest three
+    #[arg(long, requires = "headless")]
+    pub checkpoint_dir: Option<PathBuf>,
+
+    /// Generations between checkpoints, 0 for only at the end
+    #[arg(long, default_value_t = 10000)]
+    pub checkpoint_every: u64,
+
+    /// Also write a checkpoint once this many minutes have passed since the
+    /// last one
+    #[arg(long, requires = "checkpoint_dir")]
+    pub checkpoint_minutes: Option<u64>,
+
+    /// Carry on from the latest checkpoint in --checkpoint-dir, or start
+    /// from the beginning if there is none yet
+    #[arg(long, requires = "checkpoint_dir", conflicts_with = "replay")]
+    pub resume: bool,
+
+    /// Print the grid to stdout every this many generations of a headless
+    /// run
+    #[arg(long, requires = "headless", value_parser = clap::value_parser!(u64).range(1..))]
+    pub print_every: Option<u64>,
+
+    /// Characters to print the grid with: ascii ('.' and 'O'), blocks or
+    /// braille
+    #[arg(long, default_value_t = Glyphs::Ascii)]
+    pub print_glyphs: Glyphs,
+
+    /// Only print this part of the grid, as x,y,width,height
+    #[arg(long, value_parser = parse_window, requires = "print_every")]
+    pub print_window: Option<BoundingBox>,
+
+    /// Record the middle of the grid over the headless run and save it as a
+    /// space-time volume, a MagicaVoxel .vox model or an .obj point cloud
+    #[arg(long, requires = "headless")]
+    pub volume: Option<String>,
+}
+
+/// A probability or fraction, 0 to 1.
+pub fn parse_fraction(s: &str) -> Result<f64, String> {
+    let d: f64 = s.trim().parse().map_err(|e| format!("{}", e))?;
+    if (0.0..=1.0).contains(&d) {
+        Ok(d)
+    } else {
+        Err(format!("must be between 0 and 1, got {}", d))
+    }
+}
+
+/// A soup layout, reading the pattern for `tiles:<file>[:gap]`.
+pub fn parse_layout(s: &str) -> Result<Layout, String> {
+    let Some(rest) = s.trim().strip_prefix("tiles:") else {
+        return s.parse();
+    };
+    let (path, gap) = match rest.rsplit_once(':') {
+        Some((path, gap)) if gap.parse::<usize>().is_ok() => (path, gap.parse().unwrap_or(4)),
+        _ => (rest, 4)
+    };
+    let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+    Ok(Layout::Tiles { pattern: io::cells_to_plaintext(&io::parse_pattern(&text)?), gap })
+}
+
+/// A part of the grid, as `x,y,width,height`.
+pub fn parse_window(s: &str) -> Result<BoundingBox, String> {
+    match s.split(',').map(|p| p.trim().parse::<usize>()).collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())?[..] {
+        [x, y, w, h] if w > 0 && h > 0 => Ok(BoundingBox { min_x: x, min_y: y, max_x: x + w - 1, max_y: y + h - 1 }),
+        _ => Err("expected x,y,width,height with a width and height above 0".to_string())
+    }
+}
+
+/// Feed and kill rates for the reaction backend, e.g. `0.055 0.062`.
+pub fn parse_reaction(s: &str) -> Result<(f64, f64), String> {
+    match s.split(|c: char| c.is_whitespace() || c == ',').filter(|p| !p.is_empty()).collect::<Vec<_>>()[..] {
+        [feed, kill] => Ok((parse_fraction(feed).map_err(|e| format!("feed {}", e))?, parse_fraction(kill).map_err(|e| format!("kill {}", e))?)),
+        _ => Err("expected a feed rate and a kill rate".to_string())
+    }
+}
+
+/// Growth and lightning chances for the forest backend, e.g. `0.01 0.00001`.
+pub fn parse_forest(s: &str) -> Result<(f64, f64), String> {
+    match s.split(|c: char| c.is_whitespace() || c == ',').filter(|p| !p.is_empty()).collect::<Vec<_>>()[..] {
+        [growth, lightning] => Ok((parse_fraction(growth).map_err(|e| format!("growth {}", e))?, parse_fraction(lightning).map_err(|e| format!("lightning {}", e))?)),
+        _ => Err("expected a growth chance and a lightning chance".to_string())
+    }
+}
+
+/// States and threshold for the cyclic backend, e.g. `14 1`.
+pub fn parse_cyclic(s: &str) -> Result<(u8, usize), String> {
+    match s.split(|c: char| c.is_whitespace() || c == ',').filter(|p| !p.is_empty()).collect::<Vec<_>>()[..] {
+        [states, threshold] => {
+            let states = states.parse::<u8>().ok().filter(|n| *n >= 2).ok_or_else(|| format!("'{}' is not a number of states from 2 to 255", states))?;
+            let threshold = threshold.parse::<usize>().ok().filter(|t| (1..=8).contains(t)).ok_or_else(|| format!("'{}' is not a threshold from 1 to 8", threshold))?;
+            Ok((states, threshold))
+        }
+        _ => Err("expected a number of states and a threshold".to_string())
+    }
+}
+
+/// Temperature and external field for the ising backend, e.g. `2.269 0`.
+pub fn parse_ising(s: &str) -> Result<(f64, f64), String> {
+    match s.split(|c: char| c.is_whitespace() || c == ',').filter(|p| !p.is_empty()).collect::<Vec<_>>()[..] {
+        [temperature, field] => {
+            let temperature = temperature.parse::<f64>().ok().filter(|t| *t >= 0.0).ok_or_else(|| format!("'{}' is not a temperature of 0 or more", temperature))?;
+            let field = field.parse::<f64>().ok().filter(|f| f.is_finite()).ok_or_else(|| format!("'{}' is not a field strength", field))?;
+            Ok((temperature, field))
+        }
+        _ => Err("expected a temperature and a field".to_string())
+    }
+}
@@ -0,0 +1,257 @@
+//! Shared sandbox: `--host <addr>` lets others `--join <addr>` the shown
+//! universe.  The host's simulation is the only one that steps; everyone
+//! paints cells and pastes patterns, and those edits go to the host, which
+//! applies them like its own.
+//!
+//! Both directions are newline delimited JSON over TCP.  Joined clients send
+//! [`Edit`]s (only `paint` and `paste` are accepted).  The host sends an
+//! [`Update::Full`] when a client connects or the grid changes size, rule or
+//! slot, and otherwise an [`Update::Diff`] of the cells that changed, at
+//! most [`UPDATES_PER_SECOND`] times a second.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::Ordering;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use rustlife::replay::Edit;
+use rustlife::script::SharedLife;
+use rustlife::{engine, io, CellState, LifeEngine, Rule};
+
+use crate::commands::Command;
+use crate::slots::ActiveSlot;
+
+pub const UPDATES_PER_SECOND: u32 = 20;
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Update {
+    Full { width: usize, height: usize, rule: Rule, generation: u64, rle: String },
+    Diff { generation: u64, born: Vec<(usize, usize)>, died: Vec<(usize, usize)> },
+}
+
+/// What clients were last sent, to diff the next update against.
+struct Sent {
+    universe: SharedLife,
+    rule: Rule,
+    width: usize,
+    cells: Vec<bool>,
+}
+
+fn snapshot(life: &dyn LifeEngine) -> Vec<bool> {
+    (0..life.height())
+        .flat_map(|y| (0..life.width()).map(move |x| (x, y)))
+        .map(|(x, y)| life.get(x, y) == CellState::Alive)
+        .collect()
+}
+
+fn full(life: &dyn LifeEngine, generation: u64) -> Update {
+    let live: Vec<_> = (0..life.height())
+        .flat_map(|y| (0..life.width()).map(move |x| (x, y)))
+        .filter(|&(x, y)| life.get(x, y) == CellState::Alive)
+        .collect();
+    // Absolute positions, so the RLE is placed at (0, 0)
+    let rle = io::cells_to_rle(&live);
+    Update::Full { width: life.width(), height: life.height(), rule: life.rule(), generation, rle }
+}
+
+fn send(stream: &mut TcpStream, update: &Update) -> std::io::Result<()> {
+    let mut line = serde_json::to_string(update).map_err(std::io::Error::other)?;
+    line.push('\n');
+    stream.write_all(line.as_bytes())
+}
+
+/// Listens on `addr` and returns the commands joined clients send, to be
+/// run by the event loop like any other.  Clients follow whichever slot is
+/// shown.
+pub fn host(addr: &str, active: ActiveSlot) -> Result<Receiver<Command>, String> {
+    let listener = TcpListener::bind(addr).map_err(|e| format!("{}: {}", addr, e))?;
+    log::info!("Hosting a shared universe on {}", addr);
+    let (tx, rx) = channel();
+    // Clients that still need a full update
+    let joining: Arc<Mutex<Vec<TcpStream>>> = Arc::default();
+
+    let c_joining = Arc::clone(&joining);
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let peer = stream.peer_addr().map(|a| a.to_string()).unwrap_or_default();
+            log::info!("{} joined", peer);
+            match stream.try_clone() {
+                Ok(reader) => {
+                    let tx = tx.clone();
+                    thread::spawn(move || receive_edits(reader, &peer, &tx));
+                }
+                Err(e) => {
+                    log::warn!("Client {}: {}", peer, e);
+                    continue;
+                }
+            }
+            if let Ok(mut joining) = c_joining.lock() {
+                joining.push(stream);
+            }
+        }
+    });
+
+    thread::spawn(move || broadcast(&active, &joining));
+    Ok(rx)
+}
+
+fn receive_edits(stream: TcpStream, peer: &str, commands: &Sender<Command>) {
+    for line in BufReader::new(stream).lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(_) => break
+        };
+        let command = match serde_json::from_str::<Edit>(&line) {
+            Ok(Edit::Paint { x, y, alive }) => Command::SetCell { x, y, alive },
+            Ok(Edit::Paste { x, y, pattern }) => Command::Paste { pattern, at: Some((x, y)) },
+            Ok(other) => {
+                log::warn!("Client {}: ignoring {:?}", peer, other);
+                continue;
+            }
+            Err(e) => {
+                log::warn!("Client {}: {}", peer, e);
+                continue;
+            }
+        };
+        if commands.send(command).is_err() {
+            break;
+        }
+    }
+    log::info!("{} left", peer);
+}
+
+fn broadcast(active: &ActiveSlot, joining: &Mutex<Vec<TcpStream>>) {
+    let mut clients: Vec<TcpStream> = Vec::new();
+    let mut sent: Option<Sent> = None;
+    loop {
+        thread::sleep(Duration::from_secs(1) / UPDATES_PER_SECOND);
+        let (universe, generation) = match active.read() {
+            Ok(active) => (Arc::clone(&active.0), active.1.load(Ordering::Relaxed)),
+            Err(_) => return
+        };
+        let life = match universe.read() {
+            Ok(life) => life,
+            Err(_) => return
+        };
+        let mut newcomers = joining.lock().map(|mut j| std::mem::take(&mut *j)).unwrap_or_default();
+        if clients.is_empty() && newcomers.is_empty() {
+            sent = None;
+            continue;
+        }
+
+        let cells = snapshot(life.as_ref());
+        let rule = life.rule();
+        let changed_shape = sent.as_ref().is_none_or(|s| {
+            !Arc::ptr_eq(&s.universe, &universe) || s.rule != rule || s.width != life.width() || s.cells.len() != cells.len()
+        });
+        let update = if changed_shape {
+            Some(full(life.as_ref(), generation))
+        } else {
+            let old = &sent.as_ref().expect("checked above").cells;
+            let (mut born, mut died) = (Vec::new(), Vec::new());
+            for (i, (was, is)) in old.iter().zip(&cells).enumerate() {
+                if was != is {
+                    let cell = (i % life.width(), i / life.width());
+                    if *is { born.push(cell) } else { died.push(cell) }
+                }
+            }
+            (!born.is_empty() || !died.is_empty()).then_some(Update::Diff { generation, born, died })
+        };
+
+        let first = (!newcomers.is_empty()).then(|| full(life.as_ref(), generation));
+        let width = life.width();
+        // Slow clients must not hold up the simulation
+        drop(life);
+
+        if let Some(first) = &first {
+            newcomers.retain_mut(|c| send(c, first).is_ok());
+        }
+        if let Some(update) = &update {
+            clients.retain_mut(|c| send(c, update).is_ok());
+        }
+        clients.append(&mut newcomers);
+        sent = Some(Sent { universe: Arc::clone(&universe), rule, width, cells });
+    }
+}
+
+/// Connection to a host.  The local universe only mirrors the host's.
+pub struct Client {
+    stream: TcpStream,
+    // Taken by `follow`; may already hold updates sent after the first
+    reader: Option<BufReader<TcpStream>>,
+}
+
+/// Connects to the host at `addr` and returns its universe as it is now.
+/// Call [`Client::follow`] to keep it in sync.
+pub fn join(addr: &str) -> Result<(Client, Box<dyn LifeEngine>), String> {
+    let stream = TcpStream::connect(addr).map_err(|e| format!("{}: {}", addr, e))?;
+    let mut reader = BufReader::new(stream.try_clone().map_err(|e| e.to_string())?);
+    let mut line = String::new();
+    reader.read_line(&mut line).map_err(|e| format!("{}: {}", addr, e))?;
+    let life = match serde_json::from_str(&line).map_err(|e| format!("{}: {}", addr, e))? {
+        Update::Full { width, height, rule, rle, .. } => {
+            let mut life = engine::create("dense", width, height).expect("dense backend exists");
+            life.set_rule(rule);
+            io::place(life.as_mut(), &io::parse_rle(&rle)?, 0, 0);
+            life
+        }
+        Update::Diff { .. } => return Err(format!("{}: host did not start with the full grid", addr))
+    };
+    Ok((Client { stream, reader: Some(reader) }, life))
+}
+
+impl Client {
+    /// Applies the host's updates to `life` as they arrive.
+    pub fn follow(&mut self, life: SharedLife) -> Result<(), String> {
+        let reader = self.reader.take().ok_or("already following the host")?;
+        thread::spawn(move || {
+            for line in reader.lines() {
+                let update = match line.map_err(|e| e.to_string()).and_then(|l| serde_json::from_str(&l).map_err(|e| e.to_string())) {
+                    Ok(update) => update,
+                    Err(e) => {
+                        log::error!("Lost the host: {}", e);
+                        return;
+                    }
+                };
+                if let Ok(mut l) = life.write() {
+                    if let Err(e) = apply(l.as_mut(), update) {
+                        log::error!("Bad update from the host: {}", e);
+                    }
+                }
+            }
+            log::error!("The host closed the connection");
+        });
+        Ok(())
+    }
+
+    pub fn send(&mut self, edit: &Edit) -> Result<(), String> {
+        let mut line = serde_json::to_string(edit).map_err(|e| e.to_string())?;
+        line.push('\n');
+        self.stream.write_all(line.as_bytes()).map_err(|e| e.to_string())
+    }
+}
+
+fn apply(life: &mut dyn LifeEngine, update: Update) -> Result<(), String> {
+    match update {
+        Update::Full { width, height, rule, rle, .. } => {
+            life.resize(width, height, engine::Anchor::TopLeft);
+            life.set_rule(rule);
+            life.clear();
+            io::place(life, &io::parse_rle(&rle)?, 0, 0);
+        }
+        Update::Diff { born, died, .. } => {
+            let (w, h) = (life.width(), life.height());
+            let cells = born.into_iter().map(|c| (c, CellState::Alive)).chain(died.into_iter().map(|c| (c, CellState::Dead)));
+            for ((x, y), state) in cells.filter(|((x, y), _)| *x < w && *y < h) {
+                life.set(x, y, state);
+            }
+        }
+    }
+    Ok(())
+}
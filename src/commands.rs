@@ -0,0 +1,390 @@
+//! Everything the user can ask the desktop frontend to do.  Hotkeys and the
+//! command palette both produce `Command`s, which are executed in one place.
+
+use rustlife::engine::BoundingBox;
+use rustlife::gas::GasModel;
+use rustlife::sand::Element;
+use rustlife::soup::{Layout, Symmetry};
+use rustlife::species::Species;
+use rustlife::stats::Region;
+use rustlife::svg::{Shape, Style};
+use rustlife::{apgcode, io};
+
+use crate::cli;
+use crate::slots::SLOT_COUNT;
+
+#[derive(Clone, Debug, PartialEq)]
+// Some commands only come from the remote or local control channels
+#[cfg_attr(not(any(feature = "remote", unix)), allow(dead_code))]
+pub enum Command {
+    TogglePause,
+    SetPaused(bool),
+    /// Pauses, then steps this many generations
+    Step(u64),
+    Randomize,
+    Grow { keep_corner: bool },
+    Shrink { keep_corner: bool },
+    ToggleSpacetime,
+    TogglePopulationGraph,
+    /// Watches the whole grid for repeats, or stops watching
+    TogglePeriodDetection,
+    /// Watches a region, or the whole grid, for repeats
+    DetectPeriod(Option<BoundingBox>),
+    /// Starts or stops marking spaceships with their velocity
+    ToggleShips,
+    /// Shows or hides the activity metrics
+    ToggleActivity,
+    /// Starts or stops classifying how the population grows
+    ToggleGrowth,
+    /// Switches between the grid and the heatmap
+    ToggleHeatmap,
+    /// Switches between the grid and the isometric block view
+    ToggleIsometric,
+    /// Opens or closes the pattern browser
+    ToggleBrowser,
+    /// Looks a pattern up by name or apgcode, online if need be, and picks
+    /// it up to place
+    FetchPattern(String),
+    /// Picks up the next of the recently used patterns to place
+    NextRecent,
+    /// Saves the view as bookmark `number`, keeping its old name if `name`
+    /// is empty
+    SaveBookmark { number: usize, name: String },
+    /// Jumps to the bookmark with this number or name
+    GoToBookmark(String),
+    /// Shows or hides the timeline bar
+    ToggleTimeline,
+    /// Pauses and goes back, or on, to this generation from the timeline's
+    /// keyframes
+    Seek(u64),
+    /// Steps on to this generation as fast as it can, drawing only how far
+    /// it has got, then pauses
+    FastForward(u64),
+    /// Starts or stops showing what the cell under the mouse is doing
+    /// while paused
+    ToggleInspector,
+    /// Starts or stops showing which cells will be born and die next
+    /// generation while paused
+    TogglePreview,
+    /// Starts or stops explain mode, which pauses and says why clicked
+    /// cells live or die
+    ToggleExplain,
+    /// Explains a cell in explain mode
+    Explain { x: usize, y: usize },
+    /// Goes through the cells the next generation changes, then steps it
+    ExplainStep,
+    /// Puts the generation, population and whether it is paused in the
+    /// window title, and reads them out with --speak
+    Announce,
+    /// Starts the tutorial over, or closes it
+    ToggleTutorial,
+    /// Opens or closes the Life Lexicon panel
+    ToggleLexicon,
+    /// Shows or hides how long stepping and drawing take
+    ToggleProfiler,
+    /// Takes out the ruler, or puts it away
+    ToggleRuler,
+    /// Puts an end of the ruler on a cell
+    Measure { x: usize, y: usize },
+    /// Starts showing what changed since now, or stops
+    ToggleDiff,
+    /// Shows what changed since a generation, or what will have changed by
+    /// it, counted from the shown one if `relative`
+    CompareWith { generation: i64, relative: bool },
+    /// Zooms to the live cells
+    FitView,
+    /// Starts or stops keeping the live cells in view as they spread
+    ToggleFollow,
+    ShowWholeGrid,
+    SwitchSlot(usize),
+    LoadPattern(String),
+    /// Writes the universe to a compressed snapshot file
+    SaveSnapshot(String),
+    /// Replaces the universe, its size, rule and generation with a
+    /// snapshot's
+    LoadSnapshot(String),
+    /// Replaces the universe with the built in pattern of this name, centred
+    ShowBuiltin(String),
+    SetCell { x: usize, y: usize, alive: bool },
+    /// Holds a cell alive or dead for good, or frees it with `None`
+    SetWall { x: usize, y: usize, alive: Option<bool> },
+    /// Switches the mouse between painting cells and painting walls
+    ToggleWallBrush,
+    /// Makes a cell a live one of the given species
+    SetSpecies { x: usize, y: usize, species: Species },
+    /// Switches the mouse between painting cells and painting predators
+    TogglePredatorBrush,
+    /// Puts an element on a backend made of them
+    SetElement { x: usize, y: usize, element: Element },
+    /// Paints an element with the mouse, or cells again with `None`
+    SetElementBrush(Option<Element>),
+    /// Stamps a plaintext pattern at (x, y), or centred
+    Paste { pattern: String, at: Option<(usize, usize)> },
+    SetRule(String),
+    /// Prints the apgcode, and name if it has a common one, of each object
+    /// in this box, or the whole grid
+    Identify(Option<BoundingBox>),
+    /// Prints how many of each kind of object the universe holds
+    Census,
+    /// Highlights every standalone copy of a plaintext pattern, or clears
+    /// the highlights
+    FindPattern(Option<String>),
+    /// Looks for cells that step to the pattern in this box, and prints
+    /// them
+    FindPredecessor(BoundingBox),
+    SetSpeed(u32),
+    /// Chance of each cell flipping every generation
+    SetNoise(f64),
+    /// Symmetry of the soups Randomize makes
+    SetSymmetry(Symmetry),
+    /// Feed and kill rates for the reaction backend
+    SetReaction { feed: f64, kill: f64 },
+    /// Growth and lightning chances for the forest backend
+    SetForest { growth: f64, lightning: f64 },
+    /// States and threshold for the cyclic backend
+    SetCyclic { states: u8, threshold: usize },
+    /// Temperature and external field for the ising backend
+    SetIsing { temperature: f64, field: f64 },
+    /// Lattice gas model for the gas backend
+    SetGasModel(GasModel),
+    /// Wolfram code for the elementary backend
+    SetElementary(u8),
+    /// Restarts the elementary backend from a rule 110 tape of 0s and 1s
+    LoadTape(String),
+    /// How the soups Randomize makes are laid out
+    SetLayout(Layout),
+    /// Writes the live cells in the format the file's extension names,
+    /// plaintext if none, turned to their canonical orientation if asked
+    Export { path: String, canonical: bool },
+    /// Draws the pattern as SVG in the theme's colours
+    ExportSvg { path: String, cell_size: f64, shape: Shape },
+    /// Writes the recent population history as CSV
+    ExportHistory(String),
+    /// Tracks a named region's statistics, replacing one of the same name
+    AddRegion(Region),
+    RemoveRegion(String),
+    /// Writes every region's recent statistics as CSV
+    ExportRegionStats(String),
+    /// Writes what the space-time view recorded as a .vox model or .obj
+    /// point cloud
+    ExportVolume(String),
+    SaveConfig,
+    Quit,
+}
+
+impl Command {
+    /// Whether it only changes this window, the view or files, never the
+    /// universe, so a client joined to a host does it itself.
+    pub fn is_local(&self) -> bool {
+        matches!(self,
+            Command::Quit | Command::SaveConfig | Command::ToggleSpacetime | Command::Export { .. } | Command::ExportSvg { .. } | Command::Identify(_) | Command::Census | Command::FindPattern(_) | Command::FindPredecessor(_)
+            | Command::TogglePopulationGraph | Command::TogglePeriodDetection | Command::DetectPeriod(_) | Command::ToggleShips | Command::ToggleActivity | Command::ToggleGrowth | Command::ToggleHeatmap | Command::ToggleIsometric | Command::ToggleBrowser | Command::FetchPattern(_) | Command::NextRecent
+            | Command::SaveBookmark { .. } | Command::GoToBookmark(_) | Command::ToggleTimeline | Command::ToggleDiff | Command::CompareWith { .. } | Command::ToggleRuler | Command::Measure { .. } | Command::ToggleInspector | Command::TogglePreview
+            | Command::ToggleExplain | Command::Explain { .. } | Command::ToggleTutorial | Command::ToggleLexicon | Command::ToggleProfiler | Command::Announce
+            | Command::FitView | Command::ToggleFollow | Command::ShowWholeGrid | Command::ToggleWallBrush | Command::TogglePredatorBrush | Command::SetElementBrush(_)
+            | Command::AddRegion(_) | Command::RemoveRegion(_) | Command::ExportRegionStats(_) | Command::ExportVolume(_) | Command::SaveSnapshot(_))
+    }
+}
+
+/// How a palette entry turns into a command: directly, or after asking for
+/// one line of input.
+pub enum Action {
+    Run(fn() -> Command),
+    Prompt(&'static str, fn(&str) -> Result<Command, String>),
+}
+
+pub struct PaletteEntry {
+    pub label: &'static str,
+    pub action: Action,
+}
+
+fn parse_speed(s: &str) -> Result<Command, String> {
+    s.trim().parse().map(Command::SetSpeed).map_err(|_| format!("'{}' is not a number of generations per second", s))
+}
+
+fn parse_wolfram(s: &str) -> Result<Command, String> {
+    s.trim().parse().map(Command::SetElementary).map_err(|_| format!("'{}' is not a rule from 0 to 255", s.trim()))
+}
+
+fn parse_noise(s: &str) -> Result<Command, String> {
+    cli::parse_fraction(s).map(Command::SetNoise).map_err(|e| format!("noise {}", e))
+}
+
+fn parse_element(s: &str) -> Result<Command, String> {
+    match s.trim() {
+        "" => Ok(Command::SetElementBrush(None)),
+        s => s.parse().map(|e| Command::SetElementBrush(Some(e)))
+    }
+}
+
+fn parse_slot(s: &str) -> Result<Command, String> {
+    match s.trim().parse::<usize>() {
+        Ok(n) if (1..=SLOT_COUNT).contains(&n) => Ok(Command::SwitchSlot(n - 1)),
+        _ => Err(format!("slot must be 1 to {}", SLOT_COUNT))
+    }
+}
+
+fn parse_generation(s: &str) -> Result<Command, String> {
+    s.trim().parse().map(Command::Seek).map_err(|_| format!("'{}' is not a generation", s.trim()))
+}
+
+fn parse_fast_forward(s: &str) -> Result<Command, String> {
+    s.trim().parse().map(Command::FastForward).map_err(|_| format!("'{}' is not a generation", s.trim()))
+}
+
+fn parse_compare(s: &str) -> Result<Command, String> {
+    let s = s.trim();
+    let relative = s.starts_with(['+', '-']);
+    s.parse().map(|generation| Command::CompareWith { generation, relative })
+        .map_err(|_| format!("'{}' is not a generation or a number of generations before or after", s))
+}
+
+/// `file [canonical]`, the file's name possibly with spaces in it.
+fn parse_export(s: &str) -> Result<Command, String> {
+    let s = s.trim();
+    let (path, canonical) = match s.strip_suffix("canonical").filter(|rest| rest.ends_with(char::is_whitespace)) {
+        Some(rest) => (rest.trim_end(), true),
+        None => (s, false)
+    };
+    if path.is_empty() {
+        return Err("expected a file to save to".to_string());
+    }
+    Ok(Command::Export { path: path.to_string(), canonical })
+}
+
+/// `file [cell size] [square|circle]`, the last two either way round.
+fn parse_svg(s: &str) -> Result<Command, String> {
+    let mut words = s.split_whitespace();
+    let path = words.next().ok_or("expected a file to save to")?.to_string();
+    let (mut cell_size, mut shape) = (Style::default().cell_size, Shape::Square);
+    for word in words {
+        match word.parse::<f64>() {
+            Ok(size) if size > 0.0 => cell_size = size,
+            Ok(_) => return Err("cell size must be more than 0".to_string()),
+            Err(_) => shape = word.parse()?
+        }
+    }
+    Ok(Command::ExportSvg { path, cell_size, shape })
+}
+
+fn parse_apgcode(s: &str) -> Result<Command, String> {
+    let live = apgcode::decode(s)?;
+    Ok(Command::Paste { pattern: io::cells_to_plaintext(&live), at: None })
+}
+
+/// An object's name, an apgcode or a pattern file, or nothing to clear.
+fn parse_find(s: &str) -> Result<Command, String> {
+    let s = s.trim();
+    if s.is_empty() {
+        return Ok(Command::FindPattern(None));
+    }
+    let live = match apgcode::by_name(s) {
+        Some(code) => apgcode::decode(code)?,
+        None if s.starts_with('x') && !s.contains('.') => apgcode::decode(s)?,
+        None => io::parse_pattern(&std::fs::read_to_string(s).map_err(|e| format!("{}: {}", s, e))?)?
+    };
+    Ok(Command::FindPattern(Some(io::cells_to_plaintext(&live))))
+}
+
+/// `name x y width height`.
+fn parse_named_region(s: &str) -> Result<Command, String> {
+    let (name, rest) = s.trim().split_once(char::is_whitespace).ok_or("expected name x y width height")?;
+    match parse_region(rest)? {
+        Some(bb) => Ok(Command::AddRegion(Region { name: name.to_string(), x: bb.min_x, y: bb.min_y, width: bb.width(), height: bb.height() })),
+        None => Err("expected name x y width height".to_string())
+    }
+}
+
+/// `number [name]`, with the number from 1 to 9.
+fn parse_bookmark(s: &str) -> Result<Command, String> {
+    let (number, name) = s.trim().split_once(char::is_whitespace).unwrap_or((s.trim(), ""));
+    match number.parse() {
+        Ok(number @ 1..=9) => Ok(Command::SaveBookmark { number, name: name.trim().to_string() }),
+        _ => Err("expected a number from 1 to 9, then a name".to_string())
+    }
+}
+
+/// `x y width height`, or nothing for the whole grid.
+fn parse_region(s: &str) -> Result<Option<BoundingBox>, String> {
+    let numbers: Vec<usize> = s.split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|p| !p.is_empty())
+        .map(|p| p.parse().map_err(|_| format!("'{}' is not a number", p)))
+        .collect::<Result<_, _>>()?;
+    match numbers[..] {
+        [] => Ok(None),
+        [x, y, w, h] if w > 0 && h > 0 => Ok(Some(BoundingBox { min_x: x, min_y: y, max_x: x + w - 1, max_y: y + h - 1 })),
+        _ => Err("expected x y width height".to_string())
+    }
+}
+
+pub const PALETTE: &[PaletteEntry] = &[
+    PaletteEntry { label: "Pause / resume", action: Action::Run(|| Command::TogglePause) },
+    PaletteEntry { label: "Step one generation", action: Action::Run(|| Command::Step(1)) },
+    PaletteEntry { label: "Randomize", action: Action::Run(|| Command::Randomize) },
+    PaletteEntry { label: "Soup symmetry...", action: Action::Prompt("C1, C2, C4, D2, D4 or D8", |s| s.parse().map(Command::SetSymmetry)) },
+    PaletteEntry { label: "Soup layout...", action: Action::Prompt("uniform, gradient, stripes, rings, blobs or tiles:<file>", |s| cli::parse_layout(s).map(Command::SetLayout)) },
+    PaletteEntry { label: "Browse patterns", action: Action::Run(|| Command::ToggleBrowser) },
+    PaletteEntry { label: "Recent patterns", action: Action::Run(|| Command::NextRecent) },
+    PaletteEntry { label: "Fetch pattern...", action: Action::Prompt("Name or apgcode, e.g. Gosper glider gun", |s| Ok(Command::FetchPattern(s.trim().to_string()))) },
+    PaletteEntry { label: "Show built in pattern...", action: Action::Prompt("Pattern name, e.g. acorn", |s| Ok(Command::ShowBuiltin(s.trim().to_string()))) },
+    PaletteEntry { label: "Load pattern...", action: Action::Prompt("Pattern file", |s| Ok(Command::LoadPattern(s.trim().to_string()))) },
+    PaletteEntry { label: "Save snapshot...", action: Action::Prompt("Save to file, e.g. universe.snap", |s| Ok(Command::SaveSnapshot(s.trim().to_string()))) },
+    PaletteEntry { label: "Load snapshot...", action: Action::Prompt("Snapshot file", |s| Ok(Command::LoadSnapshot(s.trim().to_string()))) },
+    PaletteEntry { label: "Paint walls", action: Action::Run(|| Command::ToggleWallBrush) },
+    PaletteEntry { label: "Paint predators", action: Action::Run(|| Command::TogglePredatorBrush) },
+    PaletteEntry { label: "Paint element...", action: Action::Prompt("sand, water, wall or fire (empty for cells)", parse_element) },
+    PaletteEntry { label: "Paste apgcode...", action: Action::Prompt("apgcode (e.g. xq4_153)", parse_apgcode) },
+    PaletteEntry { label: "Identify pattern...", action: Action::Prompt("Region x y width height (empty for whole grid)", |s| parse_region(s).map(Command::Identify)) },
+    PaletteEntry { label: "Object census", action: Action::Run(|| Command::Census) },
+    PaletteEntry { label: "Find pattern...", action: Action::Prompt("Name, apgcode or pattern file (empty to clear)", parse_find) },
+    PaletteEntry { label: "Find predecessor...", action: Action::Prompt("Region x y width height, at most 8 by 8", |s| parse_region(s)?.map(Command::FindPredecessor).ok_or_else(|| "expected x y width height".to_string())) },
+    PaletteEntry { label: "Change rule...", action: Action::Prompt("Rule (e.g. B36/S23)", |s| Ok(Command::SetRule(s.trim().to_string()))) },
+    PaletteEntry { label: "Set speed...", action: Action::Prompt("Generations per second (0 = unlimited)", parse_speed) },
+    PaletteEntry { label: "Set noise...", action: Action::Prompt("Chance of each cell flipping per generation (0 = none)", parse_noise) },
+    PaletteEntry { label: "Set feed and kill rates...", action: Action::Prompt("Feed and kill, e.g. 0.055 0.062 (reaction backend)", |s| cli::parse_reaction(s).map(|(feed, kill)| Command::SetReaction { feed, kill })) },
+    PaletteEntry { label: "Set growth and lightning...", action: Action::Prompt("Growth and lightning, e.g. 0.01 0.00001 (forest backend)", |s| cli::parse_forest(s).map(|(growth, lightning)| Command::SetForest { growth, lightning })) },
+    PaletteEntry { label: "Set cyclic states and threshold...", action: Action::Prompt("States and threshold, e.g. 14 1 (cyclic backend)", |s| cli::parse_cyclic(s).map(|(states, threshold)| Command::SetCyclic { states, threshold })) },
+    PaletteEntry { label: "Set temperature and field...", action: Action::Prompt("Temperature and field, e.g. 2.269 0 (ising backend)", |s| cli::parse_ising(s).map(|(temperature, field)| Command::SetIsing { temperature, field })) },
+    PaletteEntry { label: "Set lattice gas model...", action: Action::Prompt("hpp or fhp (gas backend)", |s| s.parse().map(Command::SetGasModel)) },
+    PaletteEntry { label: "Set Wolfram rule...", action: Action::Prompt("Rule 0 to 255, e.g. 30 (elementary backend)", parse_wolfram) },
+    PaletteEntry { label: "Rule 110 tape...", action: Action::Prompt("Bits, e.g. 1101 (elementary backend)", |s| Ok(Command::LoadTape(s.trim().to_string()))) },
+    PaletteEntry { label: "Export pattern...", action: Action::Prompt("Save to file, then canonical to turn it to its canonical orientation", parse_export) },
+    PaletteEntry { label: "Export SVG...", action: Action::Prompt("Save to file, then cell size and square or circle", parse_svg) },
+    PaletteEntry { label: "Export population history...", action: Action::Prompt("Save CSV to file", |s| Ok(Command::ExportHistory(s.trim().to_string()))) },
+    PaletteEntry { label: "Add region...", action: Action::Prompt("Name x y width height", parse_named_region) },
+    PaletteEntry { label: "Remove region...", action: Action::Prompt("Region name", |s| Ok(Command::RemoveRegion(s.trim().to_string()))) },
+    PaletteEntry { label: "Export region statistics...", action: Action::Prompt("Save CSV to file", |s| Ok(Command::ExportRegionStats(s.trim().to_string()))) },
+    PaletteEntry { label: "Export space-time volume...", action: Action::Prompt("Save .vox or .obj to file", |s| Ok(Command::ExportVolume(s.trim().to_string()))) },
+    PaletteEntry { label: "Toggle population graph", action: Action::Run(|| Command::TogglePopulationGraph) },
+    PaletteEntry { label: "Detect period...", action: Action::Prompt("Region x y width height (empty for whole grid)", |s| parse_region(s).map(Command::DetectPeriod)) },
+    PaletteEntry { label: "Track spaceships", action: Action::Run(|| Command::ToggleShips) },
+    PaletteEntry { label: "Show activity metrics", action: Action::Run(|| Command::ToggleActivity) },
+    PaletteEntry { label: "Toggle growth detection", action: Action::Run(|| Command::ToggleGrowth) },
+    PaletteEntry { label: "Toggle heatmap", action: Action::Run(|| Command::ToggleHeatmap) },
+    PaletteEntry { label: "Toggle isometric view", action: Action::Run(|| Command::ToggleIsometric) },
+    PaletteEntry { label: "Fit view to pattern", action: Action::Run(|| Command::FitView) },
+    PaletteEntry { label: "Follow pattern", action: Action::Run(|| Command::ToggleFollow) },
+    PaletteEntry { label: "Show whole grid", action: Action::Run(|| Command::ShowWholeGrid) },
+    PaletteEntry { label: "Bookmark view...", action: Action::Prompt("Number 1 to 9 and a name, e.g. 2 eater farm", parse_bookmark) },
+    PaletteEntry { label: "Go to bookmark...", action: Action::Prompt("Bookmark number or name", |s| Ok(Command::GoToBookmark(s.trim().to_string()))) },
+    PaletteEntry { label: "Toggle timeline", action: Action::Run(|| Command::ToggleTimeline) },
+    PaletteEntry { label: "Go to generation...", action: Action::Prompt("Generation, e.g. 250", parse_generation) },
+    PaletteEntry { label: "Fast forward to generation...", action: Action::Prompt("Generation, e.g. 100000", parse_fast_forward) },
+    PaletteEntry { label: "Toggle cell inspector", action: Action::Run(|| Command::ToggleInspector) },
+    PaletteEntry { label: "Toggle next generation preview", action: Action::Run(|| Command::TogglePreview) },
+    PaletteEntry { label: "Explain mode", action: Action::Run(|| Command::ToggleExplain) },
+    PaletteEntry { label: "Explain the next generation", action: Action::Run(|| Command::ExplainStep) },
+    PaletteEntry { label: "Ruler", action: Action::Run(|| Command::ToggleRuler) },
+    PaletteEntry { label: "Compare with now", action: Action::Run(|| Command::ToggleDiff) },
+    PaletteEntry { label: "Compare with generation...", action: Action::Prompt("Generation, or +/- generations from this one, e.g. -2", parse_compare) },
+    PaletteEntry { label: "Toggle space-time view", action: Action::Run(|| Command::ToggleSpacetime) },
+    PaletteEntry { label: "Grow universe", action: Action::Run(|| Command::Grow { keep_corner: false }) },
+    PaletteEntry { label: "Shrink universe", action: Action::Run(|| Command::Shrink { keep_corner: false }) },
+    PaletteEntry { label: "Switch slot...", action: Action::Prompt("Slot number", parse_slot) },
+    PaletteEntry { label: "Save settings", action: Action::Run(|| Command::SaveConfig) },
+    PaletteEntry { label: "Read out status", action: Action::Run(|| Command::Announce) },
+    PaletteEntry { label: "Tutorial", action: Action::Run(|| Command::ToggleTutorial) },
+    PaletteEntry { label: "Life Lexicon", action: Action::Run(|| Command::ToggleLexicon) },
+    PaletteEntry { label: "Toggle profiler", action: Action::Run(|| Command::ToggleProfiler) },
+    PaletteEntry { label: "Quit", action: Action::Run(|| Command::Quit) },
+];
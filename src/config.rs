@@ -0,0 +1,462 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "gui")]
+use winit::event::VirtualKeyCode;
+
+use rustlife::gas::GasModel;
+use rustlife::memory::Estimate;
+use rustlife::replay::Start;
+use rustlife::{cyclic, elementary, forest, ising, reaction};
+use rustlife::soup::{Layout, Symmetry};
+use rustlife::stats::Region;
+use rustlife::stop::StopConditions;
+use rustlife::timeline;
+use rustlife::{Boundary, Rule, SimulationBuilder};
+
+use crate::cli::Args;
+use crate::render::{Theme, HEAT};
+use crate::usage;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct Config {
+    pub grid_width: usize,
+    pub grid_height: usize,
+    pub window_width: u32,
+    pub window_height: u32,
+    pub rule: String,
+    pub density: f64,
+    /// Symmetry of random soups
+    pub symmetry: Symmetry,
+    /// Side of random soups, 0 to fill the grid
+    pub soup_size: usize,
+    /// Fixed seed for the initial soup, a fresh one each run if unset
+    pub seed: Option<u64>,
+    pub backend: String,
+    pub speed: u32,
+    /// Most frames a second to draw, whatever the speed, 0 for as many as
+    /// the display shows
+    #[cfg(feature = "gui")]
+    pub max_fps: u32,
+    /// Use the power saving profile, or if unset only on battery
+    #[cfg(feature = "gui")]
+    pub power_save: Option<bool>,
+    pub threads: usize,
+    /// Pin the stepping threads to cores, each stepping its own strip
+    pub pin_threads: bool,
+    /// Most memory a universe may take, in MiB, 0 for as much as the
+    /// machine has
+    pub max_memory: u64,
+    /// Chance of each cell flipping every generation
+    pub noise: f64,
+    /// Feed and kill rates for the reaction backend
+    pub feed: f64,
+    pub kill: f64,
+    /// Growth and lightning chances for the forest backend
+    pub growth: f64,
+    pub lightning: f64,
+    /// States and threshold for the cyclic backend
+    pub cyclic_states: u8,
+    pub cyclic_threshold: usize,
+    /// Temperature and external field for the ising backend
+    pub temperature: f64,
+    pub field: f64,
+    /// Lattice gas model for the gas backend
+    pub gas: GasModel,
+    /// Wolfram code for the elementary backend
+    pub elementary: u8,
+    /// What lies past the edges of the grid
+    pub boundary: Boundary,
+    pub theme: String,
+    /// How random soups are laid out
+    pub layout: Layout,
+    /// When to pause by itself
+    pub stop: StopConditions,
+    /// Report when the whole universe enters a cycle, without pausing
+    pub detect_cycles: bool,
+    /// Hash the grid for cycles only every this many generations
+    pub cycle_every: u64,
+    #[cfg(feature = "gui")]
+    pub keys: KeyBindings,
+    /// Named regions with their own statistics
+    pub regions: Vec<Region>,
+    /// Your own pattern files for the browser, `patterns` next to the
+    /// config file if unset
+    pub patterns_dir: Option<PathBuf>,
+    /// The Life Lexicon's text version to search instead of the built in
+    /// glossary, `lexicon.txt` next to the config file if unset
+    pub lexicon: Option<PathBuf>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            grid_width: 2048,
+            grid_height: 2048,
+            window_width: 512,
+            window_height: 512,
+            rule: Rule::conway().to_string(),
+            density: 0.5,
+            symmetry: Symmetry::C1,
+            soup_size: 0,
+            seed: None,
+            backend: "dense".to_string(),
+            speed: 0,
+            #[cfg(feature = "gui")]
+            max_fps: 0,
+            #[cfg(feature = "gui")]
+            power_save: None,
+            threads: 0,
+            pin_threads: false,
+            max_memory: 0,
+            noise: 0.0,
+            feed: reaction::DEFAULT_FEED,
+            kill: reaction::DEFAULT_KILL,
+            growth: forest::DEFAULT_GROWTH,
+            lightning: forest::DEFAULT_LIGHTNING,
+            cyclic_states: cyclic::DEFAULT_STATES,
+            cyclic_threshold: cyclic::DEFAULT_THRESHOLD,
+            temperature: ising::DEFAULT_TEMPERATURE,
+            field: ising::DEFAULT_FIELD,
+            gas: GasModel::Hpp,
+            elementary: elementary::DEFAULT_RULE,
+            boundary: Boundary::Dead,
+            theme: "classic".to_string(),
+            layout: Layout::Uniform,
+            stop: StopConditions::default(),
+            detect_cycles: false,
+            cycle_every: 1,
+            #[cfg(feature = "gui")]
+            keys: KeyBindings::default(),
+            regions: Vec::new(),
+            patterns_dir: None,
+            lexicon: None,
+        }
+    }
+}
+
+#[cfg(feature = "gui")]
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct KeyBindings {
+    pub pause: VirtualKeyCode,
+    pub step: VirtualKeyCode,
+    pub randomize: VirtualKeyCode,
+    pub spacetime: VirtualKeyCode,
+    pub graph: VirtualKeyCode,
+    /// Watch the whole grid for repeats
+    pub period: VirtualKeyCode,
+    /// Find spaceships and show where they are heading
+    pub ships: VirtualKeyCode,
+    /// Show changed cells, entropy and active area
+    pub activity: VirtualKeyCode,
+    /// Classify the population's growth as bounded, linear or quadratic
+    pub growth: VirtualKeyCode,
+    /// Long-exposure view of how often each cell was alive
+    pub heatmap: VirtualKeyCode,
+    /// Draw live cells as blocks, taller the longer they have lived
+    pub isometric: VirtualKeyCode,
+    /// Pick patterns to place from thumbnails
+    pub browser: VirtualKeyCode,
+    /// Pick up the recently used patterns in turn
+    pub recent: VirtualKeyCode,
+    /// Show the timeline bar, for going back to earlier generations
+    pub timeline: VirtualKeyCode,
+    /// Show births and deaths since now
+    pub diff: VirtualKeyCode,
+    /// Measure distances and speeds between two cells
+    pub ruler: VirtualKeyCode,
+    /// Show what the cell under the mouse is doing while paused
+    pub inspector: VirtualKeyCode,
+    /// Show the cells the next generation brings to life and kills while
+    /// paused
+    pub preview: VirtualKeyCode,
+    /// Pause and explain why clicked cells live or die
+    pub explain: VirtualKeyCode,
+    /// Zoom to the live cells
+    pub fit: VirtualKeyCode,
+    /// Keep zooming to the live cells as they spread
+    pub follow: VirtualKeyCode,
+    pub whole_grid: VirtualKeyCode,
+    /// Paint walls instead of cells with the mouse
+    pub walls: VirtualKeyCode,
+    pub save_config: VirtualKeyCode,
+    /// Start the tutorial over, or close it
+    pub tutorial: VirtualKeyCode,
+    /// Look up terms in the Life Lexicon
+    pub lexicon: VirtualKeyCode,
+    /// Show how long each phase of stepping and drawing takes
+    pub profiler: VirtualKeyCode,
+    /// Read out the generation, population and whether it is paused
+    pub announce: VirtualKeyCode,
+    /// Double the universe size; hold shift to keep the top left corner fixed
+    pub grow: VirtualKeyCode,
+    /// Halve the universe size
+    pub shrink: VirtualKeyCode,
+    /// Nudge the Ising temperature up or down; hold shift for the field
+    pub warmer: VirtualKeyCode,
+    pub cooler: VirtualKeyCode,
+}
+
+#[cfg(feature = "gui")]
+impl Default for KeyBindings {
+    fn default() -> Self {
+        KeyBindings {
+            pause: VirtualKeyCode::P,
+            step: VirtualKeyCode::Space,
+            randomize: VirtualKeyCode::R,
+            spacetime: VirtualKeyCode::V,
+            graph: VirtualKeyCode::G,
+            period: VirtualKeyCode::O,
+            ships: VirtualKeyCode::S,
+            activity: VirtualKeyCode::A,
+            growth: VirtualKeyCode::U,
+            heatmap: VirtualKeyCode::H,
+            isometric: VirtualKeyCode::I,
+            browser: VirtualKeyCode::B,
+            recent: VirtualKeyCode::Tab,
+            timeline: VirtualKeyCode::L,
+            diff: VirtualKeyCode::D,
+            ruler: VirtualKeyCode::M,
+            inspector: VirtualKeyCode::C,
+            preview: VirtualKeyCode::N,
+            explain: VirtualKeyCode::E,
+            fit: VirtualKeyCode::F,
+            follow: VirtualKeyCode::T,
+            whole_grid: VirtualKeyCode::Home,
+            walls: VirtualKeyCode::W,
+            tutorial: VirtualKeyCode::F1,
+            announce: VirtualKeyCode::F3,
+            lexicon: VirtualKeyCode::F4,
+            profiler: VirtualKeyCode::F5,
+            save_config: VirtualKeyCode::F2,
+            grow: VirtualKeyCode::RBracket,
+            shrink: VirtualKeyCode::LBracket,
+            warmer: VirtualKeyCode::Period,
+            cooler: VirtualKeyCode::Comma,
+        }
+    }
+}
+
+impl Config {
+    /// `$XDG_CONFIG_HOME/rustlife/config.toml` or the platform equivalent.
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|d| d.join("rustlife").join("config.toml"))
+    }
+
+    /// Where the pattern browser looks for the user's own patterns.
+    #[cfg_attr(not(feature = "gui"), allow(dead_code))]
+    pub fn patterns_dir(&self) -> Option<PathBuf> {
+        self.patterns_dir.clone().or_else(|| Config::default_path()?.parent().map(|d| d.join("patterns")))
+    }
+
+    /// Where the Lexicon panel looks for the Life Lexicon.
+    #[cfg_attr(not(feature = "gui"), allow(dead_code))]
+    pub fn lexicon_path(&self) -> Option<PathBuf> {
+        self.lexicon.clone().or_else(|| Config::default_path()?.parent().map(|d| d.join("lexicon.txt")))
+    }
+
+    /// Reads the config file, falling back to defaults if it doesn't exist.
+    pub fn load(path: &Path) -> Result<Config, String> {
+        match fs::read_to_string(path) {
+            Ok(text) => toml::from_str(&text).map_err(|e| format!("{}: {}", path.display(), e)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Config::default()),
+            Err(e) => Err(format!("{}: {}", path.display(), e))
+        }
+    }
+
+    #[cfg_attr(not(feature = "gui"), allow(dead_code))]
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let text = toml::to_string_pretty(self).map_err(|e| e.to_string())?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+        }
+        fs::write(path, text).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// Command line flags win over whatever was in the file.
+    pub fn apply_args(&mut self, args: &Args) {
+        if let Some(v) = args.grid_width { self.grid_width = v; }
+        if let Some(v) = args.grid_height { self.grid_height = v; }
+        if let Some(v) = args.window_width { self.window_width = v; }
+        if let Some(v) = args.window_height { self.window_height = v; }
+        if let Some(v) = args.rule { self.rule = v.to_string(); }
+        if let Some(v) = args.density { self.density = v; }
+        if let Some(v) = args.symmetry { self.symmetry = v; }
+        if let Some(v) = args.soup_size { self.soup_size = v; }
+        if let Some(v) = &args.layout { self.layout = v.clone(); }
+        if let Some(v) = args.seed { self.seed = Some(v); }
+        if let Some(v) = &args.backend { self.backend = v.clone(); }
+        if let Some(v) = args.speed { self.speed = v; }
+        #[cfg(feature = "gui")]
+        if let Some(v) = args.max_fps { self.max_fps = v; }
+        if let Some(v) = args.threads { self.threads = v; }
+        if args.pin_threads { self.pin_threads = true; }
+        if let Some(v) = args.max_memory { self.max_memory = v; }
+        if let Some(v) = &args.theme { self.theme = v.clone(); }
+        if let Some(v) = args.noise { self.noise = v; }
+        if let Some((feed, kill)) = args.reaction { (self.feed, self.kill) = (feed, kill); }
+        if let Some((growth, lightning)) = args.forest { (self.growth, self.lightning) = (growth, lightning); }
+        if let Some((states, threshold)) = args.cyclic { (self.cyclic_states, self.cyclic_threshold) = (states, threshold); }
+        if let Some((temperature, field)) = args.ising { (self.temperature, self.field) = (temperature, field); }
+        if let Some(v) = args.gas { self.gas = v; }
+        if let Some(v) = args.elementary { self.elementary = v; }
+        if let Some(v) = args.boundary { self.boundary = v; }
+        if args.stop_on_extinction { self.stop.extinction = true; }
+        if args.stop_on_repeat { self.stop.repeat = true; }
+        if let Some(v) = args.stop_at { self.stop.generation = Some(v); }
+        if args.detect_cycles { self.detect_cycles = true; }
+        if let Some(v) = args.cycle_every { self.cycle_every = v.max(1); }
+    }
+
+    pub fn rule(&self) -> Result<Rule, String> {
+        self.rule.parse()
+    }
+
+    /// An empty universe of the configured size, backend and rule.
+    pub fn simulation(&self) -> Result<SimulationBuilder, String> {
+        Ok(SimulationBuilder::new(self.grid_width, self.grid_height)
+            .backend(&self.backend)
+            .rule(self.rule()?)
+            .reaction(self.feed, self.kill)
+            .forest(self.growth, self.lightning)
+            .cyclic(self.cyclic_states, self.cyclic_threshold)
+            .ising(self.temperature, self.field)
+            .gas(self.gas)
+            .elementary(self.elementary)
+            .boundary(self.boundary)
+            .memory_limit(self.memory_limit()))
+    }
+
+    /// What the configured universe will take in memory, or None on an
+    /// unknown backend.  The window also keeps the timeline's keyframes and
+    /// a snapshot to compare against.
+    pub fn memory(&self, window: bool) -> Option<Estimate> {
+        let estimate = Estimate::new(&self.backend, self.grid_width, self.grid_height)?;
+        Some(if window { estimate.keyframes(timeline::MAX_KEYFRAMES).snapshots(1) } else { estimate })
+    }
+
+    /// `max_memory` in bytes, or the machine's memory where that is known.
+    pub fn memory_limit(&self) -> u64 {
+        match self.max_memory {
+            0 => usage::physical().unwrap_or(0),
+            mib => mib.saturating_mul(1 << 20)
+        }
+    }
+
+    /// The configured soup, drawn from `seed`.
+    pub fn soup(&self, seed: u64) -> Start {
+        Start::Soup { density: self.density, seed, symmetry: self.symmetry, size: self.soup_size, layout: self.layout.clone() }
+    }
+
+    /// A built-in theme by name, or one read from a theme file.
+    pub fn theme(&self) -> Result<Theme, String> {
+        match (Theme::by_name(&self.theme), self.theme_file()) {
+            (Some(theme), _) => Ok(theme),
+            (None, Some(path)) => load_theme(&path),
+            (None, None) => Err(format!("unknown theme '{}', expected one of: {}, or a .toml theme file", self.theme, Theme::NAMES.join(", ")))
+        }
+    }
+
+    /// The theme file, if the theme isn't a built-in one.
+    pub fn theme_file(&self) -> Option<PathBuf> {
+        let path = Path::new(&self.theme);
+        (Theme::by_name(&self.theme).is_none() && (path.extension().is_some_and(|e| e == "toml") || path.is_file()))
+            .then(|| path.to_path_buf())
+    }
+}
+
+/// A theme file: `#rrggbb` colours for live and dead cells, and optionally
+/// the four heatmap colours from never to always alive.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ThemeFile {
+    alive: String,
+    dead: String,
+    heat: Option<Vec<String>>,
+}
+
+fn parse_colour(s: &str) -> Result<[u8; 3], String> {
+    let hex = s.trim().strip_prefix('#').unwrap_or(s.trim());
+    let value = u32::from_str_radix(hex, 16).ok().filter(|_| hex.len() == 6)
+        .ok_or_else(|| format!("'{}' is not a colour, expected #rrggbb", s))?;
+    Ok([(value >> 16) as u8, (value >> 8) as u8, value as u8])
+}
+
+pub fn load_theme(path: &Path) -> Result<Theme, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let file: ThemeFile = toml::from_str(&text).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let colour = |s: &str| parse_colour(s).map_err(|e| format!("{}: {}", path.display(), e));
+    let heat = match &file.heat {
+        Some(stops) if stops.len() == HEAT.len() => {
+            let mut heat = HEAT;
+            for (c, s) in heat.iter_mut().zip(stops) {
+                *c = colour(s)?;
+            }
+            heat
+        }
+        Some(stops) => return Err(format!("{}: heat needs {} colours, got {}", path.display(), HEAT.len(), stops.len())),
+        None => HEAT
+    };
+    Ok(Theme { alive: colour(&file.alive)?, dead: colour(&file.dead)?, heat })
+}
+
+// Often enough to feel instant, rarely enough not to matter
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+const FILE_POLL: Duration = Duration::from_millis(500);
+
+/// Notices when a file is saved, by its modification time.
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+pub struct FileWatch {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    checked: Instant,
+}
+
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+impl FileWatch {
+    pub fn new(path: PathBuf) -> Self {
+        let modified = fs::metadata(&path).and_then(|m| m.modified()).ok();
+        FileWatch { path, modified, checked: Instant::now() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// True if the file changed since it was last looked at, which is at
+    /// most twice a second.  A file that is gone hasn't changed, so one
+    /// being saved by renaming over it is picked up once it is back.
+    pub fn changed(&mut self) -> bool {
+        if self.checked.elapsed() < FILE_POLL {
+            return false;
+        }
+        self.checked = Instant::now();
+        let modified = fs::metadata(&self.path).and_then(|m| m.modified()).ok();
+        if modified.is_none() || modified == self.modified {
+            return false;
+        }
+        self.modified = modified;
+        true
+    }
+}
+
+/// Notices when a theme file is saved, so colours can be tweaked while the
+/// simulation runs.
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+pub struct ThemeWatch(FileWatch);
+
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+impl ThemeWatch {
+    pub fn new(path: PathBuf) -> Self {
+        ThemeWatch(FileWatch::new(path))
+    }
+
+    /// The theme read again if the file changed.
+    pub fn poll(&mut self) -> Option<Result<Theme, String>> {
+        self.0.changed().then(|| load_theme(self.0.path()))
+    }
+}
@@ -0,0 +1,259 @@
+//! The cyclic cellular automaton: each cell holds one of `states` states,
+//! and a cell in state k moves on to k + 1 (wrapping round to 0) when at
+//! least `threshold` of its eight neighbours are already there.  Each state
+//! is eaten by the one after it, and from a random start the grid settles
+//! into waves and spirals.
+//!
+//! State 0 counts as dead and the rest as alive, so population and the
+//! other statistics still mean something.  Every state is drawn in its own
+//! colour round a hue wheel.
+
+use std::any::Any;
+use std::sync::Mutex;
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+#[cfg(not(target_arch = "wasm32"))]
+use rayon::prelude::*;
+
+use crate::engine::{Anchor, CellState, LifeEngine, StaleGuard};
+use crate::rule::Rule;
+
+pub const DEFAULT_STATES: u8 = 14;
+pub const DEFAULT_THRESHOLD: usize = 1;
+
+// Population, then changed and born cells
+type Counts = (usize, usize, usize);
+
+/// The colour `i` of `n` steps round the hue wheel, at full saturation.
+pub fn hue(i: usize, n: usize) -> [u8; 3] {
+    let h = 6.0 * i as f64 / n.max(1) as f64;
+    let x = 1.0 - (h % 2.0 - 1.0).abs();
+    let (r, g, b) = match h as usize {
+        0 => (1.0, x, 0.0),
+        1 => (x, 1.0, 0.0),
+        2 => (0.0, 1.0, x),
+        3 => (0.0, x, 1.0),
+        4 => (x, 0.0, 1.0),
+        _ => (1.0, 0.0, x),
+    };
+    [(r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8]
+}
+
+pub struct CyclicEngine {
+    width: usize,
+    height: usize,
+    cells: Vec<u8>,
+    states: u8,
+    threshold: usize,
+    rule: Rule,
+    scratch: Mutex<Vec<u8>>,
+    population: usize,
+    changed: usize,
+    born: usize,
+    next_counts: Mutex<Counts>,
+    stale: StaleGuard,
+}
+
+impl CyclicEngine {
+    pub fn new(width: usize, height: usize) -> Self {
+        CyclicEngine {
+            width,
+            height,
+            cells: vec![0; width * height],
+            states: DEFAULT_STATES,
+            threshold: DEFAULT_THRESHOLD,
+            rule: Rule::default(),
+            scratch: Mutex::new(vec![0; width * height]),
+            population: 0,
+            changed: 0,
+            born: 0,
+            next_counts: Mutex::new((0, 0, 0)),
+            stale: StaleGuard::default(),
+        }
+    }
+
+    fn put(&mut self, x: usize, y: usize, state: u8) {
+        let cell = &mut self.cells[y * self.width + x];
+        match (*cell != 0, state != 0) {
+            (false, true) => self.population += 1,
+            (true, false) => self.population -= 1,
+            _ => {}
+        }
+        *cell = state;
+        self.stale.bump();
+    }
+
+    fn next_cell(&self, x: usize, y: usize) -> u8 {
+        let state = self.cells[y * self.width + x];
+        let successor = (state + 1) % self.states;
+        let mut eaters = 0;
+        for ny in y.saturating_sub(1)..(y + 2).min(self.height) {
+            for nx in x.saturating_sub(1)..(x + 2).min(self.width) {
+                if (nx, ny) != (x, y) && self.cells[ny * self.width + nx] == successor {
+                    eaters += 1;
+                }
+            }
+        }
+        if eaters >= self.threshold { successor } else { state }
+    }
+
+    /// Number of states and the threshold.
+    pub fn params(&self) -> (u8, usize) {
+        (self.states, self.threshold)
+    }
+
+    /// Cells in states past the new last one wrap round.
+    pub fn set_params(&mut self, states: u8, threshold: usize) {
+        (self.states, self.threshold) = (states.max(2), threshold.clamp(1, 8));
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let state = self.cells[y * self.width + x];
+                if state >= self.states {
+                    self.put(x, y, state % self.states);
+                }
+            }
+        }
+        self.stale.bump();
+    }
+}
+
+impl LifeEngine for CyclicEngine {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn name(&self) -> &'static str {
+        "cyclic"
+    }
+
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    fn get(&self, x: usize, y: usize) -> CellState {
+        if self.cells[y * self.width + x] != 0 { CellState::Alive } else { CellState::Dead }
+    }
+
+    /// Live cells painted onto dead ones are state 1.
+    fn set(&mut self, x: usize, y: usize, state: CellState) {
+        let old = self.cells[y * self.width + x];
+        self.put(x, y, match state {
+            CellState::Alive => old.max(1),
+            CellState::Dead => 0,
+        });
+    }
+
+    fn color(&self, x: usize, y: usize) -> Option<[u8; 3]> {
+        Some(hue(self.cells[y * self.width + x] as usize, self.states as usize))
+    }
+
+    /// Kept for the other backends' sake; the cyclic automaton has no B/S
+    /// rule.
+    fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    fn set_rule(&mut self, rule: Rule) {
+        self.rule = rule;
+    }
+
+    fn prepare_step(&self) {
+        let _span = tracing::info_span!("cyclic.prepare_step").entered();
+        if let Ok(mut scratch) = self.scratch.lock() {
+            self.stale.mark_prepared();
+            let width = self.width.max(1);
+
+            #[cfg(not(target_arch = "wasm32"))]
+            let rows = scratch.par_chunks_mut(width);
+            #[cfg(target_arch = "wasm32")]
+            let rows = scratch.chunks_mut(width);
+
+            rows.enumerate().for_each(|(y, row)| {
+                for (x, cell) in row.iter_mut().enumerate() {
+                    *cell = self.next_cell(x, y);
+                }
+            });
+
+            let mut counts: Counts = (0, 0, 0);
+            for (old, new) in self.cells.iter().zip(scratch.iter()) {
+                counts.0 += (*new != 0) as usize;
+                if old != new {
+                    counts.1 += 1;
+                    counts.2 += (*old == 0) as usize;
+                }
+            }
+            if let Ok(mut next) = self.next_counts.lock() {
+                *next = counts;
+            }
+        }
+    }
+
+    fn commit_step(&mut self) {
+        if self.stale.is_stale() {
+            self.prepare_step();
+        }
+        self.stale.committed();
+        if let (Ok(scratch), Ok(counts)) = (self.scratch.get_mut(), self.next_counts.get_mut()) {
+            std::mem::swap(&mut self.cells, scratch);
+            (self.population, self.changed, self.born) = *counts;
+        }
+    }
+
+    fn changed_cells(&self) -> usize {
+        self.changed
+    }
+
+    fn born_cells(&self) -> usize {
+        self.born
+    }
+
+    fn resize(&mut self, width: usize, height: usize, anchor: Anchor) {
+        let (ox, oy) = anchor.offset((self.width, self.height), (width, height));
+        let mut cells = vec![0; width * height];
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let (nx, ny) = (x as isize + ox, y as isize + oy);
+                if nx >= 0 && ny >= 0 && nx < width as isize && ny < height as isize {
+                    cells[ny as usize * width + nx as usize] = self.cells[y * self.width + x];
+                }
+            }
+        }
+        self.population = cells.iter().filter(|c| **c != 0).count();
+        self.cells = cells;
+        self.scratch = Mutex::new(vec![0; width * height]);
+        (self.width, self.height) = (width, height);
+        self.changed = 0;
+        self.born = 0;
+        self.stale.bump();
+    }
+
+    /// As the other backends, but each live cell of the soup is drawn in a
+    /// random state, 0 included: with a state missing the cells before it
+    /// can never move on.
+    fn randomize(&mut self, density: f64, seed: u64) {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let mut states = ChaCha8Rng::seed_from_u64(seed);
+        states.set_stream(1);
+        for y in 0..self.height {
+            rng.set_word_pos(2 * (y * self.width) as u128);
+            for x in 0..self.width {
+                let alive = rng.gen_bool(density.clamp(0.0, 1.0));
+                let state = if alive { states.gen_range(0..self.states) } else { 0 };
+                self.put(x, y, state);
+            }
+        }
+    }
+
+    fn population(&self) -> usize {
+        self.population
+    }
+}
@@ -0,0 +1,34 @@
+//! The attract mode's playlist: famous patterns shown one after another,
+//! each with a caption, for kiosks and talks.  Each is shown for its own
+//! number of generations, or until it dies out or settles.
+
+use crate::library::{self, LibraryPattern};
+
+// Built in pattern, caption, and generations to show it for
+const PLAYLIST: &[(&str, &str, u64)] = &[
+    ("Gosper glider gun", "The first gun, found by Bill Gosper in 1970: a glider every 30 generations", 1200),
+    ("R-pentomino", "Five cells that take 1103 generations to settle", 1200),
+    ("puffer train", "Gosper's puffer train moves at half the speed of light, leaving smoke behind", 1000),
+    ("copperhead", "A spaceship found in 2016, moving one cell every ten generations", 400),
+    ("Simkin glider gun", "Michael Simkin's gun of 2015: a glider every 120 generations from 36 cells", 1500),
+    ("diehard", "Seven cells that vanish completely after 130 generations", 200),
+    ("pulsar", "The most common period 3 oscillator", 60),
+    ("acorn", "Seven cells that run for 5206 generations, throwing off 13 gliders", 5300),
+    ("pentadecathlon", "A period 15 oscillator, which also grows from a row of ten cells", 90),
+];
+
+/// A pattern in the playlist.
+#[derive(Clone, Debug)]
+pub struct Show {
+    pub pattern: LibraryPattern,
+    pub caption: &'static str,
+    /// Moves on to the next after this many generations at most
+    pub generations: u64,
+}
+
+/// The patterns to show, in order.
+pub fn playlist() -> Vec<Show> {
+    PLAYLIST.iter()
+        .map(|&(name, caption, generations)| Show { pattern: library::find(name).expect("playlist patterns are built in"), caption, generations })
+        .collect()
+}
@@ -0,0 +1,2260 @@
+use pixels::{Pixels, SurfaceTexture};
+use winit::{
+    dpi::{LogicalSize, PhysicalPosition, PhysicalSize},
+    event::{Event, VirtualKeyCode, WindowEvent},
+    event_loop::{ControlFlow, EventLoop},
+    window::{Fullscreen, Window, WindowBuilder, WindowLevel},
+};
+use winit_input_helper::WinitInputHelper;
+use std::sync::{Arc, Condvar, RwLock};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::sync::atomic::{AtomicBool, AtomicI32, AtomicU32, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::info_span;
+use tracing_chrome::FlushGuard;
+use rustlife::cyclic::CyclicEngine;
+use rustlife::diff::Reference;
+use rustlife::elementary::ElementaryEngine;
+use rustlife::forest::ForestEngine;
+use rustlife::gas::GasEngine;
+use rustlife::ising::IsingEngine;
+use rustlife::reaction::ReactionEngine;
+use rustlife::snapshot::Snapshot;
+use rustlife::inspect::{self, Inspection};
+use rustlife::memory::{self, Estimate};
+use rustlife::noise::Noise;
+use rustlife::period::CycleDetector;
+use rustlife::replay::{Edit, Player, Recorder, Settings};
+use rustlife::script::SharedLife;
+use rustlife::ships::Ship;
+use rustlife::sand::{Element, SandEngine};
+use rustlife::species::{Species, SpeciesEngine};
+use rustlife::stats::{self, Ages, Heatmap, SharedHistory, SharedRegions};
+use rustlife::stop::{StopChecker, StopReason};
+use rustlife::timeline::{SharedTimeline, Timeline};
+use rustlife::timing::Phase;
+use rustlife::{census, demo, library, engine, predecessor, svg, find, io, Anchor, BoundingBox, CellState, DenseEngine, EventBus, GenerationEvent, LifeEngine, Rule};
+
+use crate::announce::Announcer;
+use crate::browser::Browser;
+use crate::commands::Command;
+use crate::error::{log_error, AppError};
+use crate::explain::Explainer;
+use crate::overlay::GLYPH_SIZE;
+use crate::glossary::Glossary;
+use crate::palette::Palette;
+use crate::profiler::Profiler;
+use crate::recent::{Recent, RecentItem};
+use crate::ruler::Ruler;
+use crate::render::{PixelMap, Theme, View};
+use crate::session::{Bookmark, Session, WindowGeometry};
+use crate::slots::{Slot, Slots};
+use crate::tutorial::Tutorial;
+use crate::watch::{self, ActivityWatch, GrowthWatch, PeriodWatch, SharedActivity, SharedAges, SharedGrowth, SharedHeatmap, SharedShips, SharedWatch};
+use crate::{cli, collab, config, overlay, power, render, screensaver, scrubber, slots, spacetime, span};
+
+// Limits for runtime resizing
+const MIN_GRID: usize = 16;
+const MAX_GRID: usize = 16384;
+// Generations per second as a wallpaper, unless --speed says otherwise
+const WALLPAPER_SPEED: u32 = 4;
+// And in the demo
+const DEMO_SPEED: u32 = 30;
+// How far the warmer and cooler keys move the Ising temperature or field
+const ISING_NUDGE: f64 = 0.05;
+
+const SLOT_KEYS: [VirtualKeyCode; slots::SLOT_COUNT] = [
+    VirtualKeyCode::Key1, VirtualKeyCode::Key2, VirtualKeyCode::Key3,
+    VirtualKeyCode::Key4, VirtualKeyCode::Key5, VirtualKeyCode::Key6,
+    VirtualKeyCode::Key7, VirtualKeyCode::Key8, VirtualKeyCode::Key9,
+];
+
+/// What the mouse paints.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Brush {
+    Cells,
+    Walls,
+    Predators,
+    Element(Element),
+}
+
+impl Brush {
+    /// Switches to `brush`, or back to cells if it is already in use.
+    fn toggle(self, brush: Brush) -> Brush {
+        if self == brush { Brush::Cells } else { brush }
+    }
+}
+
+/// Whether the stepping thread runs, and how many generations it still has
+/// to step while paused.  It sleeps until one of them changes rather than
+/// checking in a loop.
+struct Pause {
+    // Paused, the generations still to step, and whether one is being
+    // stepped now
+    state: Mutex<(bool, u64, bool)>,
+    changed: Condvar,
+}
+
+// Longest to wait for the stepping thread to finish a generation, in case
+// it has stopped for good
+const SETTLE_TIMEOUT: Duration = Duration::from_secs(1);
+
+impl Pause {
+    fn new(paused: bool) -> Self {
+        Pause { state: Mutex::new((paused, 0, false)), changed: Condvar::new() }
+    }
+
+    fn update(&self, f: impl FnOnce(&mut (bool, u64, bool))) {
+        if let Ok(mut state) = self.state.lock() {
+            f(&mut state);
+            self.changed.notify_all();
+        }
+    }
+
+    fn set(&self, paused: bool) {
+        self.update(|s| s.0 = paused);
+    }
+
+    fn toggle(&self) {
+        self.update(|s| s.0 = !s.0);
+    }
+
+    fn is_paused(&self) -> bool {
+        self.state.lock().is_ok_and(|s| s.0)
+    }
+
+    /// Pauses and steps `n` more generations.
+    fn step(&self, n: u64) {
+        self.update(|s| {
+            s.0 = true;
+            s.1 += n;
+        });
+    }
+
+    /// Drops any generations still to step.
+    fn forget(&self) {
+        self.update(|s| s.1 = 0);
+    }
+
+    /// Paused with nothing left to step and nothing being stepped.
+    fn idle(&self) -> bool {
+        self.state.lock().is_ok_and(|s| *s == (true, 0, false))
+    }
+
+    /// Pauses and waits for the stepping thread to finish the generation it
+    /// is on, so the universe can be replaced without a step landing on it.
+    fn settle(&self) {
+        let state = match self.state.lock() {
+            Ok(mut s) => {
+                (s.0, s.1) = (true, 0);
+                s
+            }
+            Err(_) => return
+        };
+        let _ = self.changed.wait_timeout_while(state, SETTLE_TIMEOUT, |s| s.2);
+    }
+
+    /// Blocks until there is a generation to step, using up one of the
+    /// pending ones if paused.  False if the lock was poisoned.
+    fn wait(&self) -> bool {
+        let state = match self.state.lock() {
+            Ok(s) => s,
+            Err(_) => return false
+        };
+        match self.changed.wait_while(state, |s| s.0 && s.1 == 0) {
+            Ok(mut s) => {
+                if s.0 {
+                    s.1 -= 1;
+                }
+                s.2 = true;
+                true
+            }
+            Err(_) => false
+        }
+    }
+
+    /// Called by the stepping thread when a generation is done.
+    fn done(&self) {
+        self.update(|s| s.2 = false);
+    }
+}
+
+/// Applies a user edit to the universe, recording it if a recording is
+/// running.  `generation` is how many generations the universe has stepped.
+fn apply_edit(life: &mut dyn LifeEngine, edit: Edit, generation: u64, recorder: Option<&Arc<Mutex<Recorder>>>, timeline: &Mutex<Timeline>) {
+    if let Err(e) = edit.apply(life) {
+        log::error!("{}", e);
+        return;
+    }
+    if let Ok(mut t) = timeline.lock() {
+        t.edited(generation);
+    }
+    if let Some(Ok(mut r)) = recorder.map(|r| r.lock()) {
+        r.record(generation, edit);
+    }
+}
+
+fn current_session(window: &Window, slots: &Slots, config: &config::Config, bookmarks: &[Bookmark]) -> Session {
+    let size = window.inner_size();
+    let slot = slots.current();
+    let (rule, grid) = match slot.life.read() {
+        Ok(l) => (Some(l.rule().to_string()), Some((l.width(), l.height()))),
+        Err(_) => (None, None)
+    };
+    Session {
+        window: window.outer_position().ok().map(|pos| WindowGeometry {
+            x: pos.x,
+            y: pos.y,
+            width: size.width,
+            height: size.height,
+        }),
+        camera: Some(slot.camera),
+        speed: Some(config.speed),
+        rule,
+        theme: Some(config.theme.clone()),
+        grid_width: grid.map(|g| g.0),
+        grid_height: grid.map(|g| g.1),
+        bookmarks: bookmarks.to_vec(),
+    }
+}
+
+/// Forgets the timeline and starts it again from the shown slot as it is
+/// now, for when the universe is replaced.
+fn restart_timeline(timeline: &Mutex<Timeline>, slots: &Slots) {
+    let slot = slots.current();
+    if let (Ok(l), Ok(mut t)) = (slot.life.read(), timeline.lock()) {
+        t.clear();
+        t.record(slot.generation.load(Ordering::Relaxed), l.as_ref());
+    }
+}
+
+/// Replaces the shown slot's universe with `cells`, centred, under `rule`
+/// if the pattern names one, and starts it and the stop conditions again
+/// from generation 0.
+fn start_over(slots: &Slots, cells: &[(usize, usize)], rule: Option<Rule>, timeline: &Mutex<Timeline>, stop: &Mutex<StopChecker>) {
+    let slot = slots.current();
+    if let Ok(mut l) = slot.life.write() {
+        // Back to 0 first, so the edits are at the generation they start
+        slot.generation.store(0, Ordering::Relaxed);
+        let pattern = io::cells_to_plaintext(cells);
+        let (x, y) = centred(l.as_ref(), &pattern);
+        let rule = rule.map(|r| Edit::Rule { rule: r.to_string() });
+        for edit in rule.into_iter().chain([Edit::Clear, Edit::Paste { x, y, pattern }]) {
+            apply_edit(l.as_mut(), edit, 0, None, timeline);
+        }
+    }
+    restart_timeline(timeline, slots);
+    if let Ok(mut s) = stop.lock() {
+        s.restart();
+    }
+}
+
+/// Reads a plaintext or RLE pattern and returns the edits that replace the
+/// universe with it, centred.
+fn load_pattern(life: &dyn LifeEngine, path: &str) -> Result<[Edit; 2], String> {
+    let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+    let live = io::parse_pattern(&text).map_err(|e| format!("{}: {}", path, e))?;
+    let (w, h) = io::extent(&live);
+    let (x, y) = (life.width().saturating_sub(w) / 2, life.height().saturating_sub(h) / 2);
+    Ok([Edit::Clear, Edit::Paste { x, y, pattern: io::cells_to_plaintext(&live) }])
+}
+
+/// Puts `item` at the top of the recent list and saves it to `path`.
+fn remember(recent: &mut Recent, path: Option<&std::path::Path>, item: RecentItem) {
+    recent.add(item);
+    if let Some(path) = path {
+        if let Err(e) = recent.save(path) {
+            log::error!("Could not save recent patterns: {}", e);
+        }
+    }
+}
+
+// Pixels the mouse has to move to end the screensaver, more than a knock
+// to the desk moves it
+const WAKE_DISTANCE: f64 = 20.0;
+// How long a stop notice flashes for
+const NOTICE_TIME: Duration = Duration::from_secs(3);
+// How often the window checks for remote and script commands and theme
+// changes while there is nothing to draw
+const IDLE_POLL: Duration = Duration::from_millis(100);
+// How often the window title's status is brought up to date
+const TITLE_INTERVAL: Duration = Duration::from_millis(250);
+// How often the progress of a fast forward is drawn; nothing else is, so
+// the time goes on stepping
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+// Generations ahead the velocity arrows reach
+const SHIP_LOOKAHEAD: f64 = 40.0;
+
+/// A jump on to a later generation, stepped with the speed cap lifted.
+struct FastForward {
+    from: u64,
+    to: u64,
+    started: Instant,
+    // The speed to go back to once there
+    speed: u32,
+}
+
+impl FastForward {
+    /// How far it has got, as a bar across the middle of the screen.
+    fn draw(&self, screen: &mut [u8], width: u32, height: u32, generation: u64) {
+        let done = (generation.saturating_sub(self.from) as f64 / (self.to - self.from) as f64).min(1.0);
+        let rate = generation.saturating_sub(self.from) as f64 / self.started.elapsed().as_secs_f64().max(f64::EPSILON);
+        let text = format!("Fast forward to {}: {} ({:.0}%, {:.0} gen/s)", self.to, generation, done * 100.0, rate);
+        overlay::banner(screen, width, height, &text);
+        let (left, top, w) = (width as i32 / 4, (height / 2 + 2 * GLYPH_SIZE) as i32, width / 2);
+        overlay::fill_rect(screen, width, height, (left, top, w, 6), [64, 64, 64, 255]);
+        overlay::fill_rect(screen, width, height, (left, top, (w as f64 * done) as u32, 6), [255, 220, 64, 255]);
+    }
+}
+
+/// Marks each ship with an arrow to where it will be in [`SHIP_LOOKAHEAD`]
+/// generations, labelled with its name and speed.
+fn draw_ships(screen: &mut [u8], width: u32, height: u32, view: &View, ships: &[Ship]) {
+    for ship in ships {
+        let (vx, vy) = ship.velocity();
+        let from = view.screen_pos((ship.x, ship.y), (width, height));
+        let to = view.screen_pos((ship.x + vx * SHIP_LOOKAHEAD, ship.y + vy * SHIP_LOOKAHEAD), (width, height));
+        overlay::line(screen, width, height, from, to, [255, 80, 80]);
+        let label = format!("{} {}", ship.name().unwrap_or(&ship.code), ship.speed());
+        overlay::draw_text(screen, width, height, from.0 + 4, from.1 + 4, &label, [255, 80, 80]);
+    }
+}
+
+/// Outlines each box, at least a few pixels across so small finds on a
+/// large grid still show.
+fn draw_found(screen: &mut [u8], width: u32, height: u32, view: &View, boxes: &[BoundingBox]) {
+    let scale = view.scale(width);
+    for bb in boxes {
+        let (x, y) = view.screen_pos((bb.min_x as f64, bb.min_y as f64), (width, height));
+        let w = ((bb.width() as f64 * scale) as u32).max(4);
+        let h = ((bb.height() as f64 * scale) as u32).max(4);
+        overlay::outline(screen, width, height, (x - 1, y - 1, w + 2, h + 2), [64, 255, 64, 255]);
+    }
+}
+
+// Colours of the cells the next generation brings to life and kills
+const NEXT_BORN: [u8; 4] = [64, 255, 64, 200];
+const NEXT_DIED: [u8; 4] = [255, 64, 64, 200];
+
+/// Marks each of `changes`, the cells the next generation changes, in
+/// green if it will be born and red if it will die.
+fn draw_preview(screen: &mut [u8], width: u32, height: u32, view: &View, life: &dyn LifeEngine, changes: &[(usize, usize)]) {
+    let size = (view.scale(width).ceil() as u32).max(1);
+    for &(x, y) in changes {
+        let (px, py) = view.screen_pos((x as f64, y as f64), (width, height));
+        let color = if life.get(x, y) == CellState::Alive { NEXT_DIED } else { NEXT_BORN };
+        overlay::fill_rect(screen, width, height, (px, py, size, size), color);
+    }
+}
+
+/// The cell inspector's lines for a cell alive `age` generations in a row.
+fn inspection(cell: &Inspection, age: u16) -> Vec<String> {
+    let state = |s: CellState| if s == CellState::Alive { "alive" } else { "dead" };
+    let mut lines = vec![match (cell.wall, cell.state, age) {
+        (Some(held), _, _) => format!("wall, held {}", state(held)),
+        (None, CellState::Alive, 1) => "alive for 1 generation".to_string(),
+        (None, CellState::Alive, age) if age > 1 => format!("alive for {} generations", age),
+        (None, s, _) => state(s).to_string()
+    }];
+    lines.push(format!("{} live neighbours", cell.neighbors));
+    if let Some((next, clause)) = &cell.next {
+        lines.push(format!("next: {} ({})", state(*next), clause));
+    }
+    lines
+}
+
+/// Where a plaintext pattern's corner goes to centre it in the universe.
+fn centred(life: &dyn LifeEngine, pattern: &str) -> (usize, usize) {
+    let (w, h) = io::extent(&io::parse_plaintext(pattern));
+    (life.width().saturating_sub(w) / 2, life.height().saturating_sub(h) / 2)
+}
+
+/// `n` with commas between the thousands, e.g. 12,503.
+fn thousands(n: u64) -> String {
+    let digits = n.to_string();
+    let mut out = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push(',');
+        }
+        out.push(c);
+    }
+    out
+}
+
+fn window_title(slot: usize, seed: Option<u64>) -> String {
+    match seed {
+        Some(seed) => format!("Conway's Game of Life [{}] - seed {}", slot + 1, seed),
+        None => format!("Conway's Game of Life [{}]", slot + 1)
+    }
+}
+
+/// Everything the window starts with, set up by [`crate::start::run`].
+pub struct Launch {
+    pub args: cli::Args,
+    pub config: config::Config,
+    pub config_path: Option<PathBuf>,
+    pub session: Session,
+    pub session_path: Option<PathBuf>,
+    /// Flushes the trace file when dropped
+    pub trace_guard: Option<FlushGuard>,
+    pub life: SharedLife,
+    pub events: EventBus,
+    pub player: Option<Player>,
+    pub recorder: Option<Arc<Mutex<Recorder>>>,
+    /// Connection to the host when joining someone else's universe
+    pub joined: Option<collab::Client>,
+    pub seed: Option<u64>,
+    pub theme: Theme,
+    pub theme_watch: Option<config::ThemeWatch>,
+    /// Stops stepping and drawing while the window can't be seen
+    pub power_save: bool,
+    /// Speed and frame rate to start at, which may be the power saving
+    /// profile's rather than the config's
+    pub limits: power::Limits,
+}
+
+/// Opens the window and runs the universe in it until it is closed.
+pub fn run(launch: Launch) -> Result<(), AppError> {
+    let Launch { args, mut config, config_path, session, session_path, mut trace_guard, life, mut events, mut player, recorder, mut joined, seed, mut theme, mut theme_watch, power_save, mut limits } = launch;
+
+    if let Some(seed) = seed {
+        println!("Seed: {}", seed);
+    }
+
+    let (width, height) = (config.window_width, config.window_height);
+    let event_loop = EventLoop::new();
+    let mut input = WinitInputHelper::new();
+
+    let monitor = span::monitor(&event_loop, args.monitor)?;
+    let window = {
+        let size = LogicalSize::new(width as f64, height as f64);
+        let mut builder = WindowBuilder::new()
+            .with_title(window_title(0, seed))
+            .with_min_inner_size(size);
+        if args.screensaver || args.fullscreen || args.span {
+            builder = builder.with_fullscreen(Some(Fullscreen::Borderless(monitor.clone())));
+        } else if args.wallpaper {
+            // Behind everything, over the whole of the main screen, and never focused
+            builder = builder
+                .with_decorations(false)
+                .with_window_level(WindowLevel::AlwaysOnBottom)
+                .with_active(false);
+            if let Some(monitor) = monitor.clone().or_else(|| event_loop.primary_monitor()) {
+                builder = builder.with_inner_size(monitor.size()).with_position(monitor.position());
+            }
+        } else if let Some(geometry) = session.window {
+            builder = builder
+                .with_inner_size(PhysicalSize::new(geometry.width, geometry.height))
+                .with_position(PhysicalPosition::new(geometry.x, geometry.y));
+        }
+        // Shown once the accessibility tree is there, which has to come first
+        #[cfg(feature = "accessibility")]
+        {
+            builder = builder.with_visible(false);
+        }
+        builder.build(&event_loop)?
+    };
+    #[cfg(feature = "accessibility")]
+    let mut access = {
+        let access = crate::access::Access::new(&window, &window_title(0, seed));
+        window.set_visible(true);
+        access
+    };
+    // Windows on the other monitors when spanning them
+    let mut followers = match monitor.or_else(|| window.current_monitor()).filter(|_| args.span) {
+        Some(main) => span::followers(&event_loop, &main, width, height, &window_title(0, seed))?,
+        None => Vec::new()
+    };
+    // The pattern file, started over whenever it is saved
+    let mut pattern_watch = args.pattern.as_ref().filter(|_| args.watch).map(|path| config::FileWatch::new(PathBuf::from(path)));
+    let screensaver = args.screensaver;
+    if screensaver {
+        window.set_cursor_visible(false);
+    }
+    let wallpaper = args.wallpaper;
+    if wallpaper {
+        // Clicks go through to the desktop underneath
+        if let Err(e) = window.set_cursor_hittest(false) {
+            log::warn!("Wallpaper window will catch clicks: {}", e);
+        }
+        if args.speed.is_none() {
+            limits.speed = WALLPAPER_SPEED;
+        }
+    }
+    // The playlist and which of it is showing, in the demo
+    let demo = args.demo.then(demo::playlist);
+    let mut showing = 0;
+    if demo.is_some() && args.speed.is_none() {
+        limits.speed = DEMO_SPEED;
+    }
+
+    let mut pixels = {
+        let window_size = window.inner_size();
+        let surface_texture = SurfaceTexture::new(window_size.width, window_size.height, &window);
+        Pixels::new(width, height, surface_texture)?
+    };
+
+    let mut slots = Slots::new(Slot::new(life, seed));
+    if let Some(camera) = session.camera {
+        slots.current_mut().camera = camera;
+    }
+    let c_active = slots.active();
+    // Views saved with Ctrl+Shift and a number, recalled with Ctrl and it
+    let mut bookmarks = session.bookmarks.clone();
+
+    let mut palette = Palette::new();
+    let mut browser = Browser::new();
+    // The Life Lexicon, opened with F4
+    let mut glossary = Glossary::new();
+    // Timings of stepping and drawing, shown with F5
+    let mut profiler = Profiler::new();
+    let timings = profiler.timings();
+    // Pattern files loaded and patterns placed lately
+    let recent_path = Recent::default_path();
+    let mut recent = recent_path.as_deref().map(Recent::load).unwrap_or_default();
+    // Patterns looked up with Fetch pattern, picked up to place once found
+    #[cfg(feature = "online")]
+    let (fetched_tx, fetched) = std::sync::mpsc::channel();
+    #[cfg(feature = "remote")]
+    let remote = args.remote.as_deref().map(|addr| crate::remote::serve(addr, slots.active())).transpose()?;
+    #[cfg(not(feature = "remote"))]
+    let remote: Option<std::sync::mpsc::Receiver<Command>> = None;
+    #[cfg(unix)]
+    let ipc = args.ipc.as_deref().map(|path| crate::ipc::serve(path, slots.active())).transpose()?;
+    #[cfg(not(unix))]
+    let ipc: Option<std::sync::mpsc::Receiver<Command>> = None;
+    let hosted = args.host.as_deref().map(|addr| collab::host(addr, slots.active())).transpose()?;
+    #[cfg(feature = "gamepad")]
+    let mut gamepad = args.gamepad.then(crate::gamepad::Gamepad::open).transpose()?;
+    // A joined universe is stepped by the host, and changes without
+    // generations being published here, so it is redrawn every frame
+    let pause = Arc::new(Pause::new(joined.is_some()));
+    let c_pause = Arc::clone(&pause);
+    let always_redraw = joined.is_some();
+    // Set by the stepping thread when it wakes the event loop for a new
+    // generation, so thousands a second don't pile up in its queue
+    let proxy = event_loop.create_proxy();
+    let woken = Arc::new(AtomicBool::new(false));
+    let c_woken = Arc::clone(&woken);
+
+    let mut now = std::time::Instant::now();
+    // Last cell painted while a mouse button is held, so a held button
+    // doesn't repaint the same cell every frame
+    let mut last_paint: Option<Command> = None;
+    let mut brush = Brush::Cells;
+
+    // The listeners from the command line, scripts, OSC, checkpoints and
+    // the like, stay attached to the first slot's universe, so only hear of
+    // its generations; those added here follow whichever slot is shown
+    let mut first_slot_events = std::mem::take(&mut events);
+
+    let frames = Arc::new(AtomicI32::new(0));
+    let c_frames = Arc::clone(&frames);
+    events.subscribe(move |_| {
+        c_frames.fetch_add(1, Ordering::Relaxed);
+    });
+
+    // Plays until dropped
+    #[cfg(feature = "audio")]
+    let mut audio_stream = if args.audio {
+        let (stream, listener) = crate::audio::start(slots.active())?;
+        events.subscribe(listener);
+        Some(stream)
+    } else {
+        None
+    };
+
+    // 3D space-time view, toggled with V.  History is only recorded while it is shown.
+    let spacetime_on = Arc::new(AtomicBool::new(false));
+    let c_spacetime_on = Arc::clone(&spacetime_on);
+    let history = Arc::new(Mutex::new(spacetime::History::new()));
+    let c_history = Arc::clone(&history);
+
+    // Population, births and deaths of the shown slot, graphed with G
+    let population_history: SharedHistory = Arc::default();
+    events.subscribe(stats::recorder(Arc::clone(&population_history)));
+    let mut graph_on = false;
+
+    // Statistics for the named regions, which are also outlined
+    let region_stats: SharedRegions = Arc::default();
+    if let Ok(mut r) = region_stats.lock() {
+        for region in &config.regions {
+            r.add(region.clone());
+        }
+    }
+    events.subscribe(watch::regions(Arc::clone(&region_stats), slots.active()));
+
+    // Period detection, toggled with O
+    let period_watch: SharedWatch = Arc::default();
+    events.subscribe(watch::periods(Arc::clone(&period_watch), slots.active()));
+    // Spaceship tracking, toggled with S
+    let ships: SharedShips = Arc::default();
+    events.subscribe(watch::ships(Arc::clone(&ships), slots.active()));
+    // Activity metrics, toggled with A
+    let activity: SharedActivity = Arc::default();
+    events.subscribe(watch::activity(Arc::clone(&activity), slots.active()));
+    // Growth detection, toggled with U
+    let growth: SharedGrowth = Arc::default();
+    events.subscribe(watch::growth(Arc::clone(&growth), slots.active()));
+    // Heatmap of how often each cell was alive, toggled with H
+    let heatmap: SharedHeatmap = Arc::default();
+    events.subscribe(watch::heatmap(Arc::clone(&heatmap), slots.active()));
+    // Ages of the live cells for the isometric view, toggled with I, and
+    // the cell inspector, toggled with C.  Only kept while one of them is on.
+    let ages: SharedAges = Arc::default();
+    events.subscribe(watch::ages(Arc::clone(&ages), slots.active()));
+    let mut isometric = false;
+    let mut inspector = false;
+    // Births and deaths to come, shown while paused, toggled with N
+    let mut preview = false;
+    // Keyframes of the shown slot to go back to, with a bar shown with L
+    let timeline: SharedTimeline = Arc::default();
+    restart_timeline(&timeline, &slots);
+    events.subscribe(watch::timeline(Arc::clone(&timeline), slots.active()));
+    let mut timeline_on = false;
+    let mut fast_forward: Option<FastForward> = None;
+    // Shown in place of the grid with births and deaths since it, toggled with D
+    let mut diff: Option<Reference> = None;
+    // Lessons for a first run, before there is a session to restore;
+    // started over or closed with F1
+    let first_run = session_path.as_ref().is_some_and(|p| !p.exists()) && !screensaver && !wallpaper && demo.is_none() && joined.is_none();
+    let mut tutorial = (args.tutorial || first_run).then(Tutorial::default);
+    // Says why cells live or die while paused, toggled with E
+    let mut explainer: Option<Explainer> = None;
+    // Measures between two cells, toggled with M
+    let mut ruler: Option<Ruler> = None;
+
+    // Keeps the view on the live cells as they spread, toggled with T
+    let mut follow = demo.is_some();
+    let mut pixel_map = PixelMap::default();
+
+    // Copies found with the Find pattern command, highlighted until cleared
+    let mut found: Option<Vec<BoundingBox>> = None;
+
+    // Pauses when a stop condition is met, with a notice flashed over the grid
+    let mut stop = StopChecker::new(config.stop).every(config.cycle_every);
+    // Cycles reported without pausing, unless --stop-on-repeat is watching
+    // for them already
+    let mut cycles = (config.detect_cycles && !config.stop.repeat).then(|| CycleDetector::new(config.cycle_every));
+    // Set when the screensaver or demo should move on instead of pausing
+    let restless = Arc::new(AtomicBool::new(false));
+    let c_restless = Arc::clone(&restless);
+    let moves_on = screensaver || demo.is_some();
+    if moves_on {
+        stop.conditions.extinction = true;
+        stop.conditions.repeat = true;
+    }
+    if screensaver {
+        stop.conditions.generation.get_or_insert(screensaver::LONGEST);
+    }
+    // Started over with the other watchers when the universe is replaced
+    let stop = Arc::new(Mutex::new(stop));
+    let c_stop = Arc::clone(&stop);
+    if let Some(shows) = &demo {
+        start_over(&slots, &shows[showing].pattern.cells, None, &timeline, &stop);
+    }
+    let notice: Arc<Mutex<Option<(String, Instant)>>> = Arc::default();
+    let c_notice = Arc::clone(&notice);
+    // Says what happens in the window title, and out loud with --speak
+    let mut announcer = Announcer::new(args.speak);
+    let mut announced_notice = None;
+    let mut title = String::new();
+    // When the title was last brought up to date, and the slot and
+    // generation then, for the speed in it
+    let mut titled = (Instant::now(), 0, 0);
+
+    // Cells flipping every generation, drawn from --seed if given.  The
+    // seed is recorded so a replay flips the same cells.
+    let noise_seed = config.seed.unwrap_or_else(rand::random);
+    let noise = Arc::new(Mutex::new(Noise::new(config.noise, noise_seed)));
+    let c_noise = Arc::clone(&noise);
+    if let Some(Ok(mut r)) = recorder.as_ref().filter(|_| config.noise > 0.0).map(|r| r.lock()) {
+        r.record(0, Edit::Noise { probability: config.noise, seed: noise_seed });
+    }
+
+    // Generations per second, 0 for as fast as possible
+    let speed = Arc::new(AtomicU32::new(limits.speed));
+    let c_speed = Arc::clone(&speed);
+
+    let c_timings = profiler.timings();
+    thread::spawn(move || {
+        loop {
+            if !c_pause.wait() {
+                break;
+            }
+            let step_start = std::time::Instant::now();
+            let (c_life, c_generation, slot) = match c_active.read() {
+                Ok(active) => (Arc::clone(&active.0), Arc::clone(&active.1), active.2),
+                Err(_) => break
+            };
+            let generation = c_generation.load(Ordering::Relaxed) + 1;
+            let gen_span = info_span!("generation", generation).entered();
+            // Replays only drive the first slot, which is the one they were recorded in
+            let replaying = slot == 0;
+            if let Some(p) = player.as_mut().filter(|_| replaying) {
+                if let Ok(mut l) = c_life.write() {
+                    if let Err(e) = p.apply_due(generation - 1, l.as_mut()) {
+                        log::error!("Replay stopped: {}", e);
+                        player = None;
+                    }
+                }
+            }
+            if let Ok(l) = info_span!("lock.read").in_scope(|| c_life.read()) {
+                c_timings.time(Phase::Step, || l.prepare_step());
+            }
+            let event = match info_span!("lock.write").in_scope(|| c_life.write()) {
+                Ok(mut l) => {
+                    c_timings.time(Phase::Swap, || l.commit_step());
+                    // A replay brings its own noise
+                    match player.as_mut().filter(|_| replaying) {
+                        Some(p) => info_span!("noise").in_scope(|| p.inject_noise(l.as_mut())),
+                        None => c_noise.lock().map_or(0, |mut n| info_span!("noise").in_scope(|| n.inject(l.as_mut())))
+                    };
+                    c_generation.store(generation, Ordering::Relaxed);
+                    GenerationEvent::from_engine(generation, l.as_ref())
+                }
+                Err(_) => break
+            };
+            tracing::info!(population = event.population, changed = event.changed, "counters");
+            if c_spacetime_on.load(Ordering::Relaxed) {
+                if let (Ok(l), Ok(mut h)) = (c_life.read(), c_history.lock()) {
+                    h.record(l.as_ref());
+                }
+            }
+            // Published with no locks held so listeners can touch the universe
+            c_timings.time(Phase::Publish, || info_span!("publish").in_scope(|| {
+                if slot == 0 {
+                    first_slot_events.publish(&event);
+                }
+                events.publish(&event)
+            }));
+            if !c_woken.swap(true, Ordering::Relaxed) {
+                let _ = proxy.send_event(());
+            }
+            drop(gen_span);
+            if let Some(mut stop) = c_stop.lock().ok().filter(|s| s.conditions.any()) {
+                let reason = c_life.read().ok().and_then(|l| stop.check(&event, l.as_ref()));
+                if reason.is_some() && moves_on {
+                    c_restless.store(true, Ordering::Relaxed);
+                } else if let Some(reason) = reason {
+                    // Also ends a run of steps, or a fast forward
+                    c_pause.set(true);
+                    c_pause.forget();
+                    match stop.cycle().filter(|_| matches!(reason, StopReason::Repeating(_))) {
+                        Some(cycle) => println!("{}, paused at generation {}", cycle, generation),
+                        None => println!("{} at generation {}, paused", reason, generation)
+                    }
+                    if let Ok(mut n) = c_notice.lock() {
+                        *n = Some((reason.to_string(), Instant::now()));
+                    }
+                }
+            }
+            if let Some(c) = cycles.as_mut() {
+                if let Some(cycle) = c_life.read().ok().and_then(|l| c.observe(&event, l.as_ref())) {
+                    println!("{}", cycle);
+                    if let Ok(mut n) = c_notice.lock() {
+                        *n = Some((format!("Cycle of period {} from generation {}", cycle.period, cycle.start), Instant::now()));
+                    }
+                }
+            }
+            c_pause.done();
+            // Minimum time per generation when a speed cap is set
+            let gps = c_speed.load(Ordering::Relaxed);
+            if gps > 0 {
+                let interval = std::time::Duration::from_secs_f64(1.0 / gps as f64);
+                if let Some(rest) = interval.checked_sub(step_start.elapsed()) {
+                    thread::sleep(rest);
+                }
+            }
+        }
+    });
+
+    // Slot and generation last drawn, to tell when there is something new
+    let mut drawn = None;
+    let mut occluded = false;
+    let mut window_events = false;
+    // Shortest time between frames with a --max-fps, when the last one was
+    // drawn, and whether one was held back until then
+    let frame_time = (limits.max_fps > 0).then(|| Duration::from_secs_f64(1.0 / limits.max_fps as f64));
+    let mut last_frame = Instant::now();
+    let mut held_back = false;
+    // Paused when the window was hidden to save power, to run on once shown
+    let mut resume_when_shown = false;
+    // Where the mouse was when the screensaver started, and whether it has
+    // been woken since
+    let started = Instant::now();
+    let mut resting_pointer: Option<PhysicalPosition<f64>> = None;
+    let mut woken_up = false;
+
+    event_loop.run(move |event, _, control_flow| {
+        match &event {
+            Event::WindowEvent { event: WindowEvent::Occluded(o), window_id } if *window_id == window.id() => occluded = *o,
+            Event::WindowEvent { .. } => window_events = true,
+            Event::UserEvent(()) => woken.store(false, Ordering::Relaxed),
+            _ => {}
+        }
+        if screensaver {
+            match &event {
+                Event::WindowEvent { event: WindowEvent::KeyboardInput { .. } | WindowEvent::MouseInput { .. } | WindowEvent::MouseWheel { .. }, .. } => woken_up = true,
+                Event::WindowEvent { event: WindowEvent::CursorMoved { position, .. }, .. } => {
+                    // The first position is wherever the pointer was left
+                    let from = *resting_pointer.get_or_insert(*position);
+                    woken_up |= (position.x - from.x).hypot(position.y - from.y) > WAKE_DISTANCE;
+                }
+                _ => {}
+            }
+        }
+        // The followers only need to know their size; the rest is the main
+        // window's
+        if let Event::WindowEvent { window_id, event } = &event {
+            if let Some(f) = followers.iter_mut().find(|f| f.id() == *window_id) {
+                if let WindowEvent::Resized(size) = event {
+                    if let Err(err) = f.resize(*size) {
+                        log_error("pixels.resize_surface", &err);
+                    }
+                }
+                return;
+            }
+            #[cfg(feature = "accessibility")]
+            access.on_event(&window, event);
+        }
+        // The one and only event that winit_input_helper doesn't have for us...
+        if let Event::LoopDestroyed = event {
+            trace_guard.take();
+            #[cfg(feature = "audio")]
+            audio_stream.take();
+        }
+        if event == Event::RedrawRequested(window.id()) {
+            let _frame_span = info_span!("frame").entered();
+            last_frame = Instant::now();
+            let theme = if screensaver { screensaver::cycle(&theme, started.elapsed()) } else { theme };
+            drawn = Some((slots.index(), slots.current().generation.load(Ordering::Relaxed)));
+            if let Some(f) = &fast_forward {
+                f.draw(pixels.frame_mut(), width, height, slots.current().generation.load(Ordering::Relaxed));
+                if let Err(err) = pixels.render() {
+                    log_error("pixels.render", &err);
+                    *control_flow = ControlFlow::Exit;
+                }
+                return;
+            }
+            //life.draw(pixels.frame_mut());
+            let shown = slots.current().life.read().ok().map(|l| ((l.width(), l.height()), l.bounding_box()));
+            let view = match shown {
+                Some((grid, bounds)) => {
+                    let slot = slots.current_mut();
+                    // Eases towards the pattern rather than jumping with every generation
+                    if let Some(bb) = bounds.filter(|_| follow) {
+                        slot.view.get_or_insert(View::whole(grid)).approach(View::fit(bb, grid), 0.15);
+                    }
+                    slot.view.unwrap_or_else(|| View::whole(grid))
+                }
+                None => View::whole((1, 1))
+            };
+            timings.time(Phase::Draw, || {
+                if spacetime_on.load(Ordering::Relaxed) {
+                    let camera = &mut slots.current_mut().camera;
+                    if let Ok(h) = history.lock() {
+                        info_span!("draw.spacetime").in_scope(|| spacetime::draw(width, height, pixels.frame_mut(), &h, camera));
+                    }
+                    camera.advance();
+                }
+                else if let Some(h) = heatmap.lock().ok().as_ref().and_then(|h| h.as_ref()) {
+                    info_span!("draw.heatmap").in_scope(|| render::draw_heatmap(width, height, pixels.frame_mut(), h, &theme, &view));
+                }
+                else if let Some(a) = ages.lock().ok().as_ref().and_then(|a| a.as_ref()).filter(|_| isometric) {
+                    if let Ok(life) = slots.current().life.read() {
+                        info_span!("draw.isometric").in_scope(|| render::draw_isometric(width, height, pixels.frame_mut(), life.as_ref(), a, &theme, &view));
+                    }
+                }
+                else if let Some(reference) = &diff {
+                    if let Ok(life) = slots.current().life.read() {
+                        info_span!("draw.diff").in_scope(|| render::draw_diff(width, height, pixels.frame_mut(), life.as_ref(), reference, &theme, &view));
+                    }
+                }
+                else if let Ok(life) = info_span!("lock.read").in_scope(|| slots.current().life.read())
+                {
+                    pixel_map.update(width, height, &view);
+                    info_span!("draw").in_scope(|| render::draw(pixels.frame_mut(), life.as_ref(), &theme, &pixel_map));
+                }
+            });
+            if graph_on {
+                if let Ok(h) = population_history.lock() {
+                    let populations: Vec<usize> = h.iter().map(|s| s.population).collect();
+                    let graph_height = height / 5;
+                    overlay::plot(pixels.frame_mut(), width, height, (0, (height - graph_height) as i32, width, graph_height), &populations, [64, 200, 255, 255]);
+                }
+            }
+            if timeline_on {
+                if let Ok(t) = timeline.lock() {
+                    scrubber::draw(pixels.frame_mut(), width, height, &t, slots.current().generation.load(Ordering::Relaxed));
+                }
+            }
+            if let Some(found) = ships.lock().ok().as_ref().and_then(|f| f.as_ref()) {
+                draw_ships(pixels.frame_mut(), width, height, &view, found);
+            }
+            if let Some(boxes) = &found {
+                draw_found(pixels.frame_mut(), width, height, &view, boxes);
+            }
+            // Cells born and cells died next generation, or None on backends
+            // with rules of their own
+            let mut next = None;
+            if let (true, Ok(l)) = (preview && pause.is_paused(), slots.current().life.read()) {
+                next = Some(l.downcast_ref::<DenseEngine>().is_some().then(|| {
+                    let changes = inspect::changes(l.as_ref());
+                    draw_preview(pixels.frame_mut(), width, height, &view, l.as_ref(), &changes);
+                    let born = changes.iter().filter(|&&(x, y)| l.get(x, y) == CellState::Dead).count();
+                    (born, changes.len() - born)
+                }));
+            }
+            let pointer = input.mouse().and_then(|pos| pixels.window_pos_to_pixel(pos).ok());
+            let grid = shown.map_or((1, 1), |(grid, _)| grid);
+            let cell = pointer.and_then(|p| view.cell_at(p, (width, height), grid));
+            let generation = slots.current().generation.load(Ordering::Relaxed);
+            if let Some(r) = &ruler {
+                r.draw(pixels.frame_mut(), width, height, &view, cell, generation);
+            }
+            let mut status = Vec::new();
+            if let (Some(e), Ok(l)) = (&explainer, slots.current().life.read()) {
+                e.draw(pixels.frame_mut(), width, height, &view, l.as_ref());
+                status.extend(e.status(l.as_ref()));
+            }
+            if let Some(r) = &ruler {
+                status.extend(r.status(cell, generation));
+            }
+            if let Ok(r) = region_stats.lock() {
+                for region in r.regions() {
+                    let bb = region.bounds();
+                    let (x, y) = view.screen_pos((bb.min_x as f64, bb.min_y as f64), (width, height));
+                    let scale = view.scale(width);
+                    let (w, h) = ((bb.width() as f64 * scale) as u32, (bb.height() as f64 * scale) as u32);
+                    overlay::outline(pixels.frame_mut(), width, height, (x, y, w, h), [255, 200, 64, 255]);
+                    overlay::draw_text(pixels.frame_mut(), width, height, x + 2, y + 2, &region.name, [255, 200, 64]);
+                    if let Some(sample) = r.latest(&region.name) {
+                        status.push(format!("{}: population {}, changed {}", region.name, sample.population, sample.changed));
+                    }
+                }
+            }
+            if let Some(show) = demo.as_ref().map(|shows| &shows[showing]) {
+                overlay::caption(pixels.frame_mut(), width, height, &show.pattern.name, show.caption);
+            }
+            if let Some(boxes) = &found {
+                status.push(format!("{} found", boxes.len()));
+            }
+            match next {
+                Some(Some((born, died))) => status.push(format!("next generation: {} born, {} die", born, died)),
+                Some(None) => status.push("no preview on this backend".to_string()),
+                None => {}
+            }
+            if let (Some(snapshot), Ok(l)) = (&diff, slots.current().life.read()) {
+                match snapshot.count(l.as_ref()) {
+                    (0, 0) => status.push(format!("same as generation {}", snapshot.generation)),
+                    (born, died) => status.push(format!("vs generation {}: {} born, {} died", snapshot.generation, born, died))
+                }
+            }
+            if let Ok(l) = slots.current().life.read() {
+                if let Some([prey, predators]) = l.downcast_ref::<SpeciesEngine>().map(SpeciesEngine::populations) {
+                    status.push(format!("prey {}, predators {}", prey, predators));
+                }
+                if let Some(counts) = l.downcast_ref::<SandEngine>().map(SandEngine::counts) {
+                    let shown: Vec<_> = Element::ALL.iter().zip(counts).skip(1).map(|(e, n)| format!("{} {}", e, n)).collect();
+                    status.push(shown.join(", "));
+                }
+                if let Some((feed, kill)) = l.downcast_ref::<ReactionEngine>().map(ReactionEngine::rates) {
+                    status.push(format!("feed {}, kill {}", feed, kill));
+                }
+                if let Some(f) = l.downcast_ref::<ForestEngine>().map(ForestEngine::stats) {
+                    status.push(format!("trees {}, burning {}, strikes {}", f.trees, f.burning, f.strikes));
+                    status.push(format!("growth {}, lightning {}", f.growth, f.lightning));
+                }
+                if let Some((states, threshold)) = l.downcast_ref::<CyclicEngine>().map(CyclicEngine::params) {
+                    status.push(format!("{} states, threshold {}", states, threshold));
+                }
+                if let Some(i) = l.downcast_ref::<IsingEngine>().map(IsingEngine::stats) {
+                    status.push(format!("magnetization {:.3}, energy {:.3}", i.magnetization, i.energy));
+                    status.push(format!("temperature {}, field {}", i.temperature, i.field));
+                }
+                if let Some(rule) = l.downcast_ref::<ElementaryEngine>().map(ElementaryEngine::code) {
+                    status.push(format!("rule {}", rule));
+                }
+                if let Some(g) = l.downcast_ref::<GasEngine>().map(GasEngine::stats) {
+                    status.push(format!("{} particles {}, momentum {:.0}, {:.0}", g.model, g.particles, g.momentum.0, g.momentum.1));
+                }
+                // Beside the population graph, with the other numbers to
+                // keep an eye on over a long run
+                if graph_on {
+                    let in_use = crate::usage::resident().map_or("unknown".to_string(), memory::bytes);
+                    match Estimate::new(l.name(), l.width(), l.height()) {
+                        Some(e) => status.push(format!("memory {} in use, grid {}", in_use, memory::bytes(e.grid + e.scratch))),
+                        None => status.push(format!("memory {} in use", in_use))
+                    }
+                }
+            }
+            if brush != Brush::Cells {
+                let painting = match brush {
+                    Brush::Element(e) => e.to_string(),
+                    other => format!("{:?}", other).to_lowercase()
+                };
+                status.push(format!("painting {}", painting));
+            }
+            let p = noise.lock().map_or(0.0, |n| n.probability());
+            if p > 0.0 {
+                status.push(format!("noise {}", p));
+            }
+            if let Some(w) = period_watch.lock().ok().as_ref().and_then(|w| w.as_ref()) {
+                status.push(w.status());
+            }
+            if let Some(a) = activity.lock().ok().as_ref().and_then(|a| a.as_ref()) {
+                status.push(a.status());
+            }
+            if let Some(g) = growth.lock().ok().as_ref().and_then(|g| g.as_ref()) {
+                status.push(g.status());
+            }
+            overlay::status(pixels.frame_mut(), width, height, &status);
+            if let Ok(mut n) = notice.lock() {
+                match n.as_ref().map(|(text, shown)| (text, shown.elapsed())) {
+                    Some((_, age)) if age > NOTICE_TIME => *n = None,
+                    // Blinks twice a second
+                    Some((text, age)) if age.as_millis() / 250 % 2 == 0 => overlay::banner(pixels.frame_mut(), width, height, text),
+                    _ => {}
+                }
+            }
+            if let Some(((x, y), (px, py))) = cell.zip(pointer).filter(|_| inspector && pause.is_paused() && !browser.is_active()) {
+                let lines = slots.current().life.read().ok().map(|l| {
+                    let age = ages.lock().ok().as_ref().and_then(|a| a.as_ref()).map_or(0, |a| a.get(x, y));
+                    inspection(&inspect::inspect(l.as_ref(), x, y), age)
+                });
+                if let Some(lines) = lines {
+                    overlay::tooltip(pixels.frame_mut(), width, height, (px as i32, py as i32), &lines);
+                }
+            }
+            if let Some(t) = &tutorial {
+                t.draw(pixels.frame_mut(), width, height, &config.keys);
+            }
+            browser.draw(width, height, pixels.frame_mut(), &view, pointer, cell);
+            glossary.draw(width, height, pixels.frame_mut());
+            profiler.draw(pixels.frame_mut(), width, height);
+            palette.draw(width, height, pixels.frame_mut());
+
+            let duration = now.elapsed().as_micros() as f64;
+            if duration >= 1_000_000.0 {
+                println!("FPS: {}", 1_000_000.0*((frames.load(Ordering::Relaxed) as f64)/duration) );
+                frames.store(0, Ordering::SeqCst);
+                now = std::time::Instant::now();
+            }
+
+            //panic!("ENd");
+            if let Err(err) = timings.time(Phase::Present, || info_span!("render").in_scope(|| pixels.render())) {
+                log_error("pixels.render", &err);
+                *control_flow = ControlFlow::Exit;
+                return;
+            }
+            if let Some(life) = slots.current().life.read().ok().filter(|_| !followers.is_empty()) {
+                for f in &mut followers {
+                    if let Err(err) = info_span!("render.follower").in_scope(|| f.draw(life.as_ref(), &theme, &view, window.inner_size())) {
+                        log_error("pixels.render", &err);
+                    }
+                }
+            }
+        }
+
+        // For everything else, for let winit_input_helper collect events to build its state.
+        // It returns `true` when it is time to update our game state and request a redraw.
+        if input.update(&event) {
+            let mut commands = Vec::new();
+            if input.close_requested() {
+                commands.push(Command::Quit);
+            }
+            if let Some(remote) = &remote {
+                commands.extend(remote.try_iter());
+            }
+            if let Some(ipc) = &ipc {
+                commands.extend(ipc.try_iter());
+            }
+            if let Some(hosted) = &hosted {
+                commands.extend(hosted.try_iter());
+            }
+            #[cfg(feature = "gamepad")]
+            let steering = gamepad.as_mut().is_some_and(|pad| {
+                commands.extend(pad.commands());
+                let slot = slots.current_mut();
+                let Ok(grid) = slot.life.read().map(|l| (l.width(), l.height())) else { return false };
+                let view = slot.view.get_or_insert(View::whole(grid));
+                pad.steer(view, grid)
+            });
+            #[cfg(not(feature = "gamepad"))]
+            let steering = false;
+            // The stick takes over from following the pattern
+            if steering {
+                follow = false;
+            }
+            let pointer = input.mouse().and_then(|pos| pixels.window_pos_to_pixel(pos).ok());
+            if screensaver {
+                if woken_up {
+                    commands.push(Command::Quit);
+                }
+                if restless.swap(false, Ordering::Relaxed) {
+                    match screensaver::pick(config.patterns_dir().as_deref(), &mut rand::thread_rng()) {
+                        Some(pattern) => start_over(&slots, &pattern.cells, None, &timeline, &stop),
+                        None => commands.push(Command::Randomize)
+                    }
+                }
+            } else if wallpaper {
+                // Keys and the mouse belong to the desktop
+            } else if palette.is_open() {
+                commands.extend(palette.update(&input));
+            } else if glossary.is_open() {
+                if let Some(pattern) = glossary.update(&input) {
+                    browser.hold(pattern, None);
+                }
+            } else if browser.is_active() {
+                let slot = slots.current();
+                let cell = pointer.zip(slot.life.read().ok()).and_then(|(p, l)| {
+                    let grid = (l.width(), l.height());
+                    slot.view.unwrap_or_else(|| View::whole(grid)).cell_at(p, (width, height), grid)
+                });
+                if input.key_pressed(config.keys.recent) {
+                    commands.push(Command::NextRecent);
+                } else if let Some(paste) = browser.update(&input, width, height, pointer, cell) {
+                    if let Some(item) = browser.held() {
+                        remember(&mut recent, recent_path.as_deref(), item);
+                    }
+                    commands.push(paste);
+                }
+            } else if input.held_control() && input.key_pressed(VirtualKeyCode::P) {
+                palette.open();
+            } else {
+                let keys = &config.keys;
+                if input.key_pressed(VirtualKeyCode::Escape) {
+                    commands.push(Command::Quit);
+                }
+                if input.key_pressed(keys.pause) {
+                    commands.push(Command::TogglePause);
+                }
+                if input.key_pressed_os(keys.step) {
+                    commands.push(if explainer.is_some() { Command::ExplainStep } else { Command::Step(1) });
+                }
+                if input.key_pressed(keys.randomize) {
+                    commands.push(Command::Randomize);
+                }
+                for (i, key) in SLOT_KEYS.iter().enumerate() {
+                    if input.key_pressed(*key) {
+                        commands.push(match (input.held_control(), input.held_shift()) {
+                            (false, _) => Command::SwitchSlot(i),
+                            (true, false) => Command::GoToBookmark((i + 1).to_string()),
+                            (true, true) => Command::SaveBookmark { number: i + 1, name: String::new() }
+                        });
+                    }
+                }
+                if input.key_pressed(keys.save_config) {
+                    commands.push(Command::SaveConfig);
+                }
+                if input.key_pressed(keys.grow) {
+                    commands.push(Command::Grow { keep_corner: input.held_shift() });
+                } else if input.key_pressed(keys.shrink) {
+                    commands.push(Command::Shrink { keep_corner: input.held_shift() });
+                }
+                if input.key_pressed(keys.spacetime) {
+                    commands.push(Command::ToggleSpacetime);
+                }
+                if input.key_pressed(keys.graph) {
+                    commands.push(Command::TogglePopulationGraph);
+                }
+                if input.key_pressed(keys.period) {
+                    commands.push(Command::TogglePeriodDetection);
+                }
+                if input.key_pressed(keys.ships) {
+                    commands.push(Command::ToggleShips);
+                }
+                if input.key_pressed(keys.activity) {
+                    commands.push(Command::ToggleActivity);
+                }
+                if input.key_pressed(keys.growth) {
+                    commands.push(Command::ToggleGrowth);
+                }
+                if input.key_pressed(keys.heatmap) {
+                    commands.push(Command::ToggleHeatmap);
+                }
+                if input.key_pressed(keys.isometric) {
+                    commands.push(Command::ToggleIsometric);
+                }
+                if input.key_pressed(keys.browser) {
+                    commands.push(Command::ToggleBrowser);
+                }
+                if input.key_pressed(keys.recent) {
+                    commands.push(Command::NextRecent);
+                }
+                if input.key_pressed(keys.timeline) {
+                    commands.push(Command::ToggleTimeline);
+                }
+                if input.key_pressed(keys.diff) {
+                    commands.push(Command::ToggleDiff);
+                }
+                if input.key_pressed(keys.ruler) {
+                    commands.push(Command::ToggleRuler);
+                }
+                if input.key_pressed(keys.inspector) {
+                    commands.push(Command::ToggleInspector);
+                }
+                if input.key_pressed(keys.preview) {
+                    commands.push(Command::TogglePreview);
+                }
+                if input.key_pressed(keys.explain) {
+                    commands.push(Command::ToggleExplain);
+                }
+                if input.key_pressed(keys.tutorial) {
+                    commands.push(Command::ToggleTutorial);
+                }
+                if input.key_pressed(keys.lexicon) {
+                    commands.push(Command::ToggleLexicon);
+                }
+                if input.key_pressed(keys.profiler) {
+                    commands.push(Command::ToggleProfiler);
+                }
+                if input.key_pressed(keys.announce) {
+                    commands.push(Command::Announce);
+                }
+                if input.key_pressed(keys.fit) {
+                    commands.push(Command::FitView);
+                }
+                if input.key_pressed(keys.follow) {
+                    commands.push(Command::ToggleFollow);
+                }
+                if input.key_pressed(keys.whole_grid) {
+                    commands.push(Command::ShowWholeGrid);
+                }
+                if input.key_pressed(keys.walls) {
+                    commands.push(Command::ToggleWallBrush);
+                }
+                for (key, nudge) in [(keys.warmer, ISING_NUDGE), (keys.cooler, -ISING_NUDGE)] {
+                    if input.key_pressed(key) && config.backend == "ising" {
+                        // Rounded so repeated nudges don't pile up float error
+                        let round = |v: f64| (v * 1000.0).round() / 1000.0;
+                        let (temperature, field) = if input.held_shift() {
+                            (config.temperature, round(config.field + nudge))
+                        } else {
+                            (round(config.temperature + nudge).max(0.0), config.field)
+                        };
+                        commands.push(Command::SetIsing { temperature, field });
+                    }
+                }
+
+                // Left button paints live cells, right button dead ones.  With
+                // the wall brush they paint walls and holes, and the middle
+                // button removes them.
+                let held = [(0, Some(true)), (1, Some(false)), (2, None)].into_iter().find(|(button, _)| input.mouse_held(*button));
+                let on_timeline = timeline_on && pointer.is_some_and(|p| scrubber::contains(p, height));
+                match (held, pointer) {
+                    // Dragging along the timeline goes to the generation under the mouse
+                    (Some((0, _)), Some(p)) if on_timeline => {
+                        let generation = slots.current().generation.load(Ordering::Relaxed);
+                        let seek = timeline.lock().ok()
+                            .and_then(|t| scrubber::generation_at(&t, generation, p, width, height))
+                            .map(Command::Seek);
+                        if seek.is_some() && last_paint != seek {
+                            commands.extend(seek.clone());
+                            last_paint = seek;
+                        }
+                    }
+                    (Some((_, alive)), Some((px, py))) if !spacetime_on.load(Ordering::Relaxed) && !on_timeline => {
+                        let slot = slots.current();
+                        let under = slot.life.read().ok().and_then(|l| {
+                            let grid = (l.width(), l.height());
+                            slot.view.unwrap_or_else(|| View::whole(grid)).cell_at((px, py), (width, height), grid)
+                        });
+                        // With the ruler out, clicks put its ends down instead
+                        if let Some((x, y)) = under.filter(|_| ruler.is_some() && input.mouse_pressed(0)) {
+                            commands.push(Command::Measure { x, y });
+                        }
+                        // And in explain mode they pick the cell to explain
+                        if let Some((x, y)) = under.filter(|_| explainer.is_some() && ruler.is_none() && input.mouse_pressed(0)) {
+                            commands.push(Command::Explain { x, y });
+                        }
+                        let paint = match (under, alive) {
+                            _ if ruler.is_some() || explainer.is_some() => None,
+                            (Some((x, y)), alive) if brush == Brush::Walls => Some(Command::SetWall { x, y, alive }),
+                            (Some((x, y)), Some(true)) if brush == Brush::Predators => Some(Command::SetSpecies { x, y, species: Species::Predator }),
+                            (Some((x, y)), Some(true)) => match brush {
+                                Brush::Element(element) => Some(Command::SetElement { x, y, element }),
+                                _ => Some(Command::SetCell { x, y, alive: true })
+                            },
+                            (Some((x, y)), Some(alive)) => Some(Command::SetCell { x, y, alive }),
+                            _ => None
+                        };
+                        if paint.is_some() && last_paint != paint {
+                            commands.extend(paint.clone());
+                            last_paint = paint;
+                        }
+                    }
+                    (None, _) => last_paint = None,
+                    _ => {}
+                }
+
+                let camera = &mut slots.current_mut().camera;
+                if input.key_held(VirtualKeyCode::Left) {
+                    camera.yaw -= 0.05;
+                }
+                if input.key_held(VirtualKeyCode::Right) {
+                    camera.yaw += 0.05;
+                }
+                if input.key_held(VirtualKeyCode::Up) {
+                    camera.pitch = (camera.pitch + 0.05).min(1.5);
+                }
+                if input.key_held(VirtualKeyCode::Down) {
+                    camera.pitch = (camera.pitch - 0.05).max(-1.5);
+                }
+            }
+
+            // The demo moves on when the pattern has had its time or settled
+            if let Some(shows) = &demo {
+                if restless.swap(false, Ordering::Relaxed) || slots.current().generation.load(Ordering::Relaxed) >= shows[showing].generations {
+                    showing = (showing + 1) % shows.len();
+                    start_over(&slots, &shows[showing].pattern.cells, None, &timeline, &stop);
+                    println!("{}", shows[showing].pattern.name);
+                }
+            }
+
+            // Hotkeys and the palette both end up here
+            if explainer.as_mut().is_some_and(|e| e.sweep_done()) {
+                pause.step(1);
+            }
+            let mut changed = !commands.is_empty();
+            let stopped = notice.lock().ok().and_then(|n| n.as_ref().map(|(text, at)| (text.clone(), *at)));
+            if let Some((text, at)) = stopped.filter(|(_, at)| announced_notice != Some(*at)) {
+                announced_notice = Some(at);
+                announcer.say(format!("{} at generation {}, paused", text, slots.current().generation.load(Ordering::Relaxed)));
+            }
+            #[cfg(feature = "online")]
+            for result in fetched.try_iter() {
+                match result {
+                    Ok(pattern) => browser.hold(pattern, None),
+                    Err(e) => log::error!("Could not fetch pattern: {}", e)
+                }
+                changed = true;
+            }
+            // Kept for the tutorial to see what was done
+            let issued = tutorial.as_ref().map(|_| commands.clone());
+            for command in commands {
+                // Joined clients send their edits to the host, which does
+                // all the stepping, and do only what stays local themselves
+                if let Some(client) = joined.as_mut().filter(|_| !command.is_local()) {
+                    let edit = match &command {
+                        Command::SetCell { x, y, alive } => Ok(Edit::Paint { x: *x, y: *y, alive: *alive }),
+                        Command::Paste { pattern, at } => slots.current().life.read().map_err(|e| e.to_string()).map(|l| {
+                            let (x, y) = at.unwrap_or_else(|| centred(l.as_ref(), pattern));
+                            Edit::Paste { x, y, pattern: pattern.clone() }
+                        }),
+                        Command::LoadPattern(path) => slots.current().life.read().map_err(|e| e.to_string())
+                            .and_then(|l| load_pattern(l.as_ref(), path))
+                            .map(|[_, paste]| paste),
+                        Command::ShowBuiltin(name) => library::builtin().into_iter().find(|p| p.name == *name).ok_or_else(|| format!("No built in pattern {}", name))
+                            .and_then(|p| slots.current().life.read().map_err(|e| e.to_string()).map(|l| {
+                                let pattern = io::cells_to_plaintext(&p.cells);
+                                let (x, y) = centred(l.as_ref(), &pattern);
+                                Edit::Paste { x, y, pattern }
+                            })),
+                        _ => {
+                            println!("Only the host can do that");
+                            continue;
+                        }
+                    };
+                    match edit {
+                        Ok(edit) => {
+                            if let Err(e) = client.send(&edit) {
+                                log::error!("Could not reach the host: {}", e);
+                            }
+                        }
+                        Err(e) => log::error!("{}", e)
+                    }
+                    continue;
+                }
+
+                let record = slots.index() == 0;
+                match command {
+                    Command::Quit => {
+                        // A full screen or wallpaper window isn't one to come back to
+                        if let Some(path) = session_path.as_ref().filter(|_| !screensaver && !wallpaper) {
+                            if let Err(e) = current_session(&window, &slots, &config, &bookmarks).save(path) {
+                                log::error!("Could not save session: {}", e);
+                            }
+                        }
+                        if let (Some(path), Some(recorder)) = (&args.record, &recorder) {
+                            let saved = recorder.lock().map_err(|e| e.to_string()).and_then(|r| r.replay().save(path));
+                            match saved {
+                                Ok(()) => println!("Saved replay to {}", path.display()),
+                                Err(e) => log::error!("Could not save replay: {}", e)
+                            }
+                        }
+                        #[cfg(unix)]
+                        if let Some(path) = &args.ipc {
+                            let _ = std::fs::remove_file(path);
+                        }
+                        *control_flow = ControlFlow::Exit;
+                        return;
+                    }
+                    Command::TogglePause | Command::SetPaused(_) => {
+                        match command {
+                            Command::SetPaused(p) => pause.set(p),
+                            _ => pause.toggle()
+                        }
+                        let generation = slots.current().generation.load(Ordering::Relaxed);
+                        announcer.say(if pause.is_paused() { format!("Paused at generation {}", generation) } else { "Running".to_string() });
+                    }
+                    Command::Announce => {
+                        let slot = slots.current();
+                        let population = slot.life.read().map_or(0, |l| l.population());
+                        let state = if pause.is_paused() { "paused" } else { "running" };
+                        announcer.say(format!("Generation {}, population {}, {}", slot.generation.load(Ordering::Relaxed), population, state));
+                    }
+                    Command::Step(n) => {
+                        pause.step(n);
+                    }
+                    Command::Randomize => {
+                        let seed = rand::random();
+                        let edit = Edit::Randomize { density: config.density, seed, symmetry: config.symmetry, size: config.soup_size, layout: config.layout.clone() };
+                        let slot = slots.current_mut();
+                        let recorder = recorder.as_ref().filter(|_| record);
+                        if let Ok(mut l) = slot.life.write() {
+                            // The soup starts again from generation 0, so
+                            // edits after it are recorded from there
+                            let generation = slot.generation.swap(0, Ordering::Relaxed);
+                            if let Some(Ok(mut r)) = recorder.map(|r| r.lock()) {
+                                r.jump(generation, 0);
+                            }
+                            apply_edit(l.as_mut(), edit, 0, recorder, &timeline);
+                        }
+                        slot.seed = Some(seed);
+                        if let Ok(mut h) = population_history.lock() {
+                            h.clear();
+                        }
+                        if let Some(w) = period_watch.lock().ok().as_mut().and_then(|w| w.as_mut()) {
+                            w.restart();
+                        }
+                        if let Some(a) = activity.lock().ok().as_mut().and_then(|a| a.as_mut()) {
+                            a.restart();
+                        }
+                        if let Some(g) = growth.lock().ok().as_mut().and_then(|g| g.as_mut()) {
+                            g.restart();
+                        }
+                        if let Ok(mut s) = stop.lock() {
+                            s.restart();
+                        }
+                        if let Some(h) = heatmap.lock().ok().as_mut().and_then(|h| h.as_mut()) {
+                            h.clear();
+                        }
+                        if let Some(a) = ages.lock().ok().as_mut().and_then(|a| a.as_mut()) {
+                            a.clear();
+                        }
+                        if let Ok(mut r) = region_stats.lock() {
+                            r.clear();
+                        }
+                        restart_timeline(&timeline, &slots);
+                        println!("Seed: {}", seed);
+                    }
+                    Command::SwitchSlot(i) => {
+                        let switched = slots.switch_to(i, || {
+                            let seed = rand::random();
+                            let l = config.simulation()?.start(config.soup(seed)).build()?;
+                            Ok(Slot::new(Arc::new(RwLock::new(l)), Some(seed)))
+                        });
+                        match switched {
+                            Ok(true) => {
+                                if let Ok(mut h) = history.lock() {
+                                    h.clear();
+                                }
+                                if let Ok(mut h) = population_history.lock() {
+                                    h.clear();
+                                }
+                                if let Some(w) = period_watch.lock().ok().as_mut().and_then(|w| w.as_mut()) {
+                                    w.restart();
+                                }
+                                if let Some(a) = activity.lock().ok().as_mut().and_then(|a| a.as_mut()) {
+                                    a.restart();
+                                }
+                                if let Some(g) = growth.lock().ok().as_mut().and_then(|g| g.as_mut()) {
+                                    g.restart();
+                                }
+                                if let Ok(mut s) = stop.lock() {
+                                    s.restart();
+                                }
+                                if let Some(h) = heatmap.lock().ok().as_mut().and_then(|h| h.as_mut()) {
+                                    h.clear();
+                                }
+                                if let Some(a) = ages.lock().ok().as_mut().and_then(|a| a.as_mut()) {
+                                    a.clear();
+                                }
+                                if let Ok(mut r) = region_stats.lock() {
+                                    r.clear();
+                                }
+                                restart_timeline(&timeline, &slots);
+                                println!("Slot {}", i + 1);
+                            }
+                            Ok(false) => {}
+                            Err(e) => log::error!("Could not create slot {}: {}", i + 1, e)
+                        }
+                    }
+                    Command::SaveConfig => match &config_path {
+                        Some(path) => match config.save(path) {
+                            Ok(()) => println!("Saved settings to {}", path.display()),
+                            Err(e) => log::error!("Could not save settings: {}", e)
+                        },
+                        None => log::error!("No config directory available, settings not saved")
+                    },
+                    Command::Grow { keep_corner } | Command::Shrink { keep_corner } => {
+                        let factor = if matches!(command, Command::Grow { .. }) { 2.0 } else { 0.5 };
+                        let anchor = if keep_corner { Anchor::TopLeft } else { Anchor::Center };
+                        let slot = slots.current();
+                        if let Ok(mut l) = slot.life.write() {
+                            let w = ((l.width() as f64 * factor) as usize).clamp(MIN_GRID, MAX_GRID);
+                            let h = ((l.height() as f64 * factor) as usize).clamp(MIN_GRID, MAX_GRID);
+                            // Held to the same limit as a grid asked for at startup
+                            let fits = Estimate::new(l.name(), w, h).map_or(Ok(()), |e| e.check(config.memory_limit()));
+                            if let Err(e) = fits {
+                                log::error!("Could not resize to {}x{}: {}; raise --max-memory to allow it", w, h, e);
+                                continue;
+                            }
+                            let edit = Edit::Resize { width: w, height: h, anchor };
+                            apply_edit(l.as_mut(), edit, slot.generation.load(Ordering::Relaxed), recorder.as_ref().filter(|_| record), &timeline);
+                            config.grid_width = w;
+                            config.grid_height = h;
+                            println!("Grid: {}x{}", w, h);
+                        }
+                        // The space-time history, the timeline and the view refer to the old grid
+                        if let Ok(mut h) = history.lock() {
+                            h.clear();
+                        }
+                        restart_timeline(&timeline, &slots);
+                        slots.current_mut().view = None;
+                    }
+                    Command::ToggleSpacetime => {
+                        let on = !spacetime_on.load(Ordering::Relaxed);
+                        if let Ok(mut h) = history.lock() {
+                            h.clear();
+                        }
+                        spacetime_on.store(on, Ordering::Relaxed);
+                    }
+                    Command::TogglePopulationGraph => graph_on = !graph_on,
+                    Command::TogglePeriodDetection => {
+                        if let Ok(mut w) = period_watch.lock() {
+                            *w = match *w {
+                                Some(_) => None,
+                                None => Some(PeriodWatch::new(None))
+                            };
+                        }
+                    }
+                    Command::ToggleShips => {
+                        if let Ok(mut f) = ships.lock() {
+                            *f = match *f {
+                                Some(_) => None,
+                                None => Some(Vec::new())
+                            };
+                        }
+                    }
+                    Command::ToggleActivity => {
+                        if let Ok(mut a) = activity.lock() {
+                            *a = match *a {
+                                Some(_) => None,
+                                None => Some(ActivityWatch::default())
+                            };
+                        }
+                    }
+                    Command::ToggleGrowth => {
+                        if let Ok(mut g) = growth.lock() {
+                            *g = match *g {
+                                Some(_) => None,
+                                None => Some(GrowthWatch::default())
+                            };
+                        }
+                    }
+                    Command::FitView => {
+                        let slot = slots.current_mut();
+                        if let Ok(l) = slot.life.read() {
+                            slot.view = l.bounding_box().map(|bb| View::fit(bb, (l.width(), l.height())));
+                        }
+                    }
+                    Command::ToggleFollow => follow = !follow,
+                    Command::ShowWholeGrid => {
+                        follow = false;
+                        slots.current_mut().view = None;
+                    }
+                    Command::ToggleHeatmap => {
+                        if let Ok(mut h) = heatmap.lock() {
+                            *h = match *h {
+                                Some(_) => None,
+                                None => Some(Heatmap::new())
+                            };
+                        }
+                    }
+                    Command::ToggleIsometric | Command::ToggleInspector => {
+                        match command {
+                            Command::ToggleIsometric => isometric = !isometric,
+                            _ => inspector = !inspector
+                        }
+                        if let Ok(mut a) = ages.lock() {
+                            *a = match (isometric || inspector, a.take()) {
+                                (true, kept) => Some(kept.unwrap_or_else(Ages::new)),
+                                (false, _) => None
+                            };
+                        }
+                    }
+                    Command::TogglePreview => preview = !preview,
+                    Command::ToggleBrowser => {
+                        if browser.is_active() {
+                            browser.close();
+                        } else {
+                            browser.open(config.patterns_dir().as_deref(), recent.items());
+                        }
+                    }
+                    Command::SaveBookmark { number, name } => {
+                        let slot = slots.current();
+                        let grid = slot.life.read().map(|l| (l.width(), l.height())).unwrap_or((1, 1));
+                        let view = slot.view.unwrap_or_else(|| View::whole(grid));
+                        let old = bookmarks.iter().position(|b| b.number == number).map(|i| bookmarks.remove(i));
+                        let name = match (name.is_empty(), old) {
+                            (false, _) => name,
+                            (true, Some(old)) => old.name,
+                            (true, None) => format!("bookmark {}", number)
+                        };
+                        println!("Bookmarked {}: {}", number, name);
+                        bookmarks.push(Bookmark { number, name, view });
+                        bookmarks.sort_by_key(|b| b.number);
+                    }
+                    Command::GoToBookmark(query) => {
+                        let found = bookmarks.iter()
+                            .find(|b| b.number.to_string() == query || b.name.eq_ignore_ascii_case(&query));
+                        match found {
+                            Some(b) => {
+                                follow = false;
+                                slots.current_mut().view = Some(b.view);
+                                println!("{}: {}", b.number, b.name);
+                            }
+                            None => println!("No bookmark {}", query)
+                        }
+                    }
+                    Command::ToggleTimeline => timeline_on = !timeline_on,
+                    Command::Seek(target) => {
+                        pause.settle();
+                        let slot = slots.current();
+                        let reached = match (slot.life.write(), timeline.lock()) {
+                            (Ok(mut l), Ok(t)) => t.seek(l.as_mut(), target).inspect(|&generation| {
+                                // Recorded as the cells it went back to, with
+                                // later edits counted on from before the seek
+                                let from = slot.generation.swap(generation, Ordering::Relaxed);
+                                if let Some(Ok(mut r)) = recorder.as_ref().filter(|_| record).map(|r| r.lock()) {
+                                    r.jump(from, generation);
+                                    let cells = io::cells_to_rle(&io::live_cells(l.as_ref()));
+                                    r.record(generation, Edit::Seek { generation, cells, settings: Settings::of(l.as_ref()) });
+                                }
+                            }),
+                            _ => None
+                        };
+                        match reached {
+                            Some(_) => {
+                                // What they watched no longer follows on
+                                if let Some(w) = period_watch.lock().ok().as_mut().and_then(|w| w.as_mut()) {
+                                    w.restart();
+                                }
+                                if let Some(a) = activity.lock().ok().as_mut().and_then(|a| a.as_mut()) {
+                                    a.restart();
+                                }
+                                if let Some(g) = growth.lock().ok().as_mut().and_then(|g| g.as_mut()) {
+                                    g.restart();
+                                }
+                                if let Ok(mut s) = stop.lock() {
+                                    s.restart();
+                                }
+                            }
+                            None => println!("Nothing on the timeline to go back to")
+                        }
+                    }
+                    Command::FastForward(to) => {
+                        let from = slots.current().generation.load(Ordering::Relaxed);
+                        if to <= from {
+                            println!("Already at generation {}; go back with the timeline", from);
+                            continue;
+                        }
+                        pause.forget();
+                        pause.step(to - from);
+                        // A second one goes on to its own generation at the speed before the first
+                        let speed = fast_forward.take().map_or_else(|| speed.swap(0, Ordering::Relaxed), |f| f.speed);
+                        fast_forward = Some(FastForward { from, to, started: Instant::now(), speed });
+                    }
+                    Command::ToggleTutorial => {
+                        tutorial = match tutorial {
+                            Some(_) => None,
+                            None => Some(Tutorial::default())
+                        };
+                    }
+                    Command::ToggleLexicon => {
+                        if glossary.is_open() {
+                            glossary.close();
+                        } else {
+                            glossary.open(config.lexicon_path().as_deref());
+                        }
+                    }
+                    Command::ToggleProfiler => profiler.toggle(),
+                    Command::ToggleExplain => {
+                        explainer = match explainer {
+                            Some(_) => None,
+                            None => {
+                                pause.set(true);
+                                Some(Explainer::default())
+                            }
+                        };
+                    }
+                    Command::Explain { x, y } => {
+                        explainer.get_or_insert_with(Explainer::default).select((x, y));
+                    }
+                    Command::ExplainStep => {
+                        let e = explainer.get_or_insert_with(Explainer::default);
+                        pause.settle();
+                        // A second press skips the rest of the sweep
+                        let swept = match slots.current().life.read() {
+                            Ok(l) if !e.is_sweeping() => !e.start_sweep(l.as_ref()),
+                            _ => true
+                        };
+                        if swept {
+                            e.stop_sweep();
+                            pause.step(1);
+                        }
+                    }
+                    Command::ToggleRuler => {
+                        ruler = match ruler {
+                            Some(_) => None,
+                            None => Some(Ruler::default())
+                        };
+                    }
+                    Command::Measure { x, y } => {
+                        let generation = slots.current().generation.load(Ordering::Relaxed);
+                        ruler.get_or_insert_with(Ruler::default).click((x, y), generation);
+                    }
+                    Command::ToggleDiff => {
+                        diff = match diff {
+                            Some(_) => None,
+                            None => {
+                                let slot = slots.current();
+                                slot.life.read().ok().map(|l| Reference::of(l.as_ref(), slot.generation.load(Ordering::Relaxed)))
+                            }
+                        };
+                    }
+                    Command::CompareWith { generation, relative } => {
+                        let slot = slots.current();
+                        let target = if relative { slot.generation.load(Ordering::Relaxed) as i64 + generation } else { generation };
+                        // Worked out from the timeline in a universe of its own
+                        let reference = u64::try_from(target).map_err(|_| format!("generation {} is before the start", target))
+                            .and_then(|target| {
+                                let l = slot.life.read().map_err(|e| e.to_string())?;
+                                let mut other = engine::create(l.name(), l.width(), l.height())
+                                    .ok_or_else(|| format!("unknown backend '{}'", l.name()))?;
+                                // The keyframe puts back the rule and the backend's
+                                // parameters, but the walls and a plugin rule aren't
+                                // kept on the timeline, so they are the current ones
+                                for (x, y) in (0..l.height()).flat_map(|y| (0..l.width()).map(move |x| (x, y))) {
+                                    if let Some(wall) = l.wall(x, y) {
+                                        other.set_wall(x, y, Some(wall));
+                                    }
+                                }
+                                if let Some(d) = l.downcast_ref::<DenseEngine>() {
+                                    other.set_custom_rule(d.state().custom_rule.clone());
+                                }
+                                let t = timeline.lock().map_err(|e| e.to_string())?;
+                                let reached = t.seek(other.as_mut(), target).ok_or("nothing on the timeline to compare with")?;
+                                Ok(Reference::of(other.as_ref(), reached))
+                            });
+                        match reference {
+                            Ok(s) => {
+                                println!("Comparing with generation {}", s.generation);
+                                diff = Some(s);
+                            }
+                            Err(e) => log::error!("Could not compare: {}", e)
+                        }
+                    }
+                    Command::NextRecent => {
+                        // The one after the pattern held, if it is a recent one
+                        let items = recent.items();
+                        let next = browser.held().and_then(|h| items.iter().position(|i| *i == h)).map_or(0, |i| i + 1);
+                        match items.get(next).or(items.first()) {
+                            Some(item) => match item.resolve() {
+                                Ok(pattern) => browser.hold(pattern, Some(item.clone())),
+                                Err(e) => log::error!("Could not pick up recent pattern: {}", e)
+                            },
+                            None => println!("No recent patterns yet")
+                        }
+                    }
+                    Command::FetchPattern(query) => {
+                        match library::find(&query) {
+                            Some(pattern) => browser.hold(pattern, None),
+                            #[cfg(feature = "online")]
+                            None => crate::online::lookup_in_background(query, fetched_tx.clone()),
+                            #[cfg(not(feature = "online"))]
+                            None => log::error!("No built in pattern called {}; searching LifeWiki needs the online feature", query)
+                        }
+                    }
+                    Command::DetectPeriod(region) => {
+                        if let Ok(mut w) = period_watch.lock() {
+                            *w = Some(PeriodWatch::new(region));
+                        }
+                    }
+                    Command::LoadPattern(path) => {
+                        let slot = slots.current();
+                        if let Ok(mut l) = slot.life.write() {
+                            match load_pattern(l.as_ref(), &path) {
+                                Ok(edits) => {
+                                    for edit in edits {
+                                        apply_edit(l.as_mut(), edit, slot.generation.load(Ordering::Relaxed), recorder.as_ref().filter(|_| record), &timeline);
+                                    }
+                                    println!("Loaded {}", path);
+                                    // Absolute, so it still works from another directory
+                                    let path = std::fs::canonicalize(&path).map_or(path, |p| p.display().to_string());
+                                    remember(&mut recent, recent_path.as_deref(), RecentItem::File { path });
+                                }
+                                Err(e) => log::error!("Could not load pattern: {}", e)
+                            }
+                        }
+                        if let Ok(mut s) = stop.lock() {
+                            s.restart();
+                        }
+                    }
+                    Command::SaveSnapshot(path) => {
+                        let slot = slots.current();
+                        let saved = slot.life.read().map(|l| Snapshot::of(slot.generation.load(Ordering::Relaxed), l.as_ref())).map_err(|e| e.to_string());
+                        match saved.and_then(|s| s.save(Path::new(&path)).map(|()| s)) {
+                            Ok(s) => println!("Saved generation {} to {}, cells packed into {}", s.generation, path, memory::bytes(s.size() as u64)),
+                            Err(e) => log::error!("Could not save snapshot: {}", e)
+                        }
+                    }
+                    Command::LoadSnapshot(path) => {
+                        pause.settle();
+                        let saved = match Snapshot::load(Path::new(&path)) {
+                            Ok(s) => s,
+                            Err(e) => {
+                                log::error!("Could not load snapshot: {}", e);
+                                continue;
+                            }
+                        };
+                        // The size comes from the file, so it is held to the same
+                        // limits as resizing and a grid asked for at startup
+                        let grid = MIN_GRID..=MAX_GRID;
+                        if !grid.contains(&saved.width) || !grid.contains(&saved.height) {
+                            log::error!("Could not load snapshot: a {}x{} grid is outside {}..{} cells a side", saved.width, saved.height, MIN_GRID, MAX_GRID);
+                            continue;
+                        }
+                        let slot = slots.current();
+                        if let Ok(mut l) = slot.life.write() {
+                            if (l.name(), l.width(), l.height()) != (saved.backend.as_str(), saved.width, saved.height) {
+                                let fits = Estimate::new(&saved.backend, saved.width, saved.height).map_or(Ok(()), |e| e.check(config.memory_limit()));
+                                if let Err(e) = fits {
+                                    log::error!("Could not load snapshot: {}x{} grid: {}; raise --max-memory to load it", saved.width, saved.height, e);
+                                    continue;
+                                }
+                            }
+                            if l.name() != saved.backend {
+                                // Taken on another backend, so nothing of this one's
+                                // carries over
+                                match engine::create(&saved.backend, saved.width, saved.height) {
+                                    Some(other) => *l = other,
+                                    None => {
+                                        log::error!("Could not load snapshot: unknown backend '{}'", saved.backend);
+                                        continue;
+                                    }
+                                }
+                                config.backend = saved.backend.clone();
+                            } else if (l.width(), l.height()) != (saved.width, saved.height) {
+                                let edit = Edit::Resize { width: saved.width, height: saved.height, anchor: Anchor::TopLeft };
+                                apply_edit(l.as_mut(), edit, slot.generation.load(Ordering::Relaxed), recorder.as_ref().filter(|_| record), &timeline);
+                            }
+                            (config.grid_width, config.grid_height) = (saved.width, saved.height);
+                            if let Err(e) = saved.restore(l.as_mut()) {
+                                log::error!("Could not load snapshot: {}", e);
+                                continue;
+                            }
+                            if record {
+                                log::warn!("The replay being recorded does not have the snapshot in it");
+                            }
+                        }
+                        slot.generation.store(saved.generation, Ordering::Relaxed);
+                        println!("Loaded generation {} of a {}x{} grid from {}", saved.generation, saved.width, saved.height, path);
+                        // What they watched no longer follows on
+                        if let Ok(mut h) = history.lock() {
+                            h.clear();
+                        }
+                        if let Some(w) = period_watch.lock().ok().as_mut().and_then(|w| w.as_mut()) {
+                            w.restart();
+                        }
+                        if let Some(a) = activity.lock().ok().as_mut().and_then(|a| a.as_mut()) {
+                            a.restart();
+                        }
+                        if let Some(g) = growth.lock().ok().as_mut().and_then(|g| g.as_mut()) {
+                            g.restart();
+                        }
+                        if let Ok(mut s) = stop.lock() {
+                            s.restart();
+                        }
+                        restart_timeline(&timeline, &slots);
+                        slots.current_mut().view = None;
+                    }
+                    Command::ShowBuiltin(name) => match library::builtin().into_iter().find(|p| p.name == name) {
+                        Some(pattern) => {
+                            start_over(&slots, &pattern.cells, None, &timeline, &stop);
+                            println!("Showing {}", pattern.name);
+                            announcer.say(pattern.name);
+                        }
+                        None => log::error!("No built in pattern {}", name)
+                    },
+                    Command::SetCell { x, y, alive } => {
+                        let slot = slots.current();
+                        if let Ok(mut l) = slot.life.write() {
+                            apply_edit(l.as_mut(), Edit::Paint { x, y, alive }, slot.generation.load(Ordering::Relaxed), recorder.as_ref().filter(|_| record), &timeline);
+                        }
+                    }
+                    Command::SetWall { x, y, alive } => {
+                        let slot = slots.current();
+                        if let Ok(mut l) = slot.life.write() {
+                            apply_edit(l.as_mut(), Edit::Wall { x, y, alive }, slot.generation.load(Ordering::Relaxed), recorder.as_ref().filter(|_| record), &timeline);
+                        }
+                    }
+                    Command::SetSpecies { x, y, species } => {
+                        let slot = slots.current();
+                        if let Ok(mut l) = slot.life.write() {
+                            apply_edit(l.as_mut(), Edit::Species { x, y, species }, slot.generation.load(Ordering::Relaxed), recorder.as_ref().filter(|_| record), &timeline);
+                        }
+                    }
+                    Command::ToggleWallBrush => {
+                        brush = brush.toggle(Brush::Walls);
+                        println!("Painting {:?}", brush);
+                    }
+                    Command::SetElement { x, y, element } => {
+                        let slot = slots.current();
+                        if let Ok(mut l) = slot.life.write() {
+                            apply_edit(l.as_mut(), Edit::Element { x, y, element }, slot.generation.load(Ordering::Relaxed), recorder.as_ref().filter(|_| record), &timeline);
+                        }
+                    }
+                    Command::SetElementBrush(element) => {
+                        brush = element.map_or(Brush::Cells, Brush::Element);
+                        println!("Painting {:?}", brush);
+                    }
+                    Command::TogglePredatorBrush => {
+                        brush = brush.toggle(Brush::Predators);
+                        println!("Painting {:?}", brush);
+                    }
+                    Command::Paste { pattern, at } => {
+                        let slot = slots.current();
+                        if let Ok(mut l) = slot.life.write() {
+                            let (x, y) = at.unwrap_or_else(|| centred(l.as_ref(), &pattern));
+                            apply_edit(l.as_mut(), Edit::Paste { x, y, pattern }, slot.generation.load(Ordering::Relaxed), recorder.as_ref().filter(|_| record), &timeline);
+                        }
+                    }
+                    Command::SetRule(rule) => {
+                        let slot = slots.current();
+                        if let Ok(mut l) = slot.life.write() {
+                            apply_edit(l.as_mut(), Edit::Rule { rule }, slot.generation.load(Ordering::Relaxed), recorder.as_ref().filter(|_| record), &timeline);
+                            println!("Rule: {}", l.rule());
+                            announcer.say(format!("Rule {}", l.rule()));
+                        }
+                    }
+                    Command::ExportHistory(path) => {
+                        let csv = population_history.lock().map(|h| h.to_csv()).map_err(|e| e.to_string());
+                        match csv.and_then(|c| std::fs::write(&path, c).map_err(|e| e.to_string())) {
+                            Ok(()) => println!("Exported population history to {}", path),
+                            Err(e) => log::error!("Could not export to {}: {}", path, e)
+                        }
+                    }
+                    Command::AddRegion(region) => {
+                        config.regions.retain(|r| r.name != region.name);
+                        config.regions.push(region.clone());
+                        if let Ok(mut r) = region_stats.lock() {
+                            r.add(region);
+                        }
+                    }
+                    Command::RemoveRegion(name) => {
+                        config.regions.retain(|r| r.name != name);
+                        if !region_stats.lock().is_ok_and(|mut r| r.remove(&name)) {
+                            println!("No region called {}", name);
+                        }
+                    }
+                    Command::ExportVolume(path) => {
+                        let volume = history.lock().map(|h| h.volume()).map_err(|e| e.to_string());
+                        match volume {
+                            Ok(v) if v.is_empty() => println!("Nothing recorded yet; the space-time view records while it is shown"),
+                            Ok(v) => match v.save(&path) {
+                                Ok(()) => println!("Exported {} generations to {}", v.len(), path),
+                                Err(e) => log::error!("Could not export: {}", e)
+                            },
+                            Err(e) => log::error!("Could not export to {}: {}", path, e)
+                        }
+                    }
+                    Command::ExportRegionStats(path) => {
+                        let csv = region_stats.lock().map(|r| r.to_csv()).map_err(|e| e.to_string());
+                        match csv.and_then(|c| std::fs::write(&path, c).map_err(|e| e.to_string())) {
+                            Ok(()) => println!("Exported region statistics to {}", path),
+                            Err(e) => log::error!("Could not export to {}: {}", path, e)
+                        }
+                    }
+                    Command::Identify(region) => {
+                        if let Ok(l) = slots.current().life.read() {
+                            let objects = census::identify_region(l.as_ref(), region);
+                            if objects.is_empty() {
+                                println!("Nothing to identify");
+                            }
+                            for object in objects {
+                                println!("{}", object);
+                            }
+                        }
+                    }
+                    Command::Census => {
+                        if let Ok(l) = slots.current().life.read() {
+                            let taken = census::take(l.as_ref());
+                            println!("Census at generation {}, {} objects:", slots.current().generation.load(Ordering::Relaxed), taken.counts.values().sum::<u64>());
+                            print!("{}", taken.report());
+                        }
+                    }
+                    Command::FindPattern(None) => found = None,
+                    Command::FindPattern(Some(pattern)) => {
+                        if let Ok(l) = slots.current().life.read() {
+                            let boxes = find::find(l.as_ref(), &io::parse_plaintext(&pattern));
+                            for bb in &boxes {
+                                println!("Found at {},{} ({}x{})", bb.min_x, bb.min_y, bb.width(), bb.height());
+                            }
+                            println!("{} found", boxes.len());
+                            found = Some(boxes);
+                        }
+                    }
+                    Command::FindPredecessor(bb) => {
+                        let Ok(l) = slots.current().life.read() else { continue };
+                        let size = (bb.width().min(l.width().saturating_sub(bb.min_x)), bb.height().min(l.height().saturating_sub(bb.min_y)));
+                        let mut pattern = Vec::new();
+                        for y in 0..size.1 {
+                            pattern.extend((0..size.0).filter(|&x| l.get(bb.min_x + x, bb.min_y + y) == CellState::Alive).map(|x| (x, y)));
+                        }
+                        let rule = l.rule();
+                        let notice = Arc::clone(&notice);
+                        // Can take a few seconds on a full 8 by 8 box
+                        thread::spawn(move || {
+                            let said = match predecessor::search(&pattern, size, rule) {
+                                Ok(Some(parent)) => {
+                                    println!("Predecessor of {},{} ({}x{}), from one cell up and left:\n{}", bb.min_x, bb.min_y, size.0, size.1, io::cells_to_rle(&parent));
+                                    "Predecessor found and printed".to_string()
+                                }
+                                Ok(None) => format!("No predecessor within {}x{}", size.0 + 2, size.1 + 2),
+                                Err(e) => e
+                            };
+                            println!("{}", said);
+                            if let Ok(mut n) = notice.lock() {
+                                *n = Some((said, Instant::now()));
+                            }
+                        });
+                    }
+                    Command::SetLayout(layout) => {
+                        println!("Soup layout: {:?}", layout);
+                        config.layout = layout;
+                    }
+                    Command::SetReaction { feed, kill } => {
+                        let slot = slots.current();
+                        if let Ok(mut l) = slot.life.write() {
+                            apply_edit(l.as_mut(), Edit::Reaction { feed, kill }, slot.generation.load(Ordering::Relaxed), recorder.as_ref().filter(|_| record), &timeline);
+                        }
+                        (config.feed, config.kill) = (feed, kill);
+                        println!("Feed {}, kill {}", feed, kill);
+                    }
+                    Command::SetForest { growth, lightning } => {
+                        let slot = slots.current();
+                        if let Ok(mut l) = slot.life.write() {
+                            apply_edit(l.as_mut(), Edit::Forest { growth, lightning }, slot.generation.load(Ordering::Relaxed), recorder.as_ref().filter(|_| record), &timeline);
+                        }
+                        (config.growth, config.lightning) = (growth, lightning);
+                        println!("Growth {}, lightning {}", growth, lightning);
+                    }
+                    Command::SetCyclic { states, threshold } => {
+                        let slot = slots.current();
+                        if let Ok(mut l) = slot.life.write() {
+                            apply_edit(l.as_mut(), Edit::Cyclic { states, threshold }, slot.generation.load(Ordering::Relaxed), recorder.as_ref().filter(|_| record), &timeline);
+                        }
+                        (config.cyclic_states, config.cyclic_threshold) = (states, threshold);
+                        println!("{} states, threshold {}", states, threshold);
+                    }
+                    Command::SetIsing { temperature, field } => {
+                        let slot = slots.current();
+                        if let Ok(mut l) = slot.life.write() {
+                            apply_edit(l.as_mut(), Edit::Ising { temperature, field }, slot.generation.load(Ordering::Relaxed), recorder.as_ref().filter(|_| record), &timeline);
+                        }
+                        (config.temperature, config.field) = (temperature, field);
+                        println!("Temperature {}, field {}", temperature, field);
+                    }
+                    Command::SetGasModel(model) => {
+                        let slot = slots.current();
+                        if let Ok(mut l) = slot.life.write() {
+                            apply_edit(l.as_mut(), Edit::Gas { model }, slot.generation.load(Ordering::Relaxed), recorder.as_ref().filter(|_| record), &timeline);
+                        }
+                        config.gas = model;
+                        println!("Lattice gas: {}", model);
+                    }
+                    Command::SetElementary(rule) => {
+                        let slot = slots.current();
+                        if let Ok(mut l) = slot.life.write() {
+                            apply_edit(l.as_mut(), Edit::Elementary { rule }, slot.generation.load(Ordering::Relaxed), recorder.as_ref().filter(|_| record), &timeline);
+                        }
+                        config.elementary = rule;
+                        println!("Wolfram rule {}", rule);
+                    }
+                    Command::LoadTape(bits) => {
+                        let slot = slots.current();
+                        if let Ok(mut l) = slot.life.write() {
+                            apply_edit(l.as_mut(), Edit::Tape { bits: bits.clone() }, slot.generation.load(Ordering::Relaxed), recorder.as_ref().filter(|_| record), &timeline);
+                            config.elementary = l.downcast_ref::<ElementaryEngine>().map_or(config.elementary, ElementaryEngine::code);
+                        }
+                        println!("Rule 110 tape: {}", bits);
+                    }
+                    Command::SetSymmetry(symmetry) => {
+                        config.symmetry = symmetry;
+                        println!("Soup symmetry: {}", symmetry);
+                    }
+                    Command::SetNoise(p) => {
+                        let seed = config.seed.unwrap_or_else(rand::random);
+                        if let Ok(mut n) = noise.lock() {
+                            *n = Noise::new(p, seed);
+                        }
+                        if let Some(Ok(mut r)) = recorder.as_ref().filter(|_| record).map(|r| r.lock()) {
+                            r.record(slots.current().generation.load(Ordering::Relaxed), Edit::Noise { probability: p, seed });
+                        }
+                        config.noise = p;
+                        println!("Noise: {}", p);
+                    }
+                    Command::SetSpeed(gps) => {
+                        speed.store(gps, Ordering::Relaxed);
+                        config.speed = gps;
+                    }
+                    Command::Export { path, canonical } => {
+                        let format = io::Format::from_path(&path).unwrap_or(io::Format::Plaintext);
+                        let text = slots.current().life.read()
+                            .map(|l| io::export(&io::live_cells(l.as_ref()), format, Some(&l.rule()), canonical))
+                            .map_err(|e| e.to_string());
+                        match text.and_then(|t| std::fs::write(&path, t).map_err(|e| e.to_string())) {
+                            Ok(()) => println!("Exported to {}", path),
+                            Err(e) => log::error!("Could not export to {}: {}", path, e)
+                        }
+                    }
+                    Command::ExportSvg { path, cell_size, shape } => {
+                        let style = svg::Style { cell_size, shape, alive: theme.alive, background: Some(theme.dead), ..svg::Style::default() };
+                        let drawing = slots.current().life.read().map(|l| svg::write(&io::live_cells(l.as_ref()), &style)).map_err(|e| e.to_string());
+                        match drawing.and_then(|d| std::fs::write(&path, d).map_err(|e| e.to_string())) {
+                            Ok(()) => println!("Exported to {}", path),
+                            Err(e) => log::error!("Could not export to {}: {}", path, e)
+                        }
+                    }
+                }
+            }
+
+            if let (Some(t), Some(issued), Ok(l)) = (tutorial.as_mut(), &issued, slots.current().life.read()) {
+                t.advance(issued, l.as_ref());
+            }
+
+            match theme_watch.as_mut().and_then(|w| w.poll()) {
+                Some(Ok(t)) => {
+                    theme = t;
+                    changed = true;
+                    println!("Reloaded theme {}", config.theme);
+                }
+                Some(Err(e)) => log::error!("Could not reload theme: {}", e),
+                None => {}
+            }
+            if let Some(path) = pattern_watch.as_mut().and_then(|w| w.changed().then(|| w.path().to_path_buf())) {
+                let read = std::fs::read_to_string(&path).map_err(|e| e.to_string());
+                match read.and_then(|text| Ok((io::parse_pattern(&text).map_err(|e| e.to_string())?, io::pattern_rule(&text)))) {
+                    Ok((cells, rule)) => {
+                        start_over(&slots, &cells, rule, &timeline, &stop);
+                        changed = true;
+                        println!("Reloaded {}", path.display());
+                    }
+                    // Likely saved half way through an edit; the next save
+                    // may well fix it
+                    Err(e) => log::error!("Could not reload {}: {}", path.display(), e)
+                }
+            }
+
+            // Not every frame, which would be too quick to read anyway
+            if titled.0.elapsed() >= TITLE_INTERVAL {
+                let slot = slots.current();
+                let generation = slot.generation.load(Ordering::Relaxed);
+                let (rule, population) = slot.life.read().map_or((String::new(), 0), |l| (l.rule().to_string(), l.population()));
+                let speed = if pause.is_paused() {
+                    "PAUSED".to_string()
+                } else {
+                    let stepped = if titled.1 == slots.index() { generation.saturating_sub(titled.2) } else { 0 };
+                    format!("{} gps", thousands((stepped as f64 / titled.0.elapsed().as_secs_f64()).round() as u64))
+                };
+                let status = format!("{} - {} - gen {} - pop {} - {}", window_title(slots.index(), slot.seed), rule, thousands(generation), thousands(population as u64), speed);
+                let wanted = match announcer.latest() {
+                    Some(said) => format!("{} - {}", status, said),
+                    None => status.clone()
+                };
+                titled = (Instant::now(), slots.index(), generation);
+                if wanted != title {
+                    #[cfg(feature = "accessibility")]
+                    access.update(&status, announcer.latest());
+                    window.set_title(&wanted);
+                    title = wanted;
+                }
+            }
+
+            // Resize the window
+            if let Some(size) = input.window_resized() {
+                if let Err(err) = pixels.resize_surface(size.width, size.height) {
+                    log_error("pixels.resize_surface", &err);
+                    *control_flow = ControlFlow::Exit;
+                    return;
+                }
+            }
+
+            // Done once there, or when something else, such as a stop
+            // condition or a switch of slot, has taken over
+            if let Some(f) = &fast_forward {
+                let generation = slots.current().generation.load(Ordering::Relaxed);
+                if generation < f.from || !pause.is_paused() || pause.idle() {
+                    pause.forget();
+                    speed.store(f.speed, Ordering::Relaxed);
+                    println!("Fast forwarded {} generations in {:.1}s", generation.saturating_sub(f.from), f.started.elapsed().as_secs_f64());
+                    announcer.say(format!("Generation {}", generation));
+                    fast_forward = None;
+                }
+            }
+
+            // Only redraws when something changed, and otherwise sleeps
+            // until the next event or stepped generation
+            let shown = (slots.index(), slots.current().generation.load(Ordering::Relaxed));
+            // The follow camera eases in a step a frame, so frames keep coming
+            // until it gets there, paused or not
+            let following = follow && slots.current().life.read().ok()
+                .and_then(|l| Some(View::fit(l.bounding_box()?, (l.width(), l.height()))))
+                .is_some_and(|target| slots.current().view.is_none_or(|v| !v.is_near(target)));
+            let animating = following || always_redraw || screensaver || steering || explainer.as_ref().is_some_and(Explainer::is_sweeping) || spacetime_on.load(Ordering::Relaxed) || profiler.is_on() || notice.lock().is_ok_and(|n| n.is_some());
+            let hidden = power_save && (occluded || window.is_minimized() == Some(true));
+            if hidden && !pause.is_paused() {
+                pause.set(true);
+                resume_when_shown = true;
+            } else if !hidden && resume_when_shown {
+                pause.set(false);
+                resume_when_shown = false;
+            }
+            let due = fast_forward.as_ref().map_or(frame_time, |_| Some(PROGRESS_INTERVAL)).map(|t| last_frame + t).filter(|&at| at > Instant::now());
+            if hidden {
+                // Nothing to do until the window is shown again
+                *control_flow = ControlFlow::Wait;
+            } else if occluded || !(changed || held_back || window_events || animating || drawn != Some(shown)) {
+                *control_flow = ControlFlow::WaitUntil(Instant::now() + IDLE_POLL);
+            } else if let Some(at) = due {
+                // Drawn once the frame time is up, however many generations
+                // go by meanwhile
+                held_back = true;
+                *control_flow = ControlFlow::WaitUntil(at);
+            } else {
+                held_back = false;
+                *control_flow = ControlFlow::Poll;
+                window.request_redraw();
+            }
+            window_events = false;
+        }
+    });
+}
@@ -0,0 +1,91 @@
+//! Differences between two states of a universe: the cells born and the
+//! cells that died between a reference and now, to check an oscillator's
+//! period or spot small changes.
+
+use serde::{Deserialize, Serialize};
+
+use crate::engine::{CellState, LifeEngine};
+use crate::packed::PackedGrid;
+
+/// How a cell differs from the reference.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Change {
+    Dead,
+    Alive,
+    Born,
+    Died,
+}
+
+/// Which cells were alive at a generation.  Serialized with the cells
+/// packed, see [`crate::packed`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(from = "SavedReference", into = "SavedReference")]
+pub struct Reference {
+    pub generation: u64,
+    width: usize,
+    height: usize,
+    alive: Vec<bool>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SavedReference {
+    generation: u64,
+    #[serde(flatten)]
+    grid: PackedGrid,
+}
+
+impl From<Reference> for SavedReference {
+    fn from(reference: Reference) -> Self {
+        SavedReference { generation: reference.generation, grid: PackedGrid::new(reference.width, reference.height, reference.alive) }
+    }
+}
+
+impl From<SavedReference> for Reference {
+    fn from(saved: SavedReference) -> Self {
+        Reference { generation: saved.generation, width: saved.grid.width, height: saved.grid.height, alive: saved.grid.alive() }
+    }
+}
+
+impl Reference {
+    pub fn of(life: &dyn LifeEngine, generation: u64) -> Self {
+        let (width, height) = (life.width(), life.height());
+        let alive = (0..width * height).map(|i| life.get(i % width, i / width) == CellState::Alive).collect();
+        Reference { generation, width, height, alive }
+    }
+
+    /// How the cell at (x, y), now `state`, differs from the reference.
+    /// Cells outside it count as dead in it.
+    pub fn change(&self, x: usize, y: usize, state: CellState) -> Change {
+        let was = x < self.width && y < self.height && self.alive[y * self.width + x];
+        match (was, state == CellState::Alive) {
+            (false, false) => Change::Dead,
+            (true, true) => Change::Alive,
+            (false, true) => Change::Born,
+            (true, false) => Change::Died
+        }
+    }
+
+    /// Cells born and cells died since the reference, both none when `life`
+    /// is the same again.
+    pub fn count(&self, life: &dyn LifeEngine) -> (usize, usize) {
+        let (mut born, mut died) = (0, 0);
+        for y in 0..life.height() {
+            for x in 0..life.width() {
+                match self.change(x, y, life.get(x, y)) {
+                    Change::Born => born += 1,
+                    Change::Died => died += 1,
+                    _ => {}
+                }
+            }
+        }
+        // Cells the universe has lost by shrinking
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if (x >= life.width() || y >= life.height()) && self.alive[y * self.width + x] {
+                    died += 1;
+                }
+            }
+        }
+        (born, died)
+    }
+}
@@ -0,0 +1,291 @@
+//! One-dimensional elementary automata.  The grid is a history: the top
+//! row is the starting tape, each generation adds the next row below, and
+//! once the grid is full it scrolls up a row at a time.  Each cell of the
+//! new row looks at the three cells above it, wrapping round at the sides,
+//! and the rule is a Wolfram code, the new state for each of the eight ways
+//! they can be.
+//!
+//! Rule 110 is universal: gliders moving through its regular background
+//! (the ether) can be made to compute anything.  Under rule 110 the cells
+//! that break from the ether are drawn highlighted, so the gliders stand
+//! out.
+
+use std::any::Any;
+use std::sync::Mutex;
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::engine::{Anchor, CellState, LifeEngine, StaleGuard};
+use crate::rule::Rule;
+
+pub const DEFAULT_RULE: u8 = 110;
+
+/// One row of rule 110's ether, which repeats every 14 cells across and 7
+/// generations down.
+const ETHER: &str = "10011011111000";
+const ETHER_WIDTH: usize = 14;
+const ETHER_PERIOD: usize = 7;
+
+const GLIDER: [u8; 3] = [0xff, 0x90, 0x20];
+const GLIDER_TRAIL: [u8; 3] = [0x50, 0x28, 0x08];
+
+/// The top row for a rule 110 tape: `bits`, 1 alive and 0 dead, in the
+/// middle of a `width` wide row of ether.  Each mismatch with the ether
+/// sends out gliders.
+pub fn tape(bits: &str, width: usize) -> Result<Vec<(usize, usize)>, String> {
+    let bits = bits.trim();
+    if let Some(c) = bits.chars().find(|c| *c != '0' && *c != '1') {
+        return Err(format!("'{}' in the tape, expected only 0 and 1", c));
+    }
+    if bits.len() > width {
+        return Err(format!("a tape of {} bits doesn't fit in {} cells", bits.len(), width));
+    }
+    let ether: Vec<char> = ETHER.chars().collect();
+    let start = (width - bits.len()) / 2 / ETHER_WIDTH * ETHER_WIDTH;
+    let live = (0..width).filter(|&x| match x.checked_sub(start).and_then(|i| bits.as_bytes().get(i)) {
+        Some(bit) => *bit == b'1',
+        None => ether[x % ETHER_WIDTH] == '1',
+    });
+    Ok(live.map(|x| (x, 0)).collect())
+}
+
+/// Replaces the contents of `life` with a rule 110 tape.  Fails on backends
+/// other than elementary ones.
+pub fn load_tape(life: &mut dyn LifeEngine, bits: &str) -> Result<Vec<(usize, usize)>, String> {
+    let cells = tape(bits, life.width())?;
+    let name = life.name();
+    life.downcast_mut::<ElementaryEngine>().ok_or_else(|| format!("the {} backend has no tape", name))?.set_code(110);
+    life.clear();
+    for &(x, y) in &cells {
+        life.set(x, y, CellState::Alive);
+    }
+    Ok(cells)
+}
+
+// Live cells in the new row, then changed and born cells
+type Counts = (usize, usize, usize);
+
+pub struct ElementaryEngine {
+    width: usize,
+    height: usize,
+    cells: Vec<bool>,
+    // Rows of history so far, the last of them the current one
+    rows: usize,
+    wolfram: u8,
+    rule: Rule,
+    scratch: Mutex<Vec<bool>>,
+    population: usize,
+    changed: usize,
+    born: usize,
+    next_counts: Mutex<Counts>,
+    stale: StaleGuard,
+}
+
+impl ElementaryEngine {
+    pub fn new(width: usize, height: usize) -> Self {
+        ElementaryEngine {
+            width,
+            height,
+            cells: vec![false; width * height],
+            rows: 1,
+            wolfram: DEFAULT_RULE,
+            rule: Rule::default(),
+            scratch: Mutex::new(vec![false; width]),
+            population: 0,
+            changed: 0,
+            born: 0,
+            next_counts: Mutex::new((0, 0, 0)),
+            stale: StaleGuard::default(),
+        }
+    }
+
+    fn row(&self, y: usize) -> &[bool] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    /// Whether the cell at (x, y) matches rule 110's ether, the same as the
+    /// cells one ether width either side and one period above.
+    fn ether(&self, x: usize, y: usize) -> bool {
+        let w = self.width;
+        let at = |x: usize, y: usize| self.cells[y * w + x % w];
+        let cell = at(x, y);
+        cell == at(x + ETHER_WIDTH, y) && cell == at(x + w - ETHER_WIDTH % w, y) && cell == at(x, y - ETHER_PERIOD)
+    }
+
+    /// The Wolfram code.
+    pub fn code(&self) -> u8 {
+        self.wolfram
+    }
+
+    pub fn set_code(&mut self, rule: u8) {
+        self.wolfram = rule;
+        self.stale.bump();
+    }
+}
+
+impl LifeEngine for ElementaryEngine {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn name(&self) -> &'static str {
+        "elementary"
+    }
+
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    fn get(&self, x: usize, y: usize) -> CellState {
+        if self.cells[y * self.width + x] { CellState::Alive } else { CellState::Dead }
+    }
+
+    /// Any row can be painted, but only the last one grown so far shapes
+    /// what comes next.
+    fn set(&mut self, x: usize, y: usize, state: CellState) {
+        let cell = &mut self.cells[y * self.width + x];
+        match (*cell, state == CellState::Alive) {
+            (false, true) => self.population += 1,
+            (true, false) => self.population -= 1,
+            _ => {}
+        }
+        *cell = state == CellState::Alive;
+        self.stale.bump();
+    }
+
+    /// Starts the history again from the top row.
+    fn clear(&mut self) {
+        self.cells.fill(false);
+        (self.rows, self.population) = (1, 0);
+        self.stale.bump();
+    }
+
+    /// Under rule 110, cells off the ether are highlighted.
+    fn color(&self, x: usize, y: usize) -> Option<[u8; 3]> {
+        if self.wolfram != 110 || y < ETHER_PERIOD || y >= self.rows || self.width < 2 * ETHER_WIDTH || self.ether(x, y) {
+            return None;
+        }
+        Some(if self.cells[y * self.width + x] { GLIDER } else { GLIDER_TRAIL })
+    }
+
+    /// Kept for the other backends' sake; the Wolfram code is set with
+    /// [`ElementaryEngine::set_code`].
+    fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    fn set_rule(&mut self, rule: Rule) {
+        self.rule = rule;
+    }
+
+    fn prepare_step(&self) {
+        let _span = tracing::info_span!("elementary.prepare_step").entered();
+        if let Ok(mut scratch) = self.scratch.lock() {
+            self.stale.mark_prepared();
+            if self.cells.is_empty() {
+                return;
+            }
+            let w = self.width;
+            let current = self.row(self.rows - 1);
+            for (x, cell) in scratch.iter_mut().enumerate() {
+                let (left, centre, right) = (current[(x + w - 1) % w], current[x], current[(x + 1) % w]);
+                let pattern = (left as u8) << 2 | (centre as u8) << 1 | right as u8;
+                *cell = self.wolfram >> pattern & 1 == 1;
+            }
+
+            let mut counts: Counts = (0, 0, 0);
+            for (old, new) in current.iter().zip(scratch.iter()) {
+                counts.0 += *new as usize;
+                if old != new {
+                    counts.1 += 1;
+                    counts.2 += *new as usize;
+                }
+            }
+            if let Ok(mut next) = self.next_counts.lock() {
+                *next = counts;
+            }
+        }
+    }
+
+    fn commit_step(&mut self) {
+        if self.stale.is_stale() {
+            self.prepare_step();
+        }
+        self.stale.committed();
+        if self.width == 0 || self.height == 0 {
+            return;
+        }
+        if let (Ok(scratch), Ok(counts)) = (self.scratch.get_mut(), self.next_counts.get_mut()) {
+            let w = self.width;
+            let live = |row: &[bool]| row.iter().filter(|c| **c).count();
+            if self.rows == self.height {
+                // The last row is left where it was as well as moved up, so
+                // only the top one is lost
+                self.population -= live(&self.cells[..w]);
+                self.cells.copy_within(w.., 0);
+            } else {
+                self.rows += 1;
+                let y = self.rows - 1;
+                self.population -= live(&self.cells[y * w..(y + 1) * w]);
+            }
+            let last = (self.rows - 1) * w;
+            self.cells[last..last + w].copy_from_slice(scratch);
+            self.population += counts.0;
+            (self.changed, self.born) = (counts.1, counts.2);
+        }
+    }
+
+    fn changed_cells(&self) -> usize {
+        self.changed
+    }
+
+    fn born_cells(&self) -> usize {
+        self.born
+    }
+
+    /// Keeps the history that fits, still ending on the current row.
+    fn resize(&mut self, width: usize, height: usize, anchor: Anchor) {
+        let (ox, _) = anchor.offset((self.width, self.height), (width, height));
+        let kept = self.rows.min(height);
+        let mut cells = vec![false; width * height];
+        for (i, y) in (self.rows - kept..self.rows).enumerate() {
+            for x in 0..self.width {
+                let nx = x as isize + ox;
+                if nx >= 0 && nx < width as isize {
+                    cells[i * width + nx as usize] = self.cells[y * self.width + x];
+                }
+            }
+        }
+        self.population = cells.iter().filter(|c| **c).count();
+        self.cells = cells;
+        self.rows = kept.max(1);
+        self.scratch = Mutex::new(vec![false; width]);
+        (self.width, self.height) = (width, height);
+        self.changed = 0;
+        self.born = 0;
+        self.stale.bump();
+    }
+
+    /// A random top row, drawn as the other backends draw their first row.
+    fn randomize(&mut self, density: f64, seed: u64) {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        self.clear();
+        for x in 0..self.width {
+            let alive = rng.gen_bool(density.clamp(0.0, 1.0));
+            self.set(x, 0, if alive { CellState::Alive } else { CellState::Dead });
+        }
+    }
+
+    fn population(&self) -> usize {
+        self.population
+    }
+}
@@ -0,0 +1,1046 @@
+use std::any::Any;
+use std::fmt;
+use std::ops::Range;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+#[cfg(not(target_arch = "wasm32"))]
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::cyclic::CyclicEngine;
+use crate::elementary::ElementaryEngine;
+use crate::forest::ForestEngine;
+use crate::gas::GasEngine;
+use crate::ising::IsingEngine;
+use crate::packed::PackedGrid;
+use crate::quadtree::Quadtree;
+use crate::reaction::ReactionEngine;
+use crate::rule::{NeighborhoodRule, Rule};
+pub use rustlife_core::CellState;
+use crate::sand::SandEngine;
+use crate::species::SpeciesEngine;
+
+/// Inclusive rectangle of cells, in grid coordinates.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BoundingBox {
+    pub min_x: usize,
+    pub min_y: usize,
+    pub max_x: usize,
+    pub max_y: usize,
+}
+
+impl BoundingBox {
+    /// The smallest box around `cells`, `None` if there are none.
+    pub fn of(cells: &[(usize, usize)]) -> Option<Self> {
+        Some(BoundingBox {
+            min_x: cells.iter().map(|c| c.0).min()?,
+            min_y: cells.iter().map(|c| c.1).min()?,
+            max_x: cells.iter().map(|c| c.0).max()?,
+            max_y: cells.iter().map(|c| c.1).max()?,
+        })
+    }
+
+    /// The smallest box holding both.
+    pub fn union(self, other: BoundingBox) -> BoundingBox {
+        BoundingBox {
+            min_x: self.min_x.min(other.min_x),
+            min_y: self.min_y.min(other.min_y),
+            max_x: self.max_x.max(other.max_x),
+            max_y: self.max_y.max(other.max_y),
+        }
+    }
+
+    /// The cells in both, if any are.
+    pub fn intersection(self, other: BoundingBox) -> Option<BoundingBox> {
+        let bb = BoundingBox {
+            min_x: self.min_x.max(other.min_x),
+            min_y: self.min_y.max(other.min_y),
+            max_x: self.max_x.min(other.max_x),
+            max_y: self.max_y.min(other.max_y),
+        };
+        (bb.min_x <= bb.max_x && bb.min_y <= bb.max_y).then_some(bb)
+    }
+
+    pub fn width(&self) -> usize {
+        self.max_x - self.min_x + 1
+    }
+
+    pub fn height(&self) -> usize {
+        self.max_y - self.min_y + 1
+    }
+}
+
+/// Which part of the old universe stays put when it is resized.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Anchor {
+    Center,
+    TopLeft,
+}
+
+impl Anchor {
+    /// Where the old grid's top left corner lands in the new grid.
+    pub fn offset(&self, old: (usize, usize), new: (usize, usize)) -> (isize, isize) {
+        match self {
+            Anchor::Center => ((new.0 as isize - old.0 as isize) / 2, (new.1 as isize - old.1 as isize) / 2),
+            Anchor::TopLeft => (0, 0),
+        }
+    }
+}
+
+/// What lies past the edges of the grid.  Only the dense backend wraps
+/// round; the others always have dead edges.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Boundary {
+    /// Dead cells, which nothing is ever born into
+    #[default]
+    Dead,
+    /// The opposite edge, so whatever leaves one side comes back on the
+    /// other
+    Torus,
+}
+
+impl Boundary {
+    pub const ALL: [Boundary; 2] = [Boundary::Dead, Boundary::Torus];
+
+    fn is_dead(&self) -> bool {
+        *self == Boundary::Dead
+    }
+}
+
+impl fmt::Display for Boundary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", format!("{:?}", self).to_lowercase())
+    }
+}
+
+impl FromStr for Boundary {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Boundary::ALL.into_iter()
+            .find(|b| b.to_string().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| format!("unknown boundary '{}', expected dead or torus", s))
+    }
+}
+
+/// A simulation backend.  The frontend and simulation thread only talk to the
+/// universe through this trait so backends can be swapped at startup.
+///
+/// Stepping is split in two so the expensive part can run while the renderer
+/// holds a read lock: `prepare_step` computes the next generation into space
+/// owned by the engine, and `commit_step` makes it current.
+pub trait LifeEngine: Send + Sync {
+    /// The backend itself, to downcast to its own type for what only it
+    /// has, such as a forest's lightning chance or the dense backend's
+    /// index.
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+
+    fn name(&self) -> &'static str;
+    fn width(&self) -> usize;
+    fn height(&self) -> usize;
+    fn get(&self, x: usize, y: usize) -> CellState;
+    fn set(&mut self, x: usize, y: usize, state: CellState);
+    fn rule(&self) -> Rule;
+    fn set_rule(&mut self, rule: Rule);
+    fn prepare_step(&self);
+    fn commit_step(&mut self);
+
+    /// Number of cells that changed state in the last committed step.
+    fn changed_cells(&self) -> usize;
+
+    /// Number of those that came alive; the rest died.
+    fn born_cells(&self) -> usize;
+
+    /// Grows or shrinks the universe, keeping the contents at `anchor`.
+    /// Cells that no longer fit are dropped.
+    fn resize(&mut self, width: usize, height: usize, anchor: Anchor);
+
+    /// Replaces the B/S rule with arbitrary per-cell logic, or goes back to
+    /// the B/S rule with `None`.  Returns false if the backend can't do it.
+    fn set_custom_rule(&mut self, _rule: Option<Arc<dyn NeighborhoodRule>>) -> bool {
+        false
+    }
+
+    /// The state a wall holds the cell at (x, y) in, if there is one.
+    fn wall(&self, _x: usize, _y: usize) -> Option<CellState> {
+        None
+    }
+
+    /// Holds the cell at (x, y) in `wall` whatever its neighbours do, or
+    /// frees it with `None`.  A wall can't be painted over or cleared, only
+    /// removed.  Returns false if the backend can't do it.
+    fn set_wall(&mut self, _x: usize, _y: usize, _wall: Option<CellState>) -> bool {
+        false
+    }
+
+    /// How alive the cell at (x, y) looks, 0 to 1.  Only continuous
+    /// backends have anything between.
+    fn level(&self, x: usize, y: usize) -> f32 {
+        if self.get(x, y) == CellState::Alive { 1.0 } else { 0.0 }
+    }
+
+    /// The colour to draw the cell at (x, y) in, on backends whose cells
+    /// have more states than alive and dead or that pick some cells out.
+    /// `None` draws it as usual, or as dead where every cell has a colour.
+    fn color(&self, _x: usize, _y: usize) -> Option<[u8; 3]> {
+        None
+    }
+
+    fn step(&mut self) {
+        self.prepare_step();
+        self.commit_step();
+    }
+
+    fn step_n(&mut self, n: u64) {
+        for _ in 0..n {
+            self.step();
+        }
+    }
+
+    fn clear(&mut self) {
+        for y in 0..self.height() {
+            for x in 0..self.width() {
+                self.set(x, y, CellState::Dead);
+            }
+        }
+    }
+
+    /// Replaces the contents with a random soup where each cell is alive with
+    /// probability `density`.  The same seed, density and grid size always
+    /// give the same soup, whatever the backend.
+    fn randomize(&mut self, density: f64, seed: u64) {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        for y in 0..self.height() {
+            // Every cell draws one u64 (two stream words), so seek to the row
+            // rather than relying on having drawn the previous rows.
+            rng.set_word_pos(2 * (y * self.width()) as u128);
+            for x in 0..self.width() {
+                let state = if rng.gen_bool(density.clamp(0.0, 1.0)) { CellState::Alive } else { CellState::Dead };
+                self.set(x, y, state);
+            }
+        }
+    }
+
+    fn population(&self) -> usize {
+        let mut count = 0;
+        for y in 0..self.height() {
+            for x in 0..self.width() {
+                if self.get(x, y) == CellState::Alive {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
+    fn bounding_box(&self) -> Option<BoundingBox> {
+        let mut bb: Option<BoundingBox> = None;
+        for y in 0..self.height() {
+            for x in 0..self.width() {
+                if self.get(x, y) == CellState::Alive {
+                    let b = bb.get_or_insert(BoundingBox { min_x: x, min_y: y, max_x: x, max_y: y });
+                    b.min_x = b.min_x.min(x);
+                    b.max_x = b.max_x.max(x);
+                    b.max_y = y;
+                }
+            }
+        }
+        bb
+    }
+
+    /// Whole grid in plaintext format.
+    fn serialize(&self) -> String {
+        let mut out = String::with_capacity((self.width() + 1) * self.height());
+        for y in 0..self.height() {
+            for x in 0..self.width() {
+                out.push(if self.get(x, y) == CellState::Alive { 'O' } else { '.' });
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+impl<'a> dyn LifeEngine + 'a {
+    /// The backend as a `T`, if that is what it is.
+    pub fn downcast_ref<T: LifeEngine + 'static>(&self) -> Option<&T> {
+        self.as_any().downcast_ref()
+    }
+
+    pub fn downcast_mut<T: LifeEngine + 'static>(&mut self) -> Option<&mut T> {
+        self.as_any_mut().downcast_mut()
+    }
+}
+
+// See `set_strips`
+static STRIPS: AtomicBool = AtomicBool::new(false);
+
+/// Steps dense grids one strip of rows per thread of the rayon pool, each
+/// strip always on the same thread, instead of in small chunks handed to
+/// whichever thread is free.  The grids made from then on are also first
+/// written strip by strip by those threads, so on Linux each strip's memory
+/// sits on the node of the thread that steps it.  Worth it when the threads
+/// are pinned to cores on a machine with several sockets or core complexes.
+pub fn set_strips(on: bool) {
+    STRIPS.store(on, Ordering::Relaxed);
+}
+
+pub fn strips() -> bool {
+    STRIPS.load(Ordering::Relaxed)
+}
+
+/// The rows of strip `i` of `n`, as even as they can be.
+pub fn strip_rows(i: usize, n: usize, height: usize) -> Range<usize> {
+    i * height / n..(i + 1) * height / n
+}
+
+/// `cells` cut into one strip of rows per thread, each behind a lock only
+/// its own thread takes.
+#[cfg(not(target_arch = "wasm32"))]
+fn split_strips<T>(mut cells: &mut [T], width: usize, height: usize) -> Vec<Mutex<&mut [T]>> {
+    let n = rayon::current_num_threads();
+    let mut strips = Vec::with_capacity(n);
+    for i in 0..n {
+        let (strip, rest) = cells.split_at_mut(strip_rows(i, n, height).len() * width);
+        strips.push(Mutex::new(strip));
+        cells = rest;
+    }
+    strips
+}
+
+/// A dead grid's cells, written strip by strip by the threads that will
+/// step them when stepping in strips.
+fn first_touch(width: usize, height: usize) -> Vec<CellState> {
+    #[cfg(not(target_arch = "wasm32"))]
+    if strips() {
+        let mut cells = Vec::with_capacity(width * height);
+        let strips = split_strips(&mut cells.spare_capacity_mut()[..width * height], width, height);
+        rayon::broadcast(|ctx| {
+            if let Ok(mut strip) = strips[ctx.index()].lock() {
+                strip.iter_mut().for_each(|c| { c.write(CellState::Dead); });
+            }
+        });
+        drop(strips);
+        // SAFETY: the strips cover the whole grid, so all width * height
+        // cells were written above; a panic while writing would not get here
+        unsafe { cells.set_len(width * height) };
+        return cells;
+    }
+    vec![CellState::Dead; width * height]
+}
+
+/// A quick, well mixed hash of the cell at (x, y) at time `time` in a run
+/// seeded with `seed`, for the backends' random choices: the same whatever
+/// order the cells are worked through in, so whatever the threads do.
+pub fn cell_hash(x: usize, y: usize, time: u64, seed: u64) -> u64 {
+    let mut h = (x as u64) ^ (y as u64).rotate_left(21) ^ time.rotate_left(42) ^ seed;
+    h = (h ^ (h >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    h = (h ^ (h >> 27)).wrapping_mul(0x94d049bb133111eb);
+    h ^ (h >> 31)
+}
+
+/// [`cell_hash`] as a uniform number in [0, 1).
+pub fn cell_chance(x: usize, y: usize, time: u64, seed: u64) -> f64 {
+    cell_hash(x, y, time, seed) as f64 / (u64::MAX as f64 + 1.0)
+}
+
+pub const BACKENDS: &[&str] = &["dense", "species", "reaction", "sand", "forest", "cyclic", "ising", "gas", "elementary"];
+
+/// Creates an empty universe using the named backend.
+pub fn create(backend: &str, width: usize, height: usize) -> Option<Box<dyn LifeEngine>> {
+    match backend {
+        "dense" => Some(Box::new(DenseEngine::new(ConwayState::empty(width, height)))),
+        "species" => Some(Box::new(SpeciesEngine::new(width, height))),
+        "reaction" => Some(Box::new(ReactionEngine::new(width, height))),
+        "sand" => Some(Box::new(SandEngine::new(width, height))),
+        "forest" => Some(Box::new(ForestEngine::new(width, height))),
+        "cyclic" => Some(Box::new(CyclicEngine::new(width, height))),
+        "ising" => Some(Box::new(IsingEngine::new(width, height))),
+        "gas" => Some(Box::new(GasEngine::new(width, height))),
+        "elementary" => Some(Box::new(ElementaryEngine::new(width, height))),
+        _ => None
+    }
+}
+
+/// Serialized with the cells packed, see [`crate::packed`].  A plugin rule
+/// isn't kept, only `rule`.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(try_from = "SavedState", into = "SavedState")]
+pub struct ConwayState {
+    pub cells: Vec<CellState>,
+    pub width: usize,
+    pub height: usize,
+    pub rule: Rule,
+    /// Overrides `rule` when set
+    pub custom_rule: Option<Arc<dyn NeighborhoodRule>>,
+    /// Cells that never change, one entry per cell, or empty when there
+    /// are none
+    pub walls: Vec<Option<CellState>>,
+    pub boundary: Boundary
+}
+
+#[derive(Serialize, Deserialize)]
+struct SavedState {
+    #[serde(flatten)]
+    grid: PackedGrid,
+    rule: Rule,
+    /// Held cells, as (x, y, alive)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    walls: Vec<(usize, usize, bool)>,
+    #[serde(default, skip_serializing_if = "Boundary::is_dead")]
+    boundary: Boundary,
+}
+
+impl From<ConwayState> for SavedState {
+    fn from(state: ConwayState) -> Self {
+        let grid = PackedGrid::new(state.width, state.height, state.cells.iter().map(|&c| c == CellState::Alive));
+        let walls = state.walls.iter().enumerate()
+            .filter_map(|(i, w)| w.map(|w| (i % state.width, i / state.width, w == CellState::Alive)))
+            .collect();
+        SavedState { grid, rule: state.rule, walls, boundary: state.boundary }
+    }
+}
+
+impl TryFrom<SavedState> for ConwayState {
+    type Error = String;
+
+    fn try_from(saved: SavedState) -> Result<Self, String> {
+        let (width, height) = (saved.grid.width, saved.grid.height);
+        let mut state = ConwayState::empty(width, height);
+        state.rule = saved.rule;
+        state.boundary = saved.boundary;
+        for (i, a) in saved.grid.alive().into_iter().enumerate() {
+            if a {
+                state.cells[i] = CellState::Alive;
+            }
+        }
+        for (x, y, alive) in saved.walls {
+            if x >= width || y >= height {
+                return Err(format!("wall at ({}, {}) is outside the {}x{} grid", x, y, width, height));
+            }
+            state.set_wall(x, y, Some(if alive { CellState::Alive } else { CellState::Dead }));
+        }
+        Ok(state)
+    }
+}
+
+impl ConwayState {
+    pub fn new(width: usize, height: usize) -> Self {
+        let mut cells = vec![CellState::Dead; width*height];
+        for c in cells.iter_mut() {
+            if rand::random::<bool>() {
+                *c = CellState::Alive;
+            }
+        }
+        ConwayState {cells, width, height, rule: Rule::default(), custom_rule: None, walls: Vec::new(), boundary: Boundary::Dead}
+    }
+
+    pub fn empty(width: usize, height: usize) -> Self {
+        ConwayState {cells: first_touch(width, height), width, height, rule: Rule::default(), custom_rule: None, walls: Vec::new(), boundary: Boundary::Dead}
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> CellState {
+        self.cells[y*self.width + x]
+    }
+
+    /// Sets the cell, unless a wall holds it.
+    pub fn set(&mut self, x: usize, y: usize, state: CellState) {
+        if self.wall(x, y).is_none() {
+            self.cells[y*self.width + x] = state;
+        }
+    }
+
+    pub fn wall(&self, x: usize, y: usize) -> Option<CellState> {
+        self.walls.get(y*self.width + x).copied().flatten()
+    }
+
+    /// Holds the cell in `wall` from now on, or frees it with `None`.
+    pub fn set_wall(&mut self, x: usize, y: usize, wall: Option<CellState>) {
+        if self.walls.is_empty() {
+            if wall.is_none() {
+                return;
+            }
+            self.walls = vec![None; self.cells.len()];
+        }
+        let id = y*self.width + x;
+        self.walls[id] = wall;
+        if let Some(state) = wall {
+            self.cells[id] = state;
+        }
+    }
+
+    pub fn population(&self) -> usize {
+        self.cells.iter().filter(|c| **c == CellState::Alive).count()
+    }
+
+    /// Smallest box holding every live cell, one pass over the rows.
+    pub fn bounding_box(&self) -> Option<BoundingBox> {
+        #[cfg(not(target_arch = "wasm32"))]
+        let rows = self.cells.par_chunks(self.width.max(1));
+        #[cfg(target_arch = "wasm32")]
+        let rows = self.cells.chunks(self.width.max(1));
+
+        rows.enumerate()
+            .filter_map(|(y, row)| {
+                let first = row.iter().position(|c| *c == CellState::Alive)?;
+                let last = row.iter().rposition(|c| *c == CellState::Alive)?;
+                Some(BoundingBox { min_x: first, min_y: y, max_x: last, max_y: y })
+            })
+            .reduce_with(BoundingBox::union)
+    }
+
+    /// An index of the live cells.
+    pub fn quadtree(&self) -> Quadtree {
+        Quadtree::from_fn(self.width, self.height, |x, y| self.get(x, y) == CellState::Alive)
+    }
+
+    /// One bit per live neighbour, in the order documented on `NeighborhoodRule`.
+    pub fn neighbor_mask(&self, x: usize, y:usize) -> u8 {
+        match self.boundary {
+            Boundary::Dead => rustlife_core::neighbor_mask(&self.cells, self.width, self.height, x, y),
+            Boundary::Torus => rustlife_core::neighbor_mask_torus(&self.cells, self.width, self.height, x, y)
+        }
+    }
+
+    /// What the cell at (x, y) will be next generation, under the plugin
+    /// rule if there is one and holding walls as they are.
+    pub fn next_cell_state(&self, x: usize, y:usize) -> CellState{
+        let linear_id = y*self.width + x;
+        if let Some(Some(wall)) = self.walls.get(linear_id) {
+            return *wall;
+        }
+        let neighbors = self.neighbor_mask(x, y);
+        match &self.custom_rule {
+            Some(rule) => rule.next(self.cells[linear_id], neighbors),
+            None => self.rule.next(self.cells[linear_id], neighbors.count_ones() as usize)
+        }
+    }
+
+    /// Computes the next generation into `scratch` on the calling thread.
+    /// Gives exactly the same result as `next_state`, without rayon, so it
+    /// can serve as the reference other backends are checked against.
+    pub fn next_state_serial(&self, scratch: &mut ConwayState) {
+        for y in 0..self.height {
+            for x in 0..self.width {
+                scratch.cells[y*self.width + x] = self.next_cell_state(x, y);
+            }
+        }
+    }
+
+    /// Returns the next generation, leaving `self` untouched.
+    pub fn step(&self) -> ConwayState {
+        let mut next = self.clone();
+        self.next_state_serial(&mut next);
+        next
+    }
+
+    /// Returns the state `n` generations on, leaving `self` untouched.
+    pub fn step_n(&self, n: usize) -> ConwayState {
+        self.generations().nth(n).expect("generations never ends")
+    }
+
+    /// Every generation from this one on, starting with a copy of `self`,
+    /// so `generations().nth(n)` is generation `n`.  Never ends.
+    ///
+    /// ```
+    /// # use rustlife::{CellState, ConwayState};
+    /// let mut blinker = ConwayState::empty(5, 5);
+    /// for x in 1..4 {
+    ///     blinker.set(x, 2, CellState::Alive);
+    /// }
+    /// let populations: Vec<usize> = blinker.generations().take(3).map(|g| g.population()).collect();
+    /// assert_eq!(populations, [3, 3, 3]);
+    /// ```
+    pub fn generations(&self) -> Generations {
+        Generations { current: self.clone(), scratch: self.clone() }
+    }
+
+    pub fn next_state(&self, scratch: &mut ConwayState) {
+        const ROWS_IN_CHUNK:usize = 2;
+        let elements_in_chunk = ROWS_IN_CHUNK * self.width;
+        let num_chunks = self.cells.len() / elements_in_chunk;
+        let rows_in_last_chunk = (self.cells.len() - num_chunks * elements_in_chunk) / self.width;
+
+        // No threads in the browser, so step the chunks one after another there
+        #[cfg(not(target_arch = "wasm32"))]
+        let chunks = scratch.cells.par_chunks_mut(elements_in_chunk);
+        #[cfg(target_arch = "wasm32")]
+        let chunks = scratch.cells.chunks_mut(elements_in_chunk);
+
+        chunks.enumerate().for_each(|(chunk, cells)| {
+            let row = chunk * ROWS_IN_CHUNK;
+            let rows = if chunk < num_chunks { ROWS_IN_CHUNK } else { rows_in_last_chunk };
+            for j in 0..rows {
+                for i in 0..self.width {
+                    cells[j*self.width + i] = self.next_cell_state(i, j + row);
+                }
+            }
+        });
+    }
+
+    /// Indices of the cells that differ between `self` and `next`.
+    pub fn changed_ids(&self, next: &ConwayState) -> Vec<usize> {
+        #[cfg(not(target_arch = "wasm32"))]
+        let pairs = self.cells.par_iter().zip(next.cells.par_iter());
+        #[cfg(target_arch = "wasm32")]
+        let pairs = self.cells.iter().zip(next.cells.iter());
+
+        pairs.enumerate().filter(|(_, (a, b))| a != b).map(|(id, _)| id).collect()
+    }
+
+    /// Like `next_state`, but with each thread of the pool stepping its own
+    /// strip of rows, see [`set_strips`].
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn next_state_strips(&self, scratch: &mut ConwayState) {
+        let width = self.width;
+        let strips = split_strips(&mut scratch.cells, width, self.height);
+        rayon::broadcast(|ctx| {
+            let rows = strip_rows(ctx.index(), ctx.num_threads(), self.height);
+            if let Ok(mut cells) = strips[ctx.index()].lock() {
+                for (j, y) in rows.enumerate() {
+                    for x in 0..width {
+                        cells[j * width + x] = self.next_cell_state(x, y);
+                    }
+                }
+            }
+        });
+    }
+
+    pub fn swap_state(&mut self, scratch: &mut ConwayState) {
+        std::mem::swap(&mut self.cells, &mut scratch.cells);
+    }
+
+    /// Population of `next`, the number of cells that differ between `self`
+    /// and `next`, and how many of those are alive in `next`.
+    pub fn diff_counts(&self, next: &ConwayState) -> (usize, usize, usize) {
+        #[cfg(not(target_arch = "wasm32"))]
+        let (cells, next_cells) = (self.cells.par_iter(), next.cells.par_iter());
+        #[cfg(target_arch = "wasm32")]
+        let (cells, next_cells) = (self.cells.iter(), next.cells.iter());
+
+        let population = next_cells.clone().filter(|c| **c == CellState::Alive).count();
+        let changed = cells.clone().zip(next_cells.clone()).filter(|(a, b)| a != b).count();
+        let born = cells.zip(next_cells).filter(|(a, b)| **a == CellState::Dead && **b == CellState::Alive).count();
+        (population, changed, born)
+    }
+}
+
+/// Iterator over successive generations, see [`ConwayState::generations`].
+pub struct Generations {
+    current: ConwayState,
+    scratch: ConwayState,
+}
+
+impl Iterator for Generations {
+    type Item = ConwayState;
+
+    fn next(&mut self) -> Option<ConwayState> {
+        let state = self.current.clone();
+        self.current.next_state(&mut self.scratch);
+        self.current.swap_state(&mut self.scratch);
+        Some(state)
+    }
+}
+
+/// Tells a backend's `commit_step` whether the generation `prepare_step`
+/// left in its scratch buffer is stale, because the universe was edited
+/// after it (or it was never prepared).
+pub(crate) struct StaleGuard {
+    edits: u64,
+    // `edits` when the scratch buffer was last computed
+    prepared: AtomicU64
+}
+
+impl Default for StaleGuard {
+    fn default() -> Self {
+        StaleGuard { edits: 0, prepared: AtomicU64::new(u64::MAX) }
+    }
+}
+
+impl StaleGuard {
+    /// Call on every edit.
+    pub(crate) fn bump(&mut self) {
+        self.edits += 1;
+    }
+
+    /// Call from `prepare_step`, before computing the next generation.
+    pub(crate) fn mark_prepared(&self) {
+        self.prepared.store(self.edits, Ordering::Relaxed);
+    }
+
+    pub(crate) fn is_stale(&mut self) -> bool {
+        *self.prepared.get_mut() != self.edits
+    }
+
+    /// Call once `commit_step` has used up the prepared generation.
+    pub(crate) fn committed(&mut self) {
+        *self.prepared.get_mut() = u64::MAX;
+    }
+}
+
+/// The original flat-array engine, stepped in parallel with rayon.
+pub struct DenseEngine {
+    state: ConwayState,
+    scratch: Mutex<ConwayState>,
+    population: usize,
+    changed: usize,
+    born: usize,
+    // Counts for the generation in `scratch`, filled in by `prepare_step`
+    next_population: AtomicUsize,
+    next_changed: AtomicUsize,
+    next_born: AtomicUsize,
+    // Live cells' bounding box, kept up to date through steps and edits.
+    // Killing a cell on its edge makes it `None` until the next step, as
+    // the box may have shrunk; `bounding_box` then scans instead.
+    bounds: Option<Option<BoundingBox>>,
+    next_bounds: Mutex<Option<Option<BoundingBox>>>,
+    // The live cells, indexed.  A step through the whole grid indexes the
+    // next generation afresh into `next_index`; one through the changes
+    // leaves it `None` and they are marked in `index` as it is committed.
+    index: Quadtree,
+    next_index: Mutex<Option<Quadtree>>,
+    // Cells the last step changed, and any edited since.  Only they and
+    // their neighbours can change next, so while there are few of them the
+    // step looks at nothing else.  `None` steps the whole grid.
+    changes: Option<Vec<usize>>,
+    next_changes: Mutex<Option<Vec<usize>>>,
+    stale: StaleGuard
+}
+
+impl DenseEngine {
+    pub fn new(mut state: ConwayState) -> Self {
+        let mut cells = first_touch(state.width, state.height);
+        cells.copy_from_slice(&state.cells);
+        state.cells = cells;
+        let scratch = Mutex::new(ConwayState::empty(state.width, state.height));
+        let population = state.population();
+        let bounds = Some(state.bounding_box());
+        let index = state.quadtree();
+        DenseEngine {
+            state,
+            scratch,
+            population,
+            changed: 0,
+            born: 0,
+            next_population: AtomicUsize::new(0),
+            next_changed: AtomicUsize::new(0),
+            next_born: AtomicUsize::new(0),
+            bounds,
+            next_bounds: Mutex::new(None),
+            index,
+            next_index: Mutex::new(None),
+            changes: None,
+            next_changes: Mutex::new(None),
+            stale: StaleGuard::default()
+        }
+    }
+
+    pub fn state(&self) -> &ConwayState {
+        &self.state
+    }
+
+    /// Joins the edges up into a torus, or makes them dead again.
+    pub fn set_boundary(&mut self, boundary: Boundary) {
+        self.state.boundary = boundary;
+        self.changes = None;
+        self.stale.bump();
+    }
+
+    /// An index of the live cells kept up to date as the universe steps and
+    /// is edited.
+    pub fn quadtree(&self) -> &Quadtree {
+        &self.index
+    }
+
+    /// Keeps the population, bounding box and change list right as the
+    /// cell at (x, y) becomes `state`.
+    fn note_change(&mut self, x: usize, y: usize, state: CellState) {
+        let was = self.state.get(x, y);
+        match (was, state) {
+            (CellState::Dead, CellState::Alive) => self.population += 1,
+            (CellState::Alive, CellState::Dead) => self.population -= 1,
+            _ => return
+        }
+        track_bounds(&mut self.bounds, x, y, state);
+        self.index.set(x, y, state == CellState::Alive);
+        let limit = self.state.cells.len() / CHANGE_SHARE;
+        if let Some(changes) = self.changes.as_mut() {
+            changes.push(y * self.state.width + x);
+            if changes.len() > limit {
+                self.changes = None;
+            }
+        }
+    }
+
+    /// The whole grid, in parallel.
+    fn next_all(&self, scratch: &mut ConwayState) {
+        tracing::info_span!("dense.next_state").in_scope(|| {
+            #[cfg(not(target_arch = "wasm32"))]
+            if strips() {
+                return self.state.next_state_strips(scratch);
+            }
+            self.state.next_state(scratch)
+        });
+        let (population, changed, born) = tracing::info_span!("dense.diff_counts")
+            .in_scope(|| self.state.diff_counts(scratch));
+        self.next_population.store(population, Ordering::Relaxed);
+        self.next_changed.store(changed, Ordering::Relaxed);
+        self.next_born.store(born, Ordering::Relaxed);
+        let index = tracing::info_span!("dense.index").in_scope(|| scratch.quadtree());
+        if let Ok(mut bounds) = self.next_bounds.lock() {
+            *bounds = Some(index.bounding_box());
+        }
+        if let Ok(mut next) = self.next_index.lock() {
+            *next = Some(index);
+        }
+        if let Ok(mut next) = self.next_changes.lock() {
+            *next = (changed <= self.state.cells.len() / CHANGE_SHARE).then(|| self.state.changed_ids(scratch));
+        }
+    }
+
+    /// Only the cells in `changes` and their neighbours.  `scratch` still
+    /// holds the generation before, which differs from this one just at
+    /// `changes`.
+    fn next_changed(&self, changes: &[usize], scratch: &mut ConwayState) {
+        let (width, height) = (self.state.width, self.state.height);
+        let cells = &self.state.cells;
+        for &id in changes {
+            scratch.cells[id] = cells[id];
+        }
+        let torus = self.state.boundary == Boundary::Torus;
+        let mut candidates = Vec::with_capacity(changes.len() * 9);
+        for &id in changes {
+            let (x, y) = (id % width, id / width);
+            if torus {
+                for dy in [height - 1, 0, 1] {
+                    for dx in [width - 1, 0, 1] {
+                        candidates.push((y + dy) % height * width + (x + dx) % width);
+                    }
+                }
+                continue;
+            }
+            for ny in y.saturating_sub(1)..=(y + 1).min(height - 1) {
+                for nx in x.saturating_sub(1)..=(x + 1).min(width - 1) {
+                    candidates.push(ny * width + nx);
+                }
+            }
+        }
+        candidates.sort_unstable();
+        candidates.dedup();
+
+        let (mut population, mut born) = (self.population, 0);
+        let mut bounds = self.bounds;
+        let mut next_changes = Vec::new();
+        for id in candidates {
+            let (x, y) = (id % width, id / width);
+            let next = self.state.next_cell_state(x, y);
+            scratch.cells[id] = next;
+            if next == cells[id] {
+                continue;
+            }
+            next_changes.push(id);
+            if next == CellState::Alive {
+                population += 1;
+                born += 1;
+            } else {
+                population -= 1;
+            }
+            track_bounds(&mut bounds, x, y, next);
+        }
+        self.next_population.store(population, Ordering::Relaxed);
+        self.next_changed.store(next_changes.len(), Ordering::Relaxed);
+        self.next_born.store(born, Ordering::Relaxed);
+        if let Ok(mut next) = self.next_bounds.lock() {
+            *next = bounds;
+        }
+        if let Ok(mut next) = self.next_changes.lock() {
+            *next = Some(next_changes);
+        }
+        if let Ok(mut next) = self.next_index.lock() {
+            *next = None;
+        }
+    }
+}
+
+// Past one cell in this many changing, stepping the whole grid in parallel
+// is quicker than going through the changes one by one
+pub(crate) const CHANGE_SHARE: usize = 64;
+
+/// Grows `bounds` to take in a cell at (x, y) that was born, or forgets
+/// them if one on the edge died, as the box may have shrunk.
+fn track_bounds(bounds: &mut Option<Option<BoundingBox>>, x: usize, y: usize, state: CellState) {
+    if state == CellState::Alive {
+        let cell = BoundingBox { min_x: x, min_y: y, max_x: x, max_y: y };
+        if let Some(b) = bounds.as_mut() {
+            *b = Some(b.map_or(cell, |b| b.union(cell)));
+        }
+    } else {
+        let on_edge = |b: &BoundingBox| x == b.min_x || x == b.max_x || y == b.min_y || y == b.max_y;
+        if bounds.is_some_and(|b| b.is_none_or(|b| on_edge(&b))) {
+            *bounds = None;
+        }
+    }
+}
+
+impl LifeEngine for DenseEngine {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn name(&self) -> &'static str {
+        "dense"
+    }
+
+    fn width(&self) -> usize {
+        self.state.width
+    }
+
+    fn height(&self) -> usize {
+        self.state.height
+    }
+
+    fn get(&self, x: usize, y: usize) -> CellState {
+        self.state.get(x, y)
+    }
+
+    fn set(&mut self, x: usize, y: usize, state: CellState) {
+        if self.state.wall(x, y).is_some() {
+            return;
+        }
+        self.note_change(x, y, state);
+        self.state.set(x, y, state);
+        self.stale.bump();
+    }
+
+    fn wall(&self, x: usize, y: usize) -> Option<CellState> {
+        self.state.wall(x, y)
+    }
+
+    fn set_wall(&mut self, x: usize, y: usize, wall: Option<CellState>) -> bool {
+        if let Some(state) = wall {
+            self.note_change(x, y, state);
+        }
+        self.state.set_wall(x, y, wall);
+        // A freed cell may change even though nothing around it has
+        if let Some(changes) = self.changes.as_mut() {
+            changes.push(y * self.state.width + x);
+        }
+        self.stale.bump();
+        true
+    }
+
+    fn rule(&self) -> Rule {
+        self.state.rule
+    }
+
+    fn set_rule(&mut self, rule: Rule) {
+        self.state.rule = rule;
+        self.changes = None;
+        self.stale.bump();
+    }
+
+    fn set_custom_rule(&mut self, rule: Option<Arc<dyn NeighborhoodRule>>) -> bool {
+        self.state.custom_rule = rule;
+        self.changes = None;
+        self.stale.bump();
+        true
+    }
+
+    fn prepare_step(&self) {
+        let _span = tracing::info_span!("dense.prepare_step").entered();
+        if let Ok(mut scratch) = self.scratch.lock() {
+            self.stale.mark_prepared();
+            match &self.changes {
+                Some(changes) => tracing::info_span!("dense.next_changed").in_scope(|| self.next_changed(changes, &mut scratch)),
+                None => self.next_all(&mut scratch)
+            }
+        }
+    }
+
+    fn commit_step(&mut self) {
+        let _span = tracing::info_span!("dense.commit_step").entered();
+        // The universe was edited after (or without) prepare_step, so the
+        // prepared generation would throw those edits away.
+        if self.stale.is_stale() {
+            self.prepare_step();
+        }
+        self.stale.committed();
+        if let Ok(scratch) = self.scratch.get_mut() {
+            self.state.swap_state(scratch);
+            self.population = *self.next_population.get_mut();
+            self.changed = *self.next_changed.get_mut();
+            self.born = *self.next_born.get_mut();
+            self.bounds = self.next_bounds.get_mut().ok().and_then(|b| *b);
+            self.changes = self.next_changes.get_mut().ok().and_then(|c| c.take());
+            match self.next_index.get_mut().ok().and_then(|i| i.take()) {
+                Some(index) => self.index = index,
+                None => {
+                    for &id in self.changes.iter().flatten() {
+                        let (x, y) = (id % self.state.width, id / self.state.width);
+                        self.index.set(x, y, self.state.cells[id] == CellState::Alive);
+                    }
+                }
+            }
+        }
+    }
+
+    fn changed_cells(&self) -> usize {
+        self.changed
+    }
+
+    fn born_cells(&self) -> usize {
+        self.born
+    }
+
+    fn resize(&mut self, width: usize, height: usize, anchor: Anchor) {
+        let old = &self.state;
+        let (ox, oy) = anchor.offset((old.width, old.height), (width, height));
+
+        let mut next = ConwayState::empty(width, height);
+        next.rule = old.rule;
+        next.custom_rule = old.custom_rule.clone();
+        next.boundary = old.boundary;
+        for y in 0..old.height {
+            let ny = y as isize + oy;
+            if ny < 0 || ny >= height as isize {
+                continue;
+            }
+            for x in 0..old.width {
+                let nx = x as isize + ox;
+                if nx >= 0 && nx < width as isize {
+                    next.set(nx as usize, ny as usize, old.get(x, y));
+                    next.set_wall(nx as usize, ny as usize, old.wall(x, y));
+                }
+            }
+        }
+
+        self.population = next.population();
+        self.bounds = Some(next.bounding_box());
+        self.index = next.quadtree();
+        self.changed = 0;
+        self.born = 0;
+        self.scratch = Mutex::new(ConwayState::empty(width, height));
+        self.state = next;
+        self.changes = None;
+        self.stale.bump();
+    }
+
+    fn population(&self) -> usize {
+        self.population
+    }
+
+    fn bounding_box(&self) -> Option<BoundingBox> {
+        self.bounds.unwrap_or_else(|| self.index.bounding_box())
+    }
+
+}
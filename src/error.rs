@@ -0,0 +1,70 @@
+use std::error::Error;
+use std::fmt;
+
+use error_iter::ErrorIter as _;
+
+/// Everything that can stop the program from starting.
+#[derive(Debug)]
+pub enum AppError {
+    /// Bad options, config, pattern or script
+    Setup(String),
+    #[cfg(feature = "gui")]
+    Window(winit::error::OsError),
+    #[cfg(feature = "gui")]
+    Pixels(pixels::Error),
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::Setup(msg) => write!(f, "{}", msg),
+            #[cfg(feature = "gui")]
+            AppError::Window(_) => write!(f, "could not create the window"),
+            #[cfg(feature = "gui")]
+            AppError::Pixels(pixels::Error::AdapterNotFound) => write!(f,
+                "no suitable graphics adapter found (try setting WGPU_BACKEND to vulkan, metal, dx12 or gl)"),
+            #[cfg(feature = "gui")]
+            AppError::Pixels(_) => write!(f, "could not set up the graphics surface"),
+        }
+    }
+}
+
+impl Error for AppError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            AppError::Setup(_) => None,
+            #[cfg(feature = "gui")]
+            AppError::Window(e) => Some(e),
+            #[cfg(feature = "gui")]
+            AppError::Pixels(e) => Some(e),
+        }
+    }
+}
+
+impl From<String> for AppError {
+    fn from(msg: String) -> Self {
+        AppError::Setup(msg)
+    }
+}
+
+#[cfg(feature = "gui")]
+impl From<winit::error::OsError> for AppError {
+    fn from(e: winit::error::OsError) -> Self {
+        AppError::Window(e)
+    }
+}
+
+#[cfg(feature = "gui")]
+impl From<pixels::Error> for AppError {
+    fn from(e: pixels::Error) -> Self {
+        AppError::Pixels(e)
+    }
+}
+
+/// Logs `err` and everything that caused it, one line each.
+pub fn log_error<E: Error + 'static>(context: &str, err: &E) {
+    log::error!("{}: {}", context, err);
+    for source in err.sources().skip(1) {
+        log::error!("  Caused by: {}", source);
+    }
+}
@@ -0,0 +1,66 @@
+//! Per-generation notifications.  Whoever steps the universe publishes a
+//! [`GenerationEvent`] after each step; statistics, auto-pause conditions,
+//! scripts and so on subscribe instead of polling the shared state.
+
+use std::sync::mpsc::{channel, Receiver, Sender};
+
+use crate::engine::LifeEngine;
+use crate::species::SpeciesEngine;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct GenerationEvent {
+    pub generation: u64,
+    pub population: usize,
+    /// Cells that changed state in this step
+    pub changed: usize,
+    /// Cells that came alive in this step, `changed - births` died
+    pub births: usize,
+    /// Live prey and predators, on backends with more than one species
+    pub species: Option<[usize; 2]>,
+}
+
+impl GenerationEvent {
+    pub fn deaths(&self) -> usize {
+        self.changed - self.births
+    }
+
+    pub fn from_engine(generation: u64, life: &dyn LifeEngine) -> Self {
+        GenerationEvent { generation, population: life.population(), changed: life.changed_cells(), births: life.born_cells(), species: life.downcast_ref::<SpeciesEngine>().map(SpeciesEngine::populations) }
+    }
+}
+
+pub type Callback = Box<dyn FnMut(&GenerationEvent) + Send>;
+
+/// Callback registry plus channel fan-out.  Callbacks run on the publishing
+/// thread, so they should be quick; use [`EventBus::channel`] to hand events
+/// to another thread instead.
+#[derive(Default)]
+pub struct EventBus {
+    callbacks: Vec<Callback>,
+    senders: Vec<Sender<GenerationEvent>>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        EventBus::default()
+    }
+
+    pub fn subscribe(&mut self, callback: impl FnMut(&GenerationEvent) + Send + 'static) {
+        self.callbacks.push(Box::new(callback));
+    }
+
+    /// Returns a receiver that gets a copy of every event.  Dropping the
+    /// receiver unsubscribes.
+    pub fn channel(&mut self) -> Receiver<GenerationEvent> {
+        let (tx, rx) = channel();
+        self.senders.push(tx);
+        rx
+    }
+
+    pub fn publish(&mut self, event: &GenerationEvent) {
+        for callback in self.callbacks.iter_mut() {
+            callback(event);
+        }
+        self.senders.retain(|tx| tx.send(*event).is_ok());
+    }
+}
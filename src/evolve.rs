@@ -0,0 +1,214 @@
+//! Evolving small seed patterns with a genetic algorithm.  A candidate is an
+//! 8x8 box of cells, one bit each; every generation the best few are kept
+//! as they are and the rest of the population is bred from winners of small
+//! tournaments, each child taking whole rows from one parent or the other
+//! and then having the odd cell flipped.
+//!
+//! Candidates are run from the middle of a [`GRID`] universe on the dense
+//! engine, whose change lists keep small patterns cheap, all of a
+//! generation's at once.  Anything that escapes runs into the dead edge of
+//! the grid and settles there.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use rayon::prelude::*;
+
+use crate::census;
+use crate::engine::{BoundingBox, CellState, ConwayState, DenseEngine, LifeEngine};
+use crate::rule::Rule;
+
+pub const SEED_BOX: usize = 8;
+/// Side of the universe candidates are run in
+pub const GRID: usize = 512;
+/// Most generations a candidate is run for before it is given up on
+pub const MAX_GENERATIONS: usize = 10_000;
+/// Generations a candidate has to travel in
+pub const TRAVEL_GENERATIONS: usize = 256;
+// Past this many live cells a candidate is taken to grow for ever
+const MAX_POPULATION: usize = 20_000;
+const CHECK_EVERY: usize = 16;
+// Candidates in each tournament, the best of whom is a parent
+const TOURNAMENT: usize = 3;
+// The best this many go on to the next generation unchanged
+const ELITE: usize = 2;
+// Chance of each cell of a child flipping
+const MUTATION: f64 = 1.0 / 32.0;
+
+/// What candidates are bred for.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Fitness {
+    /// Generations until the population settles into a cycle
+    #[default]
+    Lifespan,
+    /// Live cells once settled
+    Population,
+    /// How far the middle of the live cells' bounding box is from where it
+    /// started after [`TRAVEL_GENERATIONS`]
+    Distance,
+}
+
+impl Fitness {
+    /// A candidate's score in words.
+    pub fn describe(self, score: f64) -> String {
+        match self {
+            Fitness::Lifespan => format!("settles at generation {}", score),
+            Fitness::Population => format!("settles with {} cells", score),
+            Fitness::Distance => format!("travels {:.1} cells in {} generations", score, TRAVEL_GENERATIONS),
+        }
+    }
+}
+
+impl FromStr for Fitness {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "lifespan" => Ok(Fitness::Lifespan),
+            "population" => Ok(Fitness::Population),
+            "distance" => Ok(Fitness::Distance),
+            _ => Err(format!("unknown fitness '{}', expected lifespan, population or distance", s))
+        }
+    }
+}
+
+impl fmt::Display for Fitness {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Fitness::Lifespan => "lifespan",
+            Fitness::Population => "population",
+            Fitness::Distance => "distance",
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Candidate {
+    /// The cells of the box, bit `y * 8 + x` for (x, y)
+    pub genome: u64,
+    pub score: f64,
+}
+
+impl Candidate {
+    pub fn pattern(&self) -> Vec<(usize, usize)> {
+        pattern(self.genome)
+    }
+}
+
+/// The live cells of `genome`.
+pub fn pattern(genome: u64) -> Vec<(usize, usize)> {
+    (0..SEED_BOX * SEED_BOX).filter(|i| genome >> i & 1 == 1).map(|i| (i % SEED_BOX, i / SEED_BOX)).collect()
+}
+
+/// Runs `genome` under `rule` and scores it for `fitness`.  One that has not
+/// settled within [`MAX_GENERATIONS`], or looks to grow for ever, scores 0
+/// for lifespan and population.
+pub fn score(genome: u64, fitness: Fitness, rule: Rule) -> f64 {
+    let mut state = ConwayState::empty(GRID, GRID);
+    state.rule = rule;
+    let corner = (GRID - SEED_BOX) / 2;
+    for (x, y) in pattern(genome) {
+        state.set(corner + x, corner + y, CellState::Alive);
+    }
+    let mut life = DenseEngine::new(state);
+    let Some(start) = life.bounding_box() else { return 0.0 };
+    let middle = |bb: BoundingBox| ((bb.min_x + bb.max_x) as f64 / 2.0, (bb.min_y + bb.max_y) as f64 / 2.0);
+    let start = middle(start);
+
+    let mut populations = Vec::new();
+    for generation in 1..=MAX_GENERATIONS {
+        life.prepare_step();
+        life.commit_step();
+        let population = life.population();
+        if fitness == Fitness::Distance && (generation == TRAVEL_GENERATIONS || population == 0) {
+            let Some(bb) = life.bounding_box() else { return 0.0 };
+            let (x, y) = middle(bb);
+            return (x - start.0).hypot(y - start.1);
+        }
+        if population > MAX_POPULATION {
+            return 0.0;
+        }
+        populations.push(population);
+        if fitness != Fitness::Distance && generation % CHECK_EVERY == 0 {
+            if let Some(at) = census::settled(&populations) {
+                return match fitness {
+                    Fitness::Population => population as f64,
+                    _ => at as f64
+                };
+            }
+        }
+    }
+    0.0
+}
+
+/// A population of candidates being bred for `fitness`.
+pub struct Evolution {
+    pub fitness: Fitness,
+    pub rule: Rule,
+    /// Best first
+    pub candidates: Vec<Candidate>,
+    pub generation: usize,
+    rng: ChaCha8Rng,
+    // Scores of every genome seen, so none is run twice
+    scores: HashMap<u64, f64>,
+}
+
+impl Evolution {
+    /// `size` random candidates, about a quarter of each box alive, picked
+    /// by `seed` and scored.
+    pub fn new(size: usize, fitness: Fitness, rule: Rule, seed: u64) -> Self {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let genomes: Vec<u64> = (0..size.max(ELITE + 1)).map(|_| rng.gen::<u64>() & rng.gen::<u64>()).collect();
+        let mut evolution = Evolution { fitness, rule, candidates: Vec::new(), generation: 0, rng, scores: HashMap::new() };
+        evolution.candidates = evolution.score_all(genomes);
+        evolution
+    }
+
+    pub fn best(&self) -> Candidate {
+        self.candidates[0]
+    }
+
+    /// Breeds the next generation.
+    pub fn step(&mut self) {
+        let size = self.candidates.len();
+        let mut genomes: Vec<u64> = self.candidates[..ELITE].iter().map(|c| c.genome).collect();
+        while genomes.len() < size {
+            let (a, b) = (self.tournament(), self.tournament());
+            // Whole rows from one parent or the other
+            let rows = (0..SEED_BOX).filter(|_| self.rng.gen_bool(0.5)).fold(0u64, |mask, row| mask | 0xff << (row * SEED_BOX));
+            let mut child = (a & rows) | (b & !rows);
+            for bit in 0..SEED_BOX * SEED_BOX {
+                if self.rng.gen_bool(MUTATION) {
+                    child ^= 1 << bit;
+                }
+            }
+            genomes.push(child);
+        }
+        self.candidates = self.score_all(genomes);
+        self.generation += 1;
+    }
+
+    /// The genome of the best of [`TOURNAMENT`] candidates picked at random.
+    fn tournament(&mut self) -> u64 {
+        // Best first, so the lowest index wins
+        let best = (0..TOURNAMENT).map(|_| self.rng.gen_range(0..self.candidates.len())).min().unwrap_or(0);
+        self.candidates[best].genome
+    }
+
+    /// Scores `genomes`, those not seen before in parallel, best first with
+    /// ties to the smaller genome.
+    fn score_all(&mut self, genomes: Vec<u64>) -> Vec<Candidate> {
+        let mut new: Vec<u64> = genomes.iter().copied().filter(|g| !self.scores.contains_key(g)).collect();
+        new.sort_unstable();
+        new.dedup();
+        let (fitness, rule) = (self.fitness, self.rule);
+        let scored: Vec<(u64, f64)> = new.into_par_iter().map(|g| (g, score(g, fitness, rule))).collect();
+        self.scores.extend(scored);
+        let mut candidates: Vec<Candidate> = genomes.into_iter().map(|genome| Candidate { genome, score: self.scores[&genome] }).collect();
+        candidates.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.genome.cmp(&b.genome)));
+        candidates
+    }
+}
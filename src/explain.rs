@@ -0,0 +1,120 @@
+//! Explain mode, for learning the rules while paused: a clicked cell is
+//! shown with its neighbourhood, how many of the eight are alive and which
+//! part of the rule decides what it does next.  Stepping first goes through
+//! the cells that will change one at a time, marking each as it goes.
+
+use std::time::{Duration, Instant};
+
+use rustlife::inspect;
+use rustlife::{CellState, DenseEngine, LifeEngine};
+
+use crate::overlay;
+use crate::render::View;
+
+// How long the sweep stays on each changing cell, and longest it takes over
+// a whole generation, going faster when many cells change
+const CELL_TIME: Duration = Duration::from_millis(400);
+const SWEEP_TIME: Duration = Duration::from_secs(8);
+
+const NEIGHBOURHOOD: [u8; 4] = [255, 220, 64, 255];
+const LIVE_NEIGHBOUR: [u8; 4] = [255, 220, 64, 110];
+const BORN: [u8; 4] = [64, 255, 64, 150];
+const DIED: [u8; 4] = [255, 64, 64, 150];
+
+#[derive(Default)]
+pub struct Explainer {
+    selected: Option<(usize, usize)>,
+    // The cells that change next generation and when the sweep through
+    // them started
+    sweep: Option<(Vec<(usize, usize)>, Instant)>,
+}
+
+impl Explainer {
+    pub fn select(&mut self, cell: (usize, usize)) {
+        self.selected = Some(cell);
+    }
+
+    pub fn is_sweeping(&self) -> bool {
+        self.sweep.is_some()
+    }
+
+    /// Starts going through the cells that will change.  False if none do,
+    /// so there is nothing to show before stepping.
+    pub fn start_sweep(&mut self, life: &dyn LifeEngine) -> bool {
+        let changes = inspect::changes(life);
+        self.sweep = Some((changes, Instant::now())).filter(|(c, _)| !c.is_empty());
+        self.sweep.is_some()
+    }
+
+    /// True, once, when the sweep has been through every cell and the
+    /// generation should be stepped.
+    pub fn sweep_done(&mut self) -> bool {
+        let done = self.sweep.as_ref().is_some_and(|(changes, started)| self.swept(changes, *started) > changes.len());
+        if done {
+            self.sweep = None;
+        }
+        done
+    }
+
+    /// Ends the sweep early, as when the universe changes under it.
+    pub fn stop_sweep(&mut self) {
+        self.sweep = None;
+    }
+
+    // How many cells the sweep has reached, counting the one it is on
+    fn swept(&self, changes: &[(usize, usize)], started: Instant) -> usize {
+        let per_cell = CELL_TIME.min(SWEEP_TIME / changes.len().max(1) as u32);
+        (started.elapsed().as_micros() / per_cell.as_micros().max(1)) as usize + 1
+    }
+
+    /// The cell being explained: the one the sweep is on, or else the one
+    /// clicked.
+    fn current(&self) -> Option<(usize, usize)> {
+        match &self.sweep {
+            Some((changes, started)) => changes.get(self.swept(changes, *started) - 1).copied(),
+            None => self.selected
+        }
+    }
+
+    /// Lines for the status panel, starting with the rule.
+    pub fn status(&self, life: &dyn LifeEngine) -> Vec<String> {
+        // A plugin rule decides instead of the B/S one, when there is one
+        let plugin = life.downcast_ref::<DenseEngine>().and_then(|d| d.state().custom_rule.as_ref().map(|r| r.name().to_string()));
+        let mut lines = vec![format!("explaining {}", plugin.unwrap_or_else(|| life.rule().to_string()))];
+        match self.current().filter(|&(x, y)| x < life.width() && y < life.height()) {
+            Some((x, y)) => lines.push(format!("{},{} {}", x, y, inspect::explain(&inspect::inspect(life, x, y), &life.rule()))),
+            None => lines.push("click a cell to see why it lives or dies; step to go through a generation".to_string())
+        }
+        if let Some((changes, started)) = &self.sweep {
+            lines.push(format!("change {} of {}", self.swept(changes, *started).min(changes.len()), changes.len()));
+        }
+        lines
+    }
+
+    pub fn draw(&self, screen: &mut [u8], width: u32, height: u32, view: &View, life: &dyn LifeEngine) {
+        let scale = view.scale(width);
+        let size = (scale.ceil() as u32).max(1);
+        let cell_at = |x: usize, y: usize| view.screen_pos((x as f64, y as f64), (width, height));
+        if let Some((changes, started)) = &self.sweep {
+            // The cells already gone through, as they will be
+            for &(x, y) in changes.iter().take(self.swept(changes, *started) - 1) {
+                let (px, py) = cell_at(x, y);
+                let color = if life.get(x, y) == CellState::Alive { DIED } else { BORN };
+                overlay::fill_rect(screen, width, height, (px, py, size, size), color);
+            }
+        }
+        let Some((x, y)) = self.current() else { return };
+        for ny in y.saturating_sub(1)..=(y + 1).min(life.height() - 1) {
+            for nx in x.saturating_sub(1)..=(x + 1).min(life.width() - 1) {
+                if (nx, ny) != (x, y) && life.get(nx, ny) == CellState::Alive {
+                    let (px, py) = cell_at(nx, ny);
+                    overlay::fill_rect(screen, width, height, (px, py, size, size), LIVE_NEIGHBOUR);
+                }
+            }
+        }
+        let (px, py) = view.screen_pos((x as f64 - 1.0, y as f64 - 1.0), (width, height));
+        let (left, top) = cell_at(x, y);
+        overlay::outline(screen, width, height, (px, py, 3 * size, 3 * size), NEIGHBOURHOOD);
+        overlay::outline(screen, width, height, (left - 1, top - 1, size + 2, size + 2), NEIGHBOURHOOD);
+    }
+}
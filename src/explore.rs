@@ -0,0 +1,158 @@
+//! Rule exploration: random B/S rules are each tried on a soup for a few
+//! hundred generations and scored by how interesting the result looks.
+//! Rules that die out, fill the universe or freeze into still lifes and
+//! short oscillators score nothing; of the rest, the more cells keep
+//! changing the better, less the more of the universe has filled up.
+//!
+//! Rules with B0 are left out, as on a finite grid they only flash.  The
+//! same rule number always gives the same rule and soup.
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::engine::{CellState, ConwayState};
+use crate::rule::Rule;
+use crate::soup::{self, Symmetry};
+
+/// Side of the soup each rule starts from, in the middle of the grid
+pub const SOUP_SIZE: usize = 32;
+/// Side of the universe rules are tried in
+pub const GRID: usize = 80;
+pub const GENERATIONS: usize = 300;
+// Activity is measured over the last quarter of the run
+const MEASURED: usize = GENERATIONS / 4;
+// Settled means back where it was within this many generations
+const MAX_PERIOD: usize = 8;
+// Exploded means more than this share of the grid alive
+const EXPLODED: f64 = 0.25;
+
+/// How a rule's soup ended up.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    DiedOut,
+    Exploded,
+    /// Repeating with a short period, or still
+    Settled,
+    Active,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Trial {
+    /// Rule number, which [`random_rule`] turns into the rule
+    pub number: u64,
+    pub rule: Rule,
+    pub outcome: Outcome,
+    /// Cells that changed a generation at the end, on average
+    pub activity: f64,
+    pub population: usize,
+    pub score: f64,
+}
+
+/// Rule number `number`: each birth count from 3 and survival count from 0
+/// in it with probability 1/2.  Births on 1 or 2 neighbours make nearly any
+/// soup explode, so they come up only one time in eight.
+pub fn random_rule(number: u64) -> Rule {
+    let mut rng = ChaCha8Rng::seed_from_u64(number);
+    let mut rule = Rule { birth: [false; 9], survive: [false; 9] };
+    for n in 1..9 {
+        rule.birth[n] = rng.gen_bool(if n < 3 { 0.125 } else { 0.5 });
+    }
+    for n in 0..9 {
+        rule.survive[n] = rng.gen_bool(0.5);
+    }
+    rule
+}
+
+/// Runs `rule` from soup number `number` and scores it.
+pub fn trial(number: u64, rule: Rule) -> Trial {
+    let mut state = ConwayState::empty(GRID, GRID);
+    state.rule = rule;
+    let corner = (GRID - SOUP_SIZE) / 2;
+    for (x, y) in soup::generate(Symmetry::C1, SOUP_SIZE, 0.5, number) {
+        state.set(corner + x, corner + y, CellState::Alive);
+    }
+    let mut scratch = state.clone();
+    let mut changed = 0;
+    for generation in 1..=GENERATIONS {
+        state.next_state_serial(&mut scratch);
+        if generation > GENERATIONS - MEASURED {
+            changed += state.cells.iter().zip(&scratch.cells).filter(|(a, b)| a != b).count();
+        }
+        state.swap_state(&mut scratch);
+    }
+    let activity = changed as f64 / MEASURED as f64;
+    let population = state.population();
+    let fill = population as f64 / (GRID * GRID) as f64;
+
+    let outcome = if population == 0 {
+        Outcome::DiedOut
+    } else if fill > EXPLODED {
+        Outcome::Exploded
+    } else if settled(&state) {
+        Outcome::Settled
+    } else {
+        Outcome::Active
+    };
+    let score = match outcome {
+        Outcome::Active => activity / (SOUP_SIZE * SOUP_SIZE) as f64 * (1.0 - fill / EXPLODED),
+        _ => 0.0
+    };
+    Trial { number, rule, outcome, activity, population, score }
+}
+
+/// Whether `state` comes back to itself within [`MAX_PERIOD`] generations.
+fn settled(state: &ConwayState) -> bool {
+    state.generations().skip(1).take(MAX_PERIOD).any(|later| later.cells == state.cells)
+}
+
+/// The best scoring rules tried so far, and how the rest went.
+#[derive(Clone, Debug, Default)]
+pub struct Ranking {
+    pub keep: usize,
+    /// Best first
+    pub top: Vec<Trial>,
+    pub tried: u64,
+    pub died_out: u64,
+    pub exploded: u64,
+    pub settled: u64,
+}
+
+impl Ranking {
+    pub fn new(keep: usize) -> Self {
+        Ranking { keep, ..Ranking::default() }
+    }
+
+    pub fn add(&mut self, trial: Trial) {
+        self.tried += 1;
+        match trial.outcome {
+            Outcome::DiedOut => self.died_out += 1,
+            Outcome::Exploded => self.exploded += 1,
+            Outcome::Settled => self.settled += 1,
+            Outcome::Active => self.insert(trial)
+        }
+    }
+
+    pub fn merge(&mut self, other: Ranking) {
+        self.tried += other.tried;
+        self.died_out += other.died_out;
+        self.exploded += other.exploded;
+        self.settled += other.settled;
+        for t in other.top {
+            self.insert(t);
+        }
+    }
+
+    /// Highest first, ties to the lower number.  A rule already in is kept
+    /// once, with its better score.
+    fn insert(&mut self, t: Trial) {
+        if let Some(i) = self.top.iter().position(|o| o.rule == t.rule) {
+            if self.top[i].score >= t.score {
+                return;
+            }
+            self.top.remove(i);
+        }
+        let at = self.top.partition_point(|o| o.score > t.score || (o.score == t.score && o.number < t.number));
+        self.top.insert(at, t);
+        self.top.truncate(self.keep);
+    }
+}
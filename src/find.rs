@@ -0,0 +1,61 @@
+//! Finds every copy of a small pattern in the universe, in any of its eight
+//! orientations.  A copy only counts if it stands alone: the ring of cells
+//! around its bounding box must be dead, so a block is not found inside
+//! every larger blob.  Cells past the edge of the grid count as dead.
+
+use crate::apgcode;
+use crate::engine::{BoundingBox, CellState, LifeEngine};
+use crate::io;
+
+/// Bounding boxes of the copies of `pattern`, top to bottom.
+pub fn find(life: &dyn LifeEngine, pattern: &[(usize, usize)]) -> Vec<BoundingBox> {
+    let mut shapes = apgcode::orientations(pattern);
+    shapes.sort();
+    shapes.dedup();
+    let shapes: Vec<_> = shapes.into_iter().filter(|s| !s.is_empty()).collect();
+
+    let (w, h) = (life.width(), life.height());
+    let mut found = Vec::new();
+    for y in 0..h {
+        for x in 0..w {
+            if life.get(x, y) != CellState::Alive {
+                continue;
+            }
+            // Try each shape with its first live cell, in row order, here
+            for shape in &shapes {
+                let (fx, fy) = shape.iter().copied().min_by_key(|&(x, y)| (y, x)).unwrap_or_default();
+                if x < fx || y < fy {
+                    continue;
+                }
+                let (ox, oy) = (x - fx, y - fy);
+                let (sw, sh) = io::extent(shape);
+                if ox + sw > w || oy + sh > h || !matches(life, shape, (ox, oy), (sw, sh)) {
+                    continue;
+                }
+                found.push(BoundingBox { min_x: ox, min_y: oy, max_x: ox + sw - 1, max_y: oy + sh - 1 });
+            }
+        }
+    }
+    found
+}
+
+/// True if the box at `origin` plus a one cell ring holds exactly `shape`.
+fn matches(life: &dyn LifeEngine, shape: &[(usize, usize)], origin: (usize, usize), (sw, sh): (usize, usize)) -> bool {
+    let mut wanted = vec![false; sw * sh];
+    for &(x, y) in shape {
+        wanted[y * sw + x] = true;
+    }
+    for dy in -1..=sh as isize {
+        for dx in -1..=sw as isize {
+            let inside = dx >= 0 && dy >= 0 && (dx as usize) < sw && (dy as usize) < sh;
+            let expected = inside && wanted[dy as usize * sw + dx as usize];
+            let (x, y) = (origin.0 as isize + dx, origin.1 as isize + dy);
+            let alive = x >= 0 && y >= 0 && (x as usize) < life.width() && (y as usize) < life.height()
+                && life.get(x as usize, y as usize) == CellState::Alive;
+            if alive != expected {
+                return false;
+            }
+        }
+    }
+    true
+}
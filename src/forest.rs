@@ -0,0 +1,279 @@
+//! The Drossel-Schwabl forest-fire model.  Each generation:
+//!
+//! - a burning tree burns out, leaving empty ground,
+//! - a tree next to a burning one (up, down, left or right) catches fire,
+//! - any other tree is struck by lightning with probability `lightning`,
+//! - empty ground grows a tree with probability `growth`.
+//!
+//! With lightning much rarer than growth the forest fills up until a strike
+//! sets off a fire, and fires of every size turn up.  Chances come from a
+//! hash of the cell, generation and seed, so a run is the same however the
+//! work is split between threads.
+
+use std::any::Any;
+use std::sync::Mutex;
+
+#[cfg(not(target_arch = "wasm32"))]
+use rayon::prelude::*;
+
+use crate::engine::{cell_chance, Anchor, CellState, LifeEngine, StaleGuard};
+use crate::rule::Rule;
+
+pub const DEFAULT_GROWTH: f64 = 0.01;
+pub const DEFAULT_LIGHTNING: f64 = 0.00001;
+
+const TREE: [u8; 3] = [0x20, 0xa0, 0x30];
+const FIRE: [u8; 3] = [0xff, 0x80, 0x10];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Cell {
+    Empty,
+    Tree,
+    Burning,
+}
+
+/// The model's chances and what it looks like now.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ForestStats {
+    pub growth: f64,
+    pub lightning: f64,
+    pub trees: usize,
+    pub burning: usize,
+    /// Lightning strikes since the start
+    pub strikes: u64,
+}
+
+// Trees, burning trees, strikes, changed and born cells
+type Counts = (usize, usize, u64, usize, usize);
+
+pub struct ForestEngine {
+    width: usize,
+    height: usize,
+    cells: Vec<Cell>,
+    growth: f64,
+    lightning: f64,
+    generation: u64,
+    seed: u64,
+    rule: Rule,
+    scratch: Mutex<Vec<Cell>>,
+    trees: usize,
+    burning: usize,
+    strikes: u64,
+    changed: usize,
+    born: usize,
+    next_counts: Mutex<Counts>,
+    stale: StaleGuard,
+}
+
+impl ForestEngine {
+    pub fn new(width: usize, height: usize) -> Self {
+        ForestEngine {
+            width,
+            height,
+            cells: vec![Cell::Empty; width * height],
+            growth: DEFAULT_GROWTH,
+            lightning: DEFAULT_LIGHTNING,
+            generation: 0,
+            seed: 0,
+            rule: Rule::default(),
+            scratch: Mutex::new(vec![Cell::Empty; width * height]),
+            trees: 0,
+            burning: 0,
+            strikes: 0,
+            changed: 0,
+            born: 0,
+            next_counts: Mutex::new((0, 0, 0, 0, 0)),
+            stale: StaleGuard::default(),
+        }
+    }
+
+    fn put(&mut self, x: usize, y: usize, cell: Cell) {
+        let i = y * self.width + x;
+        match self.cells[i] {
+            Cell::Tree => self.trees -= 1,
+            Cell::Burning => self.burning -= 1,
+            Cell::Empty => {}
+        }
+        match cell {
+            Cell::Tree => self.trees += 1,
+            Cell::Burning => self.burning += 1,
+            Cell::Empty => {}
+        }
+        self.cells[i] = cell;
+        self.stale.bump();
+    }
+
+    /// Whether a burning tree is up, down, left or right of (x, y).
+    fn next_to_fire(&self, x: usize, y: usize) -> bool {
+        let burning = |nx: usize, ny: usize| self.cells[ny * self.width + nx] == Cell::Burning;
+        (x > 0 && burning(x - 1, y))
+            || (x + 1 < self.width && burning(x + 1, y))
+            || (y > 0 && burning(x, y - 1))
+            || (y + 1 < self.height && burning(x, y + 1))
+    }
+
+    fn next_cell(&self, x: usize, y: usize) -> Cell {
+        match self.cells[y * self.width + x] {
+            Cell::Burning => Cell::Empty,
+            Cell::Tree if self.next_to_fire(x, y) => Cell::Burning,
+            Cell::Tree if cell_chance(x, y, self.generation, self.seed) < self.lightning => Cell::Burning,
+            Cell::Tree => Cell::Tree,
+            Cell::Empty if cell_chance(x, y, self.generation, self.seed) < self.growth => Cell::Tree,
+            Cell::Empty => Cell::Empty,
+        }
+    }
+
+    /// Growth and lightning chances and the state of the forest.
+    pub fn stats(&self) -> ForestStats {
+        ForestStats { growth: self.growth, lightning: self.lightning, trees: self.trees, burning: self.burning, strikes: self.strikes }
+    }
+
+    pub fn set_chances(&mut self, growth: f64, lightning: f64) {
+        (self.growth, self.lightning) = (growth, lightning);
+        self.stale.bump();
+    }
+}
+
+impl LifeEngine for ForestEngine {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn name(&self) -> &'static str {
+        "forest"
+    }
+
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Trees count as alive, burning or not.
+    fn get(&self, x: usize, y: usize) -> CellState {
+        if self.cells[y * self.width + x] == Cell::Empty { CellState::Dead } else { CellState::Alive }
+    }
+
+    /// Live cells are trees.
+    fn set(&mut self, x: usize, y: usize, state: CellState) {
+        self.put(x, y, if state == CellState::Alive { Cell::Tree } else { Cell::Empty });
+    }
+
+    fn color(&self, x: usize, y: usize) -> Option<[u8; 3]> {
+        match self.cells[y * self.width + x] {
+            Cell::Empty => None,
+            Cell::Tree => Some(TREE),
+            Cell::Burning => Some(FIRE),
+        }
+    }
+
+    /// Kept for the other backends' sake; the forest has no B/S rule.
+    fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    fn set_rule(&mut self, rule: Rule) {
+        self.rule = rule;
+    }
+
+    fn prepare_step(&self) {
+        let _span = tracing::info_span!("forest.prepare_step").entered();
+        if let Ok(mut scratch) = self.scratch.lock() {
+            self.stale.mark_prepared();
+            let width = self.width.max(1);
+
+            #[cfg(not(target_arch = "wasm32"))]
+            let rows = scratch.par_chunks_mut(width);
+            #[cfg(target_arch = "wasm32")]
+            let rows = scratch.chunks_mut(width);
+
+            rows.enumerate().for_each(|(y, row)| {
+                for (x, cell) in row.iter_mut().enumerate() {
+                    *cell = self.next_cell(x, y);
+                }
+            });
+
+            let mut counts: Counts = (0, 0, self.strikes, 0, 0);
+            for (i, (old, new)) in self.cells.iter().zip(scratch.iter()).enumerate() {
+                match new {
+                    Cell::Tree => counts.0 += 1,
+                    Cell::Burning => counts.1 += 1,
+                    Cell::Empty => {}
+                }
+                if old != new {
+                    counts.3 += 1;
+                    counts.4 += (*old == Cell::Empty) as usize;
+                }
+                // A tree catching fire with no fire next to it was struck
+                if *old == Cell::Tree && *new == Cell::Burning && !self.next_to_fire(i % width, i / width) {
+                    counts.2 += 1;
+                }
+            }
+            if let Ok(mut next) = self.next_counts.lock() {
+                *next = counts;
+            }
+        }
+    }
+
+    fn commit_step(&mut self) {
+        if self.stale.is_stale() {
+            self.prepare_step();
+        }
+        self.stale.committed();
+        if let (Ok(scratch), Ok(counts)) = (self.scratch.get_mut(), self.next_counts.get_mut()) {
+            std::mem::swap(&mut self.cells, scratch);
+            (self.trees, self.burning, self.strikes, self.changed, self.born) = *counts;
+            self.generation += 1;
+        }
+    }
+
+    fn changed_cells(&self) -> usize {
+        self.changed
+    }
+
+    fn born_cells(&self) -> usize {
+        self.born
+    }
+
+    fn resize(&mut self, width: usize, height: usize, anchor: Anchor) {
+        let (ox, oy) = anchor.offset((self.width, self.height), (width, height));
+        let old = std::mem::replace(&mut self.cells, vec![Cell::Empty; width * height]);
+        let (old_width, old_height) = (self.width, self.height);
+        (self.width, self.height) = (width, height);
+        (self.trees, self.burning) = (0, 0);
+        // `put` counts the trees back in
+        for y in 0..old_height {
+            for x in 0..old_width {
+                let (nx, ny) = (x as isize + ox, y as isize + oy);
+                if nx >= 0 && ny >= 0 && nx < width as isize && ny < height as isize {
+                    self.put(nx as usize, ny as usize, old[y * old_width + x]);
+                }
+            }
+        }
+        self.scratch = Mutex::new(vec![Cell::Empty; width * height]);
+        self.changed = 0;
+        self.born = 0;
+    }
+
+    /// Plants trees as the other backends plant live cells, and reseeds the
+    /// growth and lightning so the run repeats.
+    fn randomize(&mut self, density: f64, seed: u64) {
+        (self.seed, self.generation, self.strikes) = (seed, 0, 0);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let tree = cell_chance(x, y, u64::MAX, seed) < density;
+                self.put(x, y, if tree { Cell::Tree } else { Cell::Empty });
+            }
+        }
+    }
+
+    fn population(&self) -> usize {
+        self.trees + self.burning
+    }
+}
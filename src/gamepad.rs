@@ -0,0 +1,97 @@
+//! Gamepad control, for the sofa, read with gilrs from whichever pads are
+//! plugged in.  What the sticks and buttons do is in [`rustlife::pad`];
+//! this turns gilrs' events into its inputs and its actions into commands.
+
+use std::time::Instant;
+
+use gilrs::{EventType, Gilrs};
+use rustlife::library;
+use rustlife::pad::{Action, Axis, Button, Input, Sticks};
+
+use crate::commands::Command;
+use crate::render::View;
+
+// Cells across the view when zoomed in all the way
+const CLOSEST: f64 = 8.0;
+
+pub struct Gamepad {
+    gilrs: Gilrs,
+    sticks: Sticks,
+    // The built in pattern last shown
+    showing: Option<usize>,
+    steered: Instant,
+}
+
+/// The input an event is, if it is one that does something.
+fn input(event: EventType) -> Option<Input> {
+    let button = |b| match b {
+        gilrs::Button::South => Some(Button::South),
+        gilrs::Button::East => Some(Button::East),
+        gilrs::Button::DPadLeft => Some(Button::DPadLeft),
+        gilrs::Button::DPadRight => Some(Button::DPadRight),
+        _ => None
+    };
+    match event {
+        EventType::ButtonPressed(b, _) => button(b).map(Input::Pressed),
+        EventType::AxisChanged(gilrs::Axis::LeftStickX, v, _) => Some(Input::Moved(Axis::LeftStickX, v as f64)),
+        EventType::AxisChanged(gilrs::Axis::LeftStickY, v, _) => Some(Input::Moved(Axis::LeftStickY, v as f64)),
+        EventType::ButtonChanged(gilrs::Button::LeftTrigger2, v, _) => Some(Input::Moved(Axis::LeftTrigger, v as f64)),
+        EventType::ButtonChanged(gilrs::Button::RightTrigger2, v, _) => Some(Input::Moved(Axis::RightTrigger, v as f64)),
+        EventType::Disconnected => Some(Input::Disconnected),
+        _ => None
+    }
+}
+
+impl Gamepad {
+    pub fn open() -> Result<Gamepad, String> {
+        let gilrs = Gilrs::new().map_err(|e| format!("gamepad: {}", e))?;
+        for (_, pad) in gilrs.gamepads() {
+            log::info!("Gamepad {}", pad.name());
+        }
+        Ok(Gamepad { gilrs, sticks: Sticks::default(), showing: None, steered: Instant::now() })
+    }
+
+    /// The presses since last time.
+    pub fn commands(&mut self) -> Vec<Command> {
+        let patterns = library::builtin();
+        let mut commands = Vec::new();
+        while let Some(event) = self.gilrs.next_event() {
+            let Some(action) = input(event.event).and_then(|i| self.sticks.apply(i)) else { continue };
+            let n = patterns.len();
+            let turn = |step: isize| match self.showing {
+                Some(i) => (i + n).wrapping_add_signed(step) % n,
+                None if step > 0 => 0,
+                None => n - 1
+            };
+            commands.push(match action {
+                Action::TogglePause => Command::TogglePause,
+                Action::Step => Command::Step(1),
+                Action::NextPattern | Action::PreviousPattern => {
+                    let next = turn(if action == Action::NextPattern { 1 } else { -1 });
+                    self.showing = Some(next);
+                    Command::ShowBuiltin(patterns[next].name.clone())
+                }
+            });
+        }
+        commands
+    }
+
+    /// Pans and zooms `view` as far as the stick and triggers ask for since
+    /// the last time.  False if they are all at rest.
+    pub fn steer(&mut self, view: &mut View, grid: (usize, usize)) -> bool {
+        let seconds = self.steered.elapsed().as_secs_f64();
+        self.steered = Instant::now();
+        let Some(motion) = self.sticks.motion(seconds) else { return false };
+        view.x += motion.pan.0 * view.width;
+        view.y += motion.pan.1 * view.height;
+        // About the centre, keeping the cells' shape
+        let whole = View::whole(grid);
+        let width = (view.width / motion.zoom).clamp(CLOSEST.min(whole.width), whole.width);
+        let height = view.height * width / view.width;
+        view.x += (view.width - width) / 2.0;
+        view.y += (view.height - height) / 2.0;
+        view.width = width;
+        view.height = height;
+        true
+    }
+}
@@ -0,0 +1,358 @@
+//! Lattice gases: each cell holds at most one particle moving in each of a
+//! few directions, one bit per direction.  Every generation the particles
+//! in a cell collide, then each moves one cell on.  Collisions keep the
+//! number of particles and their total momentum, which is all it takes for
+//! the gas to behave like a fluid when zoomed out.
+//!
+//! - HPP moves along the four sides of a square.  Two particles meeting head
+//!   on leave at right angles.
+//! - FHP moves along the six sides of a hexagon, laid out on the square grid
+//!   by shifting every other row half a cell right.  Two particles meeting
+//!   head on turn 60° one way or the other, and three meeting at 120° bounce
+//!   straight back.
+//!
+//! Particles bounce off the edges of the grid.  Cells are drawn by how full
+//! they are, so a zoomed out view shows the density of the gas.
+
+use std::fmt;
+use std::str::FromStr;
+use std::any::Any;
+use std::sync::Mutex;
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+#[cfg(not(target_arch = "wasm32"))]
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::engine::{cell_hash, Anchor, CellState, LifeEngine, StaleGuard};
+use crate::rule::Rule;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GasModel {
+    #[default]
+    Hpp,
+    Fhp,
+}
+
+impl GasModel {
+    pub const ALL: [GasModel; 2] = [GasModel::Hpp, GasModel::Fhp];
+
+    /// Number of directions, one bit each.
+    pub fn directions(self) -> usize {
+        match self {
+            GasModel::Hpp => 4,
+            GasModel::Fhp => 6,
+        }
+    }
+
+    /// Unit velocity of direction `d`, anticlockwise from east with y up.
+    fn velocity(self, d: usize) -> (f64, f64) {
+        let angle = std::f64::consts::TAU * d as f64 / self.directions() as f64;
+        (angle.cos(), angle.sin())
+    }
+
+    /// The cell one step from (x, y) in direction `d`, if it is on a `w` x
+    /// `h` grid.
+    fn neighbour(self, (x, y): (usize, usize), d: usize, (w, h): (usize, usize)) -> Option<(usize, usize)> {
+        let (dx, dy): (isize, isize) = match self {
+            GasModel::Hpp => [(1, 0), (0, -1), (-1, 0), (0, 1)][d],
+            // Odd rows sit half a cell right of even ones
+            GasModel::Fhp => {
+                let odd = (y % 2) as isize;
+                [(1, 0), (odd, -1), (odd - 1, -1), (-1, 0), (odd - 1, 1), (odd, 1)][d]
+            }
+        };
+        let (nx, ny) = (x as isize + dx, y as isize + dy);
+        (nx >= 0 && ny >= 0 && (nx as usize) < w && (ny as usize) < h).then_some((nx as usize, ny as usize))
+    }
+
+    /// The particles of one cell after they collide.  `turn` picks which way
+    /// FHP head-on pairs turn.
+    fn collide(self, cell: u8, turn: bool) -> u8 {
+        match self {
+            GasModel::Hpp => match cell {
+                0b0101 => 0b1010,
+                0b1010 => 0b0101,
+                _ => cell,
+            },
+            GasModel::Fhp => match cell {
+                0b001001 | 0b010010 | 0b100100 => {
+                    let rotated = if turn { cell << 1 | cell >> 5 } else { cell >> 1 | cell << 5 };
+                    rotated & 0b111111
+                }
+                0b010101 => 0b101010,
+                0b101010 => 0b010101,
+                _ => cell,
+            },
+        }
+    }
+}
+
+impl fmt::Display for GasModel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", format!("{:?}", self).to_lowercase())
+    }
+}
+
+impl FromStr for GasModel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        GasModel::ALL.into_iter()
+            .find(|m| m.to_string().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| format!("unknown lattice gas '{}', expected hpp or fhp", s))
+    }
+}
+
+/// The model and what the particles add up to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GasStats {
+    pub model: GasModel,
+    pub particles: usize,
+    /// Total momentum, x to the right and y up
+    pub momentum: (f64, f64),
+}
+
+// Particles, live cells, then changed and born cells
+type Counts = (usize, usize, usize, usize);
+
+pub struct GasEngine {
+    width: usize,
+    height: usize,
+    cells: Vec<u8>,
+    model: GasModel,
+    generation: u64,
+    rule: Rule,
+    // The grid after collisions, then after moving
+    scratch: Mutex<(Vec<u8>, Vec<u8>)>,
+    particles: usize,
+    population: usize,
+    changed: usize,
+    born: usize,
+    next_counts: Mutex<Counts>,
+    stale: StaleGuard,
+}
+
+impl GasEngine {
+    pub fn new(width: usize, height: usize) -> Self {
+        GasEngine {
+            width,
+            height,
+            cells: vec![0; width * height],
+            model: GasModel::default(),
+            generation: 0,
+            rule: Rule::default(),
+            scratch: Mutex::new((vec![0; width * height], vec![0; width * height])),
+            particles: 0,
+            population: 0,
+            changed: 0,
+            born: 0,
+            next_counts: Mutex::new((0, 0, 0, 0)),
+            stale: StaleGuard::default(),
+        }
+    }
+
+    fn full(&self) -> u8 {
+        (1 << self.model.directions()) - 1
+    }
+
+    fn put(&mut self, x: usize, y: usize, cell: u8) {
+        let old = &mut self.cells[y * self.width + x];
+        self.particles = self.particles + cell.count_ones() as usize - old.count_ones() as usize;
+        self.population = self.population + (cell != 0) as usize - (*old != 0) as usize;
+        *old = cell;
+        self.stale.bump();
+    }
+
+    /// The particles arriving at (x, y) from the collided grid `from`: each
+    /// comes from the neighbour behind it, or bounces back off the edge.
+    fn arrivals(&self, from: &[u8], x: usize, y: usize) -> u8 {
+        let n = self.model.directions();
+        let mut cell = 0;
+        for d in 0..n {
+            let back = (d + n / 2) % n;
+            let arrived = match self.model.neighbour((x, y), back, (self.width, self.height)) {
+                Some((sx, sy)) => from[sy * self.width + sx] >> d & 1,
+                None => from[y * self.width + x] >> back & 1,
+            };
+            cell |= arrived << d;
+        }
+        cell
+    }
+
+    /// The model and the particles' totals.
+    pub fn stats(&self) -> GasStats {
+        let mut momentum = (0.0, 0.0);
+        for cell in &self.cells {
+            for d in (0..self.model.directions()).filter(|d| cell >> d & 1 == 1) {
+                let (vx, vy) = self.model.velocity(d);
+                momentum = (momentum.0 + vx, momentum.1 + vy);
+            }
+        }
+        GasStats { model: self.model, particles: self.particles, momentum }
+    }
+
+    /// Switching model keeps the particles heading the directions both have
+    /// in common, east and west, and drops the rest.
+    pub fn set_model(&mut self, model: GasModel) {
+        if model != self.model {
+            let (east, west) = (1, 1 << (self.model.directions() / 2));
+            let kept: Vec<u8> = self.cells.iter().map(|c| (c & east) | ((c & west != 0) as u8) << (model.directions() / 2)).collect();
+            self.model = model;
+            for (i, cell) in kept.into_iter().enumerate() {
+                self.put(i % self.width, i / self.width, cell);
+            }
+        }
+    }
+}
+
+impl LifeEngine for GasEngine {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn name(&self) -> &'static str {
+        "gas"
+    }
+
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Cells with any particles are alive.
+    fn get(&self, x: usize, y: usize) -> CellState {
+        if self.cells[y * self.width + x] != 0 { CellState::Alive } else { CellState::Dead }
+    }
+
+    /// Live cells are full, with a particle heading every way.
+    fn set(&mut self, x: usize, y: usize, state: CellState) {
+        let cell = if state == CellState::Alive { self.full() } else { 0 };
+        self.put(x, y, cell);
+    }
+
+    fn level(&self, x: usize, y: usize) -> f32 {
+        self.cells[y * self.width + x].count_ones() as f32 / self.model.directions() as f32
+    }
+
+    /// Kept for the other backends' sake; the gas has no B/S rule.
+    fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    fn set_rule(&mut self, rule: Rule) {
+        self.rule = rule;
+    }
+
+    fn prepare_step(&self) {
+        let _span = tracing::info_span!("gas.prepare_step").entered();
+        if let Ok(mut scratch) = self.scratch.lock() {
+            self.stale.mark_prepared();
+            let (collided, next) = &mut *scratch;
+            let width = self.width.max(1);
+
+            #[cfg(not(target_arch = "wasm32"))]
+            let rows = collided.par_chunks_mut(width);
+            #[cfg(target_arch = "wasm32")]
+            let rows = collided.chunks_mut(width);
+            rows.enumerate().for_each(|(y, row)| {
+                for (x, cell) in row.iter_mut().enumerate() {
+                    let turn = cell_hash(x, y, self.generation, 0) & 1 == 1;
+                    *cell = self.model.collide(self.cells[y * self.width + x], turn);
+                }
+            });
+
+            let collided = &*collided;
+            #[cfg(not(target_arch = "wasm32"))]
+            let rows = next.par_chunks_mut(width);
+            #[cfg(target_arch = "wasm32")]
+            let rows = next.chunks_mut(width);
+            rows.enumerate().for_each(|(y, row)| {
+                for (x, cell) in row.iter_mut().enumerate() {
+                    *cell = self.arrivals(collided, x, y);
+                }
+            });
+
+            let mut counts: Counts = (0, 0, 0, 0);
+            for (old, new) in self.cells.iter().zip(next.iter()) {
+                counts.0 += new.count_ones() as usize;
+                counts.1 += (*new != 0) as usize;
+                if (*old != 0) != (*new != 0) {
+                    counts.2 += 1;
+                    counts.3 += (*new != 0) as usize;
+                }
+            }
+            if let Ok(mut next) = self.next_counts.lock() {
+                *next = counts;
+            }
+        }
+    }
+
+    fn commit_step(&mut self) {
+        if self.stale.is_stale() {
+            self.prepare_step();
+        }
+        self.stale.committed();
+        if let (Ok(scratch), Ok(counts)) = (self.scratch.get_mut(), self.next_counts.get_mut()) {
+            std::mem::swap(&mut self.cells, &mut scratch.1);
+            (self.particles, self.population, self.changed, self.born) = *counts;
+            self.generation += 1;
+        }
+    }
+
+    fn changed_cells(&self) -> usize {
+        self.changed
+    }
+
+    fn born_cells(&self) -> usize {
+        self.born
+    }
+
+    fn resize(&mut self, width: usize, height: usize, anchor: Anchor) {
+        let (ox, oy) = anchor.offset((self.width, self.height), (width, height));
+        let mut cells = vec![0; width * height];
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let (nx, ny) = (x as isize + ox, y as isize + oy);
+                if nx >= 0 && ny >= 0 && nx < width as isize && ny < height as isize {
+                    cells[ny as usize * width + nx as usize] = self.cells[y * self.width + x];
+                }
+            }
+        }
+        self.particles = cells.iter().map(|c| c.count_ones() as usize).sum();
+        self.population = cells.iter().filter(|c| **c != 0).count();
+        self.cells = cells;
+        self.scratch = Mutex::new((vec![0; width * height], vec![0; width * height]));
+        (self.width, self.height) = (width, height);
+        self.changed = 0;
+        self.born = 0;
+        self.stale.bump();
+    }
+
+    /// Each direction of each cell holds a particle with probability
+    /// `density`.
+    fn randomize(&mut self, density: f64, seed: u64) {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let n = self.model.directions();
+        for y in 0..self.height {
+            rng.set_word_pos(2 * (n * y * self.width) as u128);
+            for x in 0..self.width {
+                let cell = (0..n).fold(0, |cell, d| cell | (rng.gen_bool(density.clamp(0.0, 1.0)) as u8) << d);
+                self.put(x, y, cell);
+            }
+        }
+    }
+
+    fn population(&self) -> usize {
+        self.population
+    }
+}
@@ -0,0 +1,163 @@
+//! The Life Lexicon panel: type to narrow down the terms, arrow keys to
+//! pick one and read its definition, Enter to pick up the pattern it comes
+//! with, if any, to place like one from the browser.
+
+use std::path::Path;
+
+use winit::event::VirtualKeyCode;
+use winit_input_helper::{TextChar, WinitInputHelper};
+
+use rustlife::lexicon::{self, Entry};
+use rustlife::library::LibraryPattern;
+
+use crate::overlay::{self, GLYPH_SIZE};
+
+const VISIBLE_TERMS: usize = 8;
+const DEFINITION_LINES: usize = 10;
+const MAX_COLUMNS: u32 = 60;
+
+#[derive(Default)]
+pub struct Glossary {
+    open: bool,
+    /// Read when first opened
+    entries: Option<Vec<Entry>>,
+    /// Where the entries came from, for the panel's footer
+    source: String,
+    query: String,
+    selected: usize,
+}
+
+/// `text` broken at spaces into lines of at most `columns` characters.
+fn wrap(text: &str, columns: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for word in text.split_whitespace() {
+        match lines.last_mut() {
+            Some(line) if line.chars().count() + 1 + word.chars().count() <= columns => {
+                line.push(' ');
+                line.push_str(word);
+            }
+            _ => lines.push(word.to_string())
+        }
+    }
+    lines
+}
+
+impl Glossary {
+    pub fn new() -> Self {
+        Glossary::default()
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Opens the panel, the first time reading the Life Lexicon from
+    /// `path` if there is a file there and the built in glossary if not.
+    pub fn open(&mut self, path: Option<&Path>) {
+        if self.entries.is_none() {
+            let loaded = path.filter(|p| p.is_file()).map(|p| (lexicon::load(p), p.display().to_string()));
+            let (entries, source) = match loaded {
+                Some((Ok(entries), source)) => (entries, source),
+                Some((Err(e), _)) => {
+                    log::error!("Could not read the Life Lexicon: {}", e);
+                    (lexicon::builtin(), "the built in glossary".to_string())
+                }
+                None => (lexicon::builtin(), "the built in glossary".to_string())
+            };
+            self.entries = Some(entries);
+            self.source = source;
+        }
+        self.open = true;
+        self.query.clear();
+        self.selected = 0;
+    }
+
+    pub fn close(&mut self) {
+        self.open = false;
+    }
+
+    fn matches(&self) -> Vec<&Entry> {
+        self.entries.as_deref().map_or(Vec::new(), |e| lexicon::search(e, &self.query))
+    }
+
+    /// Handles this frame's keyboard input while the panel is open and
+    /// returns the pattern to pick up, if one was chosen.
+    pub fn update(&mut self, input: &WinitInputHelper) -> Option<LibraryPattern> {
+        if input.key_pressed(VirtualKeyCode::Escape) {
+            self.close();
+            return None;
+        }
+        for c in input.text() {
+            match c {
+                TextChar::Char(c) if !c.is_control() => self.query.push(c),
+                TextChar::Back => {
+                    self.query.pop();
+                }
+                TextChar::Char(_) => {}
+            }
+            self.selected = 0;
+        }
+        let count = self.matches().len();
+        if input.key_pressed_os(VirtualKeyCode::Down) && count > 0 {
+            self.selected = (self.selected + 1) % count;
+        }
+        if input.key_pressed_os(VirtualKeyCode::Up) && count > 0 {
+            self.selected = (self.selected + count - 1) % count;
+        }
+        if input.key_pressed(VirtualKeyCode::Return) {
+            let chosen = self.matches().get(self.selected).and_then(|e| Some(LibraryPattern { name: e.term.clone(), cells: e.pattern.clone()? }));
+            if chosen.is_some() {
+                self.close();
+            }
+            return chosen;
+        }
+        None
+    }
+
+    pub fn draw(&self, width: u32, height: u32, screen: &mut [u8]) {
+        if !self.open {
+            return;
+        }
+        let line = GLYPH_SIZE as i32 + 4;
+        let box_width = (MAX_COLUMNS * GLYPH_SIZE + 8).min(width);
+        let columns = ((box_width - 8) / GLYPH_SIZE) as usize;
+        let x = (width - box_width) as i32 / 2;
+        let matches = self.matches();
+
+        let mut lines: Vec<(String, [u8; 3], bool)> = vec![(format!("Lexicon> {}_", self.query), [255, 255, 255], false)];
+        // Keep the selection in view
+        let first = self.selected.saturating_sub(VISIBLE_TERMS - 1);
+        for (i, entry) in matches.iter().enumerate().skip(first).take(VISIBLE_TERMS) {
+            let marker = if entry.pattern.is_some() { " [pattern]" } else { "" };
+            lines.push((format!("{}{}", entry.term, marker), [200, 200, 200], i == self.selected));
+        }
+        match matches.get(self.selected) {
+            Some(entry) => {
+                lines.push((String::new(), [0, 0, 0], false));
+                let definition = wrap(&entry.definition, columns);
+                let cut = definition.len() > DEFINITION_LINES;
+                lines.extend(definition.into_iter().take(DEFINITION_LINES).map(|l| (l, [230, 230, 160], false)));
+                if cut {
+                    lines.push(("...".to_string(), [230, 230, 160], false));
+                }
+                if entry.pattern.is_some() {
+                    lines.push(("Enter picks up the pattern to place".to_string(), [96, 255, 96], false));
+                }
+            }
+            None => lines.push(("No term matches".to_string(), [255, 96, 96], false))
+        }
+        lines.push((format!("{} terms from {}", matches.len(), self.source), [140, 140, 140], false));
+
+        let mut y = 8;
+        overlay::fill_rect(screen, width, height, (x, y, box_width, lines.len() as u32 * line as u32 + 4), [0, 0, 0, 220]);
+        y += 4;
+        for (text, color, selected) in &lines {
+            if *selected {
+                overlay::fill_rect(screen, width, height, (x, y - 2, box_width, line as u32), [80, 80, 160, 255]);
+            }
+            let text: String = text.chars().take(columns).collect();
+            overlay::draw_text(screen, width, height, x + 4, y, &text, *color);
+            y += line;
+        }
+    }
+}
@@ -0,0 +1,157 @@
+//! Telling patterns that grow for ever from ones that stay bounded, by how
+//! their population and bounding box change.  A gun or puffer adds cells at
+//! a steady rate, so its population grows linearly; a breeder lays down
+//! guns at a steady rate, so it grows quadratically.  Only the last
+//! [`WINDOW`] generations count: the growth over the later half of them is
+//! compared with that over the earlier half, and a rate that has gone up
+//! by more than a cell every couple of generations is quadratic.  Going by
+//! the whole watch instead would take a breeder that has been running for
+//! a while before the watch started, or a soup that only lately turned
+//! into one, for linear.
+//!
+//! The population is sampled every few generations, and only the samples
+//! in the window are kept, so a detector takes the same memory however
+//! long it runs.  On a finite grid whatever escapes piles up at the edge
+//! or wraps round, so a long enough run always ends up bounded.
+
+use std::collections::VecDeque;
+use std::fmt;
+
+use crate::engine::{BoundingBox, LifeEngine};
+use crate::events::GenerationEvent;
+
+/// Generations watched before growth is classified at all.
+pub const MIN_GENERATIONS: u64 = 256;
+/// Generations looked back over to classify growth.
+pub const WINDOW: u64 = 2048;
+// Generations between samples
+const SAMPLE_EVERY: u64 = 8;
+// Growing by less than this many cells a generation is not growing
+const MIN_RATE: f64 = 0.05;
+// How much faster, in cells a generation, the later half of the window
+// has to grow than the earlier to be quadratic rather than linear.  A gun
+// adds a glider's worth of cells every period, which is well under this
+// once it is spread over half the window
+const QUADRATIC: f64 = 0.5;
+
+/// How a pattern's population has been growing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Growth {
+    /// The population isn't growing and the live cells aren't spreading
+    Bounded,
+    /// The population isn't growing but the live cells are spreading out,
+    /// as when spaceships fly off
+    Spreading,
+    Linear,
+    Quadratic,
+}
+
+impl Growth {
+    /// What it is in words, with what usually grows that way.
+    pub fn describe(self) -> &'static str {
+        match self {
+            Growth::Bounded => "bounded",
+            Growth::Spreading => "bounded, spreading out (spaceships)",
+            Growth::Linear => "linear growth (gun or puffer)",
+            Growth::Quadratic => "quadratic growth (breeder)",
+        }
+    }
+}
+
+impl fmt::Display for Growth {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.describe())
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Sample {
+    generation: u64,
+    population: f64,
+    /// Width plus height of the live cells' bounding box
+    extent: f64,
+}
+
+/// Watches a pattern's population and bounding box and classifies how it
+/// grows.
+#[derive(Clone, Debug)]
+pub struct GrowthDetector {
+    samples: VecDeque<Sample>,
+    first: Option<u64>,
+    growth: Option<Growth>,
+}
+
+impl GrowthDetector {
+    pub fn new() -> Self {
+        GrowthDetector { samples: VecDeque::new(), first: None, growth: None }
+    }
+
+    pub fn clear(&mut self) {
+        *self = GrowthDetector::new();
+    }
+
+    /// How the pattern has been growing, or None until it has been watched
+    /// for [`MIN_GENERATIONS`].
+    pub fn growth(&self) -> Option<Growth> {
+        self.growth
+    }
+
+    /// Records `life` after `event`, looking at its bounding box only when
+    /// a sample is due.
+    pub fn observe(&mut self, event: &GenerationEvent, life: &dyn LifeEngine) {
+        if self.due(event.generation) {
+            self.record(event.generation, event.population, life.bounding_box());
+        }
+    }
+
+    /// Records generation `generation`, with `population` live cells inside
+    /// `bounds`.  Going back to an earlier generation, as when the universe
+    /// is reset, starts over.
+    pub fn record(&mut self, generation: u64, population: usize, bounds: Option<BoundingBox>) {
+        if !self.due(generation) {
+            return;
+        }
+        if self.samples.back().is_some_and(|s| generation <= s.generation) {
+            self.clear();
+        }
+        self.first.get_or_insert(generation);
+        let extent = bounds.map_or(0, |b| b.width() + b.height()) as f64;
+        self.samples.push_back(Sample { generation, population: population as f64, extent });
+        while self.samples.front().is_some_and(|s| s.generation + WINDOW < generation) {
+            self.samples.pop_front();
+        }
+        self.growth = self.classify();
+    }
+
+    fn due(&self, generation: u64) -> bool {
+        match (self.first, self.samples.back()) {
+            (Some(first), Some(last)) => generation <= last.generation || (generation - first).is_multiple_of(SAMPLE_EVERY),
+            _ => true
+        }
+    }
+
+    fn classify(&self) -> Option<Growth> {
+        let (first, last) = (self.samples.front()?, self.samples.back()?);
+        let span = last.generation - first.generation;
+        if span < MIN_GENERATIONS {
+            return None;
+        }
+        // The latest sample at or before halfway through the window
+        let middle = first.generation + span / 2;
+        let half = &self.samples[self.samples.partition_point(|s| s.generation <= middle) - 1];
+        let rate = |a: &Sample, b: &Sample, of: fn(&Sample) -> f64| (of(b) - of(a)) / (b.generation - a.generation).max(1) as f64;
+        let late = rate(half, last, |s| s.population);
+        if late < MIN_RATE {
+            return Some(if rate(half, last, |s| s.extent) >= MIN_RATE { Growth::Spreading } else { Growth::Bounded });
+        }
+        // Growth that only got going lately is taken to be steady
+        let early = rate(first, half, |s| s.population);
+        Some(if early >= MIN_RATE && late - early > QUADRATIC { Growth::Quadratic } else { Growth::Linear })
+    }
+}
+
+impl Default for GrowthDetector {
+    fn default() -> Self {
+        GrowthDetector::new()
+    }
+}
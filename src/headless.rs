@@ -0,0 +1,421 @@
+use std::fs;
+use std::io::Write;
+use std::ops::RangeInclusive;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use rayon::prelude::*;
+use serde::Serialize;
+
+use rustlife::census::{self, Census};
+use rustlife::evolve::{Evolution, Fitness};
+use rustlife::explore::{self, Ranking};
+use rustlife::forest::{ForestEngine, ForestStats};
+use rustlife::ising::{IsingEngine, IsingStats};
+use rustlife::methuselah::{self, Leaderboard};
+use rustlife::predecessor;
+use rustlife::io::{self, Format};
+use rustlife::replay::Player;
+use rustlife::script::SharedLife;
+use rustlife::soup::Symmetry;
+use rustlife::species::SpeciesEngine;
+use rustlife::verify;
+use rustlife::{apgcode, engine, CellState, ConwayState, EventBus, GenerationEvent, LifeEngine, Rule, SimulationBuilder};
+
+use crate::usage;
+
+fn poisoned<T>(_: T) -> String {
+    "universe lock poisoned".to_string()
+}
+
+/// Runs the simulation without a window and reports the final pattern,
+/// population after every generation, and how long stepping took.
+/// `speed` caps generations per second, 0 for as fast as possible.
+/// `generations` are the ones to step to, from 1 or from just after a
+/// checkpoint the run carries on from.
+pub fn run(shared: &SharedLife, events: &mut EventBus, mut player: Option<Player>, seed: Option<u64>, generations: RangeInclusive<u64>, speed: u32, output: Option<&str>) -> Result<(), String> {
+    let step_interval = (speed > 0).then(|| Duration::from_secs_f64(1.0 / speed as f64));
+    let history = events.channel();
+    let count = generations.end().saturating_sub(generations.start() - 1);
+    let mut populations = Vec::with_capacity(count as usize + 1);
+    populations.push(shared.read().map_err(poisoned)?.population());
+    // Prey and predators after every generation, on a two-species backend
+    let mut species: Vec<[usize; 2]> = shared.read().map_err(poisoned)?.downcast_ref::<SpeciesEngine>().map(SpeciesEngine::populations).into_iter().collect();
+    // Trees and fires after every generation, on a forest-fire backend
+    let mut forest: Vec<ForestStats> = shared.read().map_err(poisoned)?.downcast_ref::<ForestEngine>().map(ForestEngine::stats).into_iter().collect();
+    // Magnetization and energy after every generation, on an Ising backend
+    let mut ising: Vec<IsingStats> = shared.read().map_err(poisoned)?.downcast_ref::<IsingEngine>().map(IsingEngine::stats).into_iter().collect();
+
+    let start = Instant::now();
+    for generation in generations.clone() {
+        let step_start = Instant::now();
+        let event = {
+            let mut life = shared.write().map_err(poisoned)?;
+            if let Some(p) = player.as_mut() {
+                p.apply_due(generation - 1, life.as_mut())?;
+            }
+            life.step();
+            if let Some(p) = player.as_mut() {
+                p.inject_noise(life.as_mut());
+            }
+            forest.extend(life.downcast_ref::<ForestEngine>().map(ForestEngine::stats));
+            ising.extend(life.downcast_ref::<IsingEngine>().map(IsingEngine::stats));
+            GenerationEvent::from_engine(generation, life.as_ref())
+        };
+        events.publish(&event);
+        if let Some(rest) = step_interval.and_then(|i| i.checked_sub(step_start.elapsed())) {
+            thread::sleep(rest);
+        }
+    }
+    let elapsed = start.elapsed();
+    for event in history.try_iter() {
+        populations.push(event.population);
+        species.extend(event.species);
+    }
+    let life = shared.read().map_err(poisoned)?;
+
+    let mut report = String::new();
+    report.push_str(&format!("# backend: {}\n", life.name()));
+    report.push_str(&format!("# rule: {}\n", life.rule()));
+    report.push_str(&format!("# grid: {}x{}\n", life.width(), life.height()));
+    if let Some(seed) = seed {
+        report.push_str(&format!("# seed: {}\n", seed));
+    }
+    report.push_str(&format!("# generations: {}\n", generations.end()));
+    if *generations.start() > 1 {
+        report.push_str(&format!("# resumed from: {}\n", generations.start() - 1));
+    }
+    report.push_str(&format!("# threads: {}{}\n", rayon::current_num_threads(), if engine::strips() { ", pinned" } else { "" }));
+    report.push_str(&format!("# time: {:.3}s ({:.1} gen/s)\n",
+        elapsed.as_secs_f64(),
+        count as f64 / elapsed.as_secs_f64().max(f64::EPSILON)));
+    report.push_str("# population:");
+    for p in &populations {
+        report.push_str(&format!(" {}", p));
+    }
+    report.push('\n');
+    for (i, name) in ["prey", "predators"].iter().enumerate().filter(|_| !species.is_empty()) {
+        report.push_str(&format!("# {}:", name));
+        for counts in &species {
+            report.push_str(&format!(" {}", counts[i]));
+        }
+        report.push('\n');
+    }
+    if let Some(last) = forest.last() {
+        report.push_str("# trees:");
+        for f in &forest {
+            report.push_str(&format!(" {}", f.trees));
+        }
+        report.push_str("\n# burning:");
+        for f in &forest {
+            report.push_str(&format!(" {}", f.burning));
+        }
+        report.push_str(&format!("\n# strikes: {}\n", last.strikes));
+    }
+    if !ising.is_empty() {
+        report.push_str("# magnetization:");
+        for i in &ising {
+            report.push_str(&format!(" {:.4}", i.magnetization));
+        }
+        report.push_str("\n# energy:");
+        for i in &ising {
+            report.push_str(&format!(" {:.4}", i.energy));
+        }
+        report.push('\n');
+    }
+    report.push_str(&io::to_plaintext(life.as_ref()));
+
+    match output {
+        Some(path) => fs::write(path, report).map_err(|e| format!("{}: {}", path, e)),
+        None => std::io::stdout().write_all(report.as_bytes()).map_err(|e| e.to_string())
+    }
+}
+
+/// Looks for a predecessor of the pattern in the file at `path` and writes
+/// it as RLE, or says there is none.
+pub fn predecessor(path: &str, rule: Rule, output: Option<&str>) -> Result<(), String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+    let pattern = io::parse_pattern(&text)?;
+    let (w, h) = io::extent(&pattern);
+    let start = Instant::now();
+    let found = predecessor::search(&pattern, (w, h), rule)?;
+    let mut report = format!("# pattern: {}x{}\n# rule: {}\n# time: {:.3}s\n", w, h, rule, start.elapsed().as_secs_f64());
+    match found {
+        Some(parent) => {
+            report.push_str("# parent, from one cell up and left of the pattern:\n");
+            report.push_str(&io::cells_to_rle(&parent));
+        }
+        None => report.push_str(&format!("# no predecessor within {}x{}\n", w + 2, h + 2))
+    }
+    match output {
+        Some(path) => fs::write(path, report).map_err(|e| format!("{}: {}", path, e)),
+        None => std::io::stdout().write_all(report.as_bytes()).map_err(|e| e.to_string())
+    }
+}
+
+/// Runs `soups` random soups, numbered from `seed`, to stabilisation and
+/// writes the census of what they left behind as an apgsearch style haul.
+pub fn census(soups: u64, seed: u64, symmetry: Symmetry, rule: Rule, output: Option<&str>) -> Result<(), String> {
+    let start = Instant::now();
+    let total = (0..soups).into_par_iter()
+        .fold(Census::new, |mut census, i| {
+            census.add(census::search(seed.wrapping_add(i), symmetry, rule));
+            census
+        })
+        .reduce(Census::new, |mut a, b| {
+            a.merge(b);
+            a
+        });
+    let elapsed = start.elapsed();
+
+    let mut report = String::new();
+    report.push_str(&format!("# seeds: {} to {}\n", seed, seed.wrapping_add(soups.saturating_sub(1))));
+    report.push_str(&format!("# unsettled: {}\n", total.unsettled));
+    report.push_str(&format!("# time: {:.3}s ({:.1} soups/s)\n",
+        elapsed.as_secs_f64(),
+        soups as f64 / elapsed.as_secs_f64().max(f64::EPSILON)));
+    report.push_str(&total.haul(rule, symmetry));
+
+    match output {
+        Some(path) => fs::write(path, report).map_err(|e| format!("{}: {}", path, e)),
+        None => std::io::stdout().write_all(report.as_bytes()).map_err(|e| e.to_string())
+    }
+}
+
+/// Runs `seeds` random seeds of `cells` cells, numbered from `seed`, to
+/// stabilisation and reports the `keep` longest lived with their patterns.
+pub fn hunt(seeds: u64, cells: usize, keep: usize, seed: u64, rule: Rule, output: Option<&str>) -> Result<(), String> {
+    let start = Instant::now();
+    let board = (0..seeds).into_par_iter()
+        .fold(|| Leaderboard::new(keep), |mut board, i| {
+            let seed = seed.wrapping_add(i);
+            board.add(seed, methuselah::run(seed, methuselah::seed_pattern(seed, cells), rule));
+            board
+        })
+        .reduce(|| Leaderboard::new(keep), |mut a, b| {
+            a.merge(b);
+            a
+        });
+    let elapsed = start.elapsed();
+
+    let mut report = String::new();
+    report.push_str(&format!("# seeds: {} to {}\n", seed, seed.wrapping_add(seeds.saturating_sub(1))));
+    report.push_str(&format!("# cells: {}\n", cells));
+    let mut unsettled = board.unsettled.clone();
+    unsettled.sort();
+    report.push_str(&format!("# unsettled: {}", unsettled.len()));
+    for s in &unsettled {
+        report.push_str(&format!(" {}", s));
+    }
+    report.push('\n');
+    report.push_str(&format!("# time: {:.3}s ({:.1} seeds/s)\n",
+        elapsed.as_secs_f64(),
+        seeds as f64 / elapsed.as_secs_f64().max(f64::EPSILON)));
+    for (rank, c) in board.top.iter().enumerate() {
+        report.push_str(&format!("\n#C {}. seed {}: settles at generation {} with {} cells\n",
+            rank + 1, c.seed, c.lifespan, c.final_population));
+        report.push_str(&io::cells_to_rle(&c.pattern));
+    }
+
+    match output {
+        Some(path) => fs::write(path, report).map_err(|e| format!("{}: {}", path, e)),
+        None => std::io::stdout().write_all(report.as_bytes()).map_err(|e| e.to_string())
+    }
+}
+
+/// Breeds `size` seed patterns for `fitness` over `generations` generations
+/// of a genetic algorithm, starting from `seed`, and writes the best `keep`
+/// as RLE.
+pub fn evolve(generations: u64, size: usize, fitness: Fitness, keep: usize, seed: u64, rule: Rule, output: Option<&str>) -> Result<(), String> {
+    let start = Instant::now();
+    let mut evolution = Evolution::new(size, fitness, rule, seed);
+    let mut progress = Vec::new();
+    for _ in 0..generations {
+        let best = evolution.best();
+        log::info!("Generation {}: best {}", evolution.generation, fitness.describe(best.score));
+        progress.push(best.score);
+        evolution.step();
+    }
+    progress.push(evolution.best().score);
+    let elapsed = start.elapsed();
+
+    let mut report = String::new();
+    report.push_str(&format!("# fitness: {}\n", fitness));
+    report.push_str(&format!("# population: {}, generations: {}, seed: {}\n", evolution.candidates.len(), generations, seed));
+    report.push_str("# best by generation:");
+    for score in &progress {
+        report.push_str(&format!(" {}", score));
+    }
+    report.push('\n');
+    report.push_str(&format!("# time: {:.3}s\n", elapsed.as_secs_f64()));
+    let mut winners = evolution.candidates.clone();
+    winners.dedup_by_key(|c| c.genome);
+    for (rank, c) in winners.iter().take(keep).enumerate() {
+        report.push_str(&format!("\n#C {}. {}\n", rank + 1, fitness.describe(c.score)));
+        report.push_str(&io::cells_to_rle(&io::trim(&c.pattern())));
+    }
+
+    match output {
+        Some(path) => fs::write(path, report).map_err(|e| format!("{}: {}", path, e)),
+        None => std::io::stdout().write_all(report.as_bytes()).map_err(|e| e.to_string())
+    }
+}
+
+/// Tries `rules` random rules, numbered from `first`, each on a soup, and
+/// writes the `keep` most interesting with how to open them.
+pub fn explore(rules: u64, keep: usize, first: u64, output: Option<&str>) -> Result<(), String> {
+    let start = Instant::now();
+    let ranking = (0..rules).into_par_iter()
+        .fold(|| Ranking::new(keep), |mut ranking, i| {
+            let number = first.wrapping_add(i);
+            ranking.add(explore::trial(number, explore::random_rule(number)));
+            ranking
+        })
+        .reduce(|| Ranking::new(keep), |mut a, b| {
+            a.merge(b);
+            a
+        });
+    let elapsed = start.elapsed();
+
+    let mut report = String::new();
+    report.push_str(&format!("# rules: {} to {}\n", first, first.wrapping_add(rules.saturating_sub(1))));
+    report.push_str(&format!("# soup: {0}x{0} in {1}x{1}, {2} generations\n", explore::SOUP_SIZE, explore::GRID, explore::GENERATIONS));
+    report.push_str(&format!("# died out: {}, exploded: {}, settled: {}\n", ranking.died_out, ranking.exploded, ranking.settled));
+    report.push_str(&format!("# time: {:.3}s ({:.1} rules/s)\n",
+        elapsed.as_secs_f64(),
+        rules as f64 / elapsed.as_secs_f64().max(f64::EPSILON)));
+    for (rank, t) in ranking.top.iter().enumerate() {
+        report.push_str(&format!("\n# {}. rule {}: score {:.3}, {:.1} cells changing a generation, {} alive at the end\n",
+            rank + 1, t.number, t.score, t.activity, t.population));
+        report.push_str(&format!("--rule {} --soup-size {}\n", t.rule, explore::SOUP_SIZE));
+    }
+
+    match output {
+        Some(path) => fs::write(path, report).map_err(|e| format!("{}: {}", path, e)),
+        None => std::io::stdout().write_all(report.as_bytes()).map_err(|e| e.to_string())
+    }
+}
+
+/// Runs `engines` in lockstep on the universe in `life` for `generations`,
+/// comparing them every `every`, and reports whether they agree or else
+/// the first cell they don't.  Fails if they disagree, once the report is
+/// written.
+pub fn verify([first, second]: [&str; 2], life: &dyn LifeEngine, seed: u64, generations: u64, every: u64, output: Option<&str>) -> Result<(), String> {
+    let mut state = ConwayState::empty(life.width(), life.height());
+    state.rule = life.rule();
+    for (x, y) in io::live_cells(life) {
+        state.set(x, y, CellState::Alive);
+    }
+    let start = Instant::now();
+    let divergence = verify::check(first, second, &state, generations, every)?;
+    let elapsed = start.elapsed();
+
+    let mut report = String::new();
+    report.push_str(&format!("# engines: {} against {}\n", first, second));
+    report.push_str(&format!("# rule: {}\n", state.rule));
+    report.push_str(&format!("# grid: {}x{}\n", state.width, state.height));
+    report.push_str(&format!("# seed: {}\n", seed));
+    report.push_str(&format!("# generations: {}, compared every {}\n", generations, every.max(1)));
+    report.push_str(&format!("# time: {:.3}s\n", elapsed.as_secs_f64()));
+    match &divergence {
+        Some(d) => report.push_str(&format!("diverged at {}\n", d)),
+        None => report.push_str("agreed\n")
+    }
+    match output {
+        Some(path) => fs::write(path, report).map_err(|e| format!("{}: {}", path, e))?,
+        None => std::io::stdout().write_all(report.as_bytes()).map_err(|e| e.to_string())?
+    }
+    match divergence {
+        Some(d) => Err(format!("{} and {} diverged at generation {}", first, second, d.generation)),
+        None => Ok(())
+    }
+}
+
+/// Converts the pattern file `input` to `output`, in `format` or the one
+/// its extension names, cropped to the live cells if `trim` and turned to
+/// their canonical orientation as well if `canonical`.  The rule is `rule`,
+/// or else the one `input` names, where the format has room for it.
+pub fn convert(input: &str, output: &str, format: Option<Format>, trim: bool, canonical: bool, rule: Option<Rule>) -> Result<(), String> {
+    let format = format.or_else(|| Format::from_path(output))
+        .ok_or_else(|| format!("{}: can't tell the format from the extension, give --format", output))?;
+    let text = fs::read_to_string(input).map_err(|e| format!("{}: {}", input, e))?;
+    let live = io::parse_pattern(&text).map_err(|e| format!("{}: {}", input, e))?;
+    let live = if canonical {
+        apgcode::canonical(&live)
+    } else if trim {
+        io::trim(&live)
+    } else {
+        live
+    };
+    let rule = rule.or_else(|| io::pattern_rule(&text));
+    fs::write(output, format.write(&live, rule.as_ref())).map_err(|e| format!("{}: {}", output, e))?;
+    println!("Wrote {} live cells to {} as {}", live.len(), output, format);
+    Ok(())
+}
+
+// Smallest grid side --scaling tries
+const SMALLEST_SIDE: usize = 512;
+
+/// One grid size and thread count timed by [`scaling`].
+#[derive(Serialize)]
+struct Timing {
+    side: usize,
+    threads: usize,
+    generations_per_second: f64,
+    cells_per_second: f64,
+    /// Resident memory the universe took, where that can be read
+    memory_bytes: Option<u64>,
+}
+
+/// Times `generations` of the universe `universe` builds for each square
+/// grid side from 512 cells, doubling up to `largest`, each with 1, 2, 4 and
+/// so on up to all of the cores, and writes the speed and memory of each as
+/// a table, or as JSON if `json`.
+pub fn scaling(generations: u64, largest: usize, universe: impl Fn(usize) -> Result<SimulationBuilder, String>, json: bool, output: Option<&str>) -> Result<(), String> {
+    let cores = thread::available_parallelism().map_or(1, |n| n.get());
+    let mut counts: Vec<usize> = std::iter::successors(Some(1), |t| Some(t * 2)).take_while(|&t| t < cores).collect();
+    counts.push(cores);
+    let sides = std::iter::successors(Some(SMALLEST_SIDE), |s| Some(s * 2)).take_while(|&s| s <= largest);
+
+    let mut timings = Vec::new();
+    for side in sides {
+        for &threads in &counts {
+            let pool = rayon::ThreadPoolBuilder::new().num_threads(threads).build().map_err(|e| e.to_string())?;
+            let before = usage::resident();
+            let mut life = universe(side)?.build()?;
+            let memory_bytes = before.zip(usage::resident()).map(|(before, after)| after.saturating_sub(before));
+            let start = Instant::now();
+            pool.install(|| {
+                for _ in 0..generations {
+                    life.step();
+                }
+            });
+            let seconds = start.elapsed().as_secs_f64().max(f64::EPSILON);
+            let timing = Timing {
+                side,
+                threads,
+                generations_per_second: generations as f64 / seconds,
+                cells_per_second: (side * side) as f64 * generations as f64 / seconds,
+                memory_bytes,
+            };
+            // As it goes, since the big grids take a while
+            eprintln!("{}x{} on {} threads: {:.1} gen/s", side, side, threads, timing.generations_per_second);
+            timings.push(timing);
+        }
+    }
+
+    let report = if json {
+        serde_json::to_string_pretty(&timings).map_err(|e| e.to_string())? + "\n"
+    } else {
+        let mut report = format!("# generations: {}\n# side threads gen/s cells/s memory_mib\n", generations);
+        for t in &timings {
+            let memory = t.memory_bytes.map_or("-".to_string(), |b| format!("{:.1}", b as f64 / (1 << 20) as f64));
+            report.push_str(&format!("{} {} {:.1} {:.3e} {}\n", t.side, t.threads, t.generations_per_second, t.cells_per_second, memory));
+        }
+        report
+    };
+    match output {
+        Some(path) => fs::write(path, report).map_err(|e| format!("{}: {}", path, e)),
+        None => std::io::stdout().write_all(report.as_bytes()).map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,130 @@
+//! What a single cell is doing and why: its live neighbours, which part of
+//! the rule applies to them, and what the cell will be next generation.
+
+use crate::engine::{Boundary, BoundingBox, CellState, DenseEngine, LifeEngine};
+use crate::rule::Rule;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Inspection {
+    pub state: CellState,
+    /// Live cells among the eight around it, those off the grid counting
+    /// as dead unless the edges wrap round
+    pub neighbors: usize,
+    /// The state it is held in, if it is a wall
+    pub wall: Option<CellState>,
+    /// What it will be next generation and the part of the rule that says
+    /// so, if the backend follows a birth/survival rule
+    pub next: Option<(CellState, String)>,
+    /// Whether a plugin rule decides it rather than the birth/survival one
+    pub plugin: bool,
+}
+
+/// The clause of the rule that decides a cell, e.g. `S3` for a live cell
+/// with three neighbours that survives, or `not B2` for a dead one with
+/// two that stays dead.
+fn clause(rule: &Rule, state: CellState, neighbors: usize) -> String {
+    let (letter, applies) = match state {
+        CellState::Alive => ('S', rule.survive[neighbors]),
+        CellState::Dead => ('B', rule.birth[neighbors])
+    };
+    let not = if applies { "" } else { "not " };
+    format!("{}{}{}", not, letter, neighbors)
+}
+
+pub fn inspect(life: &dyn LifeEngine, x: usize, y: usize) -> Inspection {
+    let state = life.get(x, y);
+    // The next state comes from the engine, so a plugin rule is followed
+    let dense = life.downcast_ref::<DenseEngine>().map(DenseEngine::state);
+    let neighbors = match dense {
+        // Which knows whether the edges wrap round
+        Some(d) => d.neighbor_mask(x, y).count_ones() as usize,
+        None => {
+            let mut neighbors = 0;
+            for dy in -1..=1isize {
+                for dx in -1..=1isize {
+                    let (nx, ny) = (x as isize + dx, y as isize + dy);
+                    let inside = nx >= 0 && ny >= 0 && (nx as usize) < life.width() && (ny as usize) < life.height();
+                    if (dx, dy) != (0, 0) && inside && life.get(nx as usize, ny as usize) == CellState::Alive {
+                        neighbors += 1;
+                    }
+                }
+            }
+            neighbors
+        }
+    };
+    let wall = life.wall(x, y);
+    let plugin = dense.is_some_and(|d| d.custom_rule.is_some());
+    let next = match (wall, dense) {
+        (Some(held), _) => Some((held, "wall".to_string())),
+        (None, Some(d)) if plugin => Some((d.next_cell_state(x, y), "plugin rule".to_string())),
+        (None, Some(d)) => Some((d.next_cell_state(x, y), clause(&d.rule, state, neighbors))),
+        // The other backends have rules of their own
+        (None, None) => None
+    };
+    Inspection { state, neighbors, wall, next, plugin }
+}
+
+/// The inspection in words for someone learning the rules, e.g. "alive
+/// with 4 live neighbours: no S4 in B3/S23, so it dies of overcrowding".
+pub fn explain(cell: &Inspection, rule: &Rule) -> String {
+    let n = cell.neighbors;
+    let state = if cell.state == CellState::Alive { "alive" } else { "dead" };
+    let counted = format!("{} with {} live neighbour{}", state, n, if n == 1 { "" } else { "s" });
+    let fate = match (&cell.wall, cell.state, &cell.next) {
+        (Some(_), _, _) => return format!("{}, but a wall, so it stays {}", counted, state),
+        (None, _, None) => return format!("{}; this backend has rules of its own", counted),
+        (None, _, Some((next, _))) if cell.plugin => {
+            let next = if *next == CellState::Alive { "alive" } else { "dead" };
+            return format!("{}; a plugin rule decides, and makes it {}", counted, next);
+        }
+        (None, CellState::Alive, Some((CellState::Alive, _))) => "it survives".to_string(),
+        (None, CellState::Alive, Some(_)) => {
+            // Too few or too many, when the survival counts all lie to one side
+            let counts: Vec<usize> = (0..9).filter(|&c| rule.survive[c]).collect();
+            match (counts.first(), counts.last()) {
+                (Some(&low), _) if n < low => "it dies of loneliness".to_string(),
+                (_, Some(&high)) if n > high => "it dies of overcrowding".to_string(),
+                _ => "it dies".to_string()
+            }
+        }
+        (None, _, Some((CellState::Alive, _))) => "it is born".to_string(),
+        (None, _, Some(_)) => "it stays dead".to_string()
+    };
+    let (letter, applies) = match cell.state {
+        CellState::Alive => ('S', rule.survive[n]),
+        CellState::Dead => ('B', rule.birth[n])
+    };
+    let verdict = if applies { format!("{}{} is in {}", letter, n, rule) } else { format!("no {}{} in {}", letter, n, rule) };
+    format!("{}: {}, so {}", counted, verdict, fate)
+}
+
+/// The cells that change next generation, in reading order, as the engine
+/// of a birth/survival backend will step them.  Empty for the others.
+pub fn changes(life: &dyn LifeEngine) -> Vec<(usize, usize)> {
+    let Some(state) = life.downcast_ref::<DenseEngine>().map(DenseEngine::state) else { return Vec::new() };
+    // Births can happen one cell outside the live ones, unless dead cells
+    // with no live neighbours can come alive, under B0 or a plugin rule, or
+    // the live ones are by an edge that wraps round to the other side
+    let by_edge = |bb: BoundingBox| bb.min_x == 0 || bb.min_y == 0 || bb.max_x + 1 >= state.width || bb.max_y + 1 >= state.height;
+    let wraps = state.boundary == Boundary::Torus && life.bounding_box().is_some_and(by_edge);
+    let area = if state.custom_rule.is_some() || state.rule.birth[0] || wraps {
+        (state.width > 0 && state.height > 0).then(|| BoundingBox { min_x: 0, min_y: 0, max_x: state.width - 1, max_y: state.height - 1 })
+    } else {
+        life.bounding_box().map(|bb| BoundingBox {
+            min_x: bb.min_x.saturating_sub(1),
+            min_y: bb.min_y.saturating_sub(1),
+            max_x: (bb.max_x + 1).min(state.width - 1),
+            max_y: (bb.max_y + 1).min(state.height - 1),
+        })
+    };
+    let Some(area) = area else { return Vec::new() };
+    let mut changed = Vec::new();
+    for y in area.min_y..=area.max_y {
+        for x in area.min_x..=area.max_x {
+            if state.next_cell_state(x, y) != state.get(x, y) {
+                changed.push((x, y));
+            }
+        }
+    }
+    changed
+}
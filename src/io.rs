@@ -0,0 +1,580 @@
+//! Reading and writing patterns in the plaintext (`.cells`), run length
+//! encoded (`.rle`), Life 1.05 and 1.06 (`.lif`) and macrocell (`.mc`)
+//! formats.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::apgcode;
+use crate::engine::{CellState, LifeEngine};
+use crate::rule::Rule;
+
+/// Parses a plaintext pattern.  Lines starting with `!` are comments, `O` is
+/// a live cell and anything else is dead.  Returns the live cells relative to
+/// the top left corner.
+pub fn parse_plaintext(text: &str) -> Vec<(usize, usize)> {
+    let mut live = Vec::new();
+    for (y, line) in text.lines().filter(|l| !l.starts_with('!')).enumerate() {
+        for (x, c) in line.chars().enumerate() {
+            if c == 'O' {
+                live.push((x, y));
+            }
+        }
+    }
+    live
+}
+
+/// Widest and tallest RLE pattern read, far past any grid, so a file can't
+/// ask for more memory than there is.
+pub const MAX_EXTENT: usize = 1 << 20;
+/// Most live cells read from an RLE pattern.
+pub const MAX_CELLS: usize = 1 << 24;
+
+/// Parses a run length encoded pattern.  `#` lines and the `x = .., y = ..`
+/// header are skipped; in the body `b` or `.` is a dead cell, any other
+/// letter a live one, `$` ends a row and `!` ends the pattern.  Returns the
+/// live cells relative to the top left corner.
+pub fn parse_rle(text: &str) -> Result<Vec<(usize, usize)>, String> {
+    let mut live = Vec::new();
+    let (mut x, mut y) = (0, 0);
+    let mut run: Option<usize> = None;
+    let body = text.lines()
+        .map(str::trim)
+        .filter(|l| !l.starts_with('#') && !l.starts_with("x ") && !l.starts_with("x="));
+    'lines: for line in body {
+        for c in line.chars() {
+            let count = run.unwrap_or(1);
+            match c {
+                '0'..='9' => {
+                    let digit = c as usize - '0' as usize;
+                    run = Some(run.unwrap_or(0).checked_mul(10).and_then(|r| r.checked_add(digit))
+                        .filter(|&r| r <= MAX_EXTENT)
+                        .ok_or("run count too large")?);
+                    continue;
+                }
+                'b' | '.' => x += count,
+                '$' => {
+                    y += count;
+                    x = 0;
+                }
+                '!' => break 'lines,
+                c if c.is_ascii_alphabetic() => {
+                    if x + count > MAX_EXTENT || live.len() + count > MAX_CELLS {
+                        return Err(format!("pattern is larger than {} cells across or {} cells in all", MAX_EXTENT, MAX_CELLS));
+                    }
+                    live.extend((x..x + count).map(|x| (x, y)));
+                    x += count;
+                }
+                c if c.is_whitespace() => {}
+                c => return Err(format!("unexpected '{}' in RLE", c))
+            }
+            run = None;
+            if x > MAX_EXTENT || y >= MAX_EXTENT {
+                return Err(format!("pattern is larger than {} cells across", MAX_EXTENT));
+            }
+        }
+    }
+    Ok(live)
+}
+
+/// Parses any of the formats, telling them apart by the Life and macrocell
+/// headers, then RLE's `x = ..` header, `#` comments or closing `!`.
+pub fn parse_pattern(text: &str) -> Result<Vec<(usize, usize)>, String> {
+    let first = text.trim_start().lines().next().unwrap_or("");
+    if first.starts_with("#Life 1.05") {
+        return parse_life105(text);
+    }
+    if first.starts_with("#Life 1.06") {
+        return parse_life106(text);
+    }
+    if first.starts_with("[M2]") {
+        return parse_macrocell(text);
+    }
+    let lines: Vec<&str> = text.lines().map(str::trim).filter(|l| !l.is_empty()).collect();
+    let rle = lines.iter().any(|l| l.starts_with('#') || l.starts_with("x ") || l.starts_with("x="))
+        || (lines.last().is_some_and(|l| l.ends_with('!')) && !lines.iter().any(|l| l.starts_with('!')));
+    if rle { parse_rle(text) } else { Ok(parse_plaintext(text)) }
+}
+
+/// Moves cells that may have negative positions to the top left corner, as
+/// the parsers return them.
+fn from_corner(cells: Vec<(i64, i64)>) -> Result<Vec<(usize, usize)>, String> {
+    let left = cells.iter().map(|c| c.0).min().unwrap_or(0);
+    let top = cells.iter().map(|c| c.1).min().unwrap_or(0);
+    cells.into_iter().map(|(x, y)| {
+        let (x, y) = (x.abs_diff(left) as usize, y.abs_diff(top) as usize);
+        if x < MAX_EXTENT && y < MAX_EXTENT { Ok((x, y)) } else { Err(format!("pattern is larger than {} cells across", MAX_EXTENT)) }
+    }).collect()
+}
+
+/// Parses a Life 1.05 pattern: blocks of `*` live and `.` dead cells, each
+/// placed by the `#P x y` line before it.  Other `#` lines are skipped.
+/// Returns the live cells relative to the top left corner.
+pub fn parse_life105(text: &str) -> Result<Vec<(usize, usize)>, String> {
+    let mut cells = Vec::new();
+    let (mut left, mut y) = (0i64, 0i64);
+    for line in text.lines().map(str::trim_end) {
+        if let Some(at) = line.strip_prefix("#P") {
+            let mut numbers = at.split_whitespace().map(str::parse::<i64>);
+            match (numbers.next(), numbers.next()) {
+                (Some(Ok(x)), Some(Ok(top))) => (left, y) = (x, top),
+                _ => return Err(format!("bad block position '{}'", line))
+            }
+            continue;
+        }
+        if line.starts_with('#') {
+            continue;
+        }
+        for (x, c) in line.chars().enumerate() {
+            match c {
+                '*' => cells.push((left + x as i64, y)),
+                '.' => {}
+                c => return Err(format!("unexpected '{}' in Life 1.05", c))
+            }
+        }
+        if cells.len() > MAX_CELLS {
+            return Err(format!("pattern has more than {} cells", MAX_CELLS));
+        }
+        y += 1;
+    }
+    from_corner(cells)
+}
+
+/// Parses a Life 1.06 pattern: a live cell's `x y` on each line, after the
+/// `#Life 1.06` header.  Returns them relative to the top left corner.
+pub fn parse_life106(text: &str) -> Result<Vec<(usize, usize)>, String> {
+    let mut cells = Vec::new();
+    for line in text.lines().map(str::trim).filter(|l| !l.is_empty() && !l.starts_with('#')) {
+        let mut numbers = line.split_whitespace().map(str::parse::<i64>);
+        match (numbers.next(), numbers.next()) {
+            (Some(Ok(x)), Some(Ok(y))) => cells.push((x, y)),
+            _ => return Err(format!("bad cell '{}'", line))
+        }
+        if cells.len() > MAX_CELLS {
+            return Err(format!("pattern has more than {} cells", MAX_CELLS));
+        }
+    }
+    from_corner(cells)
+}
+
+// Side of a macrocell leaf, and its level: 2^3 = 8
+const LEAF: u64 = 8;
+const LEAF_LEVEL: u32 = 3;
+// Nodes a macrocell pattern may take to walk, whatever its population
+const MAX_NODES: usize = 4 * MAX_CELLS;
+
+/// A macrocell node: an 8x8 leaf as bits, row by row, or a square of four
+/// earlier nodes, north west, north east, south west and south east, with 0
+/// for an empty one.
+enum Node {
+    Leaf(u64),
+    Branch(u32, [usize; 4]),
+}
+
+/// Parses a two state macrocell pattern, Golly's quadtree format: each line
+/// after the `[M2]` header is a node, either an 8x8 leaf written as rows of
+/// `.` and `*` ending in `$`, or `level nw ne sw se` giving the numbers of
+/// earlier lines, counting from 1, or 0 for an empty one.  Each node's
+/// children must be a level below it.  The last is the whole pattern.
+/// Returns the live cells relative to the top left corner.
+pub fn parse_macrocell(text: &str) -> Result<Vec<(usize, usize)>, String> {
+    // Numbered from 1, so a dummy goes first
+    let mut nodes = vec![Node::Leaf(0)];
+    // Each node's level and live cells, worked out as it is read so the
+    // walk below never goes into an empty one
+    let mut sizes: Vec<(u32, u64)> = vec![(0, 0)];
+    for line in text.lines().map(str::trim).filter(|l| !l.is_empty() && !l.starts_with('[') && !l.starts_with('#')) {
+        if line.starts_with(['.', '*', '$']) {
+            let (mut bits, mut x, mut y) = (0u64, 0, 0);
+            for c in line.chars() {
+                match c {
+                    '.' => x += 1,
+                    '*' if x < LEAF && y < LEAF => {
+                        bits |= 1 << (y * LEAF + x);
+                        x += 1;
+                    }
+                    '$' => (x, y) = (0, y + 1),
+                    _ => return Err(format!("bad leaf '{}'", line))
+                }
+            }
+            nodes.push(Node::Leaf(bits));
+            sizes.push((LEAF_LEVEL, bits.count_ones() as u64));
+            continue;
+        }
+        let numbers: Vec<usize> = line.split_whitespace().map(str::parse).collect::<Result<_, _>>().map_err(|_| format!("bad node '{}'", line))?;
+        let child_of = |level: usize, n: usize| n == 0 || (n < nodes.len() && sizes[n].0 as usize + 1 == level);
+        match numbers[..] {
+            [level, nw, ne, sw, se] if level > LEAF_LEVEL as usize && level < 63 && [nw, ne, sw, se].iter().all(|&n| child_of(level, n)) => {
+                let population = [nw, ne, sw, se].iter().fold(0u64, |sum, &n| sum.saturating_add(sizes[n].1));
+                nodes.push(Node::Branch(level as u32, [nw, ne, sw, se]));
+                sizes.push((level as u32, population));
+            }
+            _ => return Err(format!("bad node '{}'", line))
+        }
+    }
+    if sizes.last().is_some_and(|&(_, population)| population > MAX_CELLS as u64) {
+        return Err(format!("pattern has more than {} cells", MAX_CELLS));
+    }
+
+    let mut cells = Vec::new();
+    let mut stack = vec![(nodes.len() - 1, 0u64, 0u64)];
+    let mut visited = 0;
+    while let Some((index, x, y)) = stack.pop() {
+        visited += 1;
+        if visited > MAX_NODES {
+            return Err(format!("pattern takes more than {} nodes to read", MAX_NODES));
+        }
+        match &nodes[index] {
+            _ if sizes[index].1 == 0 => {}
+            Node::Leaf(bits) => {
+                for bit in (0..LEAF * LEAF).filter(|b| bits & (1 << b) != 0) {
+                    cells.push(((x + bit % LEAF) as i64, (y + bit / LEAF) as i64));
+                }
+                if cells.len() > MAX_CELLS {
+                    return Err(format!("pattern has more than {} cells", MAX_CELLS));
+                }
+            }
+            Node::Branch(level, children) => {
+                let half = 1 << (level - 1);
+                for (&child, (dx, dy)) in children.iter().zip([(0, 0), (half, 0), (0, half), (half, half)]) {
+                    stack.push((child, x + dx, y + dy));
+                }
+            }
+        }
+    }
+    cells.sort_unstable_by_key(|&(x, y)| (y, x));
+    from_corner(cells)
+}
+
+/// The rule a pattern file names: RLE's `rule =` in the header, a `#R` line
+/// as in Life 1.05 and macrocell files, a bare `#N` for Conway's, or a
+/// plaintext `!Rule:` comment.  None if it names none, or one that isn't
+/// B/S.
+pub fn pattern_rule(text: &str) -> Option<Rule> {
+    text.lines().map(str::trim).find_map(|line| {
+        let rule = if line == "#N" {
+            return Some(Rule::conway());
+        } else if let Some(rule) = line.strip_prefix("#R").or_else(|| line.strip_prefix("!Rule:")) {
+            rule
+        } else if line.starts_with('x') {
+            line.split_once("rule")?.1.trim_start().strip_prefix('=')?
+        } else {
+            return None;
+        };
+        // Bounded grids are written after a colon; RLE's #R is a position,
+        // which doesn't parse
+        rule.split(':').next()?.trim().parse().ok()
+    })
+}
+
+/// Writes `live` cells, as returned by the parsers, in plaintext format.
+pub fn cells_to_plaintext(live: &[(usize, usize)]) -> String {
+    let (w, h) = extent(live);
+    let mut rows = vec![vec!['.'; w]; h];
+    for &(x, y) in live {
+        rows[y][x] = 'O';
+    }
+    rows.into_iter().map(|row| row.into_iter().collect::<String>() + "\n").collect()
+}
+
+/// Writes `live` cells, as returned by the parsers, in run length encoded
+/// format with an `x = .., y = ..` header and lines of at most 70 characters.
+pub fn cells_to_rle(live: &[(usize, usize)]) -> String {
+    let (w, h) = extent(live);
+    let mut rows = vec![Vec::new(); h];
+    for &(x, y) in live {
+        rows[y].push(x);
+    }
+
+    // Runs of (count, tag), with dead cells at the end of a row left out
+    let mut runs: Vec<(usize, char)> = Vec::new();
+    fn push(runs: &mut Vec<(usize, char)>, count: usize, tag: char) {
+        match runs.last_mut() {
+            Some(last) if last.1 == tag => last.0 += count,
+            _ if count > 0 => runs.push((count, tag)),
+            _ => {}
+        }
+    }
+    for (y, row) in rows.iter_mut().enumerate() {
+        if y > 0 {
+            push(&mut runs, 1, '$');
+        }
+        row.sort_unstable();
+        row.dedup();
+        let mut x = 0;
+        for &cell in row.iter() {
+            push(&mut runs, cell - x, 'b');
+            push(&mut runs, 1, 'o');
+            x = cell + 1;
+        }
+    }
+    push(&mut runs, 1, '!');
+
+    let mut out = format!("x = {}, y = {}\n", w, h);
+    let mut line_len = 0;
+    for (count, tag) in runs {
+        let item = if count > 1 { format!("{}{}", count, tag) } else { tag.to_string() };
+        if line_len + item.len() > 70 {
+            out.push('\n');
+            line_len = 0;
+        }
+        line_len += item.len();
+        out.push_str(&item);
+    }
+    out.push('\n');
+    out
+}
+
+/// Writes `live` cells in Life 1.05 format, as one block centred on the
+/// origin, with the rule in S/B form if given.
+pub fn cells_to_life105(live: &[(usize, usize)], rule: Option<&Rule>) -> String {
+    let (w, h) = extent(live);
+    let mut out = "#Life 1.05\n".to_string();
+    if let Some(rule) = rule {
+        let digits = |table: &[bool; 9]| (0..9).filter(|&n| table[n]).map(|n| n.to_string()).collect::<String>();
+        out += &format!("#R {}/{}\n", digits(&rule.survive), digits(&rule.birth));
+    }
+    out += &format!("#P {} {}\n", -((w / 2) as i64), -((h / 2) as i64));
+    let rows = cells_to_plaintext(live);
+    for row in rows.lines() {
+        out += row.trim_end_matches('.').replace('O', "*").as_str();
+        out.push('\n');
+    }
+    out
+}
+
+/// Writes `live` cells in Life 1.06 format, a cell to a line, which has no
+/// room for the rule.
+pub fn cells_to_life106(live: &[(usize, usize)]) -> String {
+    let mut cells = live.to_vec();
+    cells.sort_unstable_by_key(|&(x, y)| (y, x));
+    let mut out = "#Life 1.06\n".to_string();
+    for (x, y) in cells {
+        out += &format!("{} {}\n", x, y);
+    }
+    out
+}
+
+/// Writes `live` cells in macrocell format, with the rule if given.  Equal
+/// squares are written once, which is what makes it small for big, regular
+/// patterns.
+pub fn cells_to_macrocell(live: &[(usize, usize)], rule: Option<&Rule>) -> String {
+    let (w, h) = extent(live);
+    let mut level = LEAF_LEVEL + 1;
+    while (1usize << level) < w.max(h) {
+        level += 1;
+    }
+    let mut out = "[M2] (rustlife)\n".to_string();
+    if let Some(rule) = rule {
+        out += &format!("#R {}\n", rule);
+    }
+    let mut written = Macrocell { lines: Vec::new(), leaves: HashMap::new(), branches: HashMap::new() };
+    let cells: Vec<(u64, u64)> = live.iter().map(|&(x, y)| (x as u64, y as u64)).collect();
+    written.node(&cells, 0, 0, level);
+    for line in written.lines {
+        out += &line;
+        out.push('\n');
+    }
+    out
+}
+
+/// Nodes written so far, and the line numbers of those already written.
+struct Macrocell {
+    lines: Vec<String>,
+    leaves: HashMap<u64, usize>,
+    branches: HashMap<(u32, [usize; 4]), usize>,
+}
+
+impl Macrocell {
+    /// Writes the square `2^level` across at (x, y) holding `cells`, if not
+    /// already written, and returns its line number, 0 when it is empty.
+    fn node(&mut self, cells: &[(u64, u64)], x: u64, y: u64, level: u32) -> usize {
+        if cells.is_empty() {
+            return 0;
+        }
+        if level == LEAF_LEVEL {
+            let bits = cells.iter().fold(0u64, |bits, &(cx, cy)| bits | 1 << ((cy - y) * LEAF + cx - x));
+            if let Some(&n) = self.leaves.get(&bits) {
+                return n;
+            }
+            let mut line = String::new();
+            for row in 0..LEAF {
+                let row = (bits >> (row * LEAF)) & 0xff;
+                for column in 0..(64 - row.leading_zeros()) {
+                    line.push(if row & (1 << column) != 0 { '*' } else { '.' });
+                }
+                line.push('$');
+            }
+            // Empty rows at the bottom go without saying
+            while line.ends_with("$$") {
+                line.pop();
+            }
+            self.lines.push(line);
+            self.leaves.insert(bits, self.lines.len());
+            return self.lines.len();
+        }
+        let half = 1 << (level - 1);
+        let mut quarters: [Vec<(u64, u64)>; 4] = Default::default();
+        for &(cx, cy) in cells {
+            quarters[usize::from(cx >= x + half) + 2 * usize::from(cy >= y + half)].push((cx, cy));
+        }
+        let mut children = [0; 4];
+        for (i, quarter) in quarters.iter().enumerate() {
+            let (dx, dy) = (if i % 2 == 1 { half } else { 0 }, if i >= 2 { half } else { 0 });
+            children[i] = self.node(quarter, x + dx, y + dy, level - 1);
+        }
+        if let Some(&n) = self.branches.get(&(level, children)) {
+            return n;
+        }
+        self.lines.push(format!("{} {} {} {} {}", level, children[0], children[1], children[2], children[3]));
+        self.branches.insert((level, children), self.lines.len());
+        self.lines.len()
+    }
+}
+
+/// The pattern file formats, for converting between them.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Format {
+    Plaintext,
+    Rle,
+    Life105,
+    Life106,
+    Macrocell,
+}
+
+impl Format {
+    /// The format a file name's extension stands for: `.cells` or `.txt`,
+    /// `.rle`, `.lif` or `.life` for Life 1.06, and `.mc`.
+    pub fn from_path(path: &str) -> Option<Format> {
+        let extension = std::path::Path::new(path).extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "cells" | "txt" => Some(Format::Plaintext),
+            "rle" => Some(Format::Rle),
+            "lif" | "life" => Some(Format::Life106),
+            "mc" => Some(Format::Macrocell),
+            _ => None
+        }
+    }
+
+    /// Writes `live` cells in this format, with the rule where the format
+    /// has room for it.
+    pub fn write(self, live: &[(usize, usize)], rule: Option<&Rule>) -> String {
+        match (self, rule) {
+            (Format::Plaintext, Some(rule)) => format!("!Rule: {}\n{}", rule, cells_to_plaintext(live)),
+            (Format::Plaintext, None) => cells_to_plaintext(live),
+            (Format::Rle, Some(rule)) => {
+                let rle = cells_to_rle(live);
+                let (header, body) = rle.split_once('\n').unwrap_or((&rle, ""));
+                format!("{}, rule = {}\n{}", header, rule, body)
+            }
+            (Format::Rle, None) => cells_to_rle(live),
+            (Format::Life105, rule) => cells_to_life105(live, rule),
+            (Format::Life106, _) => cells_to_life106(live),
+            (Format::Macrocell, rule) => cells_to_macrocell(live, rule),
+        }
+    }
+}
+
+/// Writes `live` cells for exporting: trimmed to their bounding box, turned
+/// to their canonical orientation if `canonical`, with the rule and the
+/// population where the format has room for them.
+pub fn export(live: &[(usize, usize)], format: Format, rule: Option<&Rule>, canonical: bool) -> String {
+    let live = if canonical { apgcode::canonical(live) } else { trim(live) };
+    let text = format.write(&live, rule);
+    let population = format!("Population: {}", live.len());
+    let (first, rest) = text.split_once('\n').unwrap_or((&text, ""));
+    match format {
+        Format::Plaintext => format!("!{}\n{}", population, text),
+        Format::Rle => format!("#C {}\n{}", population, text),
+        // After the header, which has to come first
+        Format::Life105 => format!("{}\n#D {}\n{}", first, population, rest),
+        Format::Macrocell => format!("{}\n#C {}\n{}", first, population, rest),
+        Format::Life106 => text,
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "cells" | "plaintext" => Ok(Format::Plaintext),
+            "rle" => Ok(Format::Rle),
+            "life105" => Ok(Format::Life105),
+            "life106" => Ok(Format::Life106),
+            "mc" | "macrocell" => Ok(Format::Macrocell),
+            _ => Err(format!("unknown format '{}', expected cells, rle, life105, life106 or mc", s))
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Format::Plaintext => "cells",
+            Format::Rle => "rle",
+            Format::Life105 => "life105",
+            Format::Life106 => "life106",
+            Format::Macrocell => "mc",
+        })
+    }
+}
+
+/// `live` cells moved so the first live column and row are at 0, dropping
+/// the empty margin a file may have had.
+pub fn trim(live: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    let left = live.iter().map(|c| c.0).min().unwrap_or(0);
+    let top = live.iter().map(|c| c.1).min().unwrap_or(0);
+    live.iter().map(|&(x, y)| (x - left, y - top)).collect()
+}
+
+/// Stamps `live` cells onto `state` with the pattern's corner at (x, y).
+/// Cells outside the grid are dropped.
+pub fn place(state: &mut dyn LifeEngine, live: &[(usize, usize)], x: usize, y: usize) {
+    for (px, py) in live {
+        let (cx, cy) = (x + px, y + py);
+        if cx < state.width() && cy < state.height() {
+            state.set(cx, cy, CellState::Alive);
+        }
+    }
+}
+
+/// Size of the pattern described by `live`, as (width, height).
+pub fn extent(live: &[(usize, usize)]) -> (usize, usize) {
+    let w = live.iter().map(|(x, _)| x + 1).max().unwrap_or(0);
+    let h = live.iter().map(|(_, y)| y + 1).max().unwrap_or(0);
+    (w, h)
+}
+
+/// Live cells of `state` relative to the top left corner of their bounding
+/// box, in the form the parsers return.
+pub fn live_cells(state: &dyn LifeEngine) -> Vec<(usize, usize)> {
+    let mut live = Vec::new();
+    if let Some(bb) = state.bounding_box() {
+        for y in bb.min_y..=bb.max_y {
+            for x in bb.min_x..=bb.max_x {
+                if state.get(x, y) == CellState::Alive {
+                    live.push((x - bb.min_x, y - bb.min_y));
+                }
+            }
+        }
+    }
+    live
+}
+
+/// Writes the live cells in plaintext format, cropped to their bounding box.
+pub fn to_plaintext(state: &dyn LifeEngine) -> String {
+    let bb = match state.bounding_box() {
+        Some(bb) => bb,
+        None => return String::new()
+    };
+    let mut out = String::with_capacity((bb.width() + 1) * bb.height());
+    for y in bb.min_y..=bb.max_y {
+        for x in bb.min_x..=bb.max_x {
+            out.push(if state.get(x, y) == CellState::Alive { 'O' } else { '.' });
+        }
+        out.push('\n');
+    }
+    out
+}
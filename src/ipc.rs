@@ -0,0 +1,130 @@
+//! Local control over a Unix domain socket, started with `--ipc <path>`.
+//! Each line is one command and gets one line back, `ok`, `ok <value>` or
+//! `error: <reason>`:
+//!
+//! | command          | effect                                  |
+//! |------------------|-----------------------------------------|
+//! | `pause`, `resume`| stop or restart stepping                |
+//! | `step [n]`       | pause, then step `n` generations (1)    |
+//! | `load <file>`    | replace the universe with a pattern     |
+//! | `save <file>`    | export the live cells, by its extension |
+//! | `volume <file>`  | export the space-time view's recording  |
+//! | `rule <rule>`    | change the rule, e.g. `rule B36/S23`    |
+//! | `speed <gps>`    | cap generations per second, 0 for none  |
+//! | `noise <p>`      | chance of each cell flipping, 0 to 1    |
+//! | `reaction <f> <k>` | feed and kill rates, reaction backend |
+//! | `forest <p> <f>` | growth and lightning, forest backend    |
+//! | `cyclic <n> <t>` | states and threshold, cyclic backend    |
+//! | `ising <t> <h>`  | temperature and field, ising backend    |
+//! | `gas <model>`    | hpp or fhp, gas backend                 |
+//! | `wolfram <n>`    | Wolfram code, elementary backend        |
+//! | `tape <bits>`    | restart from a rule 110 tape            |
+//! | `population`     | replies `ok <generation> <population>`  |
+//! | `census`         | replies `ok <apgcode>=<count> ...`      |
+//!
+//! For example `echo "step 100" | nc -U /tmp/life.sock`.  Like the remote
+//! control, commands act on the shown slot.
+
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::sync::atomic::Ordering;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::thread;
+
+use rustlife::census;
+
+use crate::cli;
+use crate::commands::Command;
+use crate::slots::ActiveSlot;
+
+/// Listens on the socket at `path`, replacing a stale one, and returns the
+/// commands clients send.
+pub fn serve(path: &str, active: ActiveSlot) -> Result<Receiver<Command>, String> {
+    // Left behind by a previous run that did not exit cleanly
+    if fs::metadata(path).is_ok() && UnixStream::connect(path).is_err() {
+        fs::remove_file(path).map_err(|e| format!("{}: {}", path, e))?;
+    }
+    let listener = UnixListener::bind(path).map_err(|e| format!("{}: {}", path, e))?;
+    log::info!("Accepting commands on {}", path);
+    let (tx, rx) = channel();
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let (tx, active) = (tx.clone(), active.clone());
+            thread::spawn(move || {
+                if let Err(e) = client(stream, &tx, &active) {
+                    log::warn!("IPC client: {}", e);
+                }
+            });
+        }
+    });
+    Ok(rx)
+}
+
+fn client(stream: UnixStream, commands: &Sender<Command>, active: &ActiveSlot) -> std::io::Result<()> {
+    let mut writer = stream.try_clone()?;
+    for line in BufReader::new(stream).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let reply = match handle(&line, commands, active) {
+            Ok(None) => "ok".to_string(),
+            Ok(Some(value)) => format!("ok {}", value),
+            Err(e) => format!("error: {}", e)
+        };
+        writeln!(writer, "{}", reply)?;
+    }
+    Ok(())
+}
+
+fn handle(line: &str, commands: &Sender<Command>, active: &ActiveSlot) -> Result<Option<String>, String> {
+    let (word, rest) = line.trim().split_once(char::is_whitespace).unwrap_or((line.trim(), ""));
+    let rest = rest.trim();
+    let needs = |what: &str| if rest.is_empty() { Err(format!("{} needs {}", word, what)) } else { Ok(rest.to_string()) };
+    let command = match word {
+        "pause" => Command::SetPaused(true),
+        "resume" => Command::SetPaused(false),
+        "step" if rest.is_empty() => Command::Step(1),
+        "step" => Command::Step(rest.parse().map_err(|_| format!("'{}' is not a number of generations", rest))?),
+        "load" => Command::LoadPattern(needs("a file")?),
+        "save" => Command::Export { path: needs("a file")?, canonical: false },
+        "volume" => Command::ExportVolume(needs("a file")?),
+        "rule" => Command::SetRule(needs("a rule")?),
+        "speed" => Command::SetSpeed(rest.parse().map_err(|_| format!("'{}' is not a number of generations per second", rest))?),
+        "noise" => Command::SetNoise(cli::parse_fraction(rest).map_err(|e| format!("noise {}", e))?),
+        "reaction" => {
+            let (feed, kill) = cli::parse_reaction(rest)?;
+            Command::SetReaction { feed, kill }
+        }
+        "forest" => {
+            let (growth, lightning) = cli::parse_forest(rest)?;
+            Command::SetForest { growth, lightning }
+        }
+        "cyclic" => {
+            let (states, threshold) = cli::parse_cyclic(rest)?;
+            Command::SetCyclic { states, threshold }
+        }
+        "ising" => {
+            let (temperature, field) = cli::parse_ising(rest)?;
+            Command::SetIsing { temperature, field }
+        }
+        "gas" => Command::SetGasModel(needs("a model")?.parse()?),
+        "wolfram" => Command::SetElementary(rest.parse().map_err(|_| format!("'{}' is not a rule from 0 to 255", rest))?),
+        "tape" => Command::LoadTape(needs("bits")?),
+        "population" => {
+            let active = active.read().map_err(|_| "slot lock poisoned")?;
+            let population = active.0.read().map_err(|_| "universe lock poisoned")?.population();
+            return Ok(Some(format!("{} {}", active.1.load(Ordering::Relaxed), population)));
+        }
+        "census" => {
+            let active = active.read().map_err(|_| "slot lock poisoned")?;
+            let taken = census::take(active.0.read().map_err(|_| "universe lock poisoned")?.as_ref());
+            let counts: Vec<_> = taken.sorted().iter().map(|(code, n)| format!("{}={}", code, n)).collect();
+            return Ok((!counts.is_empty()).then(|| counts.join(" ")));
+        }
+        _ => return Err(format!("unknown command '{}'", word))
+    };
+    commands.send(command).map_err(|_| "simulation has stopped")?;
+    Ok(None)
+}
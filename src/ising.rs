@@ -0,0 +1,299 @@
+//! The Ising model: every cell is a spin, up (alive) or down (dead), that
+//! would rather line up with its four neighbours and with the field.  Each
+//! generation is one Glauber sweep: a spin flips with probability
+//!
+//! ```text
+//! 1 / (1 + exp(ΔE / T)),   ΔE = 2 s (Σ neighbours + field)
+//! ```
+//!
+//! so almost surely when flipping lowers the energy, hardly ever when it
+//! raises it, and half the time when hot.  Metropolis' rule, flipping
+//! whenever the energy doesn't rise, would flip nearly every spin of a hot
+//! checkerboard at once and leave it blinking instead of disordered.
+//!
+//! Neighbouring spins can't flip at the same time, so each sweep updates the
+//! black squares of a checkerboard and then the white ones, each half in
+//! parallel rows.  Chances come from a hash of the cell, generation and
+//! seed, so a run is the same however the work is split.
+
+use std::any::Any;
+use std::sync::Mutex;
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+#[cfg(not(target_arch = "wasm32"))]
+use rayon::prelude::*;
+
+use crate::engine::{cell_chance, Anchor, CellState, LifeEngine, StaleGuard};
+use crate::rule::Rule;
+
+/// The critical temperature, where ordered domains and noise balance.
+pub const DEFAULT_TEMPERATURE: f64 = 2.269;
+pub const DEFAULT_FIELD: f64 = 0.0;
+
+/// The model's temperature and field and what they have done.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct IsingStats {
+    pub temperature: f64,
+    pub field: f64,
+    /// Mean spin, from -1 all down to 1 all up
+    pub magnetization: f64,
+    /// Energy per spin
+    pub energy: f64,
+}
+
+// Spins up, changed and born cells, then the sum over bonds
+type Counts = (usize, usize, usize, i64);
+
+fn spin(up: bool) -> f64 {
+    if up { 1.0 } else { -1.0 }
+}
+
+pub struct IsingEngine {
+    width: usize,
+    height: usize,
+    spins: Vec<bool>,
+    temperature: f64,
+    field: f64,
+    generation: u64,
+    seed: u64,
+    rule: Rule,
+    // The grid after the first half sweep, then after the second
+    scratch: Mutex<(Vec<bool>, Vec<bool>)>,
+    up: usize,
+    // Sum of s * s' over every pair of neighbours, kept up to date so the
+    // energy doesn't need a pass over the grid
+    bonds: i64,
+    changed: usize,
+    born: usize,
+    next_counts: Mutex<Counts>,
+    stale: StaleGuard,
+}
+
+impl IsingEngine {
+    pub fn new(width: usize, height: usize) -> Self {
+        IsingEngine {
+            width,
+            height,
+            spins: vec![false; width * height],
+            temperature: DEFAULT_TEMPERATURE,
+            field: DEFAULT_FIELD,
+            generation: 0,
+            seed: 0,
+            rule: Rule::default(),
+            scratch: Mutex::new((vec![false; width * height], vec![false; width * height])),
+            up: 0,
+            // All down, so every pair is lined up
+            bonds: (width.saturating_sub(1) * height + width * height.saturating_sub(1)) as i64,
+            changed: 0,
+            born: 0,
+            next_counts: Mutex::new((0, 0, 0, 0)),
+            stale: StaleGuard::default(),
+        }
+    }
+
+    /// Sum of s * s' over every pair of neighbours in `grid`, each pair
+    /// once.
+    fn bonds(&self, grid: &[bool]) -> i64 {
+        let mut bonds = 0;
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let up = grid[y * self.width + x];
+                let bond = |other: bool| if up == other { 1 } else { -1 };
+                if x + 1 < self.width { bonds += bond(grid[y * self.width + x + 1]); }
+                if y + 1 < self.height { bonds += bond(grid[(y + 1) * self.width + x]); }
+            }
+        }
+        bonds
+    }
+
+    /// Sum of the four neighbouring spins in `grid`, with nothing beyond
+    /// the edges.
+    fn neighbours(&self, grid: &[bool], x: usize, y: usize) -> f64 {
+        let at = |nx: usize, ny: usize| spin(grid[ny * self.width + nx]);
+        let mut sum = 0.0;
+        if x > 0 { sum += at(x - 1, y); }
+        if x + 1 < self.width { sum += at(x + 1, y); }
+        if y > 0 { sum += at(x, y - 1); }
+        if y + 1 < self.height { sum += at(x, y + 1); }
+        sum
+    }
+
+    /// Updates the squares of one colour of the checkerboard, reading
+    /// `from` and writing `to`.
+    fn half_sweep(&self, from: &[bool], to: &mut [bool], parity: usize) {
+        let width = self.width.max(1);
+        let half = 2 * self.generation + parity as u64;
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let rows = to.par_chunks_mut(width);
+        #[cfg(target_arch = "wasm32")]
+        let rows = to.chunks_mut(width);
+
+        rows.enumerate().for_each(|(y, row)| {
+            for (x, cell) in row.iter_mut().enumerate() {
+                let up = from[y * self.width + x];
+                *cell = up;
+                if (x + y) % 2 != parity {
+                    continue;
+                }
+                let delta = 2.0 * spin(up) * (self.neighbours(from, x, y) + self.field);
+                let flip = 1.0 / (1.0 + (delta / self.temperature.max(f64::MIN_POSITIVE)).exp());
+                if cell_chance(x, y, half, self.seed) < flip {
+                    *cell = !up;
+                }
+            }
+        });
+    }
+
+    fn put(&mut self, x: usize, y: usize, up: bool) {
+        let was = self.spins[y * self.width + x];
+        if was != up {
+            // Every bond to it turns round
+            self.bonds -= 2 * (spin(was) * self.neighbours(&self.spins, x, y)) as i64;
+            if up { self.up += 1 } else { self.up -= 1 }
+        }
+        self.spins[y * self.width + x] = up;
+        self.stale.bump();
+    }
+
+    /// Temperature, field and the state of the spins, from counts kept as
+    /// the spins change, so cheap enough for every frame.
+    pub fn stats(&self) -> IsingStats {
+        let cells = self.width * self.height;
+        let total = self.up as f64 - (cells - self.up) as f64;
+        let cells = cells.max(1) as f64;
+        IsingStats {
+            temperature: self.temperature,
+            field: self.field,
+            magnetization: total / cells,
+            energy: -(self.bonds as f64 + self.field * total) / cells,
+        }
+    }
+
+    pub fn set_params(&mut self, temperature: f64, field: f64) {
+        (self.temperature, self.field) = (temperature, field);
+        self.stale.bump();
+    }
+}
+
+impl LifeEngine for IsingEngine {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn name(&self) -> &'static str {
+        "ising"
+    }
+
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Up spins are alive.
+    fn get(&self, x: usize, y: usize) -> CellState {
+        if self.spins[y * self.width + x] { CellState::Alive } else { CellState::Dead }
+    }
+
+    fn set(&mut self, x: usize, y: usize, state: CellState) {
+        self.put(x, y, state == CellState::Alive);
+    }
+
+    /// Kept for the other backends' sake; the Ising model has no B/S rule.
+    fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    fn set_rule(&mut self, rule: Rule) {
+        self.rule = rule;
+    }
+
+    fn prepare_step(&self) {
+        let _span = tracing::info_span!("ising.prepare_step").entered();
+        if let Ok(mut scratch) = self.scratch.lock() {
+            self.stale.mark_prepared();
+            let (half, next) = &mut *scratch;
+            self.half_sweep(&self.spins, half, 0);
+            self.half_sweep(half, next, 1);
+
+            let mut counts: Counts = (0, 0, 0, self.bonds(next));
+            for (old, new) in self.spins.iter().zip(next.iter()) {
+                counts.0 += *new as usize;
+                if old != new {
+                    counts.1 += 1;
+                    counts.2 += *new as usize;
+                }
+            }
+            if let Ok(mut next) = self.next_counts.lock() {
+                *next = counts;
+            }
+        }
+    }
+
+    fn commit_step(&mut self) {
+        if self.stale.is_stale() {
+            self.prepare_step();
+        }
+        self.stale.committed();
+        if let (Ok(scratch), Ok(counts)) = (self.scratch.get_mut(), self.next_counts.get_mut()) {
+            std::mem::swap(&mut self.spins, &mut scratch.1);
+            (self.up, self.changed, self.born, self.bonds) = *counts;
+            self.generation += 1;
+        }
+    }
+
+    fn changed_cells(&self) -> usize {
+        self.changed
+    }
+
+    fn born_cells(&self) -> usize {
+        self.born
+    }
+
+    fn resize(&mut self, width: usize, height: usize, anchor: Anchor) {
+        let (ox, oy) = anchor.offset((self.width, self.height), (width, height));
+        let mut spins = vec![false; width * height];
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let (nx, ny) = (x as isize + ox, y as isize + oy);
+                if nx >= 0 && ny >= 0 && nx < width as isize && ny < height as isize {
+                    spins[ny as usize * width + nx as usize] = self.spins[y * self.width + x];
+                }
+            }
+        }
+        self.up = spins.iter().filter(|s| **s).count();
+        self.spins = spins;
+        self.scratch = Mutex::new((vec![false; width * height], vec![false; width * height]));
+        (self.width, self.height) = (width, height);
+        self.bonds = self.bonds(&self.spins);
+        self.changed = 0;
+        self.born = 0;
+        self.stale.bump();
+    }
+
+    /// As the other backends, with live cells as up spins, and reseeds the
+    /// flips so the run repeats.
+    fn randomize(&mut self, density: f64, seed: u64) {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        (self.seed, self.generation) = (seed, 0);
+        for y in 0..self.height {
+            rng.set_word_pos(2 * (y * self.width) as u128);
+            for x in 0..self.width {
+                let up = rng.gen_bool(density.clamp(0.0, 1.0));
+                self.put(x, y, up);
+            }
+        }
+    }
+
+    fn population(&self) -> usize {
+        self.up
+    }
+}
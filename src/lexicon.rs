@@ -0,0 +1,207 @@
+//! A glossary of Life terms in the format of the Life Lexicon's text
+//! version: each entry starts on a line `:term: definition`, carries on
+//! over the lines after it, and may hold a pattern drawn in `.` and `O` (or
+//! `*`) on lines starting with a tab.  A short glossary comes built in; the
+//! whole Life Lexicon can be read from its `lexicon.txt` instead.
+
+use std::fs;
+use std::path::Path;
+
+use crate::io;
+
+// The built in glossary, in the Life Lexicon's format
+const GLOSSARY: &str = "\
+:ash: The still lifes and oscillators a soup leaves behind once it has
+ settled.  Blocks, blinkers, beehives and gliders make up most of it.
+
+:block: (p1) The most common still life: a 2x2 square, each cell with
+ three live neighbours.
+\tOO
+\tOO
+
+:beehive: (p1) The second most common still life, six cells in a
+ hexagon.
+\t.OO.
+\tO..O
+\t.OO.
+
+:blinker: (p2) The smallest and most common oscillator, a row of three
+ that turns into a column of three and back.
+\tOOO
+
+:breeder: A pattern whose population grows quadratically, usually a
+ puffer that leaves guns behind it, each adding gliders of its own.
+
+:Garden of Eden: A pattern no pattern leads to, so it can only be set up,
+ never reached.  The palette's Find predecessor shows small patterns that
+ do have one.
+
+:glider: (c/4 diagonally, p4) The smallest spaceship, five cells moving
+ one cell diagonally every four generations.  Gliders are what most
+ constructions are built from.
+\t.O.
+\t..O
+\tOOO
+
+:Gosper glider gun: The first gun found, by Bill Gosper in 1970, sending
+ out a glider every 30 generations.  It settled that a finite pattern can
+ grow without limit.
+\t........................O...........
+\t......................O.O...........
+\t............OO......OO............OO
+\t...........O...O....OO............OO
+\tOO........O.....O...OO..............
+\tOO........O...O.OO....O.O...........
+\t..........O.....O.......O...........
+\t...........O...O....................
+\t............OO......................
+
+:gun: A stationary pattern that keeps sending out spaceships, so its
+ population grows linearly.
+
+:lightweight spaceship: (c/2 orthogonally, p4) The smallest spaceship
+ moving along a row or column, often called the LWSS.
+\t.O..O
+\tO....
+\tO...O
+\tOOOO.
+
+:methuselah: A small pattern that takes a long time to settle, such as
+ the R-pentomino or the acorn.
+
+:oscillator: A pattern that comes back to itself after a number of
+ generations, its period, without moving.  A still life is an oscillator
+ of period 1.
+
+:period: The number of generations an oscillator or spaceship takes to
+ come back to itself.
+
+:puffer: A spaceship that leaves debris behind it as it goes, so its
+ population grows for ever.
+
+:pulsar: (p3) The most common period 3 oscillator, with four-fold
+ symmetry.
+\t..OOO...OOO..
+\t.............
+\tO....O.O....O
+\tO....O.O....O
+\tO....O.O....O
+\t..OOO...OOO..
+\t.............
+\t..OOO...OOO..
+\tO....O.O....O
+\tO....O.O....O
+\tO....O.O....O
+\t.............
+\t..OOO...OOO..
+
+:R-pentomino: (stabilizes at time 1103) The five cell methuselah that
+ first showed how much a tiny pattern can do, leaving six gliders and a
+ field of ash behind.
+\t.OO
+\tOO.
+\t.O.
+
+:soup: A random starting pattern, usually a square with each cell alive
+ with probability 1/2.  Running many soups and counting the ash is how
+ new objects turn up.
+
+:spaceship: A pattern that comes back to itself moved over, so it travels
+ across the grid.  Its speed is given as a fraction of c, one cell a
+ generation.
+
+:still life: A pattern that doesn't change at all from one generation to
+ the next.
+";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Entry {
+    pub term: String,
+    /// The definition on one line, cross reference braces taken out
+    pub definition: String,
+    /// Live cells of the entry's first pattern, if it has one
+    pub pattern: Option<Vec<(usize, usize)>>,
+}
+
+/// The glossary that comes with the program.
+pub fn builtin() -> Vec<Entry> {
+    parse(GLOSSARY)
+}
+
+/// Reads a glossary in the Life Lexicon's format from `path`.
+pub fn load(path: &Path) -> Result<Vec<Entry>, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let entries = parse(&text);
+    if entries.is_empty() {
+        return Err(format!("{}: no entries, expected lines like ':term: definition'", path.display()));
+    }
+    Ok(entries)
+}
+
+/// Entries in Life Lexicon text, in the order given.  Anything before the
+/// first entry, and anything after a line of dashes, which the Lexicon
+/// puts between its sections, is left out.
+pub fn parse(text: &str) -> Vec<Entry> {
+    let mut entries: Vec<Entry> = Vec::new();
+    // Whether lines still belong to the last entry
+    let mut open = false;
+    let mut rows: Vec<String> = Vec::new();
+    for line in text.lines() {
+        let row = line.strip_prefix('\t').filter(|r| !r.is_empty() && r.chars().all(|c| matches!(c, '.' | 'O' | '*')));
+        if let Some(row) = row.filter(|_| open) {
+            rows.push(row.replace('*', "O"));
+            continue;
+        }
+        if let Some(entry) = entries.last_mut().filter(|e| e.pattern.is_none() && !rows.is_empty()) {
+            entry.pattern = Some(io::parse_plaintext(&rows.join("\n")));
+        }
+        rows.clear();
+        if let Some((term, definition)) = line.strip_prefix(':').and_then(|rest| rest.split_once(':')) {
+            entries.push(Entry { term: term.trim().to_string(), definition: String::new(), pattern: None });
+            open = true;
+            append(entries.last_mut().expect("just pushed"), definition);
+        } else if line.starts_with("---") {
+            open = false;
+        } else if let Some(entry) = entries.last_mut().filter(|_| open) {
+            append(entry, line);
+        }
+    }
+    if let Some(entry) = entries.last_mut().filter(|e| e.pattern.is_none() && !rows.is_empty()) {
+        entry.pattern = Some(io::parse_plaintext(&rows.join("\n")));
+    }
+    entries
+}
+
+fn append(entry: &mut Entry, text: &str) {
+    for word in text.split_whitespace() {
+        if !entry.definition.is_empty() {
+            entry.definition.push(' ');
+        }
+        entry.definition.extend(word.chars().filter(|&c| c != '{' && c != '}'));
+    }
+}
+
+/// Entries matching `query`, case insensitively: those whose term starts
+/// with it first, then those whose term holds it, then those whose
+/// definition does, each in glossary order.  Every entry for an empty
+/// query.
+pub fn search<'a>(entries: &'a [Entry], query: &str) -> Vec<&'a Entry> {
+    let query = query.trim().to_lowercase();
+    let mut ranked: Vec<(u8, usize)> = entries.iter().enumerate()
+        .filter_map(|(i, e)| {
+            let term = e.term.to_lowercase();
+            let rank = if term.starts_with(&query) {
+                0
+            } else if term.contains(&query) {
+                1
+            } else if e.definition.to_lowercase().contains(&query) {
+                2
+            } else {
+                return None;
+            };
+            Some((rank, i))
+        })
+        .collect();
+    ranked.sort();
+    ranked.into_iter().map(|(_, i)| &entries[i]).collect()
+}
@@ -0,0 +1,62 @@
+//! Conway's Game of Life engine, independent of any frontend.
+
+pub mod apgcode;
+pub mod builder;
+pub mod camera;
+pub mod census;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod checkpoint;
+pub mod cyclic;
+pub mod demo;
+pub mod diff;
+pub mod elementary;
+pub mod engine;
+pub mod events;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod evolve;
+pub mod explore;
+pub mod find;
+pub mod forest;
+pub mod gas;
+pub mod growth;
+pub mod inspect;
+pub mod io;
+pub mod ising;
+pub mod lexicon;
+pub mod library;
+pub mod memory;
+pub mod methuselah;
+pub mod noise;
+pub mod packed;
+pub mod pad;
+pub mod period;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod plugin;
+pub mod predecessor;
+pub mod quadtree;
+pub mod reaction;
+pub mod replay;
+pub mod rule;
+pub mod sand;
+pub mod script;
+pub mod ships;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod snapshot;
+pub mod soup;
+pub mod species;
+pub mod stats;
+pub mod stop;
+pub mod svg;
+pub mod text;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod timeline;
+pub mod timing;
+pub mod tour;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod verify;
+pub mod volume;
+
+pub use builder::SimulationBuilder;
+pub use engine::{Anchor, Boundary, BoundingBox, CellState, ConwayState, DenseEngine, Generations, LifeEngine};
+pub use events::{EventBus, GenerationEvent};
+pub use rule::{NeighborhoodRule, Rule};
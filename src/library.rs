@@ -0,0 +1,79 @@
+//! Patterns to browse and place: a few well known ones built in, plus the
+//! plaintext and RLE files in a directory of the user's own.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::{apgcode, io};
+
+// Names and RLE of the built in patterns, in the order they are listed
+const BUILTIN: &[(&str, &str)] = &[
+    ("glider", "bo$2bo$3o!"),
+    ("lightweight spaceship", "bo2bo$o$o3bo$4o!"),
+    ("middleweight spaceship", "3bo$bo3bo$o$o4bo$5o!"),
+    ("heavyweight spaceship", "3b2o$bo4bo$o$o5bo$6o!"),
+    ("block", "2o$2o!"),
+    ("beehive", "b2o$o2bo$b2o!"),
+    ("loaf", "b2o$o2bo$bobo$2bo!"),
+    ("boat", "2o$obo$bo!"),
+    ("blinker", "3o!"),
+    ("toad", "b3o$3o!"),
+    ("beacon", "2o$2o$2b2o$2b2o!"),
+    ("pulsar", "2b3o3b3o2$o4bobo4bo$o4bobo4bo$o4bobo4bo$2b3o3b3o2$2b3o3b3o$o4bobo4bo$o4bobo4bo$o4bobo4bo2$2b3o3b3o!"),
+    ("pentadecathlon", "2bo4bo$2ob4ob2o$2bo4bo!"),
+    ("R-pentomino", "b2o$2o$bo!"),
+    ("diehard", "6bo$2o$bo3b3o!"),
+    ("acorn", "bo$3bo$2o2b3o!"),
+    ("Gosper glider gun", "24bo$22bobo$12b2o6b2o12b2o$11bo3bo4b2o12b2o$2o8bo5bo3b2o$2o8bo3bob2o4bobo$10bo5bo7bo$11bo3bo$12b2o!"),
+    ("Simkin glider gun", "2o5b2o$2o5b2o2$4b2o$4b2o5$22b2ob2o$21bo5bo$21bo6bo2b2o$21b3o3bo3b2o$26bo4$20b2o$20bo$21b3o$23bo!"),
+    ("puffer train", "3bo$4bo$o3bo$b4o4$o$b2o$2bo$2bo$bo3$3bo$4bo$o3bo$b4o!"),
+    ("copperhead", "b2o2b2o$3b2o$3b2o$obo2bobo$o6bo2$o6bo$b2o2b2o$2b4o2$3b2o$3b2o!"),
+];
+
+#[derive(Clone, Debug)]
+pub struct LibraryPattern {
+    pub name: String,
+    /// Live cells, with the top left of the pattern at (0, 0)
+    pub cells: Vec<(usize, usize)>,
+}
+
+/// The patterns that come with the program.
+pub fn builtin() -> Vec<LibraryPattern> {
+    BUILTIN.iter()
+        .map(|(name, rle)| LibraryPattern { name: name.to_string(), cells: io::parse_rle(rle).expect("built in patterns parse") })
+        .collect()
+}
+
+/// Plaintext (`.cells`) and RLE (`.rle`) files in `dir`, sorted by name.
+pub fn pattern_files(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let entries = fs::read_dir(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+    let mut files: Vec<PathBuf> = entries.flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|e| e.eq_ignore_ascii_case("rle") || e.eq_ignore_ascii_case("cells")))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Reads a pattern file, named after the file.
+pub fn load_file(path: &Path) -> Result<LibraryPattern, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let cells = io::parse_pattern(&text).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let name = path.file_stem().map_or_else(|| path.display().to_string(), |s| s.to_string_lossy().into_owned());
+    Ok(LibraryPattern { name, cells })
+}
+
+/// The built in pattern called `query`, ignoring case, or the one an
+/// apgcode describes.
+pub fn find(query: &str) -> Option<LibraryPattern> {
+    let query = query.trim();
+    builtin().into_iter().find(|p| p.name.eq_ignore_ascii_case(query))
+        .or_else(|| apgcode::decode(query).ok().map(|cells| LibraryPattern { name: query.to_string(), cells }))
+}
+
+/// The name LifeWiki's pattern collection files a pattern under, its name
+/// in lower case with everything but letters and digits dropped, e.g.
+/// `gosperglidergun` for "Gosper glider gun".
+pub fn lifewiki_name(name: &str) -> String {
+    name.chars().filter(char::is_ascii_alphanumeric).map(|c| c.to_ascii_lowercase()).collect()
+}
@@ -1,279 +1,95 @@
-use rand::prelude::*;
-use error_iter::ErrorIter as _;
-use pixels::{Error, Pixels, SurfaceTexture};
-use winit::{
-    dpi::LogicalSize,
-    event::{Event, VirtualKeyCode},
-    event_loop::{ControlFlow, EventLoop},
-    window::WindowBuilder,
-};
-use winit_input_helper::WinitInputHelper;
-use std::sync::{Arc, RwLock};
-use std::thread;
-use std::sync::atomic::{AtomicI32, Ordering};
-use rayon::prelude::*;
-
-
-#[derive(PartialEq, Eq, Clone, Copy)]
-enum CellState {
-    Dead,
-    Alive
-}
-
-struct ConwayState {
-    cells: Vec<CellState>,
-    width: usize,
-    height: usize
-}
-
-impl ConwayState {
-    pub fn new(width: usize, height: usize) -> Self {
-        let mut cells = vec![CellState::Dead; width*height];
-        for (i,c) in cells.iter_mut().enumerate() {
-            if rand::random::<bool>() {
-                *c = CellState::Alive;
-            }
-        }
-        ConwayState {cells, width, height}
-    }
-
-    fn count_alive_neighbors(&self, x: usize, y:usize) -> usize {
-        const NEIGHBORS: [(i32, i32); 8] = [ // y, x or row, column
-            (-1, -1),
-            (-1, 0),
-            (-1, 1),
-            (0, -1),
-            (0, 1),
-            (1, -1),
-            (1, 0),
-            (1, 1)
-        ];
-        let mut count = 0;
-        let x = x as i32;
-        let y = y as i32;
-        // Boundary conidition is dead cells
-        for (j, i) in NEIGHBORS {
-            if y + j >= 0 &&
-               x + i >= 0 &&
-               y + j < self.height as i32 &&
-               x + i < self.width as i32 {
-                let linear_id = ((y + j) as usize)*self.width + ((x + i) as usize);
-                    if self.cells[linear_id] == CellState::Alive {
-                        count += 1;
-                    }
-            }
-        }
-        count
-    }
-    
-    fn next_cell_state(&self, x: usize, y:usize) -> CellState{
-        let linear_id = (y as usize)*self.width + (x as usize);
-        let cell_state = &self.cells[linear_id];
-        let live_count = self.count_alive_neighbors(x, y);
-        let ns = match (cell_state, live_count) {
-            (CellState::Dead, 3) => CellState::Alive,
-            (CellState::Alive, 2 | 3) => CellState::Alive,
-            _ => CellState::Dead
-        };
-        ns
-    }
-    
-    pub fn next_state(&self, scratch: &mut ConwayState) {
-        const rows_in_chunk:usize = 2;
-        let elements_in_chunk = rows_in_chunk * self.width;
-        let num_chunks = self.cells.len() / elements_in_chunk;
-        let rows_in_last_chunk = (self.cells.len() - num_chunks * elements_in_chunk) / self.width;
-
-        scratch.cells.par_chunks_mut(elements_in_chunk).enumerate().map(|(chunk, cells)| {
-            let row = chunk * rows_in_chunk;
-            if chunk < num_chunks
-            {
-                for j in 0..rows_in_chunk  {
-                    for i in 0..self.width {
-                        cells[j*self.width + i] = self.next_cell_state(i, j + row);
-                    }
-                }
-            }
-            else {
-                for j in 0..rows_in_last_chunk
-                {
-                    for i in 0..self.width{
-                        cells[j*self.width + i] = self.next_cell_state(i, j + row);
-                    }
-                }
-            }
-        }).count();
-    }
-
-    pub fn swap_state(&mut self, scratch: &mut ConwayState) {
-        std::mem::swap(&mut self.cells, &mut scratch.cells);
+#[cfg(feature = "accessibility")]
+mod access;
+#[cfg(not(target_arch = "wasm32"))]
+mod affinity;
+#[cfg(all(feature = "gif", not(target_arch = "wasm32")))]
+mod animation;
+#[cfg(all(feature = "gui", not(target_arch = "wasm32")))]
+mod announce;
+#[cfg(all(feature = "api", not(target_arch = "wasm32")))]
+mod api;
+#[cfg(all(feature = "audio", not(target_arch = "wasm32")))]
+mod audio;
+#[cfg(all(feature = "gui", not(target_arch = "wasm32")))]
+mod browser;
+#[cfg(not(target_arch = "wasm32"))]
+mod cli;
+#[cfg(all(feature = "gui", not(target_arch = "wasm32")))]
+mod collab;
+#[cfg(all(feature = "gui", not(target_arch = "wasm32")))]
+mod commands;
+#[cfg(not(target_arch = "wasm32"))]
+mod config;
+#[cfg(all(feature = "gui", not(target_arch = "wasm32")))]
+mod desktop;
+#[cfg(not(target_arch = "wasm32"))]
+mod error;
+#[cfg(all(feature = "gui", not(target_arch = "wasm32")))]
+mod explain;
+#[cfg(feature = "gamepad")]
+mod gamepad;
+#[cfg(all(feature = "gui", not(target_arch = "wasm32")))]
+mod glossary;
+#[cfg(not(target_arch = "wasm32"))]
+mod headless;
+#[cfg(all(feature = "gui", unix))]
+mod ipc;
+#[cfg(all(feature = "online", not(target_arch = "wasm32")))]
+mod online;
+#[cfg(not(target_arch = "wasm32"))]
+mod osc;
+#[cfg(all(feature = "gui", not(target_arch = "wasm32")))]
+mod overlay;
+#[cfg(all(feature = "gui", not(target_arch = "wasm32")))]
+mod palette;
+#[cfg(all(feature = "gui", not(target_arch = "wasm32")))]
+mod power;
+#[cfg(all(feature = "gui", not(target_arch = "wasm32")))]
+mod profiler;
+#[cfg(all(feature = "gui", not(target_arch = "wasm32")))]
+mod recent;
+#[cfg(all(feature = "remote", not(target_arch = "wasm32")))]
+mod remote;
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+mod render;
+#[cfg(all(feature = "gui", not(target_arch = "wasm32")))]
+mod ruler;
+#[cfg(all(feature = "gui", not(target_arch = "wasm32")))]
+mod scrubber;
+#[cfg(all(feature = "gui", not(target_arch = "wasm32")))]
+mod screensaver;
+#[cfg(not(target_arch = "wasm32"))]
+mod session;
+#[cfg(all(feature = "gui", not(target_arch = "wasm32")))]
+mod slots;
+#[cfg(all(feature = "gui", not(target_arch = "wasm32")))]
+mod span;
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+mod spacetime;
+#[cfg(not(target_arch = "wasm32"))]
+mod start;
+#[cfg(all(feature = "stream", not(target_arch = "wasm32")))]
+mod stream;
+#[cfg(feature = "tui")]
+mod tui;
+#[cfg(all(feature = "gui", not(target_arch = "wasm32")))]
+mod tutorial;
+#[cfg(not(target_arch = "wasm32"))]
+mod usage;
+#[cfg(all(feature = "gui", not(target_arch = "wasm32")))]
+mod watch;
+#[cfg(target_arch = "wasm32")]
+mod web;
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() {
+    if let Err(err) = start::run() {
+        error::log_error("conway", &err);
+        std::process::exit(1);
     }
 }
 
-fn pixel_scale(game_dim: u32, screen_dim: u32) -> Vec<(usize, f64)>
-{
-    let pixel_dim = ((game_dim as f64) / (screen_dim as f64)).max(1.0);
-    let states_per_pixel_dim = pixel_dim.ceil() as u32;
-    let pixel_dim_weights: Vec<_> = (0..states_per_pixel_dim)
-        .map(|x| {
-            (pixel_dim - (x as f64))/ pixel_dim
-        })
-        .enumerate().collect();
-    pixel_dim_weights
-}
-
-fn draw(width: u32, height: u32, screen: &mut [u8], state: &ConwayState) {
-    let width_f = (width) as f64;
-    let height_f = (height) as f64;
-
-    let state_width: f64 = state.width as f64;
-    let state_height: f64 = state.height as f64;
-
-    let pixel_width_weights = pixel_scale(GAME_WIDTH, WIDTH);
-    let pixel_height_weights = pixel_scale(GAME_HEIGHT, HEIGHT);
-    let normalization = 1.0/((pixel_width_weights.len() + pixel_height_weights.len()) as f64);
-
-    for (i, pix) in screen.chunks_exact_mut(4).enumerate() {
-        let y = (i as u32 / width) as f64 / height_f;
-        let x = (i as u32 % width) as f64 / width_f;
-        let x_border = x * state_width;
-        let y_border = y * state_height;
-
-       {
-            let x_id = x_border.floor() as usize;
-            let y_id = y_border.floor() as usize;
-            let mut color_acc = &mut [0.0, 0.0, 0.0, 0.0];
-
-            for (x_offset, x_weight) in &pixel_width_weights
-            {
-                for(y_offset, y_weight) in &pixel_height_weights {
-                    let linear_id = (y_id + y_offset) * state.width + x_id + x_offset;
-                    let cell_color = match state.cells[linear_id] {
-                        CellState::Alive => {
-                            [1.0, 1.0, 1.0, 1.0]
-                        },
-                        CellState::Dead => {
-                            [0.0, 0.0, 0.0, 0.0]
-                        }
-                    };
-
-                    for (i, v) in cell_color.iter().enumerate() {
-                        color_acc[i] += *v * (x_weight + y_weight) * 255.0 * normalization;
-                    }
-                }
-            }
-
-           let color:[u8;4] = [color_acc[0] as u8, color_acc[1] as u8, color_acc[2] as u8, 0xff];
-           pix.copy_from_slice(&color);
-
-        }
-    }
-}
-
-const WIDTH: u32 = 512;
-const HEIGHT: u32 = 512;
-
-const GAME_WIDTH: u32 = 4*WIDTH;
-const GAME_HEIGHT: u32 = 4*HEIGHT;
-
-fn main() -> Result<(), Error> {
-    env_logger::init();
-    let event_loop = EventLoop::new();
-    let mut input = WinitInputHelper::new();
-
-    let window = {
-        let size = LogicalSize::new(WIDTH as f64, HEIGHT as f64);
-        WindowBuilder::new()
-            .with_title("Conway's Game of Life")
-            .with_min_inner_size(size)
-            .build(&event_loop)
-            .unwrap()
-    };
-
-    let mut pixels = {
-        let window_size = window.inner_size();
-        let surface_texture = SurfaceTexture::new(window_size.width, window_size.height, &window);
-        Pixels::new(WIDTH, HEIGHT, surface_texture)?
-    };
-
-    let mut life = Arc::new(RwLock::new(ConwayState::new(GAME_WIDTH as usize, GAME_HEIGHT as usize)));
-    let c_life = Arc::clone(&life);
-
-    let mut paused = false;
-
-    let mut draw_state: Option<bool> = None;
-    let mut now = std::time::Instant::now();
-
-    let frames = Arc::new(AtomicI32::new(0));
-    let c_frames = Arc::clone(&frames);
-
-    thread::spawn(move || {
-        let mut scratch = ConwayState::new(GAME_WIDTH as usize, GAME_HEIGHT as usize);
-
-        loop {
-            if let Ok(l) = c_life.read() {
-                l.next_state(&mut scratch);
-            }
-            if let Ok(mut l) = c_life.write() {
-                l.swap_state(&mut scratch);
-            }
-            c_frames.fetch_add(1, Ordering::Relaxed);
-        }
-    });
-
-    event_loop.run(move |event, _, control_flow| {
-        // The one and only event that winit_input_helper doesn't have for us...
-        if let Event::RedrawRequested(_) = event {
-            //life.draw(pixels.frame_mut());
-            if let Ok(life) = life.read()
-            {
-                draw(WIDTH, HEIGHT, pixels.frame_mut(), &life);
-            }
-
-            let duration = now.elapsed().as_micros() as f64;
-            if(duration >= 1_000_000.0) {
-                println!("FPS: {}", 1_000_000.0*((frames.load(Ordering::Relaxed) as f64)/duration) );
-                frames.store(0, Ordering::SeqCst);
-                now = std::time::Instant::now();
-            }
-
-            //panic!("ENd");
-            if let Err(err) = pixels.render() {
-                *control_flow = ControlFlow::Exit;
-                return;
-            }
-        }
-
-        // For everything else, for let winit_input_helper collect events to build its state.
-        // It returns `true` when it is time to update our game state and request a redraw.
-        if input.update(&event) {
-            // Close events
-            if input.key_pressed(VirtualKeyCode::Escape) || input.close_requested() {
-                *control_flow = ControlFlow::Exit;
-                return;
-            }
-            if input.key_pressed(VirtualKeyCode::P) {
-                paused = !paused;
-            }
-            if input.key_pressed_os(VirtualKeyCode::Space) {
-                // Space is frame-step, so ensure we're paused
-                paused = true;
-            }
-            if input.key_pressed(VirtualKeyCode::R) {
-                //life.randomize();
-            }
-
-            // Resize the window
-            if let Some(size) = input.window_resized() {
-                if let Err(err) = pixels.resize_surface(size.width, size.height) {
-                    *control_flow = ControlFlow::Exit;
-                    return;
-                }
-            }
-            if !paused || input.key_pressed_os(VirtualKeyCode::Space) {
-                //life.update();
-            }
-            window.request_redraw();
-        }
-    });
+#[cfg(target_arch = "wasm32")]
+fn main() {
+    web::start();
 }
@@ -0,0 +1,118 @@
+//! What a universe will take in memory, worked out before anything is
+//! allocated: the grid, the scratch copy stepping writes the next
+//! generation into, the timeline's keyframes and snapshots kept to compare
+//! against.  A grid too big for the machine is then refused with an error,
+//! rather than running out of memory half way through building it.
+
+use std::fmt;
+use std::mem::size_of;
+
+use crate::engine::{self, CellState};
+use crate::sand::Element;
+use crate::species::Species;
+
+/// Bytes each part of a universe takes, at most.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Estimate {
+    pub grid: u64,
+    /// The next generation, and on the dense backend the lists of changed
+    /// cells
+    pub scratch: u64,
+    /// Timeline keyframes
+    pub history: u64,
+    pub snapshots: u64,
+    cells: u64,
+}
+
+impl Estimate {
+    /// The grid and scratch buffer of a `width` by `height` universe on
+    /// `backend`, or None for a backend there is no such thing as.
+    pub fn new(backend: &str, width: usize, height: usize) -> Option<Estimate> {
+        let cells = width as u64 * height as u64;
+        // Bytes a cell of the grid and of the scratch buffer take
+        let (grid, scratch) = match backend {
+            "dense" => {
+                let state = size_of::<CellState>() as u64;
+                // Both lists of changed cells, which are dropped past a
+                // share of the grid
+                let changes = 2.0 * size_of::<usize>() as f64 / engine::CHANGE_SHARE as f64;
+                // The quadtree, a bit a cell and a count for every four
+                // 8x8 tiles, four of those and so on, and the next one
+                let index = 1.0 / 8.0 + size_of::<usize>() as f64 / 192.0;
+                return Some(Estimate {
+                    grid: cells * state + (cells as f64 * index) as u64,
+                    scratch: cells * state + (cells as f64 * (changes + index)) as u64,
+                    cells,
+                    ..Estimate::default()
+                });
+            }
+            "species" => (size_of::<Option<Species>>(), size_of::<Option<Species>>()),
+            // Two levels a cell
+            "reaction" => (2 * size_of::<f32>(), 2 * size_of::<f32>()),
+            "sand" => (size_of::<Element>(), size_of::<Element>()),
+            // Three states in a byte
+            "forest" | "cyclic" => (1, 1),
+            // Stepped in two passes, each with its own buffer
+            "ising" => (size_of::<bool>(), 2 * size_of::<bool>()),
+            "gas" => (1, 2),
+            "elementary" => (size_of::<bool>(), size_of::<bool>()),
+            _ => return None
+        };
+        Some(Estimate { grid: cells * grid as u64, scratch: cells * scratch as u64, cells, ..Estimate::default() })
+    }
+
+    /// Adds `count` timeline keyframes, which at worst take a bit a cell.
+    pub fn keyframes(mut self, count: usize) -> Self {
+        self.history += count as u64 * self.cells.div_ceil(8);
+        self
+    }
+
+    /// Adds `count` snapshots, which take a byte a cell.
+    pub fn snapshots(mut self, count: usize) -> Self {
+        self.snapshots += count as u64 * self.cells;
+        self
+    }
+
+    pub fn total(&self) -> u64 {
+        self.grid + self.scratch + self.history + self.snapshots
+    }
+
+    /// An error saying what it would take if that is over `limit` bytes, 0
+    /// for no limit.
+    pub fn check(&self, limit: u64) -> Result<(), String> {
+        if limit > 0 && self.total() > limit {
+            return Err(format!("the universe would need {}, more than the {} allowed", self, bytes(limit)));
+        }
+        Ok(())
+    }
+}
+
+/// The total, then each part there is any of.
+impl fmt::Display for Estimate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let parts = [("grid", self.grid), ("scratch", self.scratch), ("history", self.history), ("snapshots", self.snapshots)];
+        let parts: Vec<_> = parts.iter().filter(|(_, b)| *b > 0).map(|(name, b)| format!("{} {}", name, bytes(*b))).collect();
+        write!(f, "{}", bytes(self.total()))?;
+        if !parts.is_empty() {
+            write!(f, " ({})", parts.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// A number of bytes in the biggest unit that keeps it at least one, such
+/// as "1.5 GiB".
+pub fn bytes(n: u64) -> String {
+    const UNITS: [&str; 5] = ["bytes", "KiB", "MiB", "GiB", "TiB"];
+    let mut size = n as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", n, UNITS[0])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
@@ -0,0 +1,93 @@
+//! Methuselah hunting: small random seeds are run until they settle, and
+//! the ones that take longest are kept.  A seed is `cells` distinct live
+//! cells placed at random in a [`SEED_BOX`] square, so the same seed number
+//! and cell count always give the same pattern.
+//!
+//! Settling is judged as in [`census`], by the population repeating, with
+//! anything that reaches the edge of the [`GRID`] taken out on the way.
+
+use rand::seq::index;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use crate::census;
+use crate::engine::{CellState, ConwayState};
+use crate::rule::Rule;
+
+pub const SEED_BOX: usize = 8;
+/// Side of the universe seeds are run in
+pub const GRID: usize = 256;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Candidate {
+    pub seed: u64,
+    pub pattern: Vec<(usize, usize)>,
+    /// Generation the population started repeating at
+    pub lifespan: usize,
+    /// Live cells once settled, not counting anything that escaped
+    pub final_population: usize,
+}
+
+/// The live cells of seed number `seed`.
+pub fn seed_pattern(seed: u64, cells: usize) -> Vec<(usize, usize)> {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let mut live: Vec<_> = index::sample(&mut rng, SEED_BOX * SEED_BOX, cells.min(SEED_BOX * SEED_BOX))
+        .into_iter()
+        .map(|i| (i % SEED_BOX, i / SEED_BOX))
+        .collect();
+    live.sort_by_key(|&(x, y)| (y, x));
+    live
+}
+
+/// Runs `pattern` from the middle of the grid until it settles, `None` if it
+/// does not within the generation limit.
+pub fn run(seed: u64, pattern: Vec<(usize, usize)>, rule: Rule) -> Option<Candidate> {
+    let mut state = ConwayState::empty(GRID, GRID);
+    state.rule = rule;
+    let corner = (GRID - SEED_BOX) / 2;
+    for &(x, y) in &pattern {
+        state.set(corner + x, corner + y, CellState::Alive);
+    }
+    let (lifespan, _) = census::settle(&mut state)?;
+    Some(Candidate { seed, pattern, lifespan, final_population: state.population() })
+}
+
+/// The longest lived candidates seen so far.
+#[derive(Clone, Debug, Default)]
+pub struct Leaderboard {
+    pub keep: usize,
+    /// Longest lived first
+    pub top: Vec<Candidate>,
+    pub tried: u64,
+    /// Seeds that had not settled within the generation limit
+    pub unsettled: Vec<u64>,
+}
+
+impl Leaderboard {
+    pub fn new(keep: usize) -> Self {
+        Leaderboard { keep, ..Leaderboard::default() }
+    }
+
+    pub fn add(&mut self, seed: u64, result: Option<Candidate>) {
+        self.tried += 1;
+        match result {
+            Some(c) => self.insert(c),
+            None => self.unsettled.push(seed)
+        }
+    }
+
+    pub fn merge(&mut self, other: Leaderboard) {
+        self.tried += other.tried;
+        for c in other.top {
+            self.insert(c);
+        }
+        self.unsettled.extend(other.unsettled);
+    }
+
+    /// Longest first, ties to the lower seed.
+    fn insert(&mut self, c: Candidate) {
+        let at = self.top.partition_point(|t| t.lifespan > c.lifespan || (t.lifespan == c.lifespan && t.seed < c.seed));
+        self.top.insert(at, c);
+        self.top.truncate(self.keep);
+    }
+}
@@ -0,0 +1,67 @@
+//! Noise: every cell flips with a small probability each generation, to
+//! see how patterns hold up and to keep a run from freezing into debris.
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::engine::{CellState, LifeEngine};
+
+/// Noise at a fixed chance, drawn from a seeded generator so a run with it
+/// can be repeated and replayed.
+#[derive(Clone, Debug)]
+pub struct Noise {
+    probability: f64,
+    rng: ChaCha8Rng,
+}
+
+impl Noise {
+    pub fn new(probability: f64, seed: u64) -> Self {
+        Noise { probability, rng: ChaCha8Rng::seed_from_u64(seed) }
+    }
+
+    /// No noise at all.
+    pub fn off() -> Self {
+        Noise::new(0.0, 0)
+    }
+
+    pub fn probability(&self) -> f64 {
+        self.probability
+    }
+
+    /// Flips cells for one generation, see [`inject`].
+    pub fn inject(&mut self, life: &mut dyn LifeEngine) -> usize {
+        inject(life, self.probability, &mut self.rng)
+    }
+}
+
+/// Flips each cell with probability `probability` and returns how many
+/// flipped.  Jumps straight from one flipped cell to the next, so a low
+/// probability costs little on a large grid.
+pub fn inject(life: &mut dyn LifeEngine, probability: f64, rng: &mut impl Rng) -> usize {
+    let cells = life.width() * life.height();
+    if probability <= 0.0 || cells == 0 {
+        return 0;
+    }
+    let (w, probability) = (life.width(), probability.min(1.0));
+    let mut flipped = 0;
+    let mut i = 0usize;
+    loop {
+        // Cells skipped before the next flip follow a geometric distribution.
+        // `ln_1p` keeps a tiny probability from rounding to a skip of 0.
+        if probability < 1.0 {
+            let u: f64 = rng.gen_range(f64::EPSILON..1.0);
+            i = i.saturating_add((u.ln() / (-probability).ln_1p()) as usize);
+        }
+        if i >= cells {
+            return flipped;
+        }
+        let (x, y) = (i % w, i / w);
+        let next = match life.get(x, y) {
+            CellState::Alive => CellState::Dead,
+            CellState::Dead => CellState::Alive,
+        };
+        life.set(x, y, next);
+        flipped += 1;
+        i += 1;
+    }
+}
@@ -0,0 +1,69 @@
+//! Looking up patterns that aren't built in and aren't apgcodes, for the
+//! palette's Fetch pattern.  They are fetched by name from LifeWiki's
+//! pattern collection with the system's `curl`, which saves building a TLS
+//! stack in, and kept in the cache directory so each is only downloaded
+//! once.
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::mpsc::Sender;
+use std::thread;
+
+use rustlife::library::{self, LibraryPattern};
+use rustlife::io;
+
+const LIFEWIKI: &str = "https://conwaylife.com/patterns";
+const TIMEOUT_SECONDS: &str = "20";
+
+/// `$XDG_CACHE_HOME/rustlife/lifewiki` or the platform equivalent.
+fn cache_dir() -> Option<PathBuf> {
+    dirs::cache_dir().map(|d| d.join("rustlife").join("lifewiki"))
+}
+
+fn download(url: &str) -> Result<String, String> {
+    let output = Command::new("curl")
+        .args(["--fail", "--silent", "--show-error", "--location", "--max-time", TIMEOUT_SECONDS, url])
+        .output()
+        .map_err(|e| format!("could not run curl: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("{}: {}", url, String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    String::from_utf8(output.stdout).map_err(|e| format!("{}: {}", url, e))
+}
+
+/// Finds the pattern called `query` on LifeWiki, downloading it if it
+/// isn't cached.  Blocks while downloading.
+pub fn lookup(query: &str) -> Result<LibraryPattern, String> {
+    let query = query.trim();
+    let file = library::lifewiki_name(query);
+    if file.is_empty() {
+        return Err(format!("'{}' is not a pattern name", query));
+    }
+    let cached = cache_dir().map(|d| d.join(format!("{}.rle", file)));
+    if let Some(path) = cached.as_ref().filter(|p| p.is_file()) {
+        return library::load_file(path).map(|p| LibraryPattern { name: query.to_string(), ..p });
+    }
+    let url = format!("{}/{}.rle", LIFEWIKI, file);
+    let rle = download(&url)?;
+    let cells = io::parse_rle(&rle).map_err(|e| format!("{}: {}", url, e))?;
+    if cells.is_empty() {
+        return Err(format!("{}: no live cells", url));
+    }
+    // Failing to cache only costs a download next time
+    if let Some(path) = cached {
+        let saved = path.parent().map_or(Ok(()), fs::create_dir_all).and_then(|_| fs::write(&path, &rle));
+        if let Err(e) = saved {
+            log::warn!("Could not cache {}: {}", path.display(), e);
+        }
+    }
+    Ok(LibraryPattern { name: query.to_string(), cells })
+}
+
+/// Runs [`lookup`] on its own thread and sends the result to `done`, so the
+/// window keeps drawing while it downloads.
+pub fn lookup_in_background(query: String, done: Sender<Result<LibraryPattern, String>>) {
+    thread::spawn(move || {
+        let _ = done.send(lookup(&query));
+    });
+}
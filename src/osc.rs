@@ -0,0 +1,114 @@
+//! Open Sound Control output, started with `--osc <host:port>`, for VJ
+//! software, SuperCollider, TouchDesigner and the like.  At most
+//! `--osc-rate` times a second one UDP packet per message is sent:
+//!
+//! | address            | arguments                                        |
+//! |--------------------|--------------------------------------------------|
+//! | `/life/generation` | `i` generation                                   |
+//! | `/life/population` | `i` live cells                                   |
+//! | `/life/activity`   | `i` cells that changed in the last generation    |
+//! | `/life/density`    | `ffff` live fraction of each quarter of the grid, top left, top right, bottom left, bottom right |
+//! | `/life/ships`      | `ii` spaceships, and how many of them are gliders |
+//!
+//! Finding the ships means running every small object on its own, so
+//! `/life/ships` is sent at most once a second.
+
+use std::net::UdpSocket;
+use std::time::{Duration, Instant};
+
+use rustlife::script::SharedLife;
+use rustlife::ships;
+use rustlife::{CellState, GenerationEvent, LifeEngine};
+
+// Cells sampled per quarter for the densities
+const SAMPLES: usize = 4096;
+// Shortest time between counts of the ships
+const SHIP_INTERVAL: Duration = Duration::from_secs(1);
+
+pub enum Arg {
+    Int(i32),
+    Float(f32),
+}
+
+/// Pads to a multiple of four bytes with at least one NUL, as OSC strings are.
+fn push_padded(out: &mut Vec<u8>, s: &str) {
+    out.extend_from_slice(s.as_bytes());
+    out.extend(std::iter::repeat_n(0, 4 - s.len() % 4));
+}
+
+/// Encodes one OSC message.
+pub fn message(address: &str, args: &[Arg]) -> Vec<u8> {
+    let mut out = Vec::new();
+    push_padded(&mut out, address);
+    let tags: String = std::iter::once(',')
+        .chain(args.iter().map(|a| match a { Arg::Int(_) => 'i', Arg::Float(_) => 'f' }))
+        .collect();
+    push_padded(&mut out, &tags);
+    for arg in args {
+        match arg {
+            Arg::Int(i) => out.extend_from_slice(&i.to_be_bytes()),
+            Arg::Float(f) => out.extend_from_slice(&f.to_be_bytes()),
+        }
+    }
+    out
+}
+
+/// Live fraction of each quarter of the grid, sampled on a lattice.
+fn densities(life: &dyn LifeEngine) -> [f32; 4] {
+    let (w, h) = ((life.width() / 2).max(1), (life.height() / 2).max(1));
+    let stride = (w * h / SAMPLES).max(1);
+    let mut out = [0.0; 4];
+    for (q, density) in out.iter_mut().enumerate() {
+        let (x0, y0) = ((q % 2) * w, (q / 2) * h);
+        let samples = (0..w * h).step_by(stride);
+        let count = samples.len();
+        let live = samples
+            .map(|i| (x0 + i % w, y0 + i / w))
+            .filter(|&(x, y)| x < life.width() && y < life.height() && life.get(x, y) == CellState::Alive)
+            .count();
+        *density = live as f32 / count.max(1) as f32;
+    }
+    out
+}
+
+/// Spaceships in the universe, and how many of them are gliders.
+fn ships(life: &dyn LifeEngine) -> [i32; 2] {
+    let found = ships::find(life);
+    let gliders = found.iter().filter(|s| s.name() == Some("glider")).count();
+    [found.len() as i32, gliders as i32]
+}
+
+/// Sends to `target` and returns the event listener that feeds it.
+pub fn start(target: &str, rate: u32, life: SharedLife) -> Result<impl FnMut(&GenerationEvent) + Send, String> {
+    let socket = UdpSocket::bind("0.0.0.0:0").map_err(|e| e.to_string())?;
+    socket.connect(target).map_err(|e| format!("{}: {}", target, e))?;
+    log::info!("Sending OSC to {}", target);
+
+    let interval = Duration::from_secs_f64(1.0 / rate.max(1) as f64);
+    let (mut last, mut counted): (Option<Instant>, Option<Instant>) = (None, None);
+    Ok(move |event: &GenerationEvent| {
+        if last.is_some_and(|t| t.elapsed() < interval) {
+            return;
+        }
+        last = Some(Instant::now());
+        let count = counted.is_none_or(|t| t.elapsed() >= SHIP_INTERVAL);
+        let (quarters, found) = match life.read() {
+            Ok(l) => (densities(l.as_ref()), count.then(|| ships(l.as_ref()))),
+            Err(_) => return
+        };
+        let mut messages = vec![
+            message("/life/generation", &[Arg::Int(event.generation as i32)]),
+            message("/life/population", &[Arg::Int(event.population as i32)]),
+            message("/life/activity", &[Arg::Int(event.changed as i32)]),
+            message("/life/density", &quarters.map(Arg::Float)),
+        ];
+        if let Some(found) = found {
+            counted = last;
+            messages.push(message("/life/ships", &found.map(Arg::Int)));
+        }
+        for packet in messages {
+            // Nobody listening yet is fine, OSC is fire and forget
+            let _ = socket.send(&packet);
+        }
+    })
+}
@@ -0,0 +1,136 @@
+//! Text and boxes drawn over the rendered grid, using the 8x8 public domain
+//! font from the `font8x8` crate.
+
+use font8x8::legacy::BASIC_LEGACY;
+
+pub const GLYPH_SIZE: u32 = 8;
+
+/// Blends `color` over the rectangle (x, y, w, h), clipped to the screen.
+/// The fourth component of `color` is its opacity.
+pub fn fill_rect(screen: &mut [u8], width: u32, height: u32, (x, y, w, h): (i32, i32, u32, u32), color: [u8; 4]) {
+    let alpha = color[3] as u32;
+    for py in y.max(0)..(y + h as i32).min(height as i32) {
+        for px in x.max(0)..(x + w as i32).min(width as i32) {
+            let i = (py as usize * width as usize + px as usize) * 4;
+            for c in 0..3 {
+                let old = screen[i + c] as u32;
+                screen[i + c] = ((old * (255 - alpha) + color[c] as u32 * alpha) / 255) as u8;
+            }
+        }
+    }
+}
+
+/// Draws the one pixel border of the rectangle (x, y, w, h).
+pub fn outline(screen: &mut [u8], width: u32, height: u32, (x, y, w, h): (i32, i32, u32, u32), color: [u8; 4]) {
+    let (w, h) = (w.max(1), h.max(1));
+    fill_rect(screen, width, height, (x, y, w, 1), color);
+    fill_rect(screen, width, height, (x, y + h as i32 - 1, w, 1), color);
+    fill_rect(screen, width, height, (x, y, 1, h), color);
+    fill_rect(screen, width, height, (x + w as i32 - 1, y, 1, h), color);
+}
+
+/// Draws `text` with its top left corner at (x, y).  Characters outside
+/// basic ASCII are drawn as `?`.
+pub fn draw_text(screen: &mut [u8], width: u32, height: u32, x: i32, y: i32, text: &str, color: [u8; 3]) {
+    for (n, ch) in text.chars().enumerate() {
+        let glyph = BASIC_LEGACY.get(ch as usize).unwrap_or(&BASIC_LEGACY[b'?' as usize]);
+        let gx = x + (n as u32 * GLYPH_SIZE) as i32;
+        for (row, bits) in glyph.iter().enumerate() {
+            let py = y + row as i32;
+            if py < 0 || py >= height as i32 {
+                continue;
+            }
+            for col in 0..GLYPH_SIZE as i32 {
+                let px = gx + col;
+                if bits & (1 << col) != 0 && px >= 0 && px < width as i32 {
+                    let i = (py as usize * width as usize + px as usize) * 4;
+                    screen[i..i + 3].copy_from_slice(&color);
+                }
+            }
+        }
+    }
+}
+
+/// Draws a one pixel line from (x0, y0) to (x1, y1), clipped to the screen.
+pub fn line(screen: &mut [u8], width: u32, height: u32, (x0, y0): (i32, i32), (x1, y1): (i32, i32), color: [u8; 3]) {
+    let (dx, dy) = ((x1 - x0).abs(), -(y1 - y0).abs());
+    let (sx, sy) = (if x0 < x1 { 1 } else { -1 }, if y0 < y1 { 1 } else { -1 });
+    let (mut x, mut y, mut err) = (x0, y0, dx + dy);
+    loop {
+        if x >= 0 && y >= 0 && x < width as i32 && y < height as i32 {
+            let i = (y as usize * width as usize + x as usize) * 4;
+            screen[i..i + 3].copy_from_slice(&color);
+        }
+        if x == x1 && y == y1 {
+            break;
+        }
+        let e2 = 2 * err;
+        if e2 >= dy {
+            err += dy;
+            x += sx;
+        }
+        if e2 <= dx {
+            err += dx;
+            y += sy;
+        }
+    }
+}
+
+/// Plots `values` as bars filling the rectangle (x, y, w, h), scaled so the
+/// largest value reaches the top.  Only the last `w` values fit.
+pub fn plot(screen: &mut [u8], width: u32, height: u32, (x, y, w, h): (i32, i32, u32, u32), values: &[usize], color: [u8; 4]) {
+    fill_rect(screen, width, height, (x, y, w, h), [0, 0, 0, 160]);
+    let shown = &values[values.len().saturating_sub(w as usize)..];
+    let max = shown.iter().copied().max().unwrap_or(0).max(1);
+    for (i, v) in shown.iter().enumerate() {
+        let bar = (*v as u64 * h as u64 / max as u64) as u32;
+        fill_rect(screen, width, height, (x + i as i32, y + (h - bar) as i32, 1, bar), color);
+    }
+}
+
+/// Draws `lines` one under the other on a dark box in the top left corner.
+pub fn status(screen: &mut [u8], width: u32, height: u32, lines: &[String]) {
+    if lines.is_empty() {
+        return;
+    }
+    let line = GLYPH_SIZE + 2;
+    let columns = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0) as u32;
+    fill_rect(screen, width, height, (0, 0, columns * GLYPH_SIZE + 8, lines.len() as u32 * line + 6), [0, 0, 0, 180]);
+    for (i, text) in lines.iter().enumerate() {
+        draw_text(screen, width, height, 4, 4 + (i as u32 * line) as i32, text, [255, 255, 255]);
+    }
+}
+
+/// Draws `lines` in a box beside the mouse at (x, y), moved to the other
+/// side of it where it would go off the screen.
+pub fn tooltip(screen: &mut [u8], width: u32, height: u32, (x, y): (i32, i32), lines: &[String]) {
+    let line = GLYPH_SIZE + 2;
+    let columns = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0) as u32;
+    let (w, h) = (columns * GLYPH_SIZE + 8, lines.len() as u32 * line + 6);
+    let left = if x + 16 + w as i32 > width as i32 { x - 8 - w as i32 } else { x + 16 };
+    let top = if y + 16 + h as i32 > height as i32 { y - 8 - h as i32 } else { y + 16 };
+    fill_rect(screen, width, height, (left, top, w, h), [0, 0, 0, 220]);
+    outline(screen, width, height, (left, top, w, h), [160, 160, 160, 255]);
+    for (i, text) in lines.iter().enumerate() {
+        draw_text(screen, width, height, left + 4, top + 4 + (i as u32 * line) as i32, text, [255, 255, 255]);
+    }
+}
+
+/// Draws `text` large and centred on a dark band across the middle.
+pub fn banner(screen: &mut [u8], width: u32, height: u32, text: &str) {
+    let w = text.chars().count() as u32 * GLYPH_SIZE;
+    let y = (height / 2) as i32 - GLYPH_SIZE as i32;
+    fill_rect(screen, width, height, (0, y - 4, width, 2 * GLYPH_SIZE + 8), [0, 0, 0, 200]);
+    draw_text(screen, width, height, (width as i32 - w as i32) / 2, y + (GLYPH_SIZE / 2) as i32, text, [255, 220, 64]);
+}
+
+/// Draws `title` over `caption`, centred on a dark band along the bottom.
+pub fn caption(screen: &mut [u8], width: u32, height: u32, title: &str, caption: &str) {
+    let line = GLYPH_SIZE + 4;
+    let top = height as i32 - 2 * line as i32 - 8;
+    fill_rect(screen, width, height, (0, top, width, 2 * line + 8), [0, 0, 0, 200]);
+    for (i, (text, color)) in [(title, [255, 220, 64]), (caption, [255, 255, 255])].into_iter().enumerate() {
+        let w = text.chars().count() as i32 * GLYPH_SIZE as i32;
+        draw_text(screen, width, height, (width as i32 - w) / 2, top + 6 + i as i32 * line as i32, text, color);
+    }
+}
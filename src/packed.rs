@@ -0,0 +1,97 @@
+//! Grids of live and dead cells packed into bits, row by row, with runs of
+//! empty bytes shortened so sparse patterns cost little.  Diff snapshots
+//! and serialized `ConwayState`s are kept this way.
+
+use serde::{Deserialize, Serialize};
+
+use crate::engine::{CellState, LifeEngine};
+
+/// Packs cells into bits, with runs of up to 255 empty bytes written as a
+/// zero byte and the run's length.
+pub fn pack(alive: impl IntoIterator<Item = bool>) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut empty = 0u8;
+    let mut byte = 0u8;
+    let mut bits = 0;
+    let flush = |byte: u8, empty: &mut u8, out: &mut Vec<u8>| {
+        if byte == 0 && *empty < u8::MAX {
+            *empty += 1;
+            return;
+        }
+        if *empty > 0 {
+            out.extend_from_slice(&[0, *empty]);
+            *empty = 0;
+        }
+        if byte == 0 {
+            *empty = 1;
+        } else {
+            out.push(byte);
+        }
+    };
+    for a in alive {
+        if a {
+            byte |= 1 << bits;
+        }
+        bits += 1;
+        if bits == 8 {
+            flush(byte, &mut empty, &mut out);
+            (byte, bits) = (0, 0);
+        }
+    }
+    if bits > 0 {
+        flush(byte, &mut empty, &mut out);
+    }
+    if empty > 0 {
+        out.extend_from_slice(&[0, empty]);
+    }
+    out
+}
+
+/// Undoes [`pack`], calling `alive` with the index of every live cell.
+pub fn unpack(cells: &[u8], mut alive: impl FnMut(usize)) {
+    let mut i = 0;
+    let mut bytes = cells.iter();
+    while let Some(&byte) = bytes.next() {
+        if byte == 0 {
+            i += 8 * bytes.next().copied().unwrap_or(1) as usize;
+            continue;
+        }
+        for bit in 0..8 {
+            if byte & (1 << bit) != 0 {
+                alive(i + bit);
+            }
+        }
+        i += 8;
+    }
+}
+
+/// A `width` by `height` grid's cells, packed.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PackedGrid {
+    pub width: usize,
+    pub height: usize,
+    pub cells: Vec<u8>,
+}
+
+impl PackedGrid {
+    pub fn new(width: usize, height: usize, alive: impl IntoIterator<Item = bool>) -> Self {
+        PackedGrid { width, height, cells: pack(alive) }
+    }
+
+    pub fn of(life: &dyn LifeEngine) -> Self {
+        let (width, height) = (life.width(), life.height());
+        PackedGrid::new(width, height, (0..width * height).map(|i| life.get(i % width, i / width) == CellState::Alive))
+    }
+
+    /// One entry per cell, row by row.  Bits past the end of the grid are
+    /// ignored.
+    pub fn alive(&self) -> Vec<bool> {
+        let mut alive = vec![false; self.width * self.height];
+        unpack(&self.cells, |i| {
+            if let Some(a) = alive.get_mut(i) {
+                *a = true;
+            }
+        });
+        alive
+    }
+}
@@ -0,0 +1,100 @@
+//! What a gamepad asks for, whatever it is read with: the left stick pans,
+//! the right trigger zooms in and the left one out, A pauses, B steps, and
+//! left and right on the D-pad go through the built in patterns.  The window
+//! feeds it the pad's events and moves the view by [`Sticks::motion`] every
+//! frame.
+
+// Stick movement smaller than this is the stick resting off centre
+const DEAD_ZONE: f64 = 0.2;
+// View widths a second the stick pans when pushed all the way, and how many
+// times closer a fully pulled trigger zooms a second
+const PAN_SPEED: f64 = 1.0;
+const ZOOM_SPEED: f64 = 4.0;
+// Longest time one update moves the view for, so a frame that comes late
+// doesn't jump
+const LONGEST_STEP: f64 = 0.1;
+
+/// The buttons that do something, by where they are on an Xbox style pad.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Button {
+    /// A
+    South,
+    /// B
+    East,
+    DPadLeft,
+    DPadRight,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Axis {
+    LeftStickX,
+    LeftStickY,
+    LeftTrigger,
+    RightTrigger,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Input {
+    Pressed(Button),
+    /// Sticks from -1 to 1, right and up positive, and triggers from 0 to 1
+    Moved(Axis, f64),
+    /// Unplugged, so nothing should keep moving
+    Disconnected,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    TogglePause,
+    Step,
+    /// The built in pattern one after the last shown, or one before
+    NextPattern,
+    PreviousPattern,
+}
+
+/// How far the stick and triggers are pushed.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Sticks {
+    pub x: f64,
+    /// Down positive, as on screen
+    pub y: f64,
+    pub zoom_in: f64,
+    pub zoom_out: f64,
+}
+
+/// How the view should move over one update: the distance to pan in view
+/// widths and heights, and how many times closer to zoom.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Motion {
+    pub pan: (f64, f64),
+    pub zoom: f64,
+}
+
+impl Sticks {
+    /// Takes in an event, returning what a press asks for.
+    pub fn apply(&mut self, input: Input) -> Option<Action> {
+        match input {
+            Input::Pressed(Button::South) => return Some(Action::TogglePause),
+            Input::Pressed(Button::East) => return Some(Action::Step),
+            Input::Pressed(Button::DPadRight) => return Some(Action::NextPattern),
+            Input::Pressed(Button::DPadLeft) => return Some(Action::PreviousPattern),
+            Input::Moved(Axis::LeftStickX, v) => self.x = v.clamp(-1.0, 1.0),
+            Input::Moved(Axis::LeftStickY, v) => self.y = -v.clamp(-1.0, 1.0),
+            Input::Moved(Axis::LeftTrigger, v) => self.zoom_out = v.clamp(0.0, 1.0),
+            Input::Moved(Axis::RightTrigger, v) => self.zoom_in = v.clamp(0.0, 1.0),
+            Input::Disconnected => *self = Sticks::default(),
+        }
+        None
+    }
+
+    /// How far to move the view for `seconds` of the sticks as they are, or
+    /// None if they are all at rest.
+    pub fn motion(&self, seconds: f64) -> Option<Motion> {
+        let seconds = seconds.clamp(0.0, LONGEST_STEP);
+        let live = |v: f64| if v.abs() < DEAD_ZONE { 0.0 } else { v };
+        let (x, y, zoom) = (live(self.x), live(self.y), live(self.zoom_in) - live(self.zoom_out));
+        if (x, y, zoom) == (0.0, 0.0, 0.0) {
+            return None;
+        }
+        Some(Motion { pan: (x * PAN_SPEED * seconds, y * PAN_SPEED * seconds), zoom: ZOOM_SPEED.powf(zoom * seconds) })
+    }
+}
@@ -0,0 +1,163 @@
+//! The Ctrl+P command palette: type to narrow down the list of commands,
+//! arrow keys to pick one, Enter to run it.  Commands that need an argument
+//! ask for it on the same line.
+
+use winit::event::VirtualKeyCode;
+use winit_input_helper::{TextChar, WinitInputHelper};
+
+use crate::commands::{Action, Command, PaletteEntry, PALETTE};
+use crate::overlay::{self, GLYPH_SIZE};
+
+const VISIBLE_ROWS: usize = 10;
+const MAX_COLUMNS: u32 = 48;
+
+type ParseArgument = fn(&str) -> Result<Command, String>;
+
+#[derive(Default)]
+pub struct Palette {
+    open: bool,
+    query: String,
+    selected: usize,
+    /// Set while asking for a command's argument
+    prompt: Option<(&'static str, ParseArgument)>,
+    error: Option<String>,
+}
+
+/// Scores `label` against `query` as a case insensitive subsequence, or
+/// `None` if it does not match.  Consecutive letters and letters at the
+/// start of a word score higher.
+fn fuzzy_score(query: &str, label: &str) -> Option<i32> {
+    let label: Vec<char> = label.chars().flat_map(char::to_lowercase).collect();
+    let mut score = 0;
+    let mut pos = 0;
+    let mut last: Option<usize> = None;
+    for q in query.chars().flat_map(char::to_lowercase).filter(|c| !c.is_whitespace()) {
+        let found = label[pos..].iter().position(|&c| c == q)? + pos;
+        score += 1;
+        if found > 0 && last == Some(found - 1) {
+            score += 3;
+        }
+        if found == 0 || !label[found - 1].is_alphanumeric() {
+            score += 2;
+        }
+        last = Some(found);
+        pos = found + 1;
+    }
+    Some(score)
+}
+
+impl Palette {
+    pub fn new() -> Self {
+        Palette::default()
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    pub fn open(&mut self) {
+        *self = Palette { open: true, ..Palette::default() };
+    }
+
+    pub fn close(&mut self) {
+        *self = Palette::default();
+    }
+
+    /// Entries matching the query, best first.
+    fn matches(&self) -> Vec<&'static PaletteEntry> {
+        let mut scored: Vec<(i32, usize)> = PALETTE.iter().enumerate()
+            .filter_map(|(i, entry)| fuzzy_score(&self.query, entry.label).map(|s| (s, i)))
+            .collect();
+        // Stable on the table order for equal scores
+        scored.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+        scored.into_iter().map(|(_, i)| &PALETTE[i]).collect()
+    }
+
+    /// Handles this frame's keyboard input while the palette is open and
+    /// returns the command to run, if one was chosen.
+    pub fn update(&mut self, input: &WinitInputHelper) -> Option<Command> {
+        if input.key_pressed(VirtualKeyCode::Escape) {
+            self.close();
+            return None;
+        }
+        for c in input.text() {
+            match c {
+                TextChar::Char(c) if !c.is_control() => self.query.push(c),
+                TextChar::Back => {
+                    self.query.pop();
+                }
+                TextChar::Char(_) => {}
+            }
+            self.selected = 0;
+            self.error = None;
+        }
+
+        if let Some((_, parse)) = self.prompt {
+            if input.key_pressed(VirtualKeyCode::Return) {
+                match parse(&self.query) {
+                    Ok(command) => {
+                        self.close();
+                        return Some(command);
+                    }
+                    Err(e) => self.error = Some(e),
+                }
+            }
+            return None;
+        }
+
+        let count = self.matches().len();
+        if input.key_pressed_os(VirtualKeyCode::Down) && count > 0 {
+            self.selected = (self.selected + 1) % count;
+        }
+        if input.key_pressed_os(VirtualKeyCode::Up) && count > 0 {
+            self.selected = (self.selected + count - 1) % count;
+        }
+        if input.key_pressed(VirtualKeyCode::Return) {
+            match self.matches().get(self.selected).map(|entry| &entry.action) {
+                Some(Action::Run(make)) => {
+                    self.close();
+                    return Some(make());
+                }
+                Some(Action::Prompt(label, parse)) => {
+                    self.prompt = Some((*label, *parse));
+                    self.query.clear();
+                }
+                None => {}
+            }
+        }
+        None
+    }
+
+    pub fn draw(&self, width: u32, height: u32, screen: &mut [u8]) {
+        if !self.open {
+            return;
+        }
+        let line = GLYPH_SIZE as i32 + 4;
+        let box_width = (MAX_COLUMNS * GLYPH_SIZE + 8).min(width);
+        let x = (width - box_width) as i32 / 2;
+        let mut y = 8;
+
+        let (input_line, rows) = match self.prompt {
+            Some((label, _)) => (format!("{}: {}_", label, self.query), Vec::new()),
+            None => (format!("> {}_", self.query), self.matches())
+        };
+        let row_count = rows.len().min(VISIBLE_ROWS) + 1 + self.error.is_some() as usize;
+        overlay::fill_rect(screen, width, height, (x, y, box_width, row_count as u32 * line as u32 + 4), [0, 0, 0, 220]);
+
+        // Keep the selection in view
+        let first = self.selected.saturating_sub(VISIBLE_ROWS - 1);
+        y += 4;
+        overlay::draw_text(screen, width, height, x + 4, y, &input_line, [255, 255, 255]);
+        for (i, entry) in rows.iter().enumerate().skip(first).take(VISIBLE_ROWS) {
+            y += line;
+            if i == self.selected {
+                overlay::fill_rect(screen, width, height, (x, y - 2, box_width, line as u32), [80, 80, 160, 255]);
+            }
+            overlay::draw_text(screen, width, height, x + 4, y, entry.label, [200, 200, 200]);
+        }
+        if let Some(e) = &self.error {
+            y += line;
+            overlay::draw_text(screen, width, height, x + 4, y, e, [255, 96, 96]);
+        }
+    }
+}
@@ -0,0 +1,201 @@
+//! Period detection by hashing successive states.  When a state's hash has
+//! been seen before, the universe (or the watched region) is repeating, and
+//! the distance between the two sightings is its period.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::hash::Hasher;
+
+use crate::engine::{BoundingBox, CellState, LifeEngine};
+use crate::events::GenerationEvent;
+use crate::script::SharedLife;
+
+/// How many generations back [`PeriodDetector`] remembers, and so the
+/// longest period it can find.
+pub const DEFAULT_WINDOW: usize = 1024;
+
+/// Hash of the live cells inside `region`, or the whole grid.
+pub fn hash(life: &dyn LifeEngine, region: Option<BoundingBox>) -> u64 {
+    let region = region.unwrap_or(BoundingBox { min_x: 0, min_y: 0, max_x: life.width().saturating_sub(1), max_y: life.height().saturating_sub(1) });
+    let mut hasher = DefaultHasher::new();
+    for y in region.min_y..=region.max_y.min(life.height().saturating_sub(1)) {
+        for x in region.min_x..=region.max_x.min(life.width().saturating_sub(1)) {
+            if life.get(x, y) == CellState::Alive {
+                hasher.write_usize(x);
+                hasher.write_usize(y);
+            }
+        }
+    }
+    hasher.finish()
+}
+
+pub struct PeriodDetector {
+    /// Last generation each remembered hash was seen at
+    seen: HashMap<u64, u64>,
+    order: VecDeque<u64>,
+    window: usize,
+    last: Option<u64>,
+}
+
+impl PeriodDetector {
+    pub fn new(window: usize) -> Self {
+        PeriodDetector { seen: HashMap::new(), order: VecDeque::new(), window: window.max(1), last: None }
+    }
+
+    pub fn clear(&mut self) {
+        self.seen.clear();
+        self.order.clear();
+        self.last = None;
+    }
+
+    /// Records the hash of generation `generation` and returns the period
+    /// if that state was seen within the window.  Going back to an earlier
+    /// generation, as when the universe is reset, starts over.
+    pub fn observe(&mut self, generation: u64, hash: u64) -> Option<u64> {
+        if self.last.is_some_and(|last| generation <= last) {
+            self.clear();
+        }
+        self.last = Some(generation);
+        let period = self.seen.insert(hash, generation).map(|before| generation - before);
+        self.order.push_back(hash);
+        if self.order.len() > self.window {
+            if let Some(old) = self.order.pop_front() {
+                // Only forget it if it wasn't seen again since
+                if self.seen.get(&old).is_some_and(|g| generation - g >= self.window as u64) {
+                    self.seen.remove(&old);
+                }
+            }
+        }
+        period
+    }
+}
+
+impl Default for PeriodDetector {
+    fn default() -> Self {
+        PeriodDetector::new(DEFAULT_WINDOW)
+    }
+}
+
+/// A cycle the whole universe has entered: from generation `start` on, it
+/// is back where it was every `period` generations.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    pub period: u64,
+    pub start: u64,
+}
+
+impl fmt::Display for Cycle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "universe entered a cycle of period {} at generation {}", self.period, self.start)
+    }
+}
+
+/// Watches the whole grid for a state it has been in before, which tells a
+/// universe that has truly settled from one still changing slowly.  Hashing
+/// a big grid takes a while, so it can be hashed only every `every`
+/// generations; a repeat seen that way only says the period divides the
+/// gap, so from then on every generation is hashed until the exact period
+/// shows.  The start is then the earliest sample known to be in the cycle,
+/// up to `every` generations late.  If it doesn't show within the gap, as
+/// when the universe is edited meanwhile, sampling starts over.
+pub struct CycleDetector {
+    every: u64,
+    samples: PeriodDetector,
+    // Every generation since a sampled repeat, the start it gave and the
+    // generation by which the period must have shown
+    exact: Option<(PeriodDetector, u64, u64)>,
+    cycle: Option<Cycle>,
+    last: Option<u64>,
+}
+
+impl CycleDetector {
+    pub fn new(every: u64) -> Self {
+        CycleDetector { every: every.max(1), samples: PeriodDetector::default(), exact: None, cycle: None, last: None }
+    }
+
+    /// The cycle the universe is in, if one has been found.
+    pub fn cycle(&self) -> Option<Cycle> {
+        self.cycle
+    }
+
+    pub fn clear(&mut self) {
+        self.samples.clear();
+        self.exact = None;
+        self.cycle = None;
+        self.last = None;
+    }
+
+    /// Hashes the grid after `event` if it is due, and returns the cycle if
+    /// the universe has just been found to have entered one.  An empty grid
+    /// repeats too, but that is extinction, so it isn't counted.  Going back
+    /// to an earlier generation starts over.
+    pub fn observe(&mut self, event: &GenerationEvent, life: &dyn LifeEngine) -> Option<Cycle> {
+        let generation = event.generation;
+        if self.last.is_some_and(|last| generation <= last) {
+            self.clear();
+        }
+        self.last = Some(generation);
+        if event.population == 0 {
+            self.exact = None;
+            self.cycle = None;
+            return None;
+        }
+        if let Some((exact, start, until)) = self.exact.as_mut() {
+            if let Some(period) = exact.observe(generation, hash(life, None)) {
+                let cycle = Cycle { period, start: *start };
+                self.exact = None;
+                self.cycle = Some(cycle);
+                return Some(cycle);
+            }
+            if generation < *until {
+                return None;
+            }
+            // The repeat didn't hold, so what was sampled before is no guide
+            self.exact = None;
+            self.samples.clear();
+        }
+        if !generation.is_multiple_of(self.every) {
+            return None;
+        }
+        let hash = hash(life, None);
+        let Some(gap) = self.samples.observe(generation, hash) else {
+            // Not back where it was, so out of any cycle it was in
+            self.cycle = None;
+            return None;
+        };
+        if self.cycle.is_some() {
+            return None;
+        }
+        if self.every == 1 {
+            let cycle = Cycle { period: gap, start: generation - gap };
+            self.cycle = Some(cycle);
+            return Some(cycle);
+        }
+        let mut exact = PeriodDetector::new(gap as usize + 1);
+        exact.observe(generation, hash);
+        self.exact = Some((exact, generation - gap, generation + gap));
+        None
+    }
+}
+
+/// Event listener logging when `life` enters a cycle, hashing it every
+/// `every` generations.
+pub fn reporter(life: SharedLife, every: u64) -> impl FnMut(&GenerationEvent) + Send {
+    let mut cycles = CycleDetector::new(every);
+    move |event| {
+        let Ok(l) = life.read() else { return };
+        if let Some(cycle) = cycles.observe(event, l.as_ref()) {
+            log::info!("The {}", cycle);
+        }
+    }
+}
+
+/// How a detected period reads in the status line.
+pub fn describe(period: Option<u64>) -> String {
+    match period {
+        Some(1) => "still life".to_string(),
+        Some(p) => format!("oscillator, period {}", p),
+        None => "no period yet".to_string()
+    }
+}
@@ -0,0 +1,138 @@
+//! Rule plugins loaded from shared libraries at startup.
+//!
+//! A plugin is a `cdylib` exporting one function:
+//!
+//! ```text
+//! #[no_mangle]
+//! pub extern "C" fn rustlife_rule_plugin() -> *const RulePluginDescriptor
+//! ```
+//!
+//! returning a pointer to a descriptor that lives as long as the library.
+//! `next_state` receives the current cell (0 dead, 1 alive) and the neighbour
+//! bitmask described on [`NeighborhoodRule`], and returns the new cell.
+//!
+//! ```text
+//! extern "C" fn next_state(alive: u8, neighbors: u8) -> u8 {
+//!     // Only the orthogonal neighbours count
+//!     let n = (neighbors & 0b0101_1010).count_ones();
+//!     (n == 2 || (alive == 1 && n == 1)) as u8
+//! }
+//!
+//! static DESCRIPTOR: RulePluginDescriptor = RulePluginDescriptor {
+//!     abi_version: PLUGIN_ABI_VERSION,
+//!     name: b"vonneumann2\0".as_ptr() as *const c_char,
+//!     next_state,
+//! };
+//! ```
+
+use std::collections::HashMap;
+use std::ffi::CStr;
+use std::os::raw::c_char;
+use std::path::Path;
+use std::sync::Arc;
+
+use libloading::{Library, Symbol};
+
+use crate::engine::CellState;
+use crate::rule::NeighborhoodRule;
+
+pub const PLUGIN_ABI_VERSION: u32 = 1;
+
+const ENTRY_POINT: &[u8] = b"rustlife_rule_plugin\0";
+
+#[repr(C)]
+pub struct RulePluginDescriptor {
+    pub abi_version: u32,
+    /// NUL terminated
+    pub name: *const c_char,
+    pub next_state: extern "C" fn(alive: u8, neighbors: u8) -> u8,
+}
+
+// Descriptors are immutable statics inside the plugin
+unsafe impl Sync for RulePluginDescriptor {}
+
+pub struct PluginRule {
+    name: String,
+    next_state: extern "C" fn(u8, u8) -> u8,
+    // Keeps `next_state` valid
+    _library: Library,
+}
+
+impl PluginRule {
+    /// Loads a rule plugin.
+    ///
+    /// # Safety
+    ///
+    /// This runs the library's initialisers and trusts that its entry point
+    /// has the signature and ABI documented in this module.
+    pub unsafe fn load(path: &Path) -> Result<PluginRule, String> {
+        let library = Library::new(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        let entry: Symbol<extern "C" fn() -> *const RulePluginDescriptor> = library
+            .get(ENTRY_POINT)
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+
+        let descriptor = entry();
+        if descriptor.is_null() {
+            return Err(format!("{}: plugin returned no descriptor", path.display()));
+        }
+        let descriptor = &*descriptor;
+        if descriptor.abi_version != PLUGIN_ABI_VERSION {
+            return Err(format!("{}: plugin ABI version {} but expected {}",
+                path.display(), descriptor.abi_version, PLUGIN_ABI_VERSION));
+        }
+        if descriptor.name.is_null() {
+            return Err(format!("{}: plugin has no name", path.display()));
+        }
+        let name = CStr::from_ptr(descriptor.name).to_string_lossy().into_owned();
+        let next_state = descriptor.next_state;
+
+        Ok(PluginRule { name, next_state, _library: library })
+    }
+}
+
+impl NeighborhoodRule for PluginRule {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn next(&self, cell_state: CellState, neighbors: u8) -> CellState {
+        let alive = (cell_state == CellState::Alive) as u8;
+        if (self.next_state)(alive, neighbors) != 0 { CellState::Alive } else { CellState::Dead }
+    }
+}
+
+/// Rules registered at startup, by name.
+#[derive(Default)]
+pub struct PluginRegistry {
+    rules: HashMap<String, Arc<dyn NeighborhoodRule>>,
+}
+
+impl PluginRegistry {
+    pub fn new() -> Self {
+        PluginRegistry::default()
+    }
+
+    /// Loads the plugin at `path` and registers it under its own name.
+    ///
+    /// # Safety
+    ///
+    /// See [`PluginRule::load`].
+    pub unsafe fn load(&mut self, path: &Path) -> Result<String, String> {
+        let rule = PluginRule::load(path)?;
+        let name = rule.name.clone();
+        self.rules.insert(name.clone(), Arc::new(rule));
+        Ok(name)
+    }
+
+    pub fn register(&mut self, rule: Arc<dyn NeighborhoodRule>) {
+        self.rules.insert(rule.name().to_string(), rule);
+    }
+
+    pub fn get(&self, name: &str) -> Option<Arc<dyn NeighborhoodRule>> {
+        self.rules.get(name).cloned()
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.rules.keys().map(String::as_str).collect()
+    }
+}
@@ -0,0 +1,74 @@
+//! The power saving profile, for laptops left running: fewer generations a
+//! second, fewer frames and fewer threads, and nothing stepped or drawn
+//! while the window is minimized or covered.  On by default when running on
+//! battery, where that can be told.
+
+use crate::cli::Args;
+use crate::config::Config;
+
+const SPEED: u32 = 15;
+const MAX_FPS: u32 = 15;
+const THREADS: usize = 2;
+
+/// Whether to save power: as `setting` says, or if unset when on battery.
+pub fn saving(setting: Option<bool>) -> bool {
+    setting.unwrap_or_else(|| {
+        let battery = on_battery();
+        if battery {
+            log::info!("On battery, saving power");
+        }
+        battery
+    })
+}
+
+/// The speed, frame rate and thread count a run starts with.  The profile
+/// lowers these rather than the config, so saving the config or the
+/// session on battery doesn't carry its limits over to later runs.
+#[derive(Clone, Copy, Debug)]
+pub struct Limits {
+    pub speed: u32,
+    pub max_fps: u32,
+    pub threads: usize,
+}
+
+impl Limits {
+    /// The config's, lowered to the profile's when `saving`, except those
+    /// set by a flag and any already lower.
+    pub fn new(config: &Config, args: &Args, saving: bool) -> Self {
+        let lower = |v: u32, most: u32, flag: Option<u32>| match flag {
+            None if saving && (v == 0 || v > most) => most,
+            _ => v
+        };
+        let threads = match args.threads {
+            None if saving && (config.threads == 0 || config.threads > THREADS) => THREADS,
+            _ => config.threads
+        };
+        Limits {
+            speed: lower(config.speed, SPEED, args.speed),
+            max_fps: lower(config.max_fps, MAX_FPS, args.max_fps),
+            threads,
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn on_battery() -> bool {
+    // Any battery running down; a full one on the charger says "Full" or
+    // "Not charging"
+    let Ok(supplies) = std::fs::read_dir("/sys/class/power_supply") else { return false };
+    supplies.flatten().any(|supply| {
+        let read = |name: &str| std::fs::read_to_string(supply.path().join(name)).map(|s| s.trim().to_string()).unwrap_or_default();
+        read("type") == "Battery" && read("status") == "Discharging"
+    })
+}
+
+#[cfg(target_os = "macos")]
+fn on_battery() -> bool {
+    std::process::Command::new("pmset").args(["-g", "batt"]).output()
+        .is_ok_and(|out| String::from_utf8_lossy(&out.stdout).contains("'Battery Power'"))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn on_battery() -> bool {
+    false
+}
@@ -0,0 +1,127 @@
+//! Looking for a predecessor of a small pattern: cells one generation
+//! earlier that step to exactly it inside its bounding box, whatever
+//! happens outside.  A parent can reach one cell past the box on every
+//! side, so for an n by n pattern it is looked for in the (n+2)² region
+//! around it.  Finding none shows nothing that size leads to the pattern,
+//! a step towards showing it is a Garden of Eden.
+//!
+//! The parent is put together a row at a time.  Every pair of rows the rows
+//! before them allow is kept, and each new row has to step the row above
+//! the pair to the pattern's; its cells are chosen left to right, each cell
+//! of the pattern checked as soon as the cells around it are known.
+
+use std::collections::HashMap;
+
+use crate::engine::CellState;
+use crate::io;
+use crate::rule::Rule;
+
+/// Widest and tallest pattern searched, which keeps the search to seconds.
+pub const MAX_SIDE: usize = 8;
+
+/// A parent of the `w` by `h` box holding the live cells of
+/// `pattern` under `rule`, or None if there is none within the bounds.  The
+/// parent's cells are relative to one cell up and left of the box's corner,
+/// so `pattern`'s (0, 0) is the parent's (1, 1).
+pub fn search(pattern: &[(usize, usize)], (w, h): (usize, usize), rule: Rule) -> Result<Option<Vec<(usize, usize)>>, String> {
+    if w > MAX_SIDE || h > MAX_SIDE {
+        return Err(format!("the pattern is {}x{}, at most {}x{} can be searched", w, h, MAX_SIDE, MAX_SIDE));
+    }
+    let (live_w, live_h) = io::extent(pattern);
+    if live_w > w || live_h > h {
+        return Err(format!("the pattern is {}x{}, bigger than its {}x{} box", live_w, live_h, w, h));
+    }
+    if w == 0 || h == 0 {
+        return Ok(Some(Vec::new()));
+    }
+    let mut target = vec![0u32; h];
+    for &(x, y) in pattern {
+        target[y] |= 1 << x;
+    }
+    let step = steps(rule);
+    let width = w + 2;
+
+    // levels[t] maps rows t + 1 and t + 2 of the parent, which step row
+    // t + 1 to the pattern's row t, to a row t that goes with them
+    let mut levels: Vec<HashMap<(u32, u32), u32>> = Vec::with_capacity(h);
+    for (t, &row) in target.iter().enumerate() {
+        let mut next = HashMap::new();
+        let mut extend = |above: u32, middle: u32| {
+            rows_below(above, middle, row, width, &step, &mut |below| {
+                next.entry((middle, below)).or_insert(above);
+            });
+        };
+        match levels.last() {
+            None => {
+                for above in 0..1u32 << width {
+                    for middle in 0..1u32 << width {
+                        extend(above, middle);
+                    }
+                }
+            }
+            Some(level) => {
+                for &(above, middle) in level.keys() {
+                    extend(above, middle);
+                }
+            }
+        }
+        if next.is_empty() {
+            log::debug!("No parent past row {}", t);
+            return Ok(None);
+        }
+        levels.push(next);
+    }
+
+    // Back up through the levels from any pair that made it to the end
+    let mut rows = Vec::with_capacity(h + 2);
+    let (&(mut middle, mut below), _) = levels[h - 1].iter().next().expect("the last level has a pair");
+    rows.extend([below, middle]);
+    for level in levels.iter().rev() {
+        let above = level[&(middle, below)];
+        rows.push(above);
+        (middle, below) = (above, middle);
+    }
+    rows.reverse();
+    let mut parent = Vec::new();
+    for (y, row) in rows.iter().enumerate() {
+        parent.extend((0..width).filter(|x| row >> x & 1 == 1).map(|x| (x, y)));
+    }
+    Ok(Some(parent))
+}
+
+/// Whether a cell lives on, by the 3x3 block around it: three bits from
+/// each of the rows above, through and below it, as `above << 6 | middle
+/// << 3 | below`.
+fn steps(rule: Rule) -> [bool; 512] {
+    let mut step = [false; 512];
+    for (block, lives) in step.iter_mut().enumerate() {
+        let centre = if block >> 4 & 1 == 1 { CellState::Alive } else { CellState::Dead };
+        let neighbours = (block & !(1 << 4)).count_ones() as usize;
+        *lives = rule.next(centre, neighbours) == CellState::Alive;
+    }
+    step
+}
+
+/// Calls `found` with every row that, below `above` and `middle`, steps
+/// `middle` to `target`, which is two cells narrower and starts one cell in.
+fn rows_below(above: u32, middle: u32, target: u32, width: usize, step: &[bool; 512], found: &mut impl FnMut(u32)) {
+    fn choose(x: usize, below: u32, rows: (u32, u32, u32), width: usize, step: &[bool; 512], found: &mut impl FnMut(u32)) {
+        if x == width {
+            found(below);
+            return;
+        }
+        let (above, middle, target) = rows;
+        for bit in [0, 1] {
+            let below = below | bit << x;
+            // The cell above the one before this is settled
+            if x >= 2 {
+                let block = (above >> (x - 2) & 7) << 6 | (middle >> (x - 2) & 7) << 3 | (below >> (x - 2) & 7);
+                if step[block as usize] != (target >> (x - 2) & 1 == 1) {
+                    continue;
+                }
+            }
+            choose(x + 1, below, rows, width, step, found);
+        }
+    }
+    choose(0, 0, (above, middle, target), width, step, found);
+}
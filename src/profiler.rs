@@ -0,0 +1,75 @@
+//! The profiler overlay, toggled with F5: how much of the last second went
+//! on each phase of stepping and drawing, as bars in the top right corner.
+//! The timing itself is [`rustlife::timing`].
+
+use std::time::Instant;
+
+use rustlife::timing::{Bound, Sampler, SharedTimings, Thread};
+
+use crate::overlay::{self, GLYPH_SIZE};
+
+const BAR_WIDTH: u32 = 120;
+const STEPPING: [u8; 4] = [96, 200, 255, 255];
+const DRAWING: [u8; 4] = [255, 180, 64, 255];
+
+/// The overlay, showing the last whole second's timings.
+pub struct Profiler {
+    sampler: Sampler,
+}
+
+impl Profiler {
+    pub fn new() -> Self {
+        Profiler { sampler: Sampler::default() }
+    }
+
+    /// The timings to add to, shared with the stepping thread.
+    pub fn timings(&self) -> SharedTimings {
+        self.sampler.timings()
+    }
+
+    pub fn is_on(&self) -> bool {
+        self.sampler.is_on()
+    }
+
+    pub fn toggle(&mut self) {
+        self.sampler.toggle();
+    }
+
+    /// Takes the timings once a second, and draws the last second's.
+    pub fn draw(&mut self, screen: &mut [u8], width: u32, height: u32) {
+        if !self.is_on() {
+            return;
+        }
+        self.sampler.sample(Instant::now());
+        let line = GLYPH_SIZE as i32 + 4;
+        let Some(breakdown) = self.sampler.shown() else {
+            let text = "profiling...";
+            let x = width as i32 - (text.len() as u32 * GLYPH_SIZE) as i32 - 8;
+            overlay::fill_rect(screen, width, height, (x - 4, 4, text.len() as u32 * GLYPH_SIZE + 8, line as u32 + 4), [0, 0, 0, 200]);
+            overlay::draw_text(screen, width, height, x, 8, text, [255, 255, 255]);
+            return;
+        };
+        let rows: Vec<_> = breakdown.rows.iter().map(|r| {
+            let color = if r.thread == Thread::Stepping { STEPPING } else { DRAWING };
+            (format!("{:<10} {:>6.2} ms x{:<4} {:>3.0}%", r.name, r.each, r.count, r.share * 100.0), r.share, color)
+        }).collect();
+        let verdict = match breakdown.bound() {
+            Bound::Neither => "neither thread is busy",
+            Bound::Simulation => "simulation-bound",
+            Bound::Render => "render-bound"
+        };
+        let verdict = format!("stepping {:.0}%, drawing {:.0}%: {}", breakdown.stepping * 100.0, breakdown.drawing * 100.0, verdict);
+
+        let columns = rows.iter().map(|(l, _, _)| l.chars().count()).max().unwrap_or(0) as u32;
+        let box_width = (columns * GLYPH_SIZE + BAR_WIDTH + 16).max(verdict.len() as u32 * GLYPH_SIZE + 8);
+        let x = width as i32 - box_width as i32 - 4;
+        overlay::fill_rect(screen, width, height, (x, 4, box_width, (rows.len() as u32 + 1) * line as u32 + 8), [0, 0, 0, 200]);
+        for (i, (text, share, color)) in rows.iter().enumerate() {
+            let y = 8 + i as i32 * line;
+            overlay::outline(screen, width, height, (x + 4, y, BAR_WIDTH, GLYPH_SIZE), [96, 96, 96, 255]);
+            overlay::fill_rect(screen, width, height, (x + 4, y, (share.min(1.0) * BAR_WIDTH as f64) as u32, GLYPH_SIZE), *color);
+            overlay::draw_text(screen, width, height, x + BAR_WIDTH as i32 + 12, y, text, [255, 255, 255]);
+        }
+        overlay::draw_text(screen, width, height, x + 4, 8 + rows.len() as i32 * line, &verdict, [255, 255, 255]);
+    }
+}
@@ -0,0 +1,305 @@
+//! A quadtree over the live cells, for questions about regions of the grid
+//! that shouldn't mean looking at every cell in them.  The grid is cut
+//! into 8x8 tiles, each kept as a `u64` with a bit a cell, and above them
+//! are levels of population counts for squares of 2x2 tiles, 4x4 tiles and
+//! so on up to the whole grid.  A query only goes down into squares that
+//! have something alive and are partly in the region, so on a sparse grid
+//! it takes time in proportion to the live cells near the region, not its
+//! area.
+//!
+//! The dense backend keeps one up to date as it steps and is edited; for
+//! the others [`index`] builds one when it is asked for.
+
+use std::borrow::Cow;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+#[cfg(not(target_arch = "wasm32"))]
+use rayon::prelude::*;
+
+use crate::engine::{BoundingBox, CellState, DenseEngine, LifeEngine};
+
+const TILE: usize = 8;
+
+/// A square of 2^level by 2^level tiles, by its column and row among the
+/// squares of its level.
+type Square = (usize, usize, usize);
+
+/// Live cells of a `width` by `height` grid, indexed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Quadtree {
+    width: usize,
+    height: usize,
+    /// Tiles across and down
+    across: usize,
+    down: usize,
+    tiles: Vec<u64>,
+    /// `levels[k - 1]` is the population of each square of level `k`, row
+    /// by row.  The last level is one square, the whole grid.
+    levels: Vec<Vec<usize>>,
+}
+
+impl Quadtree {
+    /// An empty grid's.
+    pub fn new(width: usize, height: usize) -> Self {
+        let (across, down) = (width.div_ceil(TILE), height.div_ceil(TILE));
+        let mut levels = Vec::new();
+        while across.div_ceil(1 << levels.len()) > 1 || down.div_ceil(1 << levels.len()) > 1 {
+            let k = levels.len() + 1;
+            levels.push(vec![0; across.div_ceil(1 << k) * down.div_ceil(1 << k)]);
+        }
+        Quadtree { width, height, across, down, tiles: vec![0; across * down], levels }
+    }
+
+    /// The index of a grid whose cell (x, y) is alive if `alive(x, y)`.
+    pub fn from_fn(width: usize, height: usize, alive: impl Fn(usize, usize) -> bool + Sync) -> Self {
+        let mut tree = Quadtree::new(width, height);
+        // Each row of tiles is filled in on its own, so they can be shared out
+        #[cfg(not(target_arch = "wasm32"))]
+        let rows = tree.tiles.par_chunks_mut(tree.across.max(1));
+        #[cfg(target_arch = "wasm32")]
+        let rows = tree.tiles.chunks_mut(tree.across.max(1));
+        rows.enumerate().for_each(|(ty, row)| {
+            for y in ty * TILE..((ty + 1) * TILE).min(height) {
+                for x in (0..width).filter(|&x| alive(x, y)) {
+                    row[x / TILE] |= bit(x, y);
+                }
+            }
+        });
+        for k in 1..=tree.levels.len() {
+            let (across, down) = tree.dims(k);
+            tree.levels[k - 1] = (0..across * down)
+                .map(|i| tree.children((k, i % across, i / across)).map(|s| tree.count(s)).sum())
+                .collect();
+        }
+        tree
+    }
+
+    /// The index of `life` as it is now.
+    pub fn of(life: &dyn LifeEngine) -> Self {
+        Quadtree::from_fn(life.width(), life.height(), |x, y| life.get(x, y) == CellState::Alive)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        self.tiles[(y / TILE) * self.across + x / TILE] & bit(x, y) != 0
+    }
+
+    /// Marks the cell at (x, y) alive or dead, keeping the counts above it
+    /// right.
+    pub fn set(&mut self, x: usize, y: usize, alive: bool) {
+        let (tx, ty) = (x / TILE, y / TILE);
+        let tile = &mut self.tiles[ty * self.across + tx];
+        if (*tile & bit(x, y) != 0) == alive {
+            return;
+        }
+        *tile ^= bit(x, y);
+        for k in 1..=self.levels.len() {
+            let across = self.dims(k).0;
+            let count = &mut self.levels[k - 1][(ty >> k) * across + (tx >> k)];
+            if alive {
+                *count += 1;
+            } else {
+                *count -= 1;
+            }
+        }
+    }
+
+    pub fn population(&self) -> usize {
+        self.root().map_or(0, |root| self.count(root))
+    }
+
+    /// Live cells inside `region`.
+    pub fn population_in(&self, region: BoundingBox) -> usize {
+        self.count_in(region, usize::MAX)
+    }
+
+    /// Whether nothing inside `region` is alive.
+    pub fn is_empty(&self, region: BoundingBox) -> bool {
+        self.count_in(region, 1) == 0
+    }
+
+    /// Counts the live cells in `region`, stopping once there are `limit`.
+    fn count_in(&self, region: BoundingBox, limit: usize) -> usize {
+        let mut total = 0;
+        let mut todo: Vec<Square> = self.root().into_iter().collect();
+        while let Some(square) = todo.pop() {
+            if total >= limit {
+                break;
+            }
+            let count = self.count(square);
+            let rect = self.rect(square);
+            match rect.intersection(region) {
+                _ if count == 0 => {}
+                None => {}
+                Some(inside) if inside == rect => total += count,
+                Some(inside) if square.0 == 0 => total += (self.tile(square) & mask(rect, inside)).count_ones() as usize,
+                Some(_) => todo.extend(self.children(square)),
+            }
+        }
+        total
+    }
+
+    /// Every live cell inside `region`, in reading order.
+    pub fn live_cells_in(&self, region: BoundingBox) -> Vec<(usize, usize)> {
+        let mut cells = Vec::new();
+        let mut todo: Vec<Square> = self.root().into_iter().collect();
+        while let Some(square) = todo.pop() {
+            let rect = self.rect(square);
+            let inside = match rect.intersection(region) {
+                Some(inside) if self.count(square) > 0 => inside,
+                _ => continue,
+            };
+            if square.0 > 0 {
+                todo.extend(self.children(square));
+                continue;
+            }
+            let mut bits = self.tile(square) & mask(rect, inside);
+            while bits != 0 {
+                let i = bits.trailing_zeros() as usize;
+                cells.push((rect.min_x + i % TILE, rect.min_y + i / TILE));
+                bits &= bits - 1;
+            }
+        }
+        cells.sort_unstable_by_key(|&(x, y)| (y, x));
+        cells
+    }
+
+    /// The live cell nearest (x, y), counting straight-line distance, or
+    /// None if nothing is alive.  Of cells equally near, the one furthest
+    /// left and then up.
+    pub fn nearest(&self, x: usize, y: usize) -> Option<(usize, usize)> {
+        // Squares sort before cells as near, so every cell at a distance is
+        // in the heap before the first of them comes out
+        #[derive(PartialEq, Eq, PartialOrd, Ord)]
+        enum Entry {
+            Square(Square),
+            Cell(usize, usize),
+        }
+        let mut heap = BinaryHeap::new();
+        heap.extend(self.root().map(|root| Reverse((0, Entry::Square(root)))));
+        while let Some(Reverse((_, entry))) = heap.pop() {
+            let square = match entry {
+                Entry::Cell(cx, cy) => return Some((cx, cy)),
+                Entry::Square(square) => square,
+            };
+            if square.0 > 0 {
+                for child in self.children(square).filter(|&s| self.count(s) > 0) {
+                    heap.push(Reverse((distance(self.rect(child), (x, y)), Entry::Square(child))));
+                }
+                continue;
+            }
+            let rect = self.rect(square);
+            let mut bits = self.tile(square);
+            while bits != 0 {
+                let i = bits.trailing_zeros() as usize;
+                let (cx, cy) = (rect.min_x + i % TILE, rect.min_y + i / TILE);
+                let cell = BoundingBox { min_x: cx, min_y: cy, max_x: cx, max_y: cy };
+                heap.push(Reverse((distance(cell, (x, y)), Entry::Cell(cx, cy))));
+                bits &= bits - 1;
+            }
+        }
+        None
+    }
+
+    /// Smallest box holding every live cell.
+    pub fn bounding_box(&self) -> Option<BoundingBox> {
+        let mut bb: Option<BoundingBox> = None;
+        let mut todo: Vec<Square> = self.root().into_iter().collect();
+        while let Some(square) = todo.pop() {
+            let rect = self.rect(square);
+            // Nothing in a square already inside the box can widen it
+            if self.count(square) == 0 || bb.is_some_and(|b| b.union(rect) == b) {
+                continue;
+            }
+            if square.0 > 0 {
+                todo.extend(self.children(square));
+                continue;
+            }
+            let tile = self.tile(square);
+            let columns = (0..TILE).fold(0u8, |columns, row| columns | (tile >> (row * TILE)) as u8);
+            let cells = BoundingBox {
+                min_x: rect.min_x + columns.trailing_zeros() as usize,
+                min_y: rect.min_y + tile.trailing_zeros() as usize / TILE,
+                max_x: rect.min_x + 7 - columns.leading_zeros() as usize,
+                max_y: rect.min_y + (63 - tile.leading_zeros() as usize) / TILE,
+            };
+            bb = Some(bb.map_or(cells, |b| b.union(cells)));
+        }
+        bb
+    }
+
+    /// Squares across and down at level `k`.
+    fn dims(&self, k: usize) -> (usize, usize) {
+        (self.across.div_ceil(1 << k), self.down.div_ceil(1 << k))
+    }
+
+    /// The square covering the whole grid, unless the grid has no cells.
+    fn root(&self) -> Option<Square> {
+        (!self.tiles.is_empty()).then_some((self.levels.len(), 0, 0))
+    }
+
+    fn tile(&self, (_, tx, ty): Square) -> u64 {
+        self.tiles[ty * self.across + tx]
+    }
+
+    fn count(&self, square: Square) -> usize {
+        match square {
+            (0, _, _) => self.tile(square).count_ones() as usize,
+            (k, sx, sy) => self.levels[k - 1][sy * self.dims(k).0 + sx],
+        }
+    }
+
+    /// The squares a level down making up `square`, those on the grid.
+    fn children(&self, (k, sx, sy): Square) -> impl Iterator<Item = Square> {
+        let (across, down) = self.dims(k - 1);
+        [(0, 0), (1, 0), (0, 1), (1, 1)]
+            .into_iter()
+            .map(move |(dx, dy)| (k - 1, 2 * sx + dx, 2 * sy + dy))
+            .filter(move |&(_, x, y)| x < across && y < down)
+    }
+
+    /// The cells `square` covers, cut off at the edges of the grid.
+    fn rect(&self, (k, sx, sy): Square) -> BoundingBox {
+        let side = TILE << k;
+        BoundingBox {
+            min_x: sx * side,
+            min_y: sy * side,
+            max_x: ((sx + 1) * side).min(self.width) - 1,
+            max_y: ((sy + 1) * side).min(self.height) - 1,
+        }
+    }
+}
+
+/// `life`'s own index, if its backend keeps one, or one built from it.
+pub fn index(life: &dyn LifeEngine) -> Cow<'_, Quadtree> {
+    match life.downcast_ref::<DenseEngine>() {
+        Some(dense) => Cow::Borrowed(dense.quadtree()),
+        None => Cow::Owned(Quadtree::of(life)),
+    }
+}
+
+/// The bit for the cell at (x, y) in its tile.
+fn bit(x: usize, y: usize) -> u64 {
+    1 << ((y % TILE) * TILE + x % TILE)
+}
+
+/// The bits of the tile covering `tile` that are inside `inside`.
+fn mask(tile: BoundingBox, inside: BoundingBox) -> u64 {
+    let row = ((1u64 << inside.width()) - 1) << (inside.min_x - tile.min_x);
+    (inside.min_y - tile.min_y..=inside.max_y - tile.min_y).fold(0, |mask, y| mask | row << (y * TILE))
+}
+
+/// Squared distance from (x, y) to the nearest cell of `rect`.
+fn distance(rect: BoundingBox, (x, y): (usize, usize)) -> u64 {
+    let gap = |v: usize, min: usize, max: usize| (min.saturating_sub(v) + v.saturating_sub(max)) as u64;
+    let (dx, dy) = (gap(x, rect.min_x, rect.max_x), gap(y, rect.min_y, rect.max_y));
+    dx * dx + dy * dy
+}
@@ -0,0 +1,270 @@
+//! Gray-Scott reaction-diffusion: two chemicals, U and V, spread over the
+//! grid, and V turns U into more of itself while both are fed and drained.
+//!
+//! ```text
+//! U' = U + Du ∇²U - UV² + feed (1 - U)
+//! V' = V + Dv ∇²V + UV² - (feed + kill) V
+//! ```
+//!
+//! The grid is continuous, but a cell counts as alive where V is above
+//! [`ALIVE_ABOVE`], so population and the other statistics still mean
+//! something.  Painting a live cell drops in V.
+
+use std::any::Any;
+use std::sync::Mutex;
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+#[cfg(not(target_arch = "wasm32"))]
+use rayon::prelude::*;
+
+use crate::engine::{Anchor, CellState, LifeEngine, StaleGuard};
+use crate::rule::Rule;
+
+/// Feed and kill rates that grow coral-like branches.
+pub const DEFAULT_FEED: f64 = 0.055;
+pub const DEFAULT_KILL: f64 = 0.062;
+
+/// Concentration of V above which a cell is alive.
+pub const ALIVE_ABOVE: f32 = 0.25;
+
+const DIFFUSE_U: f32 = 1.0;
+const DIFFUSE_V: f32 = 0.5;
+
+// Seeds dropped by `randomize` are SEED_SIZE square, one per SEED_SPACING
+// square at most
+const SEED_SIZE: usize = 6;
+const SEED_SPACING: usize = 32;
+
+// Population, then changed and born cells
+type Counts = (usize, usize, usize);
+
+pub struct ReactionEngine {
+    width: usize,
+    height: usize,
+    u: Vec<f32>,
+    v: Vec<f32>,
+    feed: f64,
+    kill: f64,
+    rule: Rule,
+    scratch: Mutex<(Vec<f32>, Vec<f32>)>,
+    population: usize,
+    changed: usize,
+    born: usize,
+    next_counts: Mutex<Counts>,
+    stale: StaleGuard,
+}
+
+impl ReactionEngine {
+    pub fn new(width: usize, height: usize) -> Self {
+        let cells = width * height;
+        ReactionEngine {
+            width,
+            height,
+            u: vec![1.0; cells],
+            v: vec![0.0; cells],
+            feed: DEFAULT_FEED,
+            kill: DEFAULT_KILL,
+            rule: Rule::default(),
+            scratch: Mutex::new((vec![1.0; cells], vec![0.0; cells])),
+            population: 0,
+            changed: 0,
+            born: 0,
+            next_counts: Mutex::new((0, 0, 0)),
+            stale: StaleGuard::default(),
+        }
+    }
+
+    /// Weighted sum of the neighbours minus the cell, 0.2 for each side and
+    /// 0.05 for each corner, with nothing beyond the edges.
+    fn laplacian(&self, grid: &[f32], x: usize, y: usize) -> f32 {
+        let mut sum = -grid[y * self.width + x];
+        for ny in y.saturating_sub(1)..(y + 2).min(self.height) {
+            for nx in x.saturating_sub(1)..(x + 2).min(self.width) {
+                let weight = match (nx == x, ny == y) {
+                    (true, true) => 0.0,
+                    (true, false) | (false, true) => 0.2,
+                    (false, false) => 0.05,
+                };
+                sum += weight * grid[ny * self.width + nx];
+            }
+        }
+        sum
+    }
+
+    fn set_chemicals(&mut self, x: usize, y: usize, u: f32, v: f32) {
+        let i = y * self.width + x;
+        match (self.v[i] > ALIVE_ABOVE, v > ALIVE_ABOVE) {
+            (false, true) => self.population += 1,
+            (true, false) => self.population -= 1,
+            _ => {}
+        }
+        self.u[i] = u;
+        self.v[i] = v;
+        self.stale.bump();
+    }
+
+    /// Feed and kill rates.
+    pub fn rates(&self) -> (f64, f64) {
+        (self.feed, self.kill)
+    }
+
+    pub fn set_rates(&mut self, feed: f64, kill: f64) {
+        self.feed = feed;
+        self.kill = kill;
+        self.stale.bump();
+    }
+}
+
+impl LifeEngine for ReactionEngine {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn name(&self) -> &'static str {
+        "reaction"
+    }
+
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    fn get(&self, x: usize, y: usize) -> CellState {
+        if self.v[y * self.width + x] > ALIVE_ABOVE { CellState::Alive } else { CellState::Dead }
+    }
+
+    fn set(&mut self, x: usize, y: usize, state: CellState) {
+        match state {
+            CellState::Alive => self.set_chemicals(x, y, 0.5, 0.5),
+            CellState::Dead => self.set_chemicals(x, y, 1.0, 0.0),
+        }
+    }
+
+    fn level(&self, x: usize, y: usize) -> f32 {
+        (self.v[y * self.width + x] * 2.5).min(1.0)
+    }
+
+    /// Kept for the other backends' sake; the reaction has no B/S rule.
+    fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    fn set_rule(&mut self, rule: Rule) {
+        self.rule = rule;
+    }
+
+    fn prepare_step(&self) {
+        let _span = tracing::info_span!("reaction.prepare_step").entered();
+        if let Ok(mut scratch) = self.scratch.lock() {
+            self.stale.mark_prepared();
+            let (next_u, next_v) = &mut *scratch;
+            let width = self.width.max(1);
+            let (feed, kill) = (self.feed as f32, self.kill as f32);
+
+            #[cfg(not(target_arch = "wasm32"))]
+            let rows = next_u.par_chunks_mut(width).zip(next_v.par_chunks_mut(width));
+            #[cfg(target_arch = "wasm32")]
+            let rows = next_u.chunks_mut(width).zip(next_v.chunks_mut(width));
+
+            rows.enumerate().for_each(|(y, (row_u, row_v))| {
+                for x in 0..row_u.len() {
+                    let i = y * self.width + x;
+                    let (u, v) = (self.u[i], self.v[i]);
+                    let reaction = u * v * v;
+                    row_u[x] = (u + DIFFUSE_U * self.laplacian(&self.u, x, y) - reaction + feed * (1.0 - u)).clamp(0.0, 1.0);
+                    row_v[x] = (v + DIFFUSE_V * self.laplacian(&self.v, x, y) + reaction - (feed + kill) * v).clamp(0.0, 1.0);
+                }
+            });
+
+            let mut counts: Counts = (0, 0, 0);
+            for (old, new) in self.v.iter().zip(next_v.iter()) {
+                let (was, is) = (*old > ALIVE_ABOVE, *new > ALIVE_ABOVE);
+                counts.0 += is as usize;
+                if was != is {
+                    counts.1 += 1;
+                    counts.2 += is as usize;
+                }
+            }
+            if let Ok(mut next) = self.next_counts.lock() {
+                *next = counts;
+            }
+        }
+    }
+
+    fn commit_step(&mut self) {
+        if self.stale.is_stale() {
+            self.prepare_step();
+        }
+        self.stale.committed();
+        if let (Ok(scratch), Ok(counts)) = (self.scratch.get_mut(), self.next_counts.get_mut()) {
+            std::mem::swap(&mut self.u, &mut scratch.0);
+            std::mem::swap(&mut self.v, &mut scratch.1);
+            (self.population, self.changed, self.born) = *counts;
+        }
+    }
+
+    fn changed_cells(&self) -> usize {
+        self.changed
+    }
+
+    fn born_cells(&self) -> usize {
+        self.born
+    }
+
+    fn resize(&mut self, width: usize, height: usize, anchor: Anchor) {
+        let (ox, oy) = anchor.offset((self.width, self.height), (width, height));
+        let (mut u, mut v) = (vec![1.0; width * height], vec![0.0; width * height]);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let (nx, ny) = (x as isize + ox, y as isize + oy);
+                if nx >= 0 && ny >= 0 && nx < width as isize && ny < height as isize {
+                    u[ny as usize * width + nx as usize] = self.u[y * self.width + x];
+                    v[ny as usize * width + nx as usize] = self.v[y * self.width + x];
+                }
+            }
+        }
+        self.population = v.iter().filter(|v| **v > ALIVE_ABOVE).count();
+        (self.u, self.v) = (u, v);
+        self.scratch = Mutex::new((vec![1.0; width * height], vec![0.0; width * height]));
+        (self.width, self.height) = (width, height);
+        self.changed = 0;
+        self.born = 0;
+        self.stale.bump();
+    }
+
+    /// A uniform soup of single cells just dissolves, so instead small
+    /// squares of V are dropped in, one in every [`SEED_SPACING`] square with
+    /// probability `density`.
+    fn randomize(&mut self, density: f64, seed: u64) {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        self.u.fill(1.0);
+        self.v.fill(0.0);
+        self.population = 0;
+        for ty in (0..self.height.saturating_sub(SEED_SIZE)).step_by(SEED_SPACING) {
+            for tx in (0..self.width.saturating_sub(SEED_SIZE)).step_by(SEED_SPACING) {
+                if !rng.gen_bool(density.clamp(0.0, 1.0)) {
+                    continue;
+                }
+                let (x0, y0) = (tx + rng.gen_range(0..SEED_SPACING - SEED_SIZE), ty + rng.gen_range(0..SEED_SPACING - SEED_SIZE));
+                for y in y0..(y0 + SEED_SIZE).min(self.height) {
+                    for x in x0..(x0 + SEED_SIZE).min(self.width) {
+                        self.set(x, y, CellState::Alive);
+                    }
+                }
+            }
+        }
+        self.stale.bump();
+    }
+
+    fn population(&self) -> usize {
+        self.population
+    }
+}
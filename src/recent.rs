@@ -0,0 +1,83 @@
+//! Pattern files loaded and patterns placed lately, newest first, kept in
+//! `recent.toml` next to the config file.  The pattern browser lists them
+//! first, and the recent key picks them up in turn.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use rustlife::io;
+use rustlife::library::{self, LibraryPattern};
+
+use crate::config::Config;
+
+const MAX_RECENT: usize = 10;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum RecentItem {
+    /// Loaded with Load pattern, read again when used so edits show up
+    File { path: String },
+    /// Placed from the browser or Fetch pattern, kept whole so it doesn't
+    /// need fetching again
+    Pattern { name: String, rle: String },
+}
+
+impl RecentItem {
+    pub fn pattern(pattern: &LibraryPattern) -> Self {
+        RecentItem::Pattern { name: pattern.name.clone(), rle: io::cells_to_rle(&pattern.cells) }
+    }
+
+    pub fn resolve(&self) -> Result<LibraryPattern, String> {
+        match self {
+            RecentItem::File { path } => library::load_file(Path::new(path)),
+            RecentItem::Pattern { name, rle } => io::parse_rle(rle)
+                .map(|cells| LibraryPattern { name: name.clone(), cells })
+                .map_err(|e| format!("{}: {}", name, e))
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct Recent {
+    items: Vec<RecentItem>,
+}
+
+impl Recent {
+    /// `recent.toml` in the config directory.
+    pub fn default_path() -> Option<PathBuf> {
+        Config::default_path()?.parent().map(|d| d.join("recent.toml"))
+    }
+
+    /// A missing or unreadable list just starts empty.
+    pub fn load(path: &Path) -> Recent {
+        match fs::read_to_string(path) {
+            Ok(text) => toml::from_str(&text).unwrap_or_else(|e| {
+                log::warn!("Ignoring recent patterns file {}: {}", path.display(), e);
+                Recent::default()
+            }),
+            Err(_) => Recent::default()
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let text = toml::to_string_pretty(self).map_err(|e| e.to_string())?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+        }
+        fs::write(path, text).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    pub fn items(&self) -> &[RecentItem] {
+        &self.items
+    }
+
+    /// Moves `item` to the front, dropping the oldest past [`MAX_RECENT`].
+    pub fn add(&mut self, item: RecentItem) {
+        self.items.retain(|i| *i != item);
+        self.items.insert(0, item);
+        self.items.truncate(MAX_RECENT);
+    }
+}
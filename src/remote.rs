@@ -0,0 +1,102 @@
+//! WebSocket remote control, built with `--features remote` and started with
+//! `--remote <addr>`.  Every text message is one JSON request and gets one
+//! JSON reply:
+//!
+//! | request                                              | reply                                  |
+//! |------------------------------------------------------|----------------------------------------|
+//! | `{"cmd": "pause"}`, `{"cmd": "resume"}`              | `{"ok": true}`                         |
+//! | `{"cmd": "step", "count": 10}`                       | `{"ok": true}`, pauses first           |
+//! | `{"cmd": "set", "x": 3, "y": 4, "alive": true}`      | `{"ok": true}`                         |
+//! | `{"cmd": "load_rle", "rle": "bo$2bo$3o!", "x": 10, "y": 10}` | `{"ok": true}`, centred without x/y |
+//! | `{"cmd": "population"}`                              | `{"ok": true, "generation": .., "population": ..}` |
+//!
+//! Failures reply `{"ok": false, "error": ".."}`.  Requests act on whichever
+//! slot is shown, and edits are recorded like edits made with the keyboard.
+
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::Ordering;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::thread;
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tungstenite::Message;
+
+use rustlife::io;
+
+use crate::commands::Command;
+use crate::slots::ActiveSlot;
+
+#[derive(Deserialize)]
+#[serde(tag = "cmd", rename_all = "snake_case")]
+enum Request {
+    Pause,
+    Resume,
+    Step {
+        #[serde(default = "one")]
+        count: u64,
+    },
+    Set { x: usize, y: usize, alive: bool },
+    LoadRle { rle: String, x: Option<usize>, y: Option<usize> },
+    Population,
+}
+
+fn one() -> u64 {
+    1
+}
+
+/// Listens on `addr` and returns the commands clients send, to be run by
+/// the event loop like any other.
+pub fn serve(addr: &str, active: ActiveSlot) -> Result<Receiver<Command>, String> {
+    let listener = TcpListener::bind(addr).map_err(|e| format!("{}: {}", addr, e))?;
+    log::info!("Remote control listening on ws://{}", addr);
+    let (tx, rx) = channel();
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let (tx, active) = (tx.clone(), active.clone());
+            thread::spawn(move || {
+                let peer = stream.peer_addr().map(|a| a.to_string()).unwrap_or_default();
+                if let Err(e) = client(stream, &tx, &active) {
+                    log::warn!("Remote client {}: {}", peer, e);
+                }
+            });
+        }
+    });
+    Ok(rx)
+}
+
+fn client(stream: TcpStream, commands: &Sender<Command>, active: &ActiveSlot) -> Result<(), String> {
+    let mut socket = tungstenite::accept(stream).map_err(|e| e.to_string())?;
+    loop {
+        let reply = match socket.read().map_err(|e| e.to_string())? {
+            Message::Text(text) => match handle(&text, commands, active) {
+                Ok(reply) => reply,
+                Err(e) => json!({ "ok": false, "error": e })
+            },
+            Message::Close(_) => return Ok(()),
+            _ => continue
+        };
+        socket.send(Message::Text(reply.to_string())).map_err(|e| e.to_string())?;
+    }
+}
+
+fn handle(text: &str, commands: &Sender<Command>, active: &ActiveSlot) -> Result<Value, String> {
+    let command = match serde_json::from_str::<Request>(text).map_err(|e| e.to_string())? {
+        Request::Pause => Command::SetPaused(true),
+        Request::Resume => Command::SetPaused(false),
+        Request::Step { count } => Command::Step(count),
+        Request::Set { x, y, alive } => Command::SetCell { x, y, alive },
+        Request::LoadRle { rle, x, y } => {
+            let pattern = io::cells_to_plaintext(&io::parse_rle(&rle)?);
+            Command::Paste { pattern, at: x.zip(y) }
+        }
+        Request::Population => {
+            let active = active.read().map_err(|_| "slot lock poisoned")?;
+            let population = active.0.read().map_err(|_| "universe lock poisoned")?.population();
+            let generation = active.1.load(Ordering::Relaxed);
+            return Ok(json!({ "ok": true, "generation": generation, "population": population }));
+        }
+    };
+    commands.send(command).map_err(|_| "simulation has stopped")?;
+    Ok(json!({ "ok": true }))
+}
@@ -0,0 +1,354 @@
+#[cfg(not(target_arch = "wasm32"))]
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use rustlife::diff::{Change, Reference};
+use rustlife::cyclic::CyclicEngine;
+use rustlife::forest::ForestEngine;
+use rustlife::sand::SandEngine;
+use rustlife::species::{Species, SpeciesEngine};
+use rustlife::stats::{Ages, Heatmap};
+use rustlife::{BoundingBox, CellState, DenseEngine, LifeEngine};
+
+/// Colours used to draw the grid, as RGB.
+#[derive(Clone, Copy, Debug)]
+pub struct Theme {
+    pub alive: [u8; 3],
+    pub dead: [u8; 3],
+    /// Heatmap colours, from cells never alive to cells always alive
+    pub heat: [[u8; 3]; 4],
+}
+
+/// Black through red and yellow to white.
+pub const HEAT: [[u8; 3]; 4] = [[0x00, 0x00, 0x00], [0xff, 0x00, 0x00], [0xff, 0xff, 0x00], [0xff, 0xff, 0xff]];
+
+impl Theme {
+    pub const NAMES: &'static [&'static str] = &["classic", "amber", "green", "paper"];
+
+    pub fn by_name(name: &str) -> Option<Theme> {
+        let (alive, dead) = match name {
+            "classic" => ([0xff, 0xff, 0xff], [0x00, 0x00, 0x00]),
+            "amber" => ([0xff, 0xb0, 0x00], [0x1a, 0x10, 0x00]),
+            "green" => ([0x33, 0xff, 0x66], [0x00, 0x14, 0x05]),
+            "paper" => ([0x20, 0x20, 0x20], [0xf4, 0xf0, 0xe6]),
+            _ => return None
+        };
+        Some(Theme { alive, dead, heat: HEAT })
+    }
+}
+
+/// The part of the grid on screen, in cells.  It may reach past the edges
+/// of the grid, which are drawn dead.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct View {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl View {
+    pub fn whole((grid_w, grid_h): (usize, usize)) -> Self {
+        View { x: 0.0, y: 0.0, width: grid_w.max(1) as f64, height: grid_h.max(1) as f64 }
+    }
+
+    /// Centres `bb` with a margin, widened in one direction so cells stay
+    /// the shape they are in the whole-grid view.
+    pub fn fit(bb: BoundingBox, grid: (usize, usize)) -> Self {
+        let whole = View::whole(grid);
+        let (w, h) = (bb.width() as f64 * 1.2 + 4.0, bb.height() as f64 * 1.2 + 4.0);
+        let scale = (w / whole.width).max(h / whole.height);
+        let (w, h) = (whole.width * scale, whole.height * scale);
+        let cx = bb.min_x as f64 + bb.width() as f64 / 2.0;
+        let cy = bb.min_y as f64 + bb.height() as f64 / 2.0;
+        View { x: cx - w / 2.0, y: cy - h / 2.0, width: w, height: h }
+    }
+
+    /// Moves `amount` (0 to 1) of the way to `target`.
+    pub fn approach(&mut self, target: View, amount: f64) {
+        self.x += (target.x - self.x) * amount;
+        self.y += (target.y - self.y) * amount;
+        self.width += (target.width - self.width) * amount;
+        self.height += (target.height - self.height) * amount;
+    }
+
+    /// Whether this is as good as `target`: every edge within a hundredth
+    /// of a cell of it, or a ten thousandth of its size when that is more.
+    pub fn is_near(&self, target: View) -> bool {
+        let tolerance = (target.width.max(target.height) / 10_000.0).max(0.01);
+        let close = |a: f64, b: f64| (a - b).abs() <= tolerance;
+        close(self.x, target.x) && close(self.y, target.y) && close(self.width, target.width) && close(self.height, target.height)
+    }
+
+    /// Screen pixel of the cell position (x, y).
+    pub fn screen_pos(&self, (x, y): (f64, f64), (width, height): (u32, u32)) -> (i32, i32) {
+        (((x - self.x) * width as f64 / self.width) as i32, ((y - self.y) * height as f64 / self.height) as i32)
+    }
+
+    /// Cell under the screen pixel, if it is on the grid.
+    pub fn cell_at(&self, (px, py): (usize, usize), (width, height): (u32, u32), (grid_w, grid_h): (usize, usize)) -> Option<(usize, usize)> {
+        let x = self.x + (px as f64 + 0.5) * self.width / width as f64;
+        let y = self.y + (py as f64 + 0.5) * self.height / height as f64;
+        (x >= 0.0 && y >= 0.0 && (x as usize) < grid_w && (y as usize) < grid_h).then_some((x as usize, y as usize))
+    }
+
+    /// Screen pixels per cell across.
+    pub fn scale(&self, width: u32) -> f64 {
+        width as f64 / self.width
+    }
+}
+
+// Walls holding cells alive, and holes holding them dead
+const WALL: [u8; 3] = [0x50, 0x78, 0xc8];
+const HOLE: [u8; 3] = [0x60, 0x18, 0x18];
+// Predators on a two-species backend; prey use the theme's live colour
+const PREDATOR: [u8; 3] = [0xff, 0x40, 0x30];
+// Past this many cells to a pixel, asking the backend's index whether they
+// are all dead is quicker than looking at each
+const SPARSE_CELLS: usize = 16;
+
+fn pixel_scale(cells_per_pixel: f64) -> Vec<(usize, f64)>
+{
+    let pixel_dim = cells_per_pixel.max(1.0);
+    let states_per_pixel_dim = pixel_dim.ceil() as u32;
+    let pixel_dim_weights: Vec<_> = (0..states_per_pixel_dim)
+        .map(|x| {
+            (pixel_dim - (x as f64))/ pixel_dim
+        })
+        .enumerate().collect();
+    pixel_dim_weights
+}
+
+/// The cell each column and row of pixels starts in, and how far past it
+/// a pixel reaches, worked out once for a window size and view rather than
+/// for every pixel of every frame.
+#[derive(Default)]
+pub struct PixelMap {
+    size: (u32, u32),
+    view: Option<View>,
+    columns: Vec<isize>,
+    rows: Vec<isize>,
+    column_weights: Vec<(usize, f64)>,
+    row_weights: Vec<(usize, f64)>,
+    normalization: f64,
+}
+
+impl PixelMap {
+    /// Rebuilds the map if the window was resized or the view moved.
+    pub fn update(&mut self, width: u32, height: u32, view: &View) {
+        if self.size == (width, height) && self.view.as_ref() == Some(view) {
+            return;
+        }
+        let (width_f, height_f) = (width as f64, height as f64);
+        self.columns = (0..width).map(|px| (view.x + px as f64 / width_f * view.width).floor() as isize).collect();
+        self.rows = (0..height).map(|py| (view.y + py as f64 / height_f * view.height).floor() as isize).collect();
+        self.column_weights = pixel_scale(view.width / width_f);
+        self.row_weights = pixel_scale(view.height / height_f);
+        self.normalization = 1.0 / ((self.column_weights.len() + self.row_weights.len()) as f64);
+        (self.size, self.view) = ((width, height), Some(*view));
+    }
+}
+
+/// Draws the grid as `map` lays it out over `screen`.
+pub fn draw(screen: &mut [u8], state: &dyn LifeEngine, theme: &Theme, map: &PixelMap) {
+    let width = map.size.0.max(1) as usize;
+    let pixel_width_weights = &map.column_weights;
+    let pixel_height_weights = &map.row_weights;
+    let normalization = map.normalization;
+    let species = state.downcast_ref::<SpeciesEngine>();
+    let own_colors = state.downcast_ref::<SandEngine>().is_some() || state.downcast_ref::<ForestEngine>().is_some() || state.downcast_ref::<CyclicEngine>().is_some();
+    let index = state.downcast_ref::<DenseEngine>().map(DenseEngine::quadtree).filter(|_| pixel_width_weights.len() * pixel_height_weights.len() > SPARSE_CELLS);
+
+    // Every pixel is worked out on its own, so they can be shared out
+    #[cfg(not(target_arch = "wasm32"))]
+    let pixels = screen.par_chunks_exact_mut(4);
+    #[cfg(target_arch = "wasm32")]
+    let pixels = screen.chunks_exact_mut(4);
+
+    pixels.enumerate().for_each(|(i, pix)| {
+       {
+            let x_id = map.columns[i % width];
+            let y_id = map.rows[i / width];
+            let mut coverage = 0.0;
+            let mut predators = 0.0;
+            let (last_x, last_y) = (x_id + pixel_width_weights.len() as isize - 1, y_id + pixel_height_weights.len() as isize - 1);
+            let empty = index.is_some_and(|index| {
+                last_x < 0 || last_y < 0 || index.is_empty(BoundingBox { min_x: x_id.max(0) as usize, min_y: y_id.max(0) as usize, max_x: last_x as usize, max_y: last_y as usize })
+            });
+
+            for (x_offset, x_weight) in pixel_width_weights.iter().filter(|_| !empty)
+            {
+                for(y_offset, y_weight) in pixel_height_weights {
+                    let (cx, cy) = (x_id + *x_offset as isize, y_id + *y_offset as isize);
+                    let inside = cx >= 0 && cy >= 0 && (cx as usize) < state.width() && (cy as usize) < state.height();
+                    let level = if inside { state.level(cx as usize, cy as usize) as f64 } else { 0.0 };
+                    if level > 0.0 {
+                        coverage += (x_weight + y_weight) * normalization * level;
+                        if species.is_some_and(|s| s.species(cx as usize, cy as usize) == Some(Species::Predator)) {
+                            predators += (x_weight + y_weight) * normalization;
+                        }
+                    }
+                }
+            }
+
+           let inside = x_id >= 0 && y_id >= 0 && (x_id as usize) < state.width() && (y_id as usize) < state.height();
+           // Sand, forests and cyclic automata are drawn in their own colours, nearest cell only
+           if own_colors {
+               let color = inside.then(|| state.color(x_id as usize, y_id as usize)).flatten();
+               let [r, g, b] = color.unwrap_or(theme.dead);
+               pix.copy_from_slice(&[r, g, b, 0xff]);
+               return;
+           }
+           if let Some(wall) = inside.then(|| state.wall(x_id as usize, y_id as usize)).flatten() {
+               let [r, g, b] = if wall == CellState::Alive { WALL } else { HOLE };
+               pix.copy_from_slice(&[r, g, b, 0xff]);
+               return;
+           }
+           // Cells the backend picks out, such as rule 110's gliders
+           if let Some([r, g, b]) = inside.then(|| state.color(x_id as usize, y_id as usize)).flatten() {
+               pix.copy_from_slice(&[r, g, b, 0xff]);
+               return;
+           }
+
+           // Blend between the theme's dead and alive colours
+           let coverage = f64::min(coverage, 1.0);
+           let predators = f64::min(predators, coverage);
+           let mut color = [0xff; 4];
+           for (i, c) in color.iter_mut().take(3).enumerate() {
+               let dead = theme.dead[i] as f64;
+               let alive = theme.alive[i] as f64;
+               let predator = PREDATOR[i] as f64;
+               *c = (dead + (alive - dead) * (coverage - predators) + (predator - dead) * predators) as u8;
+           }
+           pix.copy_from_slice(&color);
+
+        }
+    });
+}
+
+/// Colour for a cell alive `fraction` of the time, blended between the
+/// theme's heat colours.  A square root keeps rarely visited cells visible.
+fn heat(fraction: f64, stops: &[[u8; 3]; 4]) -> [u8; 3] {
+    let t = fraction.clamp(0.0, 1.0).sqrt() * 3.0;
+    let i = (t as usize).min(2);
+    let (from, to) = (stops[i], stops[i + 1]);
+    std::array::from_fn(|c| (from[c] as f64 + (to[c] as f64 - from[c] as f64) * (t - i as f64)) as u8)
+}
+
+/// Draws the heatmap, one sample per pixel.
+pub fn draw_heatmap(width: u32, height: u32, screen: &mut [u8], heatmap: &Heatmap, theme: &Theme, view: &View) {
+    #[cfg(not(target_arch = "wasm32"))]
+    let pixels = screen.par_chunks_exact_mut(4);
+    #[cfg(target_arch = "wasm32")]
+    let pixels = screen.chunks_exact_mut(4);
+
+    pixels.enumerate().for_each(|(i, pix)| {
+        let pixel = ((i as u32 % width) as usize, (i as u32 / width) as usize);
+        let fraction = view.cell_at(pixel, (width, height), (heatmap.width, heatmap.height))
+            .map_or(0.0, |(x, y)| heatmap.get(x, y));
+        let [r, g, b] = heat(fraction, &theme.heat);
+        pix.copy_from_slice(&[r, g, b, 0xff]);
+    });
+}
+
+// Colours of the cells born and the cells died since the reference
+const BORN: [u8; 3] = [0x40, 0xe0, 0x40];
+const DIED: [u8; 3] = [0xe0, 0x40, 0x40];
+
+/// Draws how the universe differs from `reference`: births and deaths
+/// stand out, and cells alive in both are drawn faintly.
+pub fn draw_diff(width: u32, height: u32, screen: &mut [u8], state: &dyn LifeEngine, reference: &Reference, theme: &Theme, view: &View) {
+    let faint: [u8; 3] = std::array::from_fn(|i| ((theme.dead[i] as u32 * 2 + theme.alive[i] as u32) / 3) as u8);
+    #[cfg(not(target_arch = "wasm32"))]
+    let pixels = screen.par_chunks_exact_mut(4);
+    #[cfg(target_arch = "wasm32")]
+    let pixels = screen.chunks_exact_mut(4);
+
+    pixels.enumerate().for_each(|(i, pix)| {
+        let pixel = ((i as u32 % width) as usize, (i as u32 / width) as usize);
+        let change = view.cell_at(pixel, (width, height), (state.width(), state.height()))
+            .map_or(Change::Dead, |(x, y)| reference.change(x, y, state.get(x, y)));
+        let [r, g, b] = match change {
+            Change::Dead => theme.dead,
+            Change::Alive => faint,
+            Change::Born => BORN,
+            Change::Died => DIED
+        };
+        pix.copy_from_slice(&[r, g, b, 0xff]);
+    });
+}
+
+// Cells this many generations old are drawn tallest and in this colour
+const OLDEST: u16 = 32;
+const OLD: [u8; 3] = [0xff, 0x60, 0x20];
+// Tallest block, in tile heights
+const RISE: f64 = 3.0;
+
+fn shade(color: [u8; 3], amount: f64) -> [u8; 4] {
+    let [r, g, b] = color.map(|c| (c as f64 * amount) as u8);
+    [r, g, b, 0xff]
+}
+
+/// Draws the live cells in view as blocks on an isometric floor, taller and
+/// warmer the longer they have been alive.  Cells are drawn back to front,
+/// so nearer blocks hide the ones behind them.
+pub fn draw_isometric(width: u32, height: u32, screen: &mut [u8], state: &dyn LifeEngine, ages: &Ages, theme: &Theme, view: &View) {
+    // Half a tile across and down, in pixels
+    let across = view.width + view.height;
+    let half_w = (width as f64 / across).min(height as f64 / (across / 2.0 + RISE));
+    let half_h = half_w / 2.0;
+    let origin_x = width as f64 / 2.0 - (view.width - view.height) * half_w / 2.0;
+    let origin_y = (height as f64 - (across + RISE) * half_h) / 2.0 + RISE * half_h;
+    let project = |u: f64, v: f64| (origin_x + (u - v) * half_w, origin_y + (u + v) * half_h);
+
+    // The floor, so empty parts of the grid can be told from beyond it
+    let floor: [u8; 3] = std::array::from_fn(|i| ((theme.dead[i] as f64 * 7.0 + theme.alive[i] as f64) / 8.0) as u8);
+    let (grid_w, grid_h) = (state.width() as f64, state.height() as f64);
+    for (i, pix) in screen.chunks_exact_mut(4).enumerate() {
+        let across = ((i as u32 % width) as f64 + 0.5 - origin_x) / half_w;
+        let down = ((i as u32 / width) as f64 + 0.5 - origin_y) / half_h;
+        let (x, y) = ((down + across) / 2.0 + view.x, (down - across) / 2.0 + view.y);
+        let on_grid = x >= 0.0 && y >= 0.0 && x < grid_w && y < grid_h;
+        let [r, g, b] = if on_grid { floor } else { theme.dead };
+        pix.copy_from_slice(&[r, g, b, 0xff]);
+    }
+
+    let mut fill = |x: i64, top: f64, bottom: f64, color: [u8; 4]| {
+        if x < 0 || x >= width as i64 {
+            return;
+        }
+        let (top, bottom) = (top.round().max(0.0) as i64, bottom.round().min(height as f64) as i64);
+        for y in top..bottom {
+            let i = (y as usize * width as usize + x as usize) * 4;
+            screen[i..i + 4].copy_from_slice(&color);
+        }
+    };
+
+    let x_range = view.x.floor().max(0.0) as usize..((view.x + view.width).ceil().max(0.0) as usize).min(state.width());
+    let y_range = view.y.floor().max(0.0) as usize..((view.y + view.height).ceil().max(0.0) as usize).min(state.height());
+    for y in y_range {
+        for x in x_range.clone() {
+            let level = state.level(x, y) as f64;
+            if level <= 0.0 {
+                continue;
+            }
+            let age = ages.get(x, y).clamp(1, OLDEST) as f64 / OLDEST as f64;
+            let top: [u8; 3] = std::array::from_fn(|i| {
+                let young = theme.dead[i] as f64 + (theme.alive[i] as f64 - theme.dead[i] as f64) * level.min(1.0);
+                (young + (OLD[i] as f64 - young) * age) as u8
+            });
+            let rise = half_h * (0.5 + (RISE - 0.5) * age);
+            let (cx, cy) = project(x as f64 - view.x + 0.5, y as f64 - view.y + 0.5);
+            let first = (cx - half_w).floor() as i64;
+            for px in first..=((cx + half_w).ceil() as i64 - 1).max(first) {
+                let dx = px as f64 + 0.5 - cx;
+                let half = half_h * (1.0 - dx.abs() / half_w).max(0.0);
+                fill(px, cy - rise - half, cy - rise + half, shade(top, 1.0));
+                fill(px, cy - rise + half, cy + half, shade(top, if dx < 0.0 { 0.75 } else { 0.5 }));
+                // Zoomed far out a block is less than a pixel, but still shown
+                if (cy + half).round() <= (cy - rise - half).round() {
+                    fill(px, cy - rise, cy - rise + 1.0, shade(top, 1.0));
+                }
+            }
+        }
+    }
+}
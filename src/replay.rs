@@ -0,0 +1,383 @@
+//! Session recording and playback.  A replay holds everything needed to
+//! rebuild the starting universe plus every edit the user made, stamped with
+//! the generation it was made after, so playing it back reproduces the run
+//! exactly regardless of how fast either machine steps.
+
+use std::fs;
+use std::path::Path;
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+
+use crate::builder::SimulationBuilder;
+use crate::cyclic::CyclicEngine;
+use crate::elementary::{self, ElementaryEngine};
+use crate::engine::{Anchor, Boundary, CellState, DenseEngine, LifeEngine};
+use crate::forest::ForestEngine;
+use crate::gas::{GasEngine, GasModel};
+use crate::io;
+use crate::ising::IsingEngine;
+use crate::noise::Noise;
+use crate::reaction::ReactionEngine;
+use crate::rule::Rule;
+use crate::sand::{Element, SandEngine};
+use crate::soup::{self, Layout, Symmetry};
+use crate::species::{Species, SpeciesEngine};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Edit {
+    Paint { x: usize, y: usize, alive: bool },
+    /// Holds the cell alive or dead for good, or frees it with `None`
+    Wall { x: usize, y: usize, alive: Option<bool> },
+    /// Makes the cell a live one of `species`
+    Species { x: usize, y: usize, species: Species },
+    /// Puts an element on a backend made of them
+    Element { x: usize, y: usize, element: Element },
+    /// Plaintext pattern stamped with its top left corner at (x, y)
+    Paste { x: usize, y: usize, pattern: String },
+    Randomize {
+        density: f64,
+        seed: u64,
+        #[serde(default)]
+        symmetry: Symmetry,
+        /// Side of the soup, 0 for the whole grid
+        #[serde(default)]
+        size: usize,
+        #[serde(default)]
+        layout: Layout,
+    },
+    Clear,
+    Rule { rule: String },
+    /// Feed and kill rates, on the reaction backend
+    Reaction { feed: f64, kill: f64 },
+    /// Growth and lightning chances, on the forest backend
+    Forest { growth: f64, lightning: f64 },
+    /// States and threshold, on the cyclic backend
+    Cyclic { states: u8, threshold: usize },
+    /// Temperature and external field, on the ising backend
+    Ising { temperature: f64, field: f64 },
+    /// Lattice gas model, on the gas backend
+    Gas { model: GasModel },
+    /// Wolfram code, on the elementary backend
+    Elementary { rule: u8 },
+    /// Restarts the elementary backend from a rule 110 tape
+    Tape { bits: String },
+    Resize { width: usize, height: usize, anchor: Anchor },
+    /// Goes back along the timeline to `generation`: every cell, as RLE,
+    /// and the rule and parameters are put back as they were then
+    Seek { generation: u64, cells: String, settings: Settings },
+    /// Noise from here on, drawn from `seed`.  It isn't applied to the
+    /// universe directly: a [`Player`] flips cells with it after each step.
+    Noise { probability: f64, seed: u64 },
+}
+
+impl Edit {
+    pub fn apply(&self, life: &mut dyn LifeEngine) -> Result<(), String> {
+        match self {
+            Edit::Paint { x, y, alive } => {
+                if *x < life.width() && *y < life.height() {
+                    life.set(*x, *y, if *alive { CellState::Alive } else { CellState::Dead });
+                }
+            }
+            Edit::Wall { x, y, alive } => {
+                let wall = alive.map(|a| if a { CellState::Alive } else { CellState::Dead });
+                if *x < life.width() && *y < life.height() && !life.set_wall(*x, *y, wall) {
+                    return Err(format!("the {} backend has no walls", life.name()));
+                }
+            }
+            Edit::Species { x, y, species } => {
+                if *x < life.width() && *y < life.height() {
+                    backend::<SpeciesEngine>(life, "has only one species")?.set_species(*x, *y, *species);
+                }
+            }
+            Edit::Element { x, y, element } => {
+                if *x < life.width() && *y < life.height() {
+                    backend::<SandEngine>(life, "has no elements")?.set_element(*x, *y, *element);
+                }
+            }
+            Edit::Paste { x, y, pattern } => io::place(life, &io::parse_plaintext(pattern), *x, *y),
+            Edit::Randomize { density, seed, symmetry, size, layout } => soup::fill(life, layout, *symmetry, *size, *density, *seed),
+            Edit::Clear => life.clear(),
+            Edit::Rule { rule } => life.set_rule(rule.parse::<Rule>()?),
+            Edit::Reaction { feed, kill } => backend::<ReactionEngine>(life, "has no feed and kill rates")?.set_rates(*feed, *kill),
+            Edit::Forest { growth, lightning } => backend::<ForestEngine>(life, "has no growth and lightning chances")?.set_chances(*growth, *lightning),
+            Edit::Cyclic { states, threshold } => backend::<CyclicEngine>(life, "isn't cyclic")?.set_params(*states, *threshold),
+            Edit::Ising { temperature, field } => backend::<IsingEngine>(life, "has no temperature or field")?.set_params(*temperature, *field),
+            Edit::Gas { model } => backend::<GasEngine>(life, "isn't a lattice gas")?.set_model(*model),
+            Edit::Elementary { rule } => backend::<ElementaryEngine>(life, "isn't one-dimensional")?.set_code(*rule),
+            Edit::Tape { bits } => {
+                elementary::load_tape(life, bits)?;
+            }
+            Edit::Resize { width, height, anchor } => life.resize(*width, *height, *anchor),
+            Edit::Seek { cells, settings, .. } => {
+                life.clear();
+                io::place(life, &io::parse_rle(cells)?, 0, 0);
+                settings.apply(life);
+            }
+            Edit::Noise { .. } => {}
+        }
+        Ok(())
+    }
+}
+
+/// `life` as the backend an edit needs, or an error saying what it `lacks`.
+fn backend<'a, T: LifeEngine + 'static>(life: &'a mut dyn LifeEngine, lacks: &str) -> Result<&'a mut T, String> {
+    let name = life.name();
+    life.downcast_mut().ok_or_else(|| format!("the {} backend {}", name, lacks))
+}
+
+/// A universe's rule and its backend's parameters, which its cells don't
+/// say.  Those the backend doesn't have are `None`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Settings {
+    pub rule: Rule,
+    #[serde(default)]
+    pub reaction: Option<(f64, f64)>,
+    #[serde(default)]
+    pub forest: Option<(f64, f64)>,
+    #[serde(default)]
+    pub cyclic: Option<(u8, usize)>,
+    #[serde(default)]
+    pub ising: Option<(f64, f64)>,
+    #[serde(default)]
+    pub gas: Option<GasModel>,
+    #[serde(default)]
+    pub elementary: Option<u8>,
+    #[serde(default)]
+    pub boundary: Option<Boundary>,
+}
+
+impl Settings {
+    pub fn of(life: &dyn LifeEngine) -> Self {
+        Settings {
+            rule: life.rule(),
+            reaction: life.downcast_ref::<ReactionEngine>().map(ReactionEngine::rates),
+            forest: life.downcast_ref::<ForestEngine>().map(ForestEngine::stats).map(|f| (f.growth, f.lightning)),
+            cyclic: life.downcast_ref::<CyclicEngine>().map(CyclicEngine::params),
+            ising: life.downcast_ref::<IsingEngine>().map(IsingEngine::stats).map(|i| (i.temperature, i.field)),
+            gas: life.downcast_ref::<GasEngine>().map(|g| g.stats().model),
+            elementary: life.downcast_ref::<ElementaryEngine>().map(ElementaryEngine::code),
+            boundary: life.downcast_ref::<DenseEngine>().map(|d| d.state().boundary),
+        }
+    }
+
+    /// Puts them back into `life`, which should be on the same backend.
+    pub fn apply(&self, life: &mut dyn LifeEngine) {
+        life.set_rule(self.rule);
+        if let (Some((feed, kill)), Some(r)) = (self.reaction, life.downcast_mut::<ReactionEngine>()) {
+            r.set_rates(feed, kill);
+        }
+        if let (Some((growth, lightning)), Some(f)) = (self.forest, life.downcast_mut::<ForestEngine>()) {
+            f.set_chances(growth, lightning);
+        }
+        if let (Some((states, threshold)), Some(c)) = (self.cyclic, life.downcast_mut::<CyclicEngine>()) {
+            c.set_params(states, threshold);
+        }
+        if let (Some((temperature, field)), Some(i)) = (self.ising, life.downcast_mut::<IsingEngine>()) {
+            i.set_params(temperature, field);
+        }
+        if let (Some(model), Some(g)) = (self.gas, life.downcast_mut::<GasEngine>()) {
+            g.set_model(model);
+        }
+        if let (Some(rule), Some(e)) = (self.elementary, life.downcast_mut::<ElementaryEngine>()) {
+            e.set_code(rule);
+        }
+        if let (Some(boundary), Some(d)) = (self.boundary, life.downcast_mut::<DenseEngine>()) {
+            d.set_boundary(boundary);
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ReplayEvent {
+    /// The edit was applied after this many generations had been stepped
+    pub generation: u64,
+    /// Wall clock time since recording started, for reference only
+    pub millis: u64,
+    pub edit: Edit,
+}
+
+/// How the recorded run started.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Start {
+    Soup {
+        density: f64,
+        seed: u64,
+        #[serde(default)]
+        symmetry: Symmetry,
+        #[serde(default)]
+        size: usize,
+        #[serde(default)]
+        layout: Layout,
+    },
+    Pattern { x: usize, y: usize, pattern: String },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Replay {
+    pub backend: String,
+    pub width: usize,
+    pub height: usize,
+    pub rule: Rule,
+    /// Feed and kill rates, on the reaction backend
+    #[serde(default)]
+    pub reaction: Option<(f64, f64)>,
+    /// Growth and lightning chances, on the forest backend
+    #[serde(default)]
+    pub forest: Option<(f64, f64)>,
+    /// States and threshold, on the cyclic backend
+    #[serde(default)]
+    pub cyclic: Option<(u8, usize)>,
+    /// Temperature and external field, on the ising backend
+    #[serde(default)]
+    pub ising: Option<(f64, f64)>,
+    /// Lattice gas model, on the gas backend
+    #[serde(default)]
+    pub gas: Option<GasModel>,
+    /// Wolfram code, on the elementary backend
+    #[serde(default)]
+    pub elementary: Option<u8>,
+    /// What lies past the edges, on the dense backend
+    #[serde(default)]
+    pub boundary: Option<Boundary>,
+    pub start: Start,
+    pub events: Vec<ReplayEvent>,
+}
+
+impl Replay {
+    pub fn load(path: &Path) -> Result<Replay, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        serde_json::from_str(&text).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let text = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        fs::write(path, text).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// Rebuilds the universe as it was when recording started.
+    pub fn initial_universe(&self) -> Result<Box<dyn LifeEngine>, String> {
+        let mut builder = SimulationBuilder::new(self.width, self.height)
+            .backend(&self.backend)
+            .rule(self.rule)
+            .start(self.start.clone());
+        if let Some((feed, kill)) = self.reaction {
+            builder = builder.reaction(feed, kill);
+        }
+        if let Some((growth, lightning)) = self.forest {
+            builder = builder.forest(growth, lightning);
+        }
+        if let Some((states, threshold)) = self.cyclic {
+            builder = builder.cyclic(states, threshold);
+        }
+        if let Some((temperature, field)) = self.ising {
+            builder = builder.ising(temperature, field);
+        }
+        if let Some(model) = self.gas {
+            builder = builder.gas(model);
+        }
+        if let Some(rule) = self.elementary {
+            builder = builder.elementary(rule);
+        }
+        if let Some(boundary) = self.boundary {
+            builder = builder.boundary(boundary);
+        }
+        builder.build()
+    }
+}
+
+pub struct Recorder {
+    replay: Replay,
+    started: Instant,
+    /// The replay's generation and the universe's at the last `jump`
+    base: (u64, u64),
+}
+
+impl Recorder {
+    /// Starts recording from the current state of `life`, which must match
+    /// `start`.
+    pub fn new(life: &dyn LifeEngine, start: Start) -> Self {
+        let Settings { rule, reaction, forest, cyclic, ising, gas, elementary, boundary } = Settings::of(life);
+        let replay = Replay {
+            backend: life.name().to_string(),
+            width: life.width(),
+            height: life.height(),
+            rule,
+            reaction,
+            forest,
+            cyclic,
+            ising,
+            gas,
+            elementary,
+            boundary,
+            start,
+            events: Vec::new(),
+        };
+        Recorder { replay, started: Instant::now(), base: (0, 0) }
+    }
+
+    /// The universe's generation count jumps from `from` to `to`, as when
+    /// it is replaced or gone back along the timeline.  The replay counts
+    /// on from `from` all the same, so edits made from then on stay after
+    /// everything recorded so far.
+    pub fn jump(&mut self, from: u64, to: u64) {
+        self.base = (self.replay_generation(from), to);
+    }
+
+    /// Records `edit`, made after the universe's `generation`th generation.
+    pub fn record(&mut self, generation: u64, edit: Edit) {
+        let millis = self.started.elapsed().as_millis() as u64;
+        self.replay.events.push(ReplayEvent { generation: self.replay_generation(generation), millis, edit });
+    }
+
+    /// The replay's count at the universe's `generation`.
+    fn replay_generation(&self, generation: u64) -> u64 {
+        self.base.0 + generation.saturating_sub(self.base.1)
+    }
+
+    pub fn replay(&self) -> &Replay {
+        &self.replay
+    }
+}
+
+/// Feeds recorded edits back in as the generations they belong to come up.
+pub struct Player {
+    events: Vec<ReplayEvent>,
+    next: usize,
+    noise: Noise,
+}
+
+impl Player {
+    pub fn new(replay: &Replay) -> Self {
+        let mut events = replay.events.clone();
+        events.sort_by_key(|e| e.generation);
+        Player { events, next: 0, noise: Noise::off() }
+    }
+
+    /// Applies every edit made after `generation` generations.  Call it
+    /// before stepping generation `generation + 1`.
+    pub fn apply_due(&mut self, generation: u64, life: &mut dyn LifeEngine) -> Result<(), String> {
+        while let Some(event) = self.events.get(self.next) {
+            if event.generation > generation {
+                break;
+            }
+            match event.edit {
+                Edit::Noise { probability, seed } => self.noise = Noise::new(probability, seed),
+                _ => event.edit.apply(life)?,
+            }
+            self.next += 1;
+        }
+        Ok(())
+    }
+
+    /// Flips cells with the recorded noise.  Call it after each step.
+    pub fn inject_noise(&mut self, life: &mut dyn LifeEngine) -> usize {
+        self.noise.inject(life)
+    }
+
+    pub fn finished(&self) -> bool {
+        self.next >= self.events.len()
+    }
+}
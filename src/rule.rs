@@ -0,0 +1,4 @@
+//! Rules live in `rustlife-core`, which builds without the standard library;
+//! they are re-exported here so the rest of the crate needn't care.
+
+pub use rustlife_core::{NeighborhoodRule, Rule};
@@ -0,0 +1,75 @@
+//! The ruler: two cells clicked on the grid, with the distance between
+//! them and, when the run went on between the clicks, the speed of
+//! something that moved from one to the other.  Until the second click the
+//! mouse stands in for it.
+
+use rustlife::apgcode::Motion;
+use rustlife::ships;
+
+use crate::overlay;
+use crate::render::View;
+
+const COLOR: [u8; 3] = [255, 160, 255];
+
+/// A cell clicked and the generation it was clicked at.
+type Point = ((usize, usize), u64);
+
+#[derive(Default)]
+pub struct Ruler {
+    from: Option<Point>,
+    to: Option<Point>,
+}
+
+impl Ruler {
+    /// Sets the first end, then the second, then starts again.
+    pub fn click(&mut self, cell: (usize, usize), generation: u64) {
+        *self = match (self.from, self.to) {
+            (Some(from), None) => Ruler { from: Some(from), to: Some((cell, generation)) },
+            _ => Ruler { from: Some((cell, generation)), to: None }
+        };
+    }
+
+    /// The two ends, with the mouse at `hovered` standing in for the second
+    /// until it is clicked.
+    fn ends(&self, hovered: Option<(usize, usize)>, generation: u64) -> Option<(Point, Point)> {
+        Some((self.from?, self.to.or(hovered.map(|cell| (cell, generation)))?))
+    }
+
+    /// What the ruler measures, as lines for the status panel.
+    pub fn status(&self, hovered: Option<(usize, usize)>, generation: u64) -> Vec<String> {
+        let (((x0, y0), g0), ((x1, y1), g1)) = match self.ends(hovered, generation) {
+            Some(ends) => ends,
+            None => return vec!["ruler: click a cell to measure from".to_string()]
+        };
+        let (dx, dy) = (x1 as isize - x0 as isize, y1 as isize - y0 as isize);
+        let length = ((dx * dx + dy * dy) as f64).sqrt();
+        let steps = dx.unsigned_abs().max(dy.unsigned_abs());
+        let mut lines = vec![format!("ruler {},{} to {},{}: dx {}, dy {}, {:.2} cells, {} steps", x0, y0, x1, y1, dx, dy, length, steps)];
+        let period = g1.abs_diff(g0) as usize;
+        if period > 0 && steps > 0 {
+            let speed = ships::speed(Motion { period, dx, dy });
+            lines.push(format!("over {} generations: {}, {:.3} cells per generation", period, speed, steps as f64 / period as f64));
+        }
+        lines
+    }
+
+    pub fn draw(&self, screen: &mut [u8], width: u32, height: u32, view: &View, hovered: Option<(usize, usize)>, generation: u64) {
+        let centre = |(x, y): (usize, usize)| view.screen_pos((x as f64 + 0.5, y as f64 + 0.5), (width, height));
+        let mark = |screen: &mut [u8], (x, y): (i32, i32)| {
+            overlay::outline(screen, width, height, (x - 3, y - 3, 7, 7), [COLOR[0], COLOR[1], COLOR[2], 255]);
+        };
+        match self.ends(hovered, generation) {
+            Some(((from, _), (to, _))) => {
+                let (from, to) = (centre(from), centre(to));
+                overlay::line(screen, width, height, from, to, COLOR);
+                mark(screen, from);
+                mark(screen, to);
+            }
+            None => {
+                if let Some((from, _)) = self.from {
+                    mark(screen, centre(from));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,345 @@
+//! Falling sand: sand and water fall, water spreads sideways, fire rises
+//! and flickers out, and walls hold everything up.
+//!
+//! Moving cells would clash if every cell moved at once, so the grid is cut
+//! into 2x2 blocks (a Margolus neighbourhood) and each block is rearranged
+//! on its own.  The blocks shift by one cell diagonally every generation so
+//! things can cross block edges.  Fire's flicker uses a hash of the cell and
+//! generation, so a run is the same however the work is split.
+
+use std::fmt;
+use std::str::FromStr;
+use std::any::Any;
+use std::sync::Mutex;
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+#[cfg(not(target_arch = "wasm32"))]
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::engine::{cell_hash, Anchor, CellState, LifeEngine, StaleGuard};
+use crate::rule::Rule;
+
+/// One in this many fires goes out each generation.
+const FIRE_LIFETIME: u64 = 12;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Element {
+    #[default]
+    Empty,
+    Sand,
+    Water,
+    Wall,
+    Fire,
+}
+
+impl Element {
+    pub const ALL: [Element; 5] = [Element::Empty, Element::Sand, Element::Water, Element::Wall, Element::Fire];
+
+    pub fn color(self) -> Option<[u8; 3]> {
+        match self {
+            Element::Empty => None,
+            Element::Sand => Some([0xe0, 0xc0, 0x70]),
+            Element::Water => Some([0x30, 0x70, 0xe0]),
+            Element::Wall => Some([0x80, 0x80, 0x80]),
+            Element::Fire => Some([0xff, 0x60, 0x10]),
+        }
+    }
+
+    /// How heavy it is: things sink through anything lighter that moves.
+    fn weight(self) -> u8 {
+        match self {
+            Element::Fire => 0,
+            Element::Empty => 1,
+            Element::Water => 2,
+            Element::Sand => 3,
+            Element::Wall => u8::MAX,
+        }
+    }
+}
+
+impl fmt::Display for Element {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", format!("{:?}", self).to_lowercase())
+    }
+}
+
+impl FromStr for Element {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Element::ALL.into_iter()
+            .find(|e| e.to_string().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| format!("unknown element '{}', expected empty, sand, water, wall or fire", s))
+    }
+}
+
+/// Rearranges one block, `[top left, top right, bottom left, bottom
+/// right]`.  `mirror` swaps which way water spreads and sand slides, so
+/// neither drifts one way.
+fn update_block(block: &mut [Element; 4], mirror: bool, burn_out: [bool; 4]) {
+    // Water meeting fire puts it out and boils away
+    if block.contains(&Element::Fire) && block.contains(&Element::Water) {
+        for cell in block.iter_mut().filter(|c| matches!(c, Element::Fire | Element::Water)) {
+            *cell = Element::Empty;
+        }
+        return;
+    }
+    for (cell, out) in block.iter_mut().zip(burn_out) {
+        if *cell == Element::Fire && out {
+            *cell = Element::Empty;
+        }
+    }
+
+    let [a, b, c, d] = if mirror { [1, 0, 3, 2] } else { [0, 1, 2, 3] };
+    let moves = |block: &[Element; 4], from: usize, to: usize| {
+        let (mover, into) = (block[from], block[to]);
+        mover != Element::Wall && into != Element::Wall && mover != into
+    };
+    // Straight down: heavier things sink, fire rises
+    for (top, bottom) in [(a, c), (b, d)] {
+        if moves(block, top, bottom) && block[top].weight() > block[bottom].weight() {
+            block.swap(top, bottom);
+        }
+    }
+    // Diagonally down when the way straight down is blocked
+    for (top, beside, below) in [(a, b, d), (b, a, c)] {
+        let mover = block[top];
+        if matches!(mover, Element::Sand | Element::Water)
+            && block[below].weight() < mover.weight()
+            && block[beside].weight() < mover.weight()
+            && moves(block, top, below) {
+            block.swap(top, below);
+        }
+    }
+    // Water spreads along the bottom
+    if block[c] == Element::Water && block[d] == Element::Empty {
+        block.swap(c, d);
+    }
+}
+
+pub struct SandEngine {
+    width: usize,
+    height: usize,
+    cells: Vec<Element>,
+    generation: u64,
+    rule: Rule,
+    scratch: Mutex<Vec<Element>>,
+    counts: [usize; 5],
+    changed: usize,
+    born: usize,
+    // Element counts, then changed and born cells, for `scratch`
+    next_counts: Mutex<([usize; 5], usize, usize)>,
+    stale: StaleGuard,
+}
+
+impl SandEngine {
+    pub fn new(width: usize, height: usize) -> Self {
+        let mut counts = [0; 5];
+        counts[Element::Empty as usize] = width * height;
+        SandEngine {
+            width,
+            height,
+            cells: vec![Element::Empty; width * height],
+            generation: 0,
+            rule: Rule::default(),
+            scratch: Mutex::new(vec![Element::Empty; width * height]),
+            counts,
+            changed: 0,
+            born: 0,
+            next_counts: Mutex::new(([0; 5], 0, 0)),
+            stale: StaleGuard::default(),
+        }
+    }
+
+    fn put(&mut self, x: usize, y: usize, element: Element) {
+        let cell = &mut self.cells[y * self.width + x];
+        self.counts[*cell as usize] -= 1;
+        self.counts[element as usize] += 1;
+        *cell = element;
+        self.stale.bump();
+    }
+
+    /// Steps the blocks of one pair of rows starting at row `y`.
+    fn update_rows(&self, rows: &mut [Element], y: usize, offset: usize) {
+        let w = self.width;
+        if rows.len() < 2 * w {
+            return;
+        }
+        for x in (offset..w.saturating_sub(1)).step_by(2) {
+            let at = [x, x + 1, w + x, w + x + 1];
+            let mut block = at.map(|i| rows[i]);
+            let burn_out = at.map(|i| cell_hash(i % w, y + i / w, self.generation, 0).is_multiple_of(FIRE_LIFETIME));
+            update_block(&mut block, cell_hash(x, y, self.generation, 0) & 1 == 1, burn_out);
+            for (i, element) in at.into_iter().zip(block) {
+                rows[i] = element;
+            }
+        }
+    }
+
+    pub fn element(&self, x: usize, y: usize) -> Element {
+        self.cells[y * self.width + x]
+    }
+
+    pub fn set_element(&mut self, x: usize, y: usize, element: Element) {
+        self.put(x, y, element);
+    }
+
+    /// How many cells hold each element, in the order of [`Element::ALL`].
+    pub fn counts(&self) -> [usize; 5] {
+        self.counts
+    }
+}
+
+impl LifeEngine for SandEngine {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn name(&self) -> &'static str {
+        "sand"
+    }
+
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Anything but empty space counts as alive.
+    fn get(&self, x: usize, y: usize) -> CellState {
+        if self.cells[y * self.width + x] == Element::Empty { CellState::Dead } else { CellState::Alive }
+    }
+
+    /// Live cells are sand.
+    fn set(&mut self, x: usize, y: usize, state: CellState) {
+        self.put(x, y, if state == CellState::Alive { Element::Sand } else { Element::Empty });
+    }
+
+    /// Walls are the wall element, which holds up whatever is on it
+    /// whether it counts as alive or dead.
+    fn wall(&self, x: usize, y: usize) -> Option<CellState> {
+        (self.cells[y * self.width + x] == Element::Wall).then_some(CellState::Alive)
+    }
+
+    fn set_wall(&mut self, x: usize, y: usize, wall: Option<CellState>) -> bool {
+        self.put(x, y, if wall.is_some() { Element::Wall } else { Element::Empty });
+        true
+    }
+
+    fn color(&self, x: usize, y: usize) -> Option<[u8; 3]> {
+        self.cells[y * self.width + x].color()
+    }
+
+    /// Kept for the other backends' sake; sand has no B/S rule.
+    fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    fn set_rule(&mut self, rule: Rule) {
+        self.rule = rule;
+    }
+
+    fn prepare_step(&self) {
+        let _span = tracing::info_span!("sand.prepare_step").entered();
+        if let Ok(mut scratch) = self.scratch.lock() {
+            self.stale.mark_prepared();
+            scratch.copy_from_slice(&self.cells);
+            let offset = (self.generation % 2) as usize;
+            let w = self.width.max(1);
+
+            // Each pair of rows is a row of independent blocks
+            let blocks = &mut scratch[(offset * w).min(self.cells.len())..];
+            #[cfg(not(target_arch = "wasm32"))]
+            let pairs = blocks.par_chunks_mut(2 * w);
+            #[cfg(target_arch = "wasm32")]
+            let pairs = blocks.chunks_mut(2 * w);
+            pairs.enumerate().for_each(|(i, rows)| self.update_rows(rows, offset + 2 * i, offset));
+
+            let mut counts = ([0; 5], 0, 0);
+            for (old, new) in self.cells.iter().zip(scratch.iter()) {
+                counts.0[*new as usize] += 1;
+                if old != new {
+                    counts.1 += 1;
+                    counts.2 += (*old == Element::Empty) as usize;
+                }
+            }
+            if let Ok(mut next) = self.next_counts.lock() {
+                *next = counts;
+            }
+        }
+    }
+
+    fn commit_step(&mut self) {
+        if self.stale.is_stale() {
+            self.prepare_step();
+        }
+        self.stale.committed();
+        if let (Ok(scratch), Ok(counts)) = (self.scratch.get_mut(), self.next_counts.get_mut()) {
+            std::mem::swap(&mut self.cells, scratch);
+            (self.counts, self.changed, self.born) = *counts;
+            self.generation += 1;
+        }
+    }
+
+    fn changed_cells(&self) -> usize {
+        self.changed
+    }
+
+    fn born_cells(&self) -> usize {
+        self.born
+    }
+
+    fn resize(&mut self, width: usize, height: usize, anchor: Anchor) {
+        let (ox, oy) = anchor.offset((self.width, self.height), (width, height));
+        let mut cells = vec![Element::Empty; width * height];
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let (nx, ny) = (x as isize + ox, y as isize + oy);
+                if nx >= 0 && ny >= 0 && nx < width as isize && ny < height as isize {
+                    cells[ny as usize * width + nx as usize] = self.cells[y * self.width + x];
+                }
+            }
+        }
+        self.counts = [0; 5];
+        for cell in &cells {
+            self.counts[*cell as usize] += 1;
+        }
+        self.cells = cells;
+        self.scratch = Mutex::new(vec![Element::Empty; width * height]);
+        (self.width, self.height) = (width, height);
+        self.changed = 0;
+        self.born = 0;
+        self.stale.bump();
+    }
+
+    /// Live cells of the soup are sand or water, half and half.
+    fn randomize(&mut self, density: f64, seed: u64) {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let mut kinds = ChaCha8Rng::seed_from_u64(seed);
+        kinds.set_stream(1);
+        for y in 0..self.height {
+            rng.set_word_pos(2 * (y * self.width) as u128);
+            for x in 0..self.width {
+                let element = match (rng.gen_bool(density.clamp(0.0, 1.0)), kinds.gen_bool(0.5)) {
+                    (false, _) => Element::Empty,
+                    (true, true) => Element::Sand,
+                    (true, false) => Element::Water,
+                };
+                self.put(x, y, element);
+            }
+        }
+    }
+
+    fn population(&self) -> usize {
+        self.width * self.height - self.counts[Element::Empty as usize]
+    }
+}
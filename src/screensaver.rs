@@ -0,0 +1,50 @@
+//! The --screensaver mode's choices: what to show once the universe has
+//! settled or died out, and the colours slowly turning while it runs.
+
+use std::path::Path;
+use std::time::Duration;
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+use rustlife::cyclic;
+use rustlife::library::{self, LibraryPattern};
+
+use crate::render::Theme;
+
+/// Generations to watch one start for at most, as a soup that leaves
+/// gliders circling the grid never settles
+pub const LONGEST: u64 = 5000;
+// Built in patterns that run for a while before settling, unlike the still
+// lifes and oscillators
+const LONG_LIVED: [&str; 4] = ["R-pentomino", "diehard", "acorn", "Gosper glider gun"];
+// How long the live cells take to go once round the hue wheel
+const CYCLE: Duration = Duration::from_secs(120);
+// Steps round the wheel, fine enough to look continuous
+const HUES: usize = 3600;
+// How much of the cycling colour is mixed into the theme's own
+const TINT: f64 = 0.6;
+
+/// A pattern to start from instead of a soup: one of the long lived built
+/// in patterns or a file from `dir`.  None about half the time, for a soup.
+pub fn pick(dir: Option<&Path>, rng: &mut impl Rng) -> Option<LibraryPattern> {
+    if rng.gen_bool(0.5) {
+        return None;
+    }
+    let files = dir.and_then(|d| library::pattern_files(d).ok()).unwrap_or_default();
+    match files.choose(rng) {
+        Some(path) if rng.gen_bool(0.5) => library::load_file(path).map_err(|e| log::warn!("{}", e)).ok(),
+        _ => library::builtin().into_iter().filter(|p| LONG_LIVED.contains(&p.name.as_str())).collect::<Vec<_>>().choose(rng).cloned()
+    }
+}
+
+/// `theme` with its live cells tinted the colour `elapsed` has reached
+/// round the hue wheel.
+pub fn cycle(theme: &Theme, elapsed: Duration) -> Theme {
+    let turn = elapsed.as_secs_f64() / CYCLE.as_secs_f64();
+    let tint = cyclic::hue((turn.fract() * HUES as f64) as usize, HUES);
+    let mut alive = theme.alive;
+    for (c, t) in alive.iter_mut().zip(tint) {
+        *c = (*c as f64 * (1.0 - TINT) + t as f64 * TINT).round() as u8;
+    }
+    Theme { alive, ..*theme }
+}
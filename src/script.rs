@@ -0,0 +1,177 @@
+//! Rhai scripting hooks.
+//!
+//! A script's top level statements run once at startup.  If it defines
+//! `on_generation(gen)`, that is called after every generation.  Scripts see
+//! the universe through these functions:
+//!
+//! | function                  | effect                                          |
+//! |---------------------------|-------------------------------------------------|
+//! | `width()`, `height()`     | grid size                                       |
+//! | `get(x, y)`               | `true` if the cell is alive                     |
+//! | `set(x, y, alive)`        | set a cell, ignored outside the grid            |
+//! | `population()`            | number of live cells                            |
+//! | `place(text, x, y)`       | stamp a plaintext pattern, rows split by `\n`   |
+//! | `clear()`                 | kill every cell                                 |
+//! | `randomize(density)`      | fill with a fresh random soup                   |
+//! | `rule()`, `set_rule(s)`   | read or change the rule in B/S notation         |
+//! | `step()`, `step(n)`       | advance the universe immediately                |
+//! | `camera(gen, x, y, zoom)` | camera keyframe for a recording, see [`camera`] |
+//!
+//! For example, to drop a glider every 500 generations and log population:
+//!
+//! ```text
+//! fn on_generation(gen) {
+//!     if gen % 500 == 0 { place(".O\n..O\nOOO", 10, 10); }
+//!     print(`${gen} ${population()}`);
+//! }
+//! ```
+//!
+//! [`camera`]: crate::camera
+
+use std::sync::{Arc, RwLock};
+
+use rhai::{Dynamic, Engine, EvalAltResult, Scope, AST};
+
+use crate::camera::{Keyframe, SharedCamera, Shot};
+use crate::engine::{CellState, LifeEngine};
+use crate::io;
+use crate::rule::Rule;
+
+pub type SharedLife = Arc<RwLock<Box<dyn LifeEngine>>>;
+
+pub struct ScriptHost {
+    engine: Engine,
+    ast: AST,
+    scope: Scope<'static>,
+    has_on_generation: bool,
+    camera: SharedCamera,
+}
+
+fn in_grid(life: &dyn LifeEngine, x: i64, y: i64) -> Option<(usize, usize)> {
+    if x >= 0 && y >= 0 && (x as usize) < life.width() && (y as usize) < life.height() {
+        Some((x as usize, y as usize))
+    } else {
+        None
+    }
+}
+
+fn script_error(e: impl ToString) -> Box<EvalAltResult> {
+    e.to_string().into()
+}
+
+/// A number given as an integer or a float.
+fn number(value: Dynamic) -> Result<f64, Box<EvalAltResult>> {
+    match value.as_float() {
+        Ok(f) => Ok(f),
+        Err(_) => value.as_int().map(|i| i as f64).map_err(|t| script_error(format!("expected a number, got {}", t)))
+    }
+}
+
+impl ScriptHost {
+    /// Compiles `source` and registers the universe API against `life`.
+    /// Nothing is run until [`ScriptHost::on_start`].
+    pub fn new(life: SharedLife, source: &str) -> Result<Self, String> {
+        let mut engine = Engine::new();
+
+        let l = life.clone();
+        engine.register_fn("width", move || l.read().map(|l| l.width() as i64).unwrap_or(0));
+        let l = life.clone();
+        engine.register_fn("height", move || l.read().map(|l| l.height() as i64).unwrap_or(0));
+        let l = life.clone();
+        engine.register_fn("population", move || l.read().map(|l| l.population() as i64).unwrap_or(0));
+
+        let l = life.clone();
+        engine.register_fn("get", move |x: i64, y: i64| {
+            l.read().ok()
+                .and_then(|l| in_grid(l.as_ref(), x, y).map(|(x, y)| l.get(x, y) == CellState::Alive))
+                .unwrap_or(false)
+        });
+        let l = life.clone();
+        engine.register_fn("set", move |x: i64, y: i64, alive: bool| {
+            if let Ok(mut l) = l.write() {
+                if let Some((x, y)) = in_grid(l.as_ref(), x, y) {
+                    l.set(x, y, if alive { CellState::Alive } else { CellState::Dead });
+                }
+            }
+        });
+
+        let l = life.clone();
+        engine.register_fn("place", move |text: &str, x: i64, y: i64| {
+            if let Ok(mut l) = l.write() {
+                if x >= 0 && y >= 0 {
+                    io::place(l.as_mut(), &io::parse_plaintext(text), x as usize, y as usize);
+                }
+            }
+        });
+        let l = life.clone();
+        engine.register_fn("clear", move || {
+            if let Ok(mut l) = l.write() {
+                l.clear();
+            }
+        });
+        let l = life.clone();
+        engine.register_fn("randomize", move |density: f64| {
+            if let Ok(mut l) = l.write() {
+                l.randomize(density, rand::random());
+            }
+        });
+
+        let l = life.clone();
+        engine.register_fn("rule", move || l.read().map(|l| l.rule().to_string()).unwrap_or_default());
+        let l = life.clone();
+        engine.register_fn("set_rule", move |rule: &str| -> Result<(), Box<EvalAltResult>> {
+            let rule: Rule = rule.parse().map_err(script_error)?;
+            l.write().map_err(script_error)?.set_rule(rule);
+            Ok(())
+        });
+
+        let l = life.clone();
+        engine.register_fn("step", move || {
+            if let Ok(mut l) = l.write() {
+                l.step();
+            }
+        });
+        let l = life;
+        engine.register_fn("step", move |n: i64| {
+            if let Ok(mut l) = l.write() {
+                for _ in 0..n {
+                    l.step();
+                }
+            }
+        });
+
+        let camera = SharedCamera::default();
+        let c = Arc::clone(&camera);
+        engine.register_fn("camera", move |generation: i64, x: Dynamic, y: Dynamic, zoom: Dynamic| -> Result<(), Box<EvalAltResult>> {
+            let shot = Shot { x: number(x)?, y: number(y)?, zoom: number(zoom)? };
+            c.lock().map_err(script_error)?.add(Keyframe { generation: generation.max(0) as u64, shot }).map_err(script_error)
+        });
+
+        let ast = engine.compile(source).map_err(|e| e.to_string())?;
+        let has_on_generation = ast.iter_functions().any(|f| f.name == "on_generation" && f.params.len() == 1);
+        Ok(ScriptHost { engine, ast, scope: Scope::new(), has_on_generation, camera })
+    }
+
+    /// The keyframes the script has given with `camera(...)`, shared so
+    /// ones it adds later still reach the recording.
+    pub fn camera(&self) -> SharedCamera {
+        Arc::clone(&self.camera)
+    }
+
+    /// Runs the script's top level statements.
+    pub fn on_start(&mut self) -> Result<(), String> {
+        self.engine.run_ast_with_scope(&mut self.scope, &self.ast).map_err(|e| e.to_string())
+    }
+
+    /// Calls `on_generation(gen)` if the script defines it.  Must not be
+    /// called while holding a lock on the universe.
+    pub fn on_generation(&mut self, generation: u64) -> Result<(), String> {
+        if !self.has_on_generation {
+            return Ok(());
+        }
+        self.engine
+            .call_fn::<Dynamic>(&mut self.scope, &self.ast, "on_generation", (generation as i64,))
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,55 @@
+//! The timeline bar along the bottom of the window: a tick for every
+//! keyframe kept and a marker at the shown generation.  Clicking or
+//! dragging along it goes to the generation under the mouse.
+
+use rustlife::timeline::Timeline;
+
+use crate::overlay::{self, GLYPH_SIZE};
+
+pub const HEIGHT: u32 = 2 * GLYPH_SIZE + 12;
+// Space left of and right of the track, in pixels
+const MARGIN: u32 = 8;
+
+/// First and last generation the bar covers: from the first keyframe to
+/// the later of the last one and `generation`.
+fn span(timeline: &Timeline, generation: u64) -> Option<(u64, u64)> {
+    let first = timeline.generations().next()?;
+    let last = timeline.generations().last().unwrap_or(first).max(generation);
+    Some((first, last))
+}
+
+/// Whether the screen pixel is on the bar.
+pub fn contains((_, y): (usize, usize), height: u32) -> bool {
+    y as u32 >= height.saturating_sub(HEIGHT)
+}
+
+/// The generation under the screen pixel, if it is on the bar.
+pub fn generation_at(timeline: &Timeline, generation: u64, (x, y): (usize, usize), width: u32, height: u32) -> Option<u64> {
+    let (first, last) = span(timeline, generation).filter(|_| contains((x, y), height))?;
+    let track = width.saturating_sub(2 * MARGIN).max(1) as f64;
+    let along = ((x as f64 - MARGIN as f64) / track).clamp(0.0, 1.0);
+    Some(first + ((last - first) as f64 * along).round() as u64)
+}
+
+/// Draws the bar with the shown slot at `generation`.
+pub fn draw(screen: &mut [u8], width: u32, height: u32, timeline: &Timeline, generation: u64) {
+    let top = height.saturating_sub(HEIGHT) as i32;
+    overlay::fill_rect(screen, width, height, (0, top, width, HEIGHT), [0, 0, 0, 200]);
+    let (first, last) = match span(timeline, generation) {
+        Some(span) => span,
+        None => {
+            overlay::draw_text(screen, width, height, MARGIN as i32, top + 4, "Timeline: nothing kept yet", [200, 200, 200]);
+            return;
+        }
+    };
+    let label = format!("generation {} of {}, kept every {} ({} KB)", generation, last, timeline.interval(), timeline.size().div_ceil(1024));
+    overlay::draw_text(screen, width, height, MARGIN as i32, top + 4, &label, [200, 200, 200]);
+    let track = width.saturating_sub(2 * MARGIN);
+    let x_of = |g: u64| MARGIN as i32 + (track as f64 * (g - first) as f64 / (last - first).max(1) as f64) as i32;
+    let line = top + GLYPH_SIZE as i32 + 8;
+    overlay::fill_rect(screen, width, height, (MARGIN as i32, line + 2, track, 2), [90, 90, 90, 255]);
+    for g in timeline.generations() {
+        overlay::fill_rect(screen, width, height, (x_of(g), line, 1, 6), [160, 160, 160, 255]);
+    }
+    overlay::fill_rect(screen, width, height, (x_of(generation.max(first)) - 1, line - 2, 3, 10), [255, 220, 64, 255]);
+}
@@ -0,0 +1,79 @@
+//! State restored from the previous run: where the window was, the view, and
+//! the settings that were in effect.  Sits between the config file and the
+//! command line: config < session < flags.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+use crate::render::View;
+use crate::spacetime::Camera;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct WindowGeometry {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// A view saved to jump back to with Ctrl and its number.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Bookmark {
+    pub number: usize,
+    pub name: String,
+    pub view: View,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct Session {
+    pub window: Option<WindowGeometry>,
+    pub camera: Option<Camera>,
+    pub speed: Option<u32>,
+    pub rule: Option<String>,
+    pub theme: Option<String>,
+    pub grid_width: Option<usize>,
+    pub grid_height: Option<usize>,
+    pub bookmarks: Vec<Bookmark>,
+}
+
+impl Session {
+    /// `$XDG_STATE_HOME/rustlife/session.toml` or the platform equivalent.
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::state_dir()
+            .or_else(dirs::data_local_dir)
+            .map(|d| d.join("rustlife").join("session.toml"))
+    }
+
+    /// A missing or unreadable session is not an error, it just means
+    /// starting fresh.
+    pub fn load(path: &Path) -> Session {
+        match fs::read_to_string(path) {
+            Ok(text) => toml::from_str(&text).unwrap_or_else(|e| {
+                log::warn!("Ignoring session file {}: {}", path.display(), e);
+                Session::default()
+            }),
+            Err(_) => Session::default()
+        }
+    }
+
+    #[cfg_attr(not(feature = "gui"), allow(dead_code))]
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let text = toml::to_string_pretty(self).map_err(|e| e.to_string())?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+        }
+        fs::write(path, text).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    pub fn apply_to(&self, config: &mut Config) {
+        if let Some(v) = self.speed { config.speed = v; }
+        if let Some(v) = &self.rule { config.rule = v.clone(); }
+        if let Some(v) = &self.theme { config.theme = v.clone(); }
+        if let Some(v) = self.grid_width { config.grid_width = v; }
+        if let Some(v) = self.grid_height { config.grid_height = v; }
+    }
+}
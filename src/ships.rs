@@ -0,0 +1,76 @@
+//! Spaceship detection.  Each object small enough is run on its own until
+//! its shape repeats; if by then it has moved, it is a spaceship and the
+//! offset over its period is its velocity.
+
+use crate::apgcode::{self, Motion};
+use crate::census::{self, UNIDENTIFIED};
+use crate::engine::{BoundingBox, LifeEngine};
+
+/// Objects wider or taller than this are not considered.
+pub const MAX_SHIP: usize = 32;
+
+#[derive(Clone, Debug)]
+pub struct Ship {
+    /// apgcode, or [`UNIDENTIFIED`]
+    pub code: String,
+    /// Centre of the bounding box, in cells
+    pub x: f64,
+    pub y: f64,
+    pub motion: Motion,
+}
+
+impl Ship {
+    pub fn name(&self) -> Option<&'static str> {
+        apgcode::name(&self.code)
+    }
+
+    /// Cells per generation in x and y.
+    pub fn velocity(&self) -> (f64, f64) {
+        let p = self.motion.period as f64;
+        (self.motion.dx as f64 / p, self.motion.dy as f64 / p)
+    }
+
+    /// The speed as usually written, e.g. `c/4 diagonal` for the glider.
+    pub fn speed(&self) -> String {
+        speed(self.motion)
+    }
+}
+
+/// A displacement over a number of generations as a speed is usually
+/// written, e.g. `2c/5 orthogonal`.
+pub fn speed(motion: Motion) -> String {
+    let Motion { period, dx, dy } = motion;
+    let (ax, ay) = (dx.unsigned_abs(), dy.unsigned_abs());
+    let distance = ax.max(ay);
+    let d = gcd(distance, period);
+    let (distance, period) = (distance / d, period / d);
+    let fraction = match (distance, period) {
+        (1, 1) => "c".to_string(),
+        (1, p) => format!("c/{}", p),
+        (n, p) => format!("{}c/{}", n, p)
+    };
+    let direction = if ax == 0 || ay == 0 { "orthogonal" } else if ax == ay { "diagonal" } else { "oblique" };
+    format!("{} {}", fraction, direction)
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+/// Every spaceship in the universe.
+pub fn find(life: &dyn LifeEngine) -> Vec<Ship> {
+    let rule = life.rule();
+    census::objects_in(life)
+        .into_iter()
+        .filter_map(|object| {
+            let bb = BoundingBox::of(&object).filter(|bb| bb.width() <= MAX_SHIP && bb.height() <= MAX_SHIP)?;
+            let motion = apgcode::motion(&object, rule).filter(|m| m.dx != 0 || m.dy != 0)?;
+            Some(Ship {
+                code: apgcode::identify(&object, rule).unwrap_or_else(|| UNIDENTIFIED.to_string()),
+                x: bb.min_x as f64 + bb.width() as f64 / 2.0,
+                y: bb.min_y as f64 + bb.height() as f64 / 2.0,
+                motion,
+            })
+        })
+        .collect()
+}
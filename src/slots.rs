@@ -0,0 +1,84 @@
+//! Several independent simulations held in memory at once, switched with the
+//! number keys.  Only the active slot is stepped; the others stay frozen
+//! until they are switched back to.
+
+use std::sync::atomic::AtomicU64;
+use std::sync::{Arc, RwLock};
+
+use rustlife::script::SharedLife;
+
+use crate::render::View;
+use crate::spacetime::Camera;
+
+pub const SLOT_COUNT: usize = 9;
+
+pub struct Slot {
+    pub life: SharedLife,
+    pub generation: Arc<AtomicU64>,
+    /// Seed of the current soup, `None` if it came from a pattern
+    pub seed: Option<u64>,
+    pub camera: Camera,
+    /// Part of the grid shown, the whole grid if `None`
+    pub view: Option<View>,
+}
+
+impl Slot {
+    pub fn new(life: SharedLife, seed: Option<u64>) -> Self {
+        Slot { life, generation: Arc::new(AtomicU64::new(0)), seed, camera: Camera::new(), view: None }
+    }
+}
+
+/// The universe and generation counter the simulation thread should step,
+/// and the index of the slot they are in.
+pub type ActiveSlot = Arc<RwLock<(SharedLife, Arc<AtomicU64>, usize)>>;
+
+pub struct Slots {
+    slots: Vec<Option<Slot>>,
+    current: usize,
+    active: ActiveSlot,
+}
+
+impl Slots {
+    pub fn new(first: Slot) -> Self {
+        let active = Arc::new(RwLock::new((Arc::clone(&first.life), Arc::clone(&first.generation), 0)));
+        let mut slots: Vec<Option<Slot>> = (0..SLOT_COUNT).map(|_| None).collect();
+        slots[0] = Some(first);
+        Slots { slots, current: 0, active }
+    }
+
+    pub fn active(&self) -> ActiveSlot {
+        Arc::clone(&self.active)
+    }
+
+    /// Zero based index of the slot being shown.
+    pub fn index(&self) -> usize {
+        self.current
+    }
+
+    pub fn current(&self) -> &Slot {
+        self.slots[self.current].as_ref().expect("current slot is always filled")
+    }
+
+    pub fn current_mut(&mut self) -> &mut Slot {
+        self.slots[self.current].as_mut().expect("current slot is always filled")
+    }
+
+    /// Makes slot `index` current, filling it with `make` first if it is
+    /// empty.  Returns false if `index` was already current.
+    pub fn switch_to(&mut self, index: usize, make: impl FnOnce() -> Result<Slot, String>) -> Result<bool, String> {
+        if index >= SLOT_COUNT || index == self.current {
+            return Ok(false);
+        }
+        if self.slots[index].is_none() {
+            self.slots[index] = Some(make()?);
+        }
+        self.current = index;
+
+        let slot = self.current();
+        let handle = (Arc::clone(&slot.life), Arc::clone(&slot.generation), index);
+        if let Ok(mut active) = self.active.write() {
+            *active = handle;
+        }
+        Ok(true)
+    }
+}
@@ -0,0 +1,185 @@
+//! Compressed snapshots of a universe, small enough to save even the
+//! biggest grids often: a 16384x16384 universe, a quarter of a gigabyte in
+//! memory, takes a few megabytes once it has settled.  A snapshot file is
+//!
+//! - `RLSNAP` and the format version, 1, as a little endian `u16`
+//! - the width and height as little endian `u32`s, then the generation as
+//!   a `u64`
+//! - the rule in B/S notation and the backend's name, each a byte giving
+//!   its length and then the text
+//! - the cells, row by row, eight to a byte with the leftmost in the lowest
+//!   bit and each row starting on a new byte, compressed as one zstd frame
+//!
+//! Like the timeline's keyframes, only whether cells are alive is kept, not
+//! walls, species or the levels of the continuous backends.  Checkpoints,
+//! the timeline and the palette's Save snapshot all write this format.
+
+use std::fs;
+use std::io::{Read, Write};
+use std::path::Path;
+
+use crate::engine::{CellState, LifeEngine};
+use crate::rule::Rule;
+
+const MAGIC: &[u8; 6] = b"RLSNAP";
+const VERSION: u16 = 1;
+// zstd's own default, a good deal faster than the higher levels for little
+// less compression of mostly empty grids
+const LEVEL: i32 = 3;
+
+/// A universe's cells, compressed, with what is needed to put them back.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Snapshot {
+    pub generation: u64,
+    /// The backend it was taken on
+    pub backend: String,
+    pub rule: Rule,
+    pub width: usize,
+    pub height: usize,
+    /// The packed rows as a zstd frame
+    cells: Vec<u8>,
+}
+
+/// Bytes a packed row of `width` cells takes.
+fn row_bytes(width: usize) -> usize {
+    width.div_ceil(8)
+}
+
+impl Snapshot {
+    /// Takes a snapshot of `life` at `generation`.
+    pub fn of(generation: u64, life: &dyn LifeEngine) -> Self {
+        let width = life.width();
+        let rows = (0..life.height()).map(|y| (0..width).map(move |x| life.get(x, y) == CellState::Alive));
+        Snapshot::from_rows(generation, life.name(), life.rule(), (width, life.height()), rows)
+    }
+
+    /// A snapshot of a `width` by `height` grid whose cells are `alive`,
+    /// one entry per cell, row by row.
+    pub fn new(generation: u64, backend: &str, rule: Rule, (width, height): (usize, usize), alive: &[bool]) -> Self {
+        let rows = alive.chunks(width.max(1)).take(height).map(|row| row.iter().copied());
+        Snapshot::from_rows(generation, backend, rule, (width, height), rows)
+    }
+
+    fn from_rows<R: IntoIterator<Item = bool>>(generation: u64, backend: &str, rule: Rule, (width, height): (usize, usize), rows: impl Iterator<Item = R>) -> Self {
+        let mut encoder = zstd::stream::Encoder::new(Vec::new(), LEVEL).expect("zstd takes its default level");
+        let mut packed = vec![0u8; row_bytes(width)];
+        for row in rows {
+            packed.fill(0);
+            for (x, alive) in row.into_iter().enumerate() {
+                if alive {
+                    packed[x / 8] |= 1 << (x % 8);
+                }
+            }
+            encoder.write_all(&packed).expect("writing to memory");
+        }
+        let cells = encoder.finish().expect("writing to memory");
+        Snapshot { generation, backend: backend.to_string(), rule, width, height, cells }
+    }
+
+    /// Bytes the cells take compressed.
+    pub fn size(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Calls `row` with each row of cells, packed as in the file.
+    fn rows(&self, mut row: impl FnMut(usize, &[u8])) -> Result<(), String> {
+        let mut decoder = zstd::stream::Decoder::new(&self.cells[..]).map_err(|e| e.to_string())?;
+        let mut packed = vec![0u8; row_bytes(self.width)];
+        for y in 0..self.height {
+            decoder.read_exact(&mut packed).map_err(|e| format!("row {} of the snapshot: {}", y, e))?;
+            row(y, &packed);
+        }
+        Ok(())
+    }
+
+    /// One entry per cell, row by row.
+    pub fn alive(&self) -> Result<Vec<bool>, String> {
+        let mut alive = Vec::with_capacity(self.width * self.height);
+        self.rows(|_, packed| alive.extend((0..self.width).map(|x| packed[x / 8] & (1 << (x % 8)) != 0)))?;
+        Ok(alive)
+    }
+
+    /// Sets every cell of `life`, which must be the same size, to the
+    /// snapshot's, leaving its rule alone.
+    pub fn paint(&self, life: &mut dyn LifeEngine) -> Result<(), String> {
+        if (self.width, self.height) != (life.width(), life.height()) {
+            return Err(format!("the snapshot is of a {}x{} grid, not {}x{}", self.width, self.height, life.width(), life.height()));
+        }
+        self.rows(|y, packed| {
+            for x in 0..self.width {
+                let alive = packed[x / 8] & (1 << (x % 8)) != 0;
+                life.set(x, y, if alive { CellState::Alive } else { CellState::Dead });
+            }
+        })
+    }
+
+    /// Puts the snapshot's cells and rule back into `life`, which must be
+    /// the same size.
+    pub fn restore(&self, life: &mut dyn LifeEngine) -> Result<(), String> {
+        self.paint(life)?;
+        life.set_rule(self.rule);
+        Ok(())
+    }
+
+    /// The snapshot as a file's contents.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let (rule, backend) = (self.rule.to_string(), &self.backend);
+        let mut bytes = Vec::with_capacity(32 + rule.len() + backend.len() + self.cells.len());
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&VERSION.to_le_bytes());
+        bytes.extend_from_slice(&(self.width as u32).to_le_bytes());
+        bytes.extend_from_slice(&(self.height as u32).to_le_bytes());
+        bytes.extend_from_slice(&self.generation.to_le_bytes());
+        for text in [&rule, backend] {
+            bytes.push(text.len().min(u8::MAX as usize) as u8);
+            bytes.extend_from_slice(&text.as_bytes()[..text.len().min(u8::MAX as usize)]);
+        }
+        bytes.extend_from_slice(&self.cells);
+        bytes
+    }
+
+    /// Reads a snapshot from a file's contents.  Only the header is checked;
+    /// the cells are found wanting, if they are, when they are unpacked.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        if !Snapshot::is_snapshot(bytes) {
+            return Err("not a snapshot".to_string());
+        }
+        let mut rest = &bytes[MAGIC.len()..];
+        let mut take = |n: usize| -> Result<&[u8], String> {
+            if rest.len() < n {
+                return Err("the snapshot is cut short".to_string());
+            }
+            let (taken, left) = rest.split_at(n);
+            rest = left;
+            Ok(taken)
+        };
+        let version = u16::from_le_bytes(take(2)?.try_into().expect("two bytes"));
+        if version != VERSION {
+            return Err(format!("snapshot format version {} is newer than this program's, {}", version, VERSION));
+        }
+        let width = u32::from_le_bytes(take(4)?.try_into().expect("four bytes")) as usize;
+        let height = u32::from_le_bytes(take(4)?.try_into().expect("four bytes")) as usize;
+        let generation = u64::from_le_bytes(take(8)?.try_into().expect("eight bytes"));
+        let mut text = || -> Result<String, String> {
+            let length = take(1)?[0] as usize;
+            String::from_utf8(take(length)?.to_vec()).map_err(|e| e.to_string())
+        };
+        let rule: Rule = text()?.parse()?;
+        let backend = text()?;
+        Ok(Snapshot { generation, backend, rule, width, height, cells: rest.to_vec() })
+    }
+
+    /// Whether `bytes` start like a snapshot.
+    pub fn is_snapshot(bytes: &[u8]) -> bool {
+        bytes.starts_with(MAGIC)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        fs::write(path, self.to_bytes()).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let bytes = fs::read(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Snapshot::from_bytes(&bytes).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+}
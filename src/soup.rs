@@ -0,0 +1,234 @@
+//! Random soups, optionally symmetric.  Symmetric soups are how searches
+//! turn up new oscillators and spaceships: a symmetric start can only
+//! evolve into symmetric debris, which favours rarer objects.
+//!
+//! A soup is a square patch with every cell alive with some probability.
+//! Under a symmetry other than C1, only one cell of each group of cells the
+//! symmetry maps onto each other is drawn, and the rest copy it.
+
+use std::fmt;
+use std::str::FromStr;
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::engine::LifeEngine;
+use crate::io;
+
+/// Symmetry of a soup about its centre, named as in apgsearch.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Symmetry {
+    /// None
+    #[default]
+    C1,
+    /// Unchanged by a half turn
+    C2,
+    /// Unchanged by a quarter turn
+    C4,
+    /// Mirrored left to right
+    D2,
+    /// Mirrored left to right and top to bottom
+    D4,
+    /// Mirrored left to right, top to bottom and across both diagonals
+    D8,
+}
+
+impl Symmetry {
+    pub const ALL: [Symmetry; 6] = [Symmetry::C1, Symmetry::C2, Symmetry::C4, Symmetry::D2, Symmetry::D4, Symmetry::D8];
+
+    /// Where the symmetry maps (x, y) in a `size` square, including (x, y)
+    /// itself.
+    fn images(self, (x, y): (usize, usize), size: usize) -> Vec<(usize, usize)> {
+        let s = size - 1;
+        let half_turn = (s - x, s - y);
+        match self {
+            Symmetry::C1 => vec![(x, y)],
+            Symmetry::C2 => vec![(x, y), half_turn],
+            Symmetry::C4 => vec![(x, y), (s - y, x), half_turn, (y, s - x)],
+            Symmetry::D2 => vec![(x, y), (s - x, y)],
+            Symmetry::D4 => vec![(x, y), (s - x, y), (x, s - y), half_turn],
+            Symmetry::D8 => vec![(x, y), (s - x, y), (x, s - y), half_turn, (y, x), (s - y, x), (y, s - x), (s - y, s - x)],
+        }
+    }
+
+    /// Its name in apgsearch hauls for a `size` square soup, which says
+    /// whether the centre is a cell, for odd sizes, or a corner between
+    /// four, for even ones: D4 on a 16x16 soup is D4_+4.  apgsearch has no
+    /// plain D4.
+    pub fn apgsearch(self, size: usize) -> &'static str {
+        let even = size.is_multiple_of(2);
+        match self {
+            Symmetry::C1 => "C1",
+            Symmetry::C2 => if even { "C2_4" } else { "C2_1" },
+            Symmetry::C4 => if even { "C4_4" } else { "C4_1" },
+            Symmetry::D2 => if even { "D2_+2" } else { "D2_+1" },
+            Symmetry::D4 => if even { "D4_+4" } else { "D4_+1" },
+            Symmetry::D8 => if even { "D8_4" } else { "D8_1" },
+        }
+    }
+}
+
+impl fmt::Display for Symmetry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl FromStr for Symmetry {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Symmetry::ALL.into_iter()
+            .find(|sym| sym.to_string().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| format!("unknown symmetry '{}', expected one of: C1, C2, C4, D2, D4, D8", s))
+    }
+}
+
+/// How a soup's density varies over it, or a tiling of one pattern in
+/// place of a soup.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Layout {
+    /// The same density everywhere
+    #[default]
+    Uniform,
+    /// Empty at the left edge, rising to the full density at the right
+    Gradient,
+    /// Horizontal bands of soup `width` cells tall, with empty bands between
+    Stripes { width: usize },
+    /// Rings of soup `width` cells wide around the centre, with empty rings
+    /// between
+    Rings { width: usize },
+    /// `count` Gaussian blobs of soup at random places, fading out over
+    /// `radius` cells, or a tenth of the soup's side for 0
+    Blobs { count: usize, radius: f64 },
+    /// Copies of a plaintext pattern with `gap` empty cells between them
+    Tiles { pattern: String, gap: usize },
+}
+
+impl FromStr for Layout {
+    type Err = String;
+
+    /// `uniform`, `gradient`, `stripes[:width]`, `rings[:width]` or
+    /// `blobs[:count[:radius]]`.  Tiles need a pattern so can't be written
+    /// this way.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.trim().split(':');
+        let name = parts.next().unwrap_or("").to_ascii_lowercase();
+        let numbers: Vec<f64> = parts
+            .map(|p| p.trim().parse().map_err(|_| format!("'{}' is not a number", p)))
+            .collect::<Result<_, _>>()?;
+        let number = |i: usize, default: f64| numbers.get(i).copied().unwrap_or(default);
+        let layout = match name.as_str() {
+            "uniform" => Layout::Uniform,
+            "gradient" => Layout::Gradient,
+            "stripes" => Layout::Stripes { width: number(0, 16.0).max(1.0) as usize },
+            "rings" => Layout::Rings { width: number(0, 16.0).max(1.0) as usize },
+            "blobs" => Layout::Blobs { count: number(0, 5.0) as usize, radius: number(1, 0.0).max(0.0) },
+            _ => return Err(format!("unknown layout '{}', expected uniform, gradient, stripes, rings, blobs or tiles", s))
+        };
+        Ok(layout)
+    }
+}
+
+impl Layout {
+    /// Share of the full density at each cell of a `w` x `h` soup.
+    fn weights(&self, (w, h): (usize, usize), seed: u64) -> Vec<f64> {
+        let (cx, cy) = (w as f64 / 2.0, h as f64 / 2.0);
+        // Blob centres come from their own stream so the cells drawn later
+        // don't depend on how many blobs there are
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        rng.set_stream(1);
+        let (centres, sigma) = match self {
+            Layout::Blobs { count, radius } => {
+                let centres: Vec<(f64, f64)> = (0..*count).map(|_| (rng.gen_range(0.0..w.max(1) as f64), rng.gen_range(0.0..h.max(1) as f64))).collect();
+                let radius = if *radius > 0.0 { *radius } else { w.min(h) as f64 / 10.0 };
+                (centres, radius.max(1.0))
+            }
+            _ => (Vec::new(), 1.0)
+        };
+
+        (0..w * h).map(|i| {
+            let (x, y) = ((i % w) as f64 + 0.5, (i / w) as f64 + 0.5);
+            match self {
+                Layout::Uniform | Layout::Tiles { .. } => 1.0,
+                Layout::Gradient => x / w as f64,
+                Layout::Stripes { width } => ((i / w) / (*width).max(1)).is_multiple_of(2) as u8 as f64,
+                Layout::Rings { width } => {
+                    let d = ((x - cx).powi(2) + (y - cy).powi(2)).sqrt();
+                    (d as usize / (*width).max(1)).is_multiple_of(2) as u8 as f64
+                }
+                Layout::Blobs { .. } => centres.iter()
+                    .map(|(bx, by)| (-((x - bx).powi(2) + (y - by).powi(2)) / (2.0 * sigma * sigma)).exp())
+                    .fold(0.0, f64::max),
+            }
+        }).collect()
+    }
+}
+
+/// The live cells of a `size` square soup with the given symmetry, each
+/// drawn alive with probability `density`.  A C1 soup draws its cells in
+/// row order, one per cell, so it matches the plain soups of earlier
+/// versions.
+pub fn generate(symmetry: Symmetry, size: usize, density: f64, seed: u64) -> Vec<(usize, usize)> {
+    generate_layout(&Layout::Uniform, symmetry, (size, size), density, seed)
+}
+
+/// The live cells of a `w` x `h` soup laid out as `layout`.  Only C1 soups
+/// can be other than square; the others use the largest square that fits.
+pub fn generate_layout(layout: &Layout, symmetry: Symmetry, (w, h): (usize, usize), density: f64, seed: u64) -> Vec<(usize, usize)> {
+    if let Layout::Tiles { pattern, gap } = layout {
+        return tiles(&io::parse_plaintext(pattern), *gap, (w, h));
+    }
+    let (w, h) = if symmetry == Symmetry::C1 { (w, h) } else { (w.min(h), w.min(h)) };
+    let weights = layout.weights((w, h), seed);
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let drawn: Vec<bool> = weights.iter().map(|weight| rng.gen_bool((density * weight).clamp(0.0, 1.0))).collect();
+    (0..w * h)
+        .map(|i| (i % w, i / w))
+        .filter(|&cell| {
+            // Every cell in a group copies the first of them in row order
+            let (x, y) = symmetry.images(cell, w).into_iter().min_by_key(|&(x, y)| (y, x)).unwrap_or(cell);
+            drawn[y * w + x]
+        })
+        .collect()
+}
+
+/// As many whole copies of `live` as fit in `w` x `h`, `gap` cells apart.
+fn tiles(live: &[(usize, usize)], gap: usize, (w, h): (usize, usize)) -> Vec<(usize, usize)> {
+    let (pw, ph) = io::extent(live);
+    if pw == 0 || pw > w || ph > h {
+        return Vec::new();
+    }
+    let mut cells = Vec::new();
+    for ty in (0..=h - ph).step_by(ph + gap) {
+        for tx in (0..=w - pw).step_by(pw + gap) {
+            cells.extend(live.iter().map(|&(x, y)| (tx + x, ty + y)));
+        }
+    }
+    cells
+}
+
+/// Replaces the contents of `life` with a soup.  A uniform C1 soup with
+/// `size` 0 fills the grid as [`LifeEngine::randomize`] does.  Otherwise a
+/// `size` square soup is placed in the middle of an empty grid, or for 0 one
+/// filling the grid, or the largest square that fits if it is symmetric.
+pub fn fill(life: &mut dyn LifeEngine, layout: &Layout, symmetry: Symmetry, size: usize, density: f64, seed: u64) {
+    if *layout == Layout::Uniform && symmetry == Symmetry::C1 && size == 0 {
+        life.randomize(density, seed);
+        return;
+    }
+    let area = match size {
+        0 => (life.width(), life.height()),
+        size => (size.min(life.width()), size.min(life.height()))
+    };
+    let (w, h) = match (layout, symmetry) {
+        (Layout::Tiles { .. }, _) | (_, Symmetry::C1) => area,
+        _ => (area.0.min(area.1), area.0.min(area.1))
+    };
+    life.clear();
+    let cells = generate_layout(layout, symmetry, (w, h), density, seed);
+    let (x, y) = ((life.width() - w) / 2, (life.height() - h) / 2);
+    io::place(life, &cells, x, y);
+}
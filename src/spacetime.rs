@@ -0,0 +1,141 @@
+//! The space-time view: recent generations stacked up the vertical axis and
+//! turned slowly, so gliders show as slanted trails and oscillators as
+//! columns.
+//!
+//! The points are projected on the CPU into the same `pixels` frame every
+//! other view draws into, rather than through a wgpu pipeline of their own.
+//! `pixels` already puts the frame on screen through wgpu, and a second
+//! pipeline would need its own shaders, depth buffer and render pass fitted
+//! around `pixels`' scaling one for a view that never has more than
+//! 128x128 cells a generation over 96 generations, which the CPU keeps up
+//! with.  The population graph and the timeline are drawn over it as they
+//! are over the other views.
+
+use std::collections::VecDeque;
+use std::f64::consts::PI;
+
+use serde::{Deserialize, Serialize};
+
+use rustlife::volume::Volume;
+use rustlife::{CellState, LifeEngine};
+
+// Only a window in the middle of the universe is captured, otherwise every
+// generation of a random soup would be millions of points.
+const CAPTURE_SIZE: usize = 128;
+const HISTORY_DEPTH: usize = 96;
+
+/// Ring buffer of recent generations, each stored as the live cells that fall
+/// inside the capture window, in window-local coordinates.
+pub struct History {
+    generations: VecDeque<Vec<(u16, u16)>>,
+    depth: usize,
+    // Side of the capture window, which is smaller on small grids
+    size: usize,
+}
+
+impl History {
+    pub fn new() -> Self {
+        History { generations: VecDeque::with_capacity(HISTORY_DEPTH), depth: HISTORY_DEPTH, size: 0 }
+    }
+
+    pub fn clear(&mut self) {
+        self.generations.clear();
+    }
+
+    pub fn record(&mut self, state: &dyn LifeEngine) {
+        let size = CAPTURE_SIZE.min(state.width()).min(state.height());
+        self.size = size;
+        let x0 = (state.width() - size) / 2;
+        let y0 = (state.height() - size) / 2;
+
+        let mut live = Vec::new();
+        for y in 0..size {
+            for x in 0..size {
+                if state.get(x0 + x, y0 + y) == CellState::Alive {
+                    live.push((x as u16, y as u16));
+                }
+            }
+        }
+
+        if self.generations.len() == self.depth {
+            self.generations.pop_back();
+        }
+        self.generations.push_front(live);
+    }
+
+    /// The recorded generations as a volume for export.
+    pub fn volume(&self) -> Volume {
+        Volume::from_frames(self.size, self.size, self.generations.iter().rev().cloned())
+    }
+}
+
+/// Orbit camera looking at the centre of the space-time volume.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct Camera {
+    pub yaw: f64,
+    pub pitch: f64,
+    pub distance: f64,
+    pub spin: f64,
+}
+
+impl Camera {
+    pub fn new() -> Self {
+        Camera { yaw: 0.0, pitch: 0.5, distance: 2.5, spin: 0.01 }
+    }
+
+    pub fn advance(&mut self) {
+        self.yaw = (self.yaw + self.spin) % (2.0 * PI);
+    }
+
+    // Returns screen coordinates and depth for a point in normalized volume space.
+    fn project(&self, p: [f64; 3], width: u32, height: u32) -> Option<(i64, i64, f64)> {
+        let (sy, cy) = self.yaw.sin_cos();
+        let (sp, cp) = self.pitch.sin_cos();
+
+        // Rotate about the vertical (time) axis, then tilt towards the viewer.
+        let x = p[0] * cy - p[1] * sy;
+        let z = p[0] * sy + p[1] * cy;
+        let y = p[2] * cp - z * sp;
+        let z = p[2] * sp + z * cp + self.distance;
+
+        if z <= 0.1 {
+            return None;
+        }
+        let focal = 0.5 * width.min(height) as f64;
+        let sx = (width as f64) * 0.5 + x / z * focal;
+        let sy = (height as f64) * 0.5 - y / z * focal;
+        Some((sx as i64, sy as i64, z))
+    }
+}
+
+/// Renders the history as a point cloud with time running up the vertical
+/// axis. The newest generation is at the top and brightest.
+pub fn draw(width: u32, height: u32, screen: &mut [u8], history: &History, camera: &Camera) {
+    for pix in screen.chunks_exact_mut(4) {
+        pix.copy_from_slice(&[0, 0, 0, 0xff]);
+    }
+
+    let half = CAPTURE_SIZE as f64 / 2.0;
+    let depth = history.depth as f64;
+
+    // Painter's order: oldest first so recent generations end up on top.
+    for (age, live) in history.generations.iter().enumerate().rev() {
+        let t = 1.0 - (age as f64) / depth;
+        let fade = (64.0 + 191.0 * t) as u8;
+        let color = [fade / 3, fade, fade, 0xff];
+
+        for (x, y) in live {
+            let p = [
+                (*x as f64 - half) / CAPTURE_SIZE as f64,
+                (*y as f64 - half) / CAPTURE_SIZE as f64,
+                t - 0.5,
+            ];
+            if let Some((sx, sy, _)) = camera.project(p, width, height) {
+                if sx >= 0 && sy >= 0 && (sx as u32) < width && (sy as u32) < height {
+                    let i = (sy as usize * width as usize + sx as usize) * 4;
+                    screen[i..i + 4].copy_from_slice(&color);
+                }
+            }
+        }
+    }
+}
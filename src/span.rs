@@ -0,0 +1,91 @@
+//! Fullscreen on a chosen monitor, and spanning the universe over all of
+//! them for video walls: the main window goes on one monitor and a window
+//! on each of the others shows the part of the universe that lies that way,
+//! at the same scale, as if the monitors were one big screen.
+
+use pixels::{Pixels, SurfaceTexture};
+use winit::dpi::PhysicalSize;
+use winit::event_loop::EventLoop;
+use winit::monitor::MonitorHandle;
+use winit::window::{Fullscreen, Window, WindowBuilder, WindowId};
+
+use rustlife::LifeEngine;
+
+use crate::error::AppError;
+use crate::render::{self, PixelMap, Theme, View};
+
+/// Monitor `number`, counting from 1, or None to leave it to the system.
+pub fn monitor<T>(event_loop: &EventLoop<T>, number: Option<usize>) -> Result<Option<MonitorHandle>, String> {
+    let Some(number) = number else { return Ok(None) };
+    let monitors: Vec<_> = event_loop.available_monitors().collect();
+    for (i, m) in monitors.iter().enumerate() {
+        let (size, at) = (m.size(), m.position());
+        log::info!("Monitor {}: {} {}x{} at {},{}", i + 1, m.name().unwrap_or_default(), size.width, size.height, at.x, at.y);
+    }
+    number.checked_sub(1).and_then(|i| monitors.get(i)).cloned().map(Some)
+        .ok_or_else(|| format!("no monitor {}, there are {}", number, monitors.len()))
+}
+
+/// A window on another monitor, showing the universe beyond the main one.
+pub struct Follower {
+    window: Window,
+    pixels: Pixels,
+    map: PixelMap,
+    // Pixel buffer size, the same as the main window's
+    buffer: (u32, u32),
+    // Where its monitor is from the main window's, in pixels
+    offset: (i32, i32),
+}
+
+/// Opens a fullscreen follower on every monitor but `main`.  The pixel
+/// buffers are `width` by `height`, the same as the main window's.
+pub fn followers<T>(event_loop: &EventLoop<T>, main: &MonitorHandle, width: u32, height: u32, title: &str) -> Result<Vec<Follower>, AppError> {
+    let origin = main.position();
+    let mut followers = Vec::new();
+    for monitor in event_loop.available_monitors().filter(|m| m != main) {
+        let at = monitor.position();
+        let window = WindowBuilder::new()
+            .with_title(title)
+            .with_fullscreen(Some(Fullscreen::Borderless(Some(monitor))))
+            .with_active(false)
+            .build(event_loop)?;
+        // Only for looking at
+        window.set_cursor_visible(false);
+        let size = window.inner_size();
+        let pixels = Pixels::new(width, height, SurfaceTexture::new(size.width, size.height, &window))?;
+        followers.push(Follower { window, pixels, map: PixelMap::default(), buffer: (width, height), offset: (at.x - origin.x, at.y - origin.y) });
+    }
+    Ok(followers)
+}
+
+impl Follower {
+    pub fn id(&self) -> WindowId {
+        self.window.id()
+    }
+
+    pub fn resize(&mut self, size: PhysicalSize<u32>) -> Result<(), pixels::TextureError> {
+        self.pixels.resize_surface(size.width, size.height)
+    }
+
+    /// The part of the universe on this monitor when `view` fills the main
+    /// window, `main` pixels in size.
+    fn view(&self, view: &View, main: PhysicalSize<u32>) -> View {
+        let size = self.window.inner_size();
+        let (across, down) = (view.width / main.width.max(1) as f64, view.height / main.height.max(1) as f64);
+        View {
+            x: view.x + self.offset.0 as f64 * across,
+            y: view.y + self.offset.1 as f64 * down,
+            width: size.width as f64 * across,
+            height: size.height as f64 * down,
+        }
+    }
+
+    /// Draws its part of the universe, without any of the main window's
+    /// overlays.
+    pub fn draw(&mut self, life: &dyn LifeEngine, theme: &Theme, view: &View, main: PhysicalSize<u32>) -> Result<(), pixels::Error> {
+        let view = self.view(view, main);
+        self.map.update(self.buffer.0, self.buffer.1, &view);
+        render::draw(self.pixels.frame_mut(), life, theme, &self.map);
+        self.pixels.render()
+    }
+}
@@ -0,0 +1,262 @@
+//! Predator and prey: a two-species backend where both follow the B/S rule
+//! counting every live neighbour, whatever its species, but interact.
+//!
+//! - A prey cell with a predator next to it is eaten and becomes a predator.
+//! - A predator only survives with prey next to it to feed on.
+//! - A cell is born as whichever species has more of the neighbours that
+//!   bore it, prey on a tie.
+//!
+//! Predators spread through prey and then starve once they have eaten it,
+//! leaving room for the prey to grow back.
+
+use std::any::Any;
+use std::sync::Mutex;
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+#[cfg(not(target_arch = "wasm32"))]
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::engine::{Anchor, CellState, LifeEngine, StaleGuard};
+use crate::rule::Rule;
+
+/// Share of the live cells in a random soup that are predators.
+pub const PREDATOR_SHARE: f64 = 0.2;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Species {
+    Prey,
+    Predator,
+}
+
+// Population of each species, then changed and born cells
+type Counts = ([usize; 2], usize, usize);
+
+pub struct SpeciesEngine {
+    width: usize,
+    height: usize,
+    cells: Vec<Option<Species>>,
+    rule: Rule,
+    scratch: Mutex<Vec<Option<Species>>>,
+    populations: [usize; 2],
+    changed: usize,
+    born: usize,
+    // Counts for the generation in `scratch`, filled in by `prepare_step`
+    next_counts: Mutex<Counts>,
+    stale: StaleGuard,
+}
+
+impl SpeciesEngine {
+    pub fn new(width: usize, height: usize) -> Self {
+        SpeciesEngine {
+            width,
+            height,
+            cells: vec![None; width * height],
+            rule: Rule::default(),
+            scratch: Mutex::new(vec![None; width * height]),
+            populations: [0; 2],
+            changed: 0,
+            born: 0,
+            next_counts: Mutex::new(([0; 2], 0, 0)),
+            stale: StaleGuard::default(),
+        }
+    }
+
+    fn next_cell(&self, x: usize, y: usize) -> Option<Species> {
+        let (mut prey, mut predators) = (0, 0);
+        for ny in y.saturating_sub(1)..(y + 2).min(self.height) {
+            for nx in x.saturating_sub(1)..(x + 2).min(self.width) {
+                match self.cells[ny * self.width + nx] {
+                    _ if (nx, ny) == (x, y) => {}
+                    Some(Species::Prey) => prey += 1,
+                    Some(Species::Predator) => predators += 1,
+                    None => {}
+                }
+            }
+        }
+        let live = prey + predators;
+        match self.cells[y * self.width + x] {
+            Some(Species::Prey) if predators > 0 => Some(Species::Predator),
+            Some(Species::Prey) => self.rule.survive[live].then_some(Species::Prey),
+            Some(Species::Predator) => (prey > 0 && self.rule.survive[live]).then_some(Species::Predator),
+            None if self.rule.birth[live] => Some(if predators > prey { Species::Predator } else { Species::Prey }),
+            None => None,
+        }
+    }
+
+    fn count(&mut self, old: Option<Species>, new: Option<Species>) {
+        if let Some(s) = old {
+            self.populations[s as usize] -= 1;
+        }
+        if let Some(s) = new {
+            self.populations[s as usize] += 1;
+        }
+    }
+
+    /// The species of the live cell at (x, y).
+    pub fn species(&self, x: usize, y: usize) -> Option<Species> {
+        self.cells[y * self.width + x]
+    }
+
+    /// Makes the cell at (x, y) a live one of `species`.
+    pub fn set_species(&mut self, x: usize, y: usize, species: Species) {
+        let old = self.cells[y * self.width + x];
+        self.count(old, Some(species));
+        self.cells[y * self.width + x] = Some(species);
+        self.stale.bump();
+    }
+
+    /// Live prey and predators.
+    pub fn populations(&self) -> [usize; 2] {
+        self.populations
+    }
+}
+
+impl LifeEngine for SpeciesEngine {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn name(&self) -> &'static str {
+        "species"
+    }
+
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    fn get(&self, x: usize, y: usize) -> CellState {
+        if self.cells[y * self.width + x].is_some() { CellState::Alive } else { CellState::Dead }
+    }
+
+    /// Live cells painted onto dead ones are prey.
+    fn set(&mut self, x: usize, y: usize, state: CellState) {
+        let old = self.cells[y * self.width + x];
+        let new = match state {
+            CellState::Alive => old.or(Some(Species::Prey)),
+            CellState::Dead => None,
+        };
+        self.count(old, new);
+        self.cells[y * self.width + x] = new;
+        self.stale.bump();
+    }
+
+    fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    fn set_rule(&mut self, rule: Rule) {
+        self.rule = rule;
+        self.stale.bump();
+    }
+
+    fn prepare_step(&self) {
+        let _span = tracing::info_span!("species.prepare_step").entered();
+        if let Ok(mut scratch) = self.scratch.lock() {
+            self.stale.mark_prepared();
+            let width = self.width.max(1);
+
+            #[cfg(not(target_arch = "wasm32"))]
+            let rows = scratch.par_chunks_mut(width);
+            #[cfg(target_arch = "wasm32")]
+            let rows = scratch.chunks_mut(width);
+
+            rows.enumerate().for_each(|(y, row)| {
+                for (x, cell) in row.iter_mut().enumerate() {
+                    *cell = self.next_cell(x, y);
+                }
+            });
+
+            let mut counts: Counts = ([0; 2], 0, 0);
+            for (old, new) in self.cells.iter().zip(scratch.iter()) {
+                if let Some(s) = new {
+                    counts.0[*s as usize] += 1;
+                }
+                if old != new {
+                    counts.1 += 1;
+                    if old.is_none() {
+                        counts.2 += 1;
+                    }
+                }
+            }
+            if let Ok(mut next) = self.next_counts.lock() {
+                *next = counts;
+            }
+        }
+    }
+
+    fn commit_step(&mut self) {
+        if self.stale.is_stale() {
+            self.prepare_step();
+        }
+        self.stale.committed();
+        if let (Ok(scratch), Ok(counts)) = (self.scratch.get_mut(), self.next_counts.get_mut()) {
+            std::mem::swap(&mut self.cells, scratch);
+            (self.populations, self.changed, self.born) = *counts;
+        }
+    }
+
+    fn changed_cells(&self) -> usize {
+        self.changed
+    }
+
+    fn born_cells(&self) -> usize {
+        self.born
+    }
+
+    fn resize(&mut self, width: usize, height: usize, anchor: Anchor) {
+        let (ox, oy) = anchor.offset((self.width, self.height), (width, height));
+        let mut cells = vec![None; width * height];
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let (nx, ny) = (x as isize + ox, y as isize + oy);
+                if nx >= 0 && ny >= 0 && nx < width as isize && ny < height as isize {
+                    cells[ny as usize * width + nx as usize] = self.cells[y * self.width + x];
+                }
+            }
+        }
+        self.populations = [0; 2];
+        for s in cells.iter().flatten() {
+            self.populations[*s as usize] += 1;
+        }
+        self.cells = cells;
+        self.scratch = Mutex::new(vec![None; width * height]);
+        (self.width, self.height) = (width, height);
+        self.changed = 0;
+        self.born = 0;
+        self.stale.bump();
+    }
+
+    /// As the other backends, then [`PREDATOR_SHARE`] of the live cells
+    /// become predators.
+    fn randomize(&mut self, density: f64, seed: u64) {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let mut predators = ChaCha8Rng::seed_from_u64(seed);
+        predators.set_stream(1);
+        for y in 0..self.height {
+            rng.set_word_pos(2 * (y * self.width) as u128);
+            for x in 0..self.width {
+                let alive = rng.gen_bool(density.clamp(0.0, 1.0));
+                let cell = alive.then(|| if predators.gen_bool(PREDATOR_SHARE) { Species::Predator } else { Species::Prey });
+                let old = self.cells[y * self.width + x];
+                self.count(old, cell);
+                self.cells[y * self.width + x] = cell;
+            }
+        }
+        self.stale.bump();
+    }
+
+    fn population(&self) -> usize {
+        self.populations[0] + self.populations[1]
+    }
+}
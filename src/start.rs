@@ -0,0 +1,369 @@
+//! Reads the options, config file and session, builds the starting
+//! universe, and hands it to the headless runner or the window.
+
+use std::io::Read;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+
+use clap::Parser;
+use tracing_subscriber::prelude::*;
+
+#[cfg(feature = "gif")]
+use rustlife::camera::{CameraPath, SharedCamera};
+use rustlife::checkpoint::{self, Checkpoint};
+use rustlife::memory::Estimate;
+use rustlife::period;
+use rustlife::plugin::PluginRegistry;
+#[cfg(feature = "gui")]
+use rustlife::replay::Recorder;
+use rustlife::replay::{Player, Replay, Start};
+use rustlife::script::{ScriptHost, SharedLife};
+use rustlife::volume::{self, SharedVolume, Volume};
+use rustlife::{builder, elementary, engine, io, text, EventBus, GenerationEvent, LifeEngine};
+
+#[cfg(feature = "gif")]
+use crate::animation::{Animation, SharedAnimation};
+use crate::error::{log_error, AppError};
+use crate::session::Session;
+use crate::{affinity, cli, config, headless};
+
+/// Builds the starting universe: either the pattern given on the command
+/// line centred in an empty grid, or a random soup from `seed`.  Also returns
+/// how it was started, for recording.
+fn initial_universe(config: &config::Config, args: &cli::Args, seed: u64) -> Result<(Box<dyn LifeEngine>, Start), String> {
+    let mut builder = config.simulation()?;
+
+    let mut plugins = PluginRegistry::new();
+    for path in &args.plugins {
+        // Plugins are explicitly requested on the command line and trusted
+        let name = unsafe { plugins.load(path)? };
+        log::info!("Loaded rule plugin '{}' from {}", name, path.display());
+    }
+    if let Some(name) = &args.rule_plugin {
+        let rule = plugins.get(name)
+            .ok_or_else(|| format!("no plugin rule named '{}', loaded: {}", name, plugins.names().join(", ")))?;
+        builder = builder.custom_rule(rule);
+    }
+
+    let text = match &args.pattern {
+        Some(path) => Some(std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?),
+        None if args.stdin => {
+            let mut text = String::new();
+            std::io::stdin().read_to_string(&mut text).map_err(|e| format!("stdin: {}", e))?;
+            Some(text)
+        }
+        None => None
+    };
+    if let Some(bits) = &args.tape {
+        let mut life = builder.build()?;
+        let live = elementary::load_tape(life.as_mut(), bits)?;
+        return Ok((life, Start::Pattern { x: 0, y: 0, pattern: io::cells_to_plaintext(&live) }));
+    }
+    let builder = match text {
+        Some(text) => builder.pattern(&io::parse_pattern(&text)?),
+        None => builder.start(config.soup(seed))
+    };
+    let start = builder.starting().cloned().expect("a pattern or soup to start from");
+    Ok((builder.build()?, start))
+}
+
+/// Runs the script's `on_generation` for every event, switching it off after
+/// the first error rather than reporting the same error every generation.
+fn script_listener(script: ScriptHost) -> impl FnMut(&GenerationEvent) + Send {
+    let mut script = Some(script);
+    move |event| {
+        if let Some(s) = script.as_mut() {
+            if let Err(e) = s.on_generation(event.generation) {
+                log::error!("Script error, disabling script: {}", e);
+                script = None;
+            }
+        }
+    }
+}
+
+pub fn run() -> Result<(), AppError> {
+    env_logger::init();
+
+    let args = cli::Args::parse();
+
+    // Flushes the trace file when dropped
+    #[cfg_attr(not(feature = "gui"), allow(unused_variables))]
+    let trace_guard = args.trace_chrome.as_ref().map(|path| {
+        let (chrome, guard) = tracing_chrome::ChromeLayerBuilder::new()
+            .file(path)
+            .include_args(true)
+            .build();
+        // Not `init()`, which would also try to take over the `log` facade from env_logger
+        if let Err(e) = tracing::subscriber::set_global_default(tracing_subscriber::registry().with(chrome)) {
+            log_error("Could not install trace recorder", &e);
+        }
+        guard
+    });
+    let config_path = args.config.clone().or_else(config::Config::default_path);
+    let mut config = match &config_path {
+        Some(path) => config::Config::load(path)?,
+        None => config::Config::default()
+    };
+    let session_path = if args.no_session { None } else { Session::default_path() };
+    let session = session_path.as_deref().map(Session::load).unwrap_or_default();
+    session.apply_to(&mut config);
+    config.apply_args(&args);
+    #[cfg(feature = "gui")]
+    let power_save = !args.headless && crate::power::saving(args.power_save.or(config.power_save));
+    #[cfg(feature = "gui")]
+    let limits = crate::power::Limits::new(&config, &args, power_save);
+    #[cfg(feature = "gui")]
+    let threads = limits.threads;
+    #[cfg(not(feature = "gui"))]
+    let threads = config.threads;
+
+    if config.pin_threads {
+        match affinity::allowed_cores() {
+            Ok(cores) => {
+                let pool = rayon::ThreadPoolBuilder::new()
+                    .num_threads(if threads > 0 { threads } else { cores.len() })
+                    .start_handler(move |i| {
+                        if let Err(e) = affinity::pin(cores[i % cores.len()]) {
+                            log::warn!("Could not pin thread {}: {}", i, e);
+                        }
+                    })
+                    .build_global();
+                match pool {
+                    Ok(()) => engine::set_strips(true),
+                    Err(e) => log_error("Could not start pinned threads", &e)
+                }
+            }
+            Err(e) => log::warn!("Not pinning threads: {}", e)
+        }
+    }
+    if threads > 0 && !engine::strips() {
+        if let Err(e) = builder::set_threads(threads) {
+            log::error!("Could not set thread count: {}", e);
+        }
+    }
+
+    if let Some(soups) = args.census {
+        headless::census(soups, config.seed.unwrap_or_else(rand::random), config.symmetry, config.rule()?, args.output.as_deref())?;
+        return Ok(());
+    }
+    if let Some(generations) = args.scaling {
+        // The same soup every time, scaled to each size
+        let seed = config.seed.unwrap_or_else(rand::random);
+        let universe = |side| {
+            let mut config = config.clone();
+            (config.grid_width, config.grid_height) = (side, side);
+            Ok(config.simulation()?.start(config.soup(seed)))
+        };
+        headless::scaling(generations, args.scaling_max, universe, args.json, args.output.as_deref())?;
+        return Ok(());
+    }
+    if let Some([input, output]) = args.convert.as_deref() {
+        headless::convert(input, output, args.format, args.trim, args.canonical, args.rule)?;
+        return Ok(());
+    }
+    if let Some(path) = &args.predecessor {
+        headless::predecessor(path, config.rule()?, args.output.as_deref())?;
+        return Ok(());
+    }
+    if let Some(generations) = args.evolve {
+        let seed = config.seed.unwrap_or_else(rand::random);
+        headless::evolve(generations, args.evolve_size as usize, args.fitness, args.evolve_keep, seed, config.rule()?, args.output.as_deref())?;
+        return Ok(());
+    }
+    if let Some(rules) = args.explore {
+        headless::explore(rules, args.explore_keep, config.seed.unwrap_or_else(rand::random), args.output.as_deref())?;
+        return Ok(());
+    }
+    if let Some(engine) = &args.verify {
+        let seed = config.seed.unwrap_or_else(rand::random);
+        let life = config.simulation()?.backend("dense").start(config.soup(seed)).build()?;
+        headless::verify([engine, &args.verify_against], life.as_ref(), seed, args.generations, args.verify_every, args.output.as_deref())?;
+        return Ok(());
+    }
+    if let Some(seeds) = args.hunt {
+        headless::hunt(seeds, args.hunt_cells as usize, args.hunt_keep, config.seed.unwrap_or_else(rand::random), config.rule()?, args.output.as_deref())?;
+        return Ok(());
+    }
+
+    #[cfg(feature = "tui")]
+    let terminal = args.tui;
+    #[cfg(not(feature = "tui"))]
+    #[cfg_attr(feature = "gui", allow(unused_variables))]
+    let terminal = false;
+
+    #[cfg(not(feature = "gui"))]
+    if !args.headless && !terminal {
+        return Err("this build has no window, so it only does --headless, --census and --hunt runs; build with the gui feature for the rest".to_string().into());
+    }
+
+    let replay = args.replay.as_deref().map(Replay::load).transpose()?;
+    #[cfg(feature = "gui")]
+    let mut joined = None;
+    let (engine, start) = match (&replay, &args.join) {
+        (Some(replay), _) => {
+            // The size comes from the file, so it is checked like one asked for
+            if let Some(estimate) = Estimate::new(&replay.backend, replay.width, replay.height) {
+                estimate.check(config.memory_limit()).map_err(|e| format!("{}x{} replay: {}; raise --max-memory to play it", replay.width, replay.height, e))?;
+            }
+            (replay.initial_universe()?, replay.start.clone())
+        }
+        #[cfg(feature = "gui")]
+        (None, Some(addr)) => {
+            let (client, life) = crate::collab::join(addr)?;
+            joined = Some(client);
+            let (x, y) = life.bounding_box().map_or((0, 0), |bb| (bb.min_x, bb.min_y));
+            let start = Start::Pattern { x, y, pattern: io::to_plaintext(life.as_ref()) };
+            (life, start)
+        }
+        (None, _) => {
+            // Before anything is allocated, so a grid too big for the machine
+            // is refused rather than running out of memory
+            if let Some(estimate) = config.memory(!args.headless && !terminal) {
+                log::info!("The universe will take {}", estimate);
+                estimate.check(config.memory_limit()).map_err(|e| format!("{}; make the grid smaller or raise --max-memory", e))?;
+            }
+            initial_universe(&config, &args, config.seed.unwrap_or_else(rand::random))?
+        }
+    };
+    let seed = match start {
+        Start::Soup { seed, .. } => Some(seed),
+        Start::Pattern { .. } => None
+    };
+    #[cfg(feature = "gui")]
+    let recorder = args.record.as_ref().map(|_| Arc::new(Mutex::new(Recorder::new(engine.as_ref(), start))));
+    let player = replay.as_ref().map(Player::new);
+    #[cfg_attr(not(any(feature = "gui", feature = "stream", feature = "api", feature = "gif")), allow(unused_variables))]
+    let theme = config.theme()?;
+    // A theme file is read again whenever it is saved
+    #[cfg(feature = "gui")]
+    let theme_watch = config.theme_file().map(config::ThemeWatch::new);
+
+    let life: SharedLife = Arc::new(RwLock::new(engine));
+    #[cfg(feature = "gui")]
+    if let Some(client) = joined.as_mut() {
+        client.follow(Arc::clone(&life))?;
+    }
+    let script = match &args.script {
+        Some(path) => {
+            let source = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+            let mut host = ScriptHost::new(Arc::clone(&life), &source)
+                .map_err(|e| format!("{}: {}", path.display(), e))?;
+            host.on_start().map_err(|e| format!("{}: {}", path.display(), e))?;
+            Some(host)
+        }
+        None => None
+    };
+
+    // Keyframes from the script as well as the camera file
+    #[cfg(feature = "gif")]
+    let camera = script.as_ref().map_or_else(SharedCamera::default, ScriptHost::camera);
+
+    let mut events = EventBus::new();
+    if let Some(script) = script {
+        events.subscribe(script_listener(script));
+    }
+
+    #[cfg(feature = "stream")]
+    let streaming = match &args.stream {
+        Some(addr) => {
+            let frames = crate::stream::serve(addr, args.stream_fps, config.window_width, config.window_height, theme, Arc::clone(&life))?;
+            events.subscribe(frames);
+            true
+        }
+        None => false
+    };
+    #[cfg(not(feature = "stream"))]
+    let streaming = false;
+
+    if let Some(target) = &args.osc {
+        events.subscribe(crate::osc::start(target, args.osc_rate, Arc::clone(&life))?);
+    }
+
+    #[cfg(feature = "tui")]
+    if terminal {
+        return crate::tui::run(&life, &mut events, &config).map_err(Into::into);
+    }
+
+    if args.headless {
+        // Only hold back a headless run when someone is watching it
+        let speed = if streaming { config.speed } else { 0 };
+        let recording = match &args.volume {
+            Some(_) => {
+                let v = life.read().map_err(|e| e.to_string()).map(|l| {
+                    let mut v = Volume::centred(l.width(), l.height());
+                    v.record(l.as_ref());
+                    v
+                })?;
+                let v: SharedVolume = Arc::new(Mutex::new(v));
+                events.subscribe(volume::recorder(Arc::clone(&v), Arc::clone(&life)));
+                Some(v)
+            }
+            None => None
+        };
+        if let Some(every) = args.print_every {
+            events.subscribe(text::printer(Arc::clone(&life), every, args.print_window, args.print_glyphs, std::io::stdout()));
+        }
+        let mut first = 1;
+        if let Some(dir) = &args.checkpoint_dir {
+            if args.resume {
+                match Checkpoint::latest(dir)? {
+                    Some(checkpoint) => {
+                        checkpoint.restore(life.write().map_err(|e| e.to_string())?.as_mut()).map_err(|e| format!("{}: {}", dir.display(), e))?;
+                        log::info!("Carrying on from generation {}", checkpoint.generation());
+                        first = checkpoint.generation() + 1;
+                    }
+                    None => log::info!("No checkpoint in {} yet, starting from the beginning", dir.display())
+                }
+            }
+            let interval = args.checkpoint_minutes.map(|m| Duration::from_secs(m * 60));
+            events.subscribe(checkpoint::writer(Arc::clone(&life), dir.clone(), args.checkpoint_every, interval));
+        }
+        if config.detect_cycles {
+            events.subscribe(period::reporter(Arc::clone(&life), config.cycle_every));
+        }
+        #[cfg(feature = "gif")]
+        let animation = match &args.gif {
+            Some(path) => {
+                if let Some(file) = &args.camera {
+                    let mut c = camera.lock().map_err(|e| e.to_string())?;
+                    for &keyframe in CameraPath::load(file)?.keyframes() {
+                        c.add(keyframe)?;
+                    }
+                }
+                let mut a = Animation::create(path, (config.window_width, config.window_height), args.gif_fps, theme)?
+                    .every(args.gif_every)
+                    .camera(Arc::clone(&camera));
+                a.record(first - 1, life.read().map_err(|e| e.to_string())?.as_ref())?;
+                let a: SharedAnimation = Arc::new(Mutex::new(a));
+                events.subscribe(crate::animation::recorder(Arc::clone(&a), Arc::clone(&life)));
+                Some(a)
+            }
+            None => None
+        };
+        headless::run(&life, &mut events, player, seed, first..=args.generations, speed, args.output.as_deref())?;
+        if let Some(dir) = &args.checkpoint_dir {
+            let l = life.read().map_err(|e| e.to_string())?;
+            Checkpoint::of(args.generations.max(first - 1), l.as_ref()).save(dir)?;
+        }
+        if let (Some(path), Some(v)) = (&args.volume, recording) {
+            v.lock().map_err(|e| e.to_string())?.save(path)?;
+        }
+        #[cfg(feature = "gif")]
+        if let (Some(path), Some(a)) = (&args.gif, animation) {
+            let frames = a.lock().map_err(|e| e.to_string())?.finish()?;
+            log::info!("Recorded {} frames to {}", frames, path.display());
+        }
+        #[cfg(feature = "api")]
+        if let Some(addr) = &args.api {
+            crate::api::serve(addr, &life, &mut events, args.generations, theme)?;
+        }
+        return Ok(());
+    }
+
+    #[cfg(feature = "gui")]
+    return crate::desktop::run(crate::desktop::Launch {
+        args, config, config_path, session, session_path, trace_guard, life, events, player, recorder, joined, seed, theme, theme_watch, power_save, limits
+    });
+    #[cfg(not(feature = "gui"))]
+    unreachable!("only headless runs get this far");
+}
@@ -0,0 +1,404 @@
+//! Population history: population, births and deaths for every recent
+//! generation, kept in a ring buffer that overlays, CSV export and anything
+//! watching for the universe to settle can read.  Recording costs one short
+//! lock per generation on the stepping thread.
+//!
+//! Activity metrics look at the cells themselves, so they cost a pass over
+//! the grid and are only taken when asked for: the spatial entropy of the
+//! 2x2 blocks, which falls as a soup turns into sparse debris, and the area
+//! of the [`TILE`] sized tiles that changed since the last measurement.
+
+use std::collections::VecDeque;
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+
+use crate::engine::{BoundingBox, CellState, LifeEngine};
+use crate::events::GenerationEvent;
+
+pub const DEFAULT_CAPACITY: usize = 4096;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Sample {
+    pub generation: u64,
+    pub population: usize,
+    pub births: usize,
+    pub deaths: usize,
+}
+
+impl From<&GenerationEvent> for Sample {
+    fn from(event: &GenerationEvent) -> Self {
+        Sample { generation: event.generation, population: event.population, births: event.births, deaths: event.deaths() }
+    }
+}
+
+/// The last `capacity` samples, oldest first.
+pub struct PopulationHistory {
+    samples: VecDeque<Sample>,
+    capacity: usize,
+}
+
+pub type SharedHistory = Arc<Mutex<PopulationHistory>>;
+
+impl PopulationHistory {
+    pub fn new(capacity: usize) -> Self {
+        PopulationHistory { samples: VecDeque::with_capacity(capacity), capacity: capacity.max(1) }
+    }
+
+    pub fn record(&mut self, sample: Sample) {
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    pub fn latest(&self) -> Option<&Sample> {
+        self.samples.back()
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &Sample> + ExactSizeIterator {
+        self.samples.iter()
+    }
+
+    /// `generation,population,births,deaths` with a header row.
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from("generation,population,births,deaths\n");
+        for s in &self.samples {
+            let _ = writeln!(out, "{},{},{},{}", s.generation, s.population, s.births, s.deaths);
+        }
+        out
+    }
+}
+
+impl Default for PopulationHistory {
+    fn default() -> Self {
+        PopulationHistory::new(DEFAULT_CAPACITY)
+    }
+}
+
+/// Event listener that records every generation into `history`.
+pub fn recorder(history: SharedHistory) -> impl FnMut(&GenerationEvent) + Send {
+    move |event| {
+        if let Ok(mut h) = history.lock() {
+            h.record(event.into());
+        }
+    }
+}
+
+/// Side of the square tiles activity is measured over; one tile fits a u64.
+pub const TILE: usize = 8;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Activity {
+    pub generation: u64,
+    /// Cells that changed state in this step
+    pub changed: usize,
+    /// Shannon entropy of the 2x2 blocks in bits, from 0 for a uniform
+    /// grid to 4 for a random one
+    pub entropy: f64,
+    /// Cells in tiles that changed since the last measurement
+    pub active_area: usize,
+    /// Cells in the grid, for showing the active area as a fraction
+    pub area: usize,
+}
+
+/// Each tile as a bitmask, row by row, bit `y * TILE + x` for the cell at
+/// (x, y) within the tile.
+fn tiles(life: &dyn LifeEngine) -> Vec<u64> {
+    let (w, h) = (life.width(), life.height());
+    let mut masks = Vec::with_capacity(w.div_ceil(TILE) * h.div_ceil(TILE));
+    for ty in (0..h).step_by(TILE) {
+        for tx in (0..w).step_by(TILE) {
+            let mut mask = 0;
+            for y in ty..(ty + TILE).min(h) {
+                for x in tx..(tx + TILE).min(w) {
+                    if life.get(x, y) == CellState::Alive {
+                        mask |= 1 << ((y - ty) * TILE + x - tx);
+                    }
+                }
+            }
+            masks.push(mask);
+        }
+    }
+    masks
+}
+
+/// Entropy of the aligned 2x2 blocks in `masks`.  Blocks hanging over the
+/// edge of the grid count their missing cells as dead.
+fn block_entropy(masks: &[u64]) -> f64 {
+    let mut counts = [0usize; 16];
+    for &mask in masks {
+        for by in (0..TILE).step_by(2) {
+            for bx in (0..TILE).step_by(2) {
+                let bit = |x: usize, y: usize| ((mask >> (y * TILE + x)) & 1) as usize;
+                counts[bit(bx, by) | bit(bx + 1, by) << 1 | bit(bx, by + 1) << 2 | bit(bx + 1, by + 1) << 3] += 1;
+            }
+        }
+    }
+    let total = counts.iter().sum::<usize>().max(1) as f64;
+    counts.iter().filter(|&&n| n > 0).map(|&n| {
+        let p = n as f64 / total;
+        -p * p.log2()
+    }).sum()
+}
+
+/// Spatial entropy of the universe, see [`Activity::entropy`].
+pub fn entropy(life: &dyn LifeEngine) -> f64 {
+    block_entropy(&tiles(life))
+}
+
+/// Takes [`Activity`] measurements, remembering the tiles in between.
+#[derive(Default)]
+pub struct ActivityMeter {
+    previous: Vec<u64>,
+}
+
+impl ActivityMeter {
+    pub fn new() -> Self {
+        ActivityMeter::default()
+    }
+
+    /// Forgets the last measurement, so the next counts every tile with live
+    /// cells as active.
+    pub fn clear(&mut self) {
+        self.previous.clear();
+    }
+
+    pub fn measure(&mut self, event: &GenerationEvent, life: &dyn LifeEngine) -> Activity {
+        let (w, h) = (life.width(), life.height());
+        let masks = tiles(life);
+        if self.previous.len() != masks.len() {
+            self.previous = vec![0; masks.len()];
+        }
+        let across = w.div_ceil(TILE);
+        let active_area = masks.iter().zip(&self.previous).enumerate()
+            .filter(|(_, (now, before))| now != before)
+            .map(|(i, _)| {
+                let (tx, ty) = ((i % across) * TILE, (i / across) * TILE);
+                (w - tx).min(TILE) * (h - ty).min(TILE)
+            })
+            .sum();
+        let activity = Activity { generation: event.generation, changed: event.changed, entropy: block_entropy(&masks), active_area, area: w * h };
+        self.previous = masks;
+        activity
+    }
+}
+
+/// How many generations each cell has been alive, for a long-exposure view.
+#[derive(Clone, Debug, Default)]
+pub struct Heatmap {
+    pub width: usize,
+    pub height: usize,
+    /// Row by row
+    pub counts: Vec<u32>,
+    pub generations: u32,
+}
+
+impl Heatmap {
+    pub fn new() -> Self {
+        Heatmap::default()
+    }
+
+    pub fn clear(&mut self) {
+        self.counts.iter_mut().for_each(|c| *c = 0);
+        self.generations = 0;
+    }
+
+    /// Adds the live cells of `life`, starting over if its size changed.
+    pub fn record(&mut self, life: &dyn LifeEngine) {
+        let (w, h) = (life.width(), life.height());
+        if (w, h) != (self.width, self.height) {
+            *self = Heatmap { width: w, height: h, counts: vec![0; w * h], generations: 0 };
+        }
+        for y in 0..h {
+            for x in 0..w {
+                if life.get(x, y) == CellState::Alive {
+                    self.counts[y * w + x] += 1;
+                }
+            }
+        }
+        self.generations += 1;
+    }
+
+    /// Fraction of the recorded generations the cell was alive for.
+    pub fn get(&self, x: usize, y: usize) -> f64 {
+        match self.counts.get(y * self.width + x) {
+            Some(&c) if x < self.width && self.generations > 0 => c as f64 / self.generations as f64,
+            _ => 0.0
+        }
+    }
+}
+
+/// How many generations in a row each cell has been alive, for drawing
+/// old cells taller.
+#[derive(Clone, Debug, Default)]
+pub struct Ages {
+    pub width: usize,
+    pub height: usize,
+    /// Row by row, 0 for dead cells
+    pub ages: Vec<u16>,
+}
+
+impl Ages {
+    pub fn new() -> Self {
+        Ages::default()
+    }
+
+    pub fn clear(&mut self) {
+        self.ages.iter_mut().for_each(|a| *a = 0);
+    }
+
+    /// Ages the live cells of `life` by a generation and forgets the dead
+    /// ones, starting over if its size changed.
+    pub fn record(&mut self, life: &dyn LifeEngine) {
+        let (w, h) = (life.width(), life.height());
+        if (w, h) != (self.width, self.height) {
+            *self = Ages { width: w, height: h, ages: vec![0; w * h] };
+        }
+        for y in 0..h {
+            for x in 0..w {
+                let age = &mut self.ages[y * w + x];
+                *age = if life.get(x, y) == CellState::Alive { age.saturating_add(1) } else { 0 };
+            }
+        }
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> u16 {
+        match self.ages.get(y * self.width + x) {
+            Some(&a) if x < self.width => a,
+            _ => 0
+        }
+    }
+}
+
+/// A named rectangle of the grid, tracked on its own.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Region {
+    pub name: String,
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Region {
+    pub fn bounds(&self) -> BoundingBox {
+        BoundingBox { min_x: self.x, min_y: self.y, max_x: self.x + self.width.max(1) - 1, max_y: self.y + self.height.max(1) - 1 }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct RegionSample {
+    pub generation: u64,
+    pub population: usize,
+    /// Cells in the region that changed since the last sample
+    pub changed: usize,
+}
+
+struct Tracked {
+    region: Region,
+    // The region's cells at the last sample, row by row
+    previous: Vec<bool>,
+    samples: VecDeque<RegionSample>,
+}
+
+/// The last `capacity` samples of every region, in the order they were added.
+pub struct RegionStats {
+    tracked: Vec<Tracked>,
+    capacity: usize,
+}
+
+pub type SharedRegions = Arc<Mutex<RegionStats>>;
+
+impl RegionStats {
+    pub fn new(capacity: usize) -> Self {
+        RegionStats { tracked: Vec::new(), capacity: capacity.max(1) }
+    }
+
+    /// Starts tracking `region`, replacing any region with the same name.
+    pub fn add(&mut self, region: Region) {
+        self.remove(&region.name);
+        self.tracked.push(Tracked { region, previous: Vec::new(), samples: VecDeque::new() });
+    }
+
+    /// Returns false if there was no region by that name.
+    pub fn remove(&mut self, name: &str) -> bool {
+        let before = self.tracked.len();
+        self.tracked.retain(|t| t.region.name != name);
+        self.tracked.len() != before
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tracked.is_empty()
+    }
+
+    pub fn regions(&self) -> impl Iterator<Item = &Region> {
+        self.tracked.iter().map(|t| &t.region)
+    }
+
+    pub fn latest(&self, name: &str) -> Option<&RegionSample> {
+        self.tracked.iter().find(|t| t.region.name == name)?.samples.back()
+    }
+
+    /// Forgets every sample, keeping the regions.
+    pub fn clear(&mut self) {
+        for t in &mut self.tracked {
+            t.previous.clear();
+            t.samples.clear();
+        }
+    }
+
+    /// Samples every region.  Parts of a region off the grid count as dead.
+    pub fn record(&mut self, generation: u64, life: &dyn LifeEngine) {
+        for t in &mut self.tracked {
+            let Region { x, y, width, height, .. } = t.region;
+            let cells: Vec<bool> = (y..y + height)
+                .flat_map(|cy| (x..x + width).map(move |cx| (cx, cy)))
+                .map(|(cx, cy)| cx < life.width() && cy < life.height() && life.get(cx, cy) == CellState::Alive)
+                .collect();
+            let changed = if t.previous.len() == cells.len() {
+                cells.iter().zip(&t.previous).filter(|(a, b)| a != b).count()
+            } else {
+                0
+            };
+            if t.samples.len() == self.capacity {
+                t.samples.pop_front();
+            }
+            t.samples.push_back(RegionSample { generation, population: cells.iter().filter(|c| **c).count(), changed });
+            t.previous = cells;
+        }
+    }
+
+    /// `generation,region,population,changed` with a header row, one row
+    /// per region and generation.
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from("generation,region,population,changed\n");
+        let mut rows: Vec<(u64, usize, &RegionSample)> = self.tracked.iter().enumerate()
+            .flat_map(|(i, t)| t.samples.iter().map(move |s| (s.generation, i, s)))
+            .collect();
+        rows.sort_by_key(|&(generation, i, _)| (generation, i));
+        for (_, i, s) in rows {
+            let _ = writeln!(out, "{},{},{},{}", s.generation, self.tracked[i].region.name, s.population, s.changed);
+        }
+        out
+    }
+}
+
+impl Default for RegionStats {
+    fn default() -> Self {
+        RegionStats::new(DEFAULT_CAPACITY)
+    }
+}
@@ -0,0 +1,98 @@
+//! Conditions that stop a run instead of letting it spin forever: the
+//! population dying out, the universe repeating a state, or a target
+//! generation.  Each fires once when it becomes true, so a run paused by
+//! one can be resumed.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::engine::LifeEngine;
+use crate::events::GenerationEvent;
+use crate::period::{Cycle, CycleDetector};
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(default)]
+pub struct StopConditions {
+    /// Stop when the population reaches zero
+    pub extinction: bool,
+    /// Stop when the whole grid repeats, as a still life or oscillator
+    pub repeat: bool,
+    /// Stop on reaching this generation
+    pub generation: Option<u64>,
+}
+
+impl StopConditions {
+    pub fn any(&self) -> bool {
+        self.extinction || self.repeat || self.generation.is_some()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum StopReason {
+    Extinct,
+    /// With the period, 1 for a still life
+    Repeating(u64),
+    Reached(u64),
+}
+
+impl fmt::Display for StopReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StopReason::Extinct => write!(f, "Extinct"),
+            StopReason::Repeating(1) => write!(f, "Stabilized"),
+            StopReason::Repeating(p) => write!(f, "Oscillating with period {}", p),
+            StopReason::Reached(g) => write!(f, "Reached generation {}", g),
+        }
+    }
+}
+
+/// Checks [`StopConditions`] after every generation.
+pub struct StopChecker {
+    pub conditions: StopConditions,
+    cycles: CycleDetector,
+    alive: bool,
+}
+
+impl StopChecker {
+    pub fn new(conditions: StopConditions) -> Self {
+        StopChecker { conditions, cycles: CycleDetector::new(1), alive: true }
+    }
+
+    /// Hashes the grid for `repeat` only every `every` generations, which
+    /// finds the repeat a little later but spares time on big grids.
+    pub fn every(mut self, every: u64) -> Self {
+        self.cycles = CycleDetector::new(every);
+        self
+    }
+
+    /// The cycle `repeat` last found the universe in, if it is still in it.
+    pub fn cycle(&self) -> Option<Cycle> {
+        self.cycles.cycle()
+    }
+
+    /// Forgets the run so far, for when the universe has been replaced, so
+    /// a cycle or extinction is only found in what comes after.
+    pub fn restart(&mut self) {
+        self.cycles.clear();
+        self.alive = true;
+    }
+
+    /// Returns why the run should stop, if one of the conditions has just
+    /// become true.  Only hashes the grid when `repeat` is set.
+    pub fn check(&mut self, event: &GenerationEvent, life: &dyn LifeEngine) -> Option<StopReason> {
+        let was_alive = std::mem::replace(&mut self.alive, event.population > 0);
+        if self.conditions.extinction && was_alive && !self.alive {
+            return Some(StopReason::Extinct);
+        }
+        if self.conditions.generation == Some(event.generation) {
+            return Some(StopReason::Reached(event.generation));
+        }
+        if self.conditions.repeat {
+            if let Some(cycle) = self.cycles.observe(event, life) {
+                return Some(StopReason::Repeating(cycle.period));
+            }
+        }
+        None
+    }
+}
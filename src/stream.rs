@@ -0,0 +1,100 @@
+//! Live MJPEG stream of the universe over HTTP, built with
+//! `--features stream` and started with `--stream <addr>`.  Any number of
+//! spectators can open `http://<addr>/` in a browser (or point an `<img>`
+//! at it) to watch.  Frames are rendered from generation events, at most
+//! `fps` times a second.
+
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use jpeg_encoder::{ColorType, Encoder};
+
+use rustlife::script::SharedLife;
+use rustlife::GenerationEvent;
+
+use crate::render::{self, PixelMap, Theme, View};
+
+const JPEG_QUALITY: u8 = 80;
+
+/// The most recent frame, numbered so spectators can wait for the next one.
+#[derive(Default)]
+struct Latest {
+    number: u64,
+    jpeg: Arc<Vec<u8>>,
+}
+
+type Shared = Arc<(Mutex<Latest>, Condvar)>;
+
+/// Listens on `addr` and returns the event listener that renders frames
+/// from `life` at `width` x `height` pixels.
+pub fn serve(addr: &str, fps: u32, width: u32, height: u32, theme: Theme, life: SharedLife) -> Result<impl FnMut(&GenerationEvent) + Send, String> {
+    let listener = TcpListener::bind(addr).map_err(|e| format!("{}: {}", addr, e))?;
+    log::info!("Streaming on http://{}/", addr);
+    let latest: Shared = Arc::default();
+
+    let c_latest = Arc::clone(&latest);
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let latest = Arc::clone(&c_latest);
+            thread::spawn(move || {
+                // Spectators leaving is not worth reporting
+                let _ = spectator(stream, &latest);
+            });
+        }
+    });
+
+    let interval = Duration::from_secs_f64(1.0 / fps.max(1) as f64);
+    let mut last_frame: Option<Instant> = None;
+    let mut screen = vec![0u8; width as usize * height as usize * 4];
+    let mut pixel_map = PixelMap::default();
+    Ok(move |_: &GenerationEvent| {
+        if last_frame.is_some_and(|t| t.elapsed() < interval) {
+            return;
+        }
+        last_frame = Some(Instant::now());
+        if let Ok(l) = life.read() {
+            pixel_map.update(width, height, &View::whole((l.width(), l.height())));
+            render::draw(&mut screen, l.as_ref(), &theme, &pixel_map);
+        }
+        let mut jpeg = Vec::new();
+        if let Err(e) = Encoder::new(&mut jpeg, JPEG_QUALITY).encode(&screen, width as u16, height as u16, ColorType::Rgba) {
+            log::error!("Could not encode frame: {}", e);
+            return;
+        }
+        let (frame, ready) = &*latest;
+        if let Ok(mut f) = frame.lock() {
+            f.number += 1;
+            f.jpeg = Arc::new(jpeg);
+            ready.notify_all();
+        }
+    })
+}
+
+fn spectator(mut stream: TcpStream, latest: &Shared) -> std::io::Result<()> {
+    // Whatever was asked for, the answer is the stream
+    let mut request = [0u8; 1024];
+    let _ = stream.read(&mut request)?;
+    stream.write_all(b"HTTP/1.1 200 OK\r\n\
+        Content-Type: multipart/x-mixed-replace; boundary=frame\r\n\
+        Cache-Control: no-cache\r\n\
+        Connection: close\r\n\r\n")?;
+
+    let (frame, ready) = &**latest;
+    let mut seen = 0;
+    loop {
+        let jpeg = {
+            let mut f = frame.lock().map_err(|_| std::io::ErrorKind::Other)?;
+            while f.number == seen {
+                f = ready.wait(f).map_err(|_| std::io::ErrorKind::Other)?;
+            }
+            seen = f.number;
+            Arc::clone(&f.jpeg)
+        };
+        write!(stream, "--frame\r\nContent-Type: image/jpeg\r\nContent-Length: {}\r\n\r\n", jpeg.len())?;
+        stream.write_all(&jpeg)?;
+        stream.write_all(b"\r\n")?;
+    }
+}
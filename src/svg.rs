@@ -0,0 +1,100 @@
+//! Patterns drawn as SVG, for figures that stay sharp at any size.  Square
+//! cells are joined into one rectangle per run along a row, so no seams show
+//! between neighbours when the drawing is scaled; round cells are a circle
+//! each.
+
+use std::fmt::{self, Write};
+use std::str::FromStr;
+
+use crate::io;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Shape {
+    #[default]
+    Square,
+    Circle,
+}
+
+impl FromStr for Shape {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "square" | "squares" => Ok(Shape::Square),
+            "circle" | "circles" => Ok(Shape::Circle),
+            _ => Err(format!("unknown cell shape '{}', expected square or circle", s))
+        }
+    }
+}
+
+impl fmt::Display for Shape {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Shape::Square => "square",
+            Shape::Circle => "circle",
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Style {
+    /// Side of a cell, in pixels
+    pub cell_size: f64,
+    pub shape: Shape,
+    pub alive: [u8; 3],
+    /// Filled in behind the cells, or left transparent
+    pub background: Option<[u8; 3]>,
+    /// Empty cells around the pattern
+    pub margin: usize,
+}
+
+impl Default for Style {
+    /// Black squares on white, ten pixels a cell.
+    fn default() -> Self {
+        Style { cell_size: 10.0, shape: Shape::Square, alive: [0, 0, 0], background: Some([0xff, 0xff, 0xff]), margin: 1 }
+    }
+}
+
+fn colour([r, g, b]: [u8; 3]) -> String {
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+/// Draws `live` cells, cropped to the pattern, as an SVG document.
+pub fn write(live: &[(usize, usize)], style: &Style) -> String {
+    let (min_x, min_y) = live.iter().fold((usize::MAX, usize::MAX), |(mx, my), &(x, y)| (mx.min(x), my.min(y)));
+    let cropped: Vec<_> = live.iter().map(|&(x, y)| (x - min_x, y - min_y)).collect();
+    let (w, h) = io::extent(&cropped);
+    // By row, so runs along a row are next to each other
+    let mut cells: Vec<_> = cropped.iter().map(|&(x, y)| (y + style.margin, x + style.margin)).collect();
+    cells.sort_unstable();
+    cells.dedup();
+    let size = style.cell_size;
+    let (width, height) = ((w + 2 * style.margin) as f64 * size, (h + 2 * style.margin) as f64 * size);
+
+    let mut out = String::new();
+    // Writing to a String can't fail
+    let _ = writeln!(out, r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}">"#, width, height, width, height);
+    if let Some(background) = style.background {
+        let _ = writeln!(out, r#"<rect width="100%" height="100%" fill="{}"/>"#, colour(background));
+    }
+    match style.shape {
+        Shape::Square => {
+            let _ = writeln!(out, r#"<g fill="{}" shape-rendering="crispEdges">"#, colour(style.alive));
+            let mut i = 0;
+            while i < cells.len() {
+                let (y, x) = cells[i];
+                let run = cells[i..].iter().enumerate().take_while(|&(n, &c)| c == (y, x + n)).count();
+                let _ = writeln!(out, r#"<rect x="{}" y="{}" width="{}" height="{}"/>"#, x as f64 * size, y as f64 * size, run as f64 * size, size);
+                i += run;
+            }
+        }
+        Shape::Circle => {
+            let _ = writeln!(out, r#"<g fill="{}">"#, colour(style.alive));
+            for &(y, x) in &cells {
+                let _ = writeln!(out, r#"<circle cx="{}" cy="{}" r="{}"/>"#, (x as f64 + 0.5) * size, (y as f64 + 0.5) * size, size * 0.45);
+            }
+        }
+    }
+    out.push_str("</g>\n</svg>\n");
+    out
+}
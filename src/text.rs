@@ -0,0 +1,122 @@
+//! Grids drawn as text, for terminals and log files: a character a cell,
+//! half blocks holding two cells one above the other, or Braille holding a
+//! block of two by four.
+
+use std::fmt;
+use std::io::Write;
+use std::str::FromStr;
+
+use crate::engine::{BoundingBox, CellState, LifeEngine};
+use crate::events::GenerationEvent;
+use crate::script::SharedLife;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Glyphs {
+    /// `O` alive and `.` dead, as in plaintext patterns
+    Ascii,
+    #[default]
+    Blocks,
+    Braille,
+}
+
+impl Glyphs {
+    pub const ALL: [Glyphs; 3] = [Glyphs::Ascii, Glyphs::Blocks, Glyphs::Braille];
+
+    /// Cells across and down one character holds.
+    pub fn cells(&self) -> (usize, usize) {
+        match self {
+            Glyphs::Ascii => (1, 1),
+            Glyphs::Blocks => (1, 2),
+            Glyphs::Braille => (2, 4),
+        }
+    }
+
+    /// The next one along, for cycling through them.
+    pub fn next(&self) -> Glyphs {
+        match self {
+            Glyphs::Ascii => Glyphs::Blocks,
+            Glyphs::Blocks => Glyphs::Braille,
+            Glyphs::Braille => Glyphs::Ascii,
+        }
+    }
+}
+
+impl FromStr for Glyphs {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "ascii" => Ok(Glyphs::Ascii),
+            "blocks" => Ok(Glyphs::Blocks),
+            "braille" => Ok(Glyphs::Braille),
+            _ => Err(format!("unknown glyphs '{}', expected ascii, blocks or braille", s))
+        }
+    }
+}
+
+impl fmt::Display for Glyphs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Glyphs::Ascii => "ascii",
+            Glyphs::Blocks => "blocks",
+            Glyphs::Braille => "braille",
+        })
+    }
+}
+
+// Bit of each dot in a Braille character, by column then row
+const BRAILLE_DOTS: [[u32; 4]; 2] = [[0x01, 0x02, 0x04, 0x40], [0x08, 0x10, 0x20, 0x80]];
+
+/// The cells inside `window` as lines of text.  Cells past the edges of the
+/// grid are drawn dead.
+pub fn render(life: &dyn LifeEngine, window: BoundingBox, glyphs: Glyphs) -> Vec<String> {
+    let alive = |x: usize, y: usize| x < life.width() && y < life.height() && life.get(x, y) == CellState::Alive;
+    let (across, down) = glyphs.cells();
+    let columns = window.width().div_ceil(across);
+    let rows = window.height().div_ceil(down);
+    (0..rows).map(|row| {
+        let y = window.min_y + row * down;
+        (0..columns).map(|column| {
+            let x = window.min_x + column * across;
+            match glyphs {
+                Glyphs::Ascii => if alive(x, y) { 'O' } else { '.' },
+                Glyphs::Blocks => match (alive(x, y), alive(x, y + 1)) {
+                    (false, false) => ' ',
+                    (true, false) => '▀',
+                    (false, true) => '▄',
+                    (true, true) => '█',
+                },
+                Glyphs::Braille => {
+                    let mut bits = 0;
+                    for (dx, dots) in BRAILLE_DOTS.iter().enumerate() {
+                        for (dy, bit) in dots.iter().enumerate() {
+                            if alive(x + dx, y + dy) {
+                                bits |= bit;
+                            }
+                        }
+                    }
+                    char::from_u32(0x2800 + bits).unwrap_or(' ')
+                }
+            }
+        }).collect()
+    }).collect()
+}
+
+/// Event listener writing the cells inside `window`, or the whole grid, to
+/// `out` every `every` generations, each frame headed by its generation and
+/// population.
+pub fn printer(life: SharedLife, every: u64, window: Option<BoundingBox>, glyphs: Glyphs, mut out: impl Write + Send) -> impl FnMut(&GenerationEvent) + Send {
+    move |event| {
+        if event.generation % every.max(1) != 0 {
+            return;
+        }
+        let Ok(l) = life.read() else { return };
+        let whole = BoundingBox { min_x: 0, min_y: 0, max_x: l.width() - 1, max_y: l.height() - 1 };
+        let mut frame = format!("# generation {}, population {}\n", event.generation, event.population);
+        for line in render(l.as_ref(), window.unwrap_or(whole), glyphs) {
+            frame.push_str(&line);
+            frame.push('\n');
+        }
+        let _ = out.write_all(frame.as_bytes()).and_then(|_| out.flush());
+    }
+}
@@ -0,0 +1,126 @@
+//! Keyframes of a run every so many generations, for going back to any
+//! point in it.  Keyframes are compressed snapshots, so sparse patterns
+//! cost little.  A generation between keyframes is reached by restoring the
+//! one before it and stepping forward, which gives the same cells again on
+//! deterministic backends; noise, scripts and replays change the universe
+//! without the timeline knowing, so runs using them don't come back the
+//! same.
+//!
+//! Only whether cells are alive is kept, with the rule and the backend's
+//! parameters.  Walls, species and the levels of the continuous backends
+//! stay as they are when going back.
+
+use std::sync::{Arc, Mutex};
+
+use crate::engine::LifeEngine;
+use crate::replay::Settings;
+use crate::snapshot::Snapshot;
+
+/// Most keyframes kept.  Past it every other one is dropped and the
+/// interval doubles, so the whole run stays covered.
+pub const MAX_KEYFRAMES: usize = 64;
+pub const DEFAULT_INTERVAL: u64 = 100;
+
+struct Keyframe {
+    generation: u64,
+    /// Taken just after an edit, so it can't be thinned out
+    edited: bool,
+    grid: Snapshot,
+    /// The rule and parameters then, so stepping on from here goes as the
+    /// run did even if they have been changed since
+    settings: Settings,
+}
+
+pub struct Timeline {
+    interval: u64,
+    keyframes: Vec<Keyframe>,
+    /// Set by an edit, so the next generation is kept whatever the interval
+    edited: bool,
+}
+
+impl Default for Timeline {
+    fn default() -> Self {
+        Timeline::new(DEFAULT_INTERVAL)
+    }
+}
+
+impl Timeline {
+    pub fn new(interval: u64) -> Self {
+        Timeline { interval: interval.max(1), keyframes: Vec::new(), edited: false }
+    }
+
+    /// Generations between keyframes, which grows as the run gets longer.
+    pub fn interval(&self) -> u64 {
+        self.interval
+    }
+
+    pub fn clear(&mut self) {
+        self.keyframes.clear();
+        self.edited = false;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keyframes.is_empty()
+    }
+
+    /// Generations that have keyframes, oldest first.
+    pub fn generations(&self) -> impl Iterator<Item = u64> + '_ {
+        self.keyframes.iter().map(|k| k.generation)
+    }
+
+    /// Bytes the keyframes take up.
+    pub fn size(&self) -> usize {
+        self.keyframes.iter().map(|k| k.grid.size()).sum()
+    }
+
+    /// Keeps `life` at `generation` if it is due a keyframe: the first
+    /// generation seen, every interval, and the first after an edit.
+    pub fn record(&mut self, generation: u64, life: &dyn LifeEngine) {
+        let due = self.keyframes.is_empty() || self.edited || generation.is_multiple_of(self.interval);
+        // Stepping on again after going back gives the same generations
+        if !due || (!self.edited && self.keyframes.iter().any(|k| k.generation == generation)) {
+            return;
+        }
+        self.keyframes.retain(|k| k.generation < generation);
+        let keyframe = Keyframe { generation, edited: self.edited, grid: Snapshot::of(generation, life), settings: Settings::of(life) };
+        self.keyframes.push(keyframe);
+        self.edited = false;
+        if self.keyframes.len() > MAX_KEYFRAMES {
+            self.thin();
+        }
+    }
+
+    /// Doubles the interval, dropping keyframes that are no longer on it
+    /// apart from the first and those after edits, then the oldest if that
+    /// was not enough.
+    fn thin(&mut self) {
+        self.interval *= 2;
+        let interval = self.interval;
+        let first = self.keyframes.first().map(|k| k.generation);
+        self.keyframes.retain(|k| k.edited || Some(k.generation) == first || k.generation.is_multiple_of(interval));
+        let excess = self.keyframes.len().saturating_sub(MAX_KEYFRAMES);
+        self.keyframes.drain(..excess);
+    }
+
+    /// Notes that the universe was edited at `generation`.  What was kept
+    /// after it no longer follows, and the next generation is kept.
+    pub fn edited(&mut self, generation: u64) {
+        self.keyframes.retain(|k| k.generation <= generation);
+        self.edited = true;
+    }
+
+    /// Puts `life` back as it was at `generation`, from the keyframe at or
+    /// before it, or the first keyframe if there is none that early.
+    /// Returns the generation reached, or `None` if nothing was kept for a
+    /// grid of this size.
+    pub fn seek(&self, life: &mut dyn LifeEngine, generation: u64) -> Option<u64> {
+        let keyframe = self.keyframes.iter().rev().find(|k| k.generation <= generation).or(self.keyframes.first())?;
+        keyframe.grid.paint(life).ok()?;
+        keyframe.settings.apply(life);
+        let steps = generation.saturating_sub(keyframe.generation);
+        life.step_n(steps);
+        Some(keyframe.generation + steps)
+    }
+}
+
+pub type SharedTimeline = Arc<Mutex<Timeline>>;
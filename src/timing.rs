@@ -0,0 +1,188 @@
+//! Timing each phase of stepping and drawing for the profiler overlay, and
+//! reading the timings back a second at a time.  Stepping runs on its own
+//! thread and drawing on the window's, so each thread's phases are a share
+//! of its own second, and whichever thread is busier is what holds the
+//! other back.
+
+use std::array;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// A phase of stepping or drawing a generation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Phase {
+    /// Counting neighbours into the next generation
+    Step,
+    /// Swapping the next generation in, with the universe locked
+    Swap,
+    /// Handing the generation to the listeners
+    Publish,
+    /// Filling the window's pixels
+    Draw,
+    /// Handing the pixels to the GPU and showing them
+    Present,
+}
+
+/// The thread a phase runs on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Thread {
+    Stepping,
+    Drawing,
+}
+
+/// Every phase, in the order shown, with its name and thread.
+pub const PHASES: [(Phase, &str, Thread); 5] = [
+    (Phase::Step, "neighbours", Thread::Stepping),
+    (Phase::Swap, "swap", Thread::Stepping),
+    (Phase::Publish, "publish", Thread::Stepping),
+    (Phase::Draw, "pixel fill", Thread::Drawing),
+    (Phase::Present, "present", Thread::Drawing),
+];
+
+/// Time spent in each phase since it was last taken, added to from both
+/// threads.  Nothing is timed while it is off.
+#[derive(Default)]
+pub struct Timings {
+    on: AtomicBool,
+    nanos: [AtomicU64; PHASES.len()],
+    counts: [AtomicU64; PHASES.len()],
+}
+
+pub type SharedTimings = Arc<Timings>;
+
+impl Timings {
+    /// Runs `f`, adding how long it took to `phase` if timing is on.
+    pub fn time<T>(&self, phase: Phase, f: impl FnOnce() -> T) -> T {
+        if !self.is_on() {
+            return f();
+        }
+        let start = Instant::now();
+        let result = f();
+        self.add(phase, start.elapsed());
+        result
+    }
+
+    /// Adds one run of `phase` taking `time`.
+    pub fn add(&self, phase: Phase, time: Duration) {
+        let i = PHASES.iter().position(|&(p, _, _)| p == phase).expect("every phase is listed");
+        self.nanos[i].fetch_add(time.as_nanos() as u64, Ordering::Relaxed);
+        self.counts[i].fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn is_on(&self) -> bool {
+        self.on.load(Ordering::Relaxed)
+    }
+
+    /// Each phase's time and count so far, starting them over.
+    fn take(&self) -> [(Duration, u64); PHASES.len()] {
+        array::from_fn(|i| (Duration::from_nanos(self.nanos[i].swap(0, Ordering::Relaxed)), self.counts[i].swap(0, Ordering::Relaxed)))
+    }
+}
+
+/// One phase over a second.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Row {
+    pub name: &'static str,
+    pub thread: Thread,
+    /// Milliseconds a run took on average
+    pub each: f64,
+    pub count: u64,
+    /// Share of its thread's second
+    pub share: f64,
+}
+
+/// Which thread holds the other back.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Bound {
+    /// Neither is busy half the time
+    Neither,
+    Simulation,
+    Render,
+}
+
+/// Every phase over a second, and how busy each thread was.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Breakdown {
+    pub rows: Vec<Row>,
+    pub stepping: f64,
+    pub drawing: f64,
+}
+
+impl Breakdown {
+    /// The breakdown of `phases`' times and counts over `second`, however
+    /// long it really was.
+    pub fn of(phases: &[(Duration, u64); PHASES.len()], second: Duration) -> Self {
+        let second = second.as_secs_f64();
+        let mut breakdown = Breakdown { rows: Vec::new(), stepping: 0.0, drawing: 0.0 };
+        for (&(_, name, thread), &(time, count)) in PHASES.iter().zip(phases) {
+            let share = time.as_secs_f64() / second;
+            match thread {
+                Thread::Stepping => breakdown.stepping += share,
+                Thread::Drawing => breakdown.drawing += share,
+            }
+            let each = if count > 0 { time.as_secs_f64() * 1000.0 / count as f64 } else { 0.0 };
+            breakdown.rows.push(Row { name, thread, each, count, share });
+        }
+        breakdown
+    }
+
+    pub fn bound(&self) -> Bound {
+        if self.stepping.max(self.drawing) < 0.5 {
+            Bound::Neither
+        } else if self.stepping >= self.drawing {
+            Bound::Simulation
+        } else {
+            Bound::Render
+        }
+    }
+}
+
+/// Turns the timings on and off, and takes them once a second.
+pub struct Sampler {
+    timings: SharedTimings,
+    since: Instant,
+    shown: Option<Breakdown>,
+}
+
+impl Default for Sampler {
+    fn default() -> Self {
+        Sampler { timings: SharedTimings::default(), since: Instant::now(), shown: None }
+    }
+}
+
+impl Sampler {
+    /// The timings to add to, shared with the stepping thread.
+    pub fn timings(&self) -> SharedTimings {
+        Arc::clone(&self.timings)
+    }
+
+    pub fn is_on(&self) -> bool {
+        self.timings.is_on()
+    }
+
+    /// Turns timing on or off, throwing away what was timed so far.
+    pub fn toggle(&mut self) {
+        let on = !self.is_on();
+        self.timings.take();
+        (self.since, self.shown) = (Instant::now(), None);
+        self.timings.on.store(on, Ordering::Relaxed);
+    }
+
+    /// Takes the timings if a second has gone by at `now`, returning true
+    /// if there is a new breakdown to show.
+    pub fn sample(&mut self, now: Instant) -> bool {
+        let elapsed = now.saturating_duration_since(self.since);
+        if !self.is_on() || elapsed < Duration::from_secs(1) {
+            return false;
+        }
+        self.shown = Some(Breakdown::of(&self.timings.take(), elapsed));
+        self.since = now;
+        true
+    }
+
+    /// The last second's breakdown, None until a second has been timed.
+    pub fn shown(&self) -> Option<&Breakdown> {
+        self.shown.as_ref()
+    }
+}
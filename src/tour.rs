@@ -0,0 +1,86 @@
+//! The first run tutorial's lessons and what moves it on from each: pause,
+//! step, paint a glider, place a library pattern and change the rule.  The
+//! window tells it what was done, whether from a key, the mouse or the
+//! palette, and draws the lesson it is on.
+
+use crate::{find, io, CellState, ConwayState, DenseEngine, LifeEngine};
+
+// The glider's two shapes; the others are turned or mirrored
+const GLIDERS: [&str; 2] = [".O\n..O\nOOO", "O.O\n.OO\n.O"];
+// Cells around a painted one to look for a glider in: the glider and the
+// dead ring around it
+const REACH: usize = 3;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Lesson {
+    Pause,
+    Step,
+    Glider,
+    Library,
+    Rule,
+    Done,
+}
+
+pub const LESSONS: [Lesson; 6] = [Lesson::Pause, Lesson::Step, Lesson::Glider, Lesson::Library, Lesson::Rule, Lesson::Done];
+
+/// Something done that a lesson can ask for.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Act {
+    Paused,
+    Stepped,
+    /// Brought the cell at (x, y) to life
+    Painted(usize, usize),
+    /// Placed or loaded a pattern
+    Placed,
+    ChangedRule,
+}
+
+/// Which lesson the tutorial is on.
+#[derive(Clone, Debug, Default)]
+pub struct Tour {
+    lesson: usize,
+}
+
+impl Tour {
+    pub fn lesson(&self) -> Lesson {
+        LESSONS[self.lesson.min(LESSONS.len() - 1)]
+    }
+
+    /// The lesson's place in [`LESSONS`], from 1.
+    pub fn number(&self) -> usize {
+        self.lesson.min(LESSONS.len() - 1) + 1
+    }
+
+    /// Moves on to the next lesson if one of `acts` did what this one asks.
+    /// `life` is the shown universe once they have been carried out.
+    pub fn advance(&mut self, acts: &[Act], life: &dyn LifeEngine) {
+        let done = acts.iter().any(|act| match (self.lesson(), act) {
+            (Lesson::Pause, Act::Paused) | (Lesson::Step, Act::Stepped) => true,
+            (Lesson::Glider, Act::Painted(x, y)) => glider_near(life, *x, *y),
+            (Lesson::Library, Act::Placed) | (Lesson::Rule, Act::ChangedRule) => true,
+            _ => false
+        });
+        if done {
+            self.lesson += 1;
+        }
+    }
+}
+
+/// Whether a glider stands alone somewhere around (x, y).
+pub fn glider_near(life: &dyn LifeEngine, x: usize, y: usize) -> bool {
+    // Copied out so the search doesn't go over the whole grid
+    let side = 2 * REACH + 1;
+    let mut patch = ConwayState::empty(side, side);
+    for py in 0..side {
+        for px in 0..side {
+            let (gx, gy) = ((x + px).checked_sub(REACH), (y + py).checked_sub(REACH));
+            if let (Some(gx), Some(gy)) = (gx, gy) {
+                if gx < life.width() && gy < life.height() && life.get(gx, gy) == CellState::Alive {
+                    patch.set(px, py, CellState::Alive);
+                }
+            }
+        }
+    }
+    let patch = DenseEngine::new(patch);
+    GLIDERS.iter().any(|g| !find::find(&patch, &io::parse_plaintext(g)).is_empty())
+}
@@ -0,0 +1,158 @@
+//! Terminal frontend, for `--tui`: the grid drawn in half blocks or Braille
+//! with ratatui on crossterm, so the simulator runs over SSH without a GPU
+//! or a window.  It steps the same engine as the window, on the same event
+//! bus.
+//!
+//! Space pauses, `.` steps, `+` and `-` change the speed, the arrows or
+//! `hjkl` move around, `g` switches between blocks, Braille and ASCII, `r`
+//! makes a new soup and `q` or Ctrl+C quits.
+
+use std::time::{Duration, Instant};
+
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::Paragraph;
+
+use rustlife::script::SharedLife;
+use rustlife::text::{self, Glyphs};
+use rustlife::{BoundingBox, EventBus, GenerationEvent};
+
+use crate::config::Config;
+
+// Redraws a second, and so how long a frame has to step in at full speed
+const FRAME_RATE: u32 = 30;
+// Generations a second, the last, 0, as many as fit between redraws
+const SPEEDS: [u32; 8] = [1, 2, 5, 10, 20, 60, 200, 0];
+
+/// Puts the terminal back as it was when dropped, however the frontend
+/// exits.
+struct Restore;
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        ratatui::restore();
+    }
+}
+
+/// Steps the universe once and publishes the generation.
+fn step(life: &SharedLife, events: &mut EventBus, generation: &mut u64) -> Result<(), String> {
+    let event = {
+        let mut l = life.write().map_err(|e| e.to_string())?;
+        l.step();
+        *generation += 1;
+        GenerationEvent::from_engine(*generation, l.as_ref())
+    };
+    events.publish(&event);
+    Ok(())
+}
+
+pub fn run(life: &SharedLife, events: &mut EventBus, config: &Config) -> Result<(), String> {
+    // Raw mode takes Ctrl+C as a key, so it is handled below rather than
+    // killing the process with the terminal left raw
+    let mut terminal = ratatui::try_init().map_err(|e| format!("--tui needs a terminal: {}", e))?;
+    let _restore = Restore;
+    let mut glyphs = Glyphs::Blocks;
+    let mut paused = false;
+    let mut speed = match config.speed {
+        0 => SPEEDS.len() - 1,
+        gps => SPEEDS.iter().position(|&s| s >= gps || s == 0).unwrap_or(SPEEDS.len() - 1)
+    };
+    let mut generation = 0u64;
+    let (grid_w, grid_h) = life.read().map(|l| (l.width(), l.height())).map_err(|e| e.to_string())?;
+    // Columns and rows the grid gets, all but the status line
+    let shown = |terminal: &ratatui::DefaultTerminal| {
+        terminal.size().map_or((80, 23), |s| (s.width as usize, (s.height as usize).saturating_sub(1)))
+    };
+    // Top left cell on screen, starting with the middle of the grid
+    let (columns, rows) = shown(&terminal);
+    let (across, down) = glyphs.cells();
+    let (mut x, mut y) = (grid_w.saturating_sub(columns * across) / 2, grid_h.saturating_sub(rows * down) / 2);
+    let mut stepped = Instant::now();
+    let frame_time = Duration::from_secs(1) / FRAME_RATE;
+
+    loop {
+        let frame = Instant::now();
+        let mut steps = 0;
+        while event::poll(Duration::ZERO).map_err(|e| e.to_string())? {
+            let Event::Key(key) = event::read().map_err(|e| e.to_string())? else { continue };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            let (across, down) = glyphs.cells();
+            let (columns, rows) = shown(&terminal);
+            match key.code {
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(()),
+                KeyCode::Char('q') => return Ok(()),
+                KeyCode::Char(' ') => paused = !paused,
+                KeyCode::Char('.') => {
+                    paused = true;
+                    steps += 1;
+                }
+                KeyCode::Char('+') | KeyCode::Char('=') => speed = (speed + 1).min(SPEEDS.len() - 1),
+                KeyCode::Char('-') => speed = speed.saturating_sub(1),
+                KeyCode::Char('g') => glyphs = glyphs.next(),
+                KeyCode::Char('r') => {
+                    if let Ok(mut l) = life.write() {
+                        l.randomize(config.density, rand::random());
+                    }
+                }
+                KeyCode::Up | KeyCode::Char('k') => y = y.saturating_sub(rows / 4 * down),
+                KeyCode::Down | KeyCode::Char('j') => y = (y + rows / 4 * down).min(grid_h.saturating_sub(down)),
+                KeyCode::Left | KeyCode::Char('h') => x = x.saturating_sub(columns / 4 * across),
+                KeyCode::Right | KeyCode::Char('l') => x = (x + columns / 4 * across).min(grid_w.saturating_sub(across)),
+                _ => {}
+            }
+        }
+
+        for _ in 0..steps {
+            step(life, events, &mut generation)?;
+        }
+        if !paused {
+            match SPEEDS[speed] {
+                0 => {
+                    while frame.elapsed() < frame_time {
+                        step(life, events, &mut generation)?;
+                    }
+                }
+                s => {
+                    let due = (stepped.elapsed().as_secs_f64() * s as f64) as u64;
+                    if due > 0 {
+                        stepped = Instant::now();
+                    }
+                    for _ in 0..due {
+                        step(life, events, &mut generation)?;
+                    }
+                }
+            }
+        }
+
+        let (across, down) = glyphs.cells();
+        let (columns, rows) = shown(&terminal);
+        // A terminal one row high has only the status line
+        let lines = match (columns * across).checked_sub(1).zip((rows * down).checked_sub(1)) {
+            Some((w, h)) => {
+                let window = BoundingBox { min_x: x, min_y: y, max_x: x + w, max_y: y + h };
+                life.read().map(|l| text::render(l.as_ref(), window, glyphs)).map_err(|e| e.to_string())?
+            }
+            None => Vec::new()
+        };
+        let population = life.read().map(|l| l.population()).map_err(|e| e.to_string())?;
+        let speed_label = match SPEEDS[speed] {
+            0 => "max".to_string(),
+            s => format!("{}/s", s)
+        };
+        let status = format!(" gen {}  pop {}  {}{}  at {},{}  space . + - arrows g r q",
+            generation, population, speed_label, if paused { "  paused" } else { "" }, x, y);
+        terminal.draw(|f| {
+            let [grid, bar] = Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(f.area());
+            f.render_widget(Paragraph::new(lines.into_iter().map(Line::from).collect::<Vec<_>>()), grid);
+            f.render_widget(Paragraph::new(status).style(Style::new().add_modifier(Modifier::REVERSED)), bar);
+        }).map_err(|e| e.to_string())?;
+
+        if let Some(rest) = frame_time.checked_sub(frame.elapsed()) {
+            std::thread::sleep(rest);
+        }
+    }
+}
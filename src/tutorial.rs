@@ -0,0 +1,90 @@
+//! The first run tutorial: a handful of lessons shown in a box over the
+//! grid, each one done when the commands it asks for come through, whether
+//! from a key, the mouse or the palette.  The lessons and what finishes
+//! them are [`rustlife::tour`]; this says what each one means in commands
+//! and draws it.
+
+use rustlife::tour::{Act, Lesson, Tour, LESSONS};
+use rustlife::LifeEngine;
+
+use crate::commands::Command;
+use crate::config::KeyBindings;
+use crate::overlay::{self, GLYPH_SIZE};
+
+#[derive(Default)]
+pub struct Tutorial {
+    tour: Tour,
+}
+
+/// What `command` does that a lesson can ask for.
+fn act(command: &Command) -> Option<Act> {
+    match command {
+        Command::TogglePause | Command::SetPaused(true) => Some(Act::Paused),
+        Command::Step(_) | Command::ExplainStep => Some(Act::Stepped),
+        Command::SetCell { x, y, alive: true } => Some(Act::Painted(*x, *y)),
+        Command::Paste { .. } | Command::LoadPattern(_) => Some(Act::Placed),
+        Command::SetRule(_) => Some(Act::ChangedRule),
+        _ => None
+    }
+}
+
+impl Tutorial {
+    /// Moves on to the next lesson if `issued` did what this one asks.
+    /// `life` is the shown universe once they have been carried out.
+    pub fn advance(&mut self, issued: &[Command], life: &dyn LifeEngine) {
+        let acts: Vec<_> = issued.iter().filter_map(act).collect();
+        self.tour.advance(&acts, life);
+    }
+
+    fn lines(&self, keys: &KeyBindings) -> Vec<String> {
+        let mut lines = match self.tour.lesson() {
+            Lesson::Pause => vec![
+                "Welcome!  Cells live or die by how many of".to_string(),
+                format!("their eight neighbours are alive.  Press {:?}", keys.pause),
+                "to pause the universe.".to_string(),
+            ],
+            Lesson::Step => vec![
+                format!("Paused.  Press {:?} to step one generation", keys.step),
+                "at a time and watch what changes.".to_string(),
+            ],
+            Lesson::Glider => vec![
+                "Paint a glider with the left mouse button".to_string(),
+                "on an empty patch (the right button erases):".to_string(),
+                "  .O.".to_string(),
+                "  ..O".to_string(),
+                "  OOO".to_string(),
+            ],
+            Lesson::Library => vec![
+                format!("Press {:?} to open the pattern browser, pick", keys.browser),
+                "a pattern and click on the grid to place it.".to_string(),
+            ],
+            Lesson::Rule => vec![
+                "Press Ctrl+P for the palette, pick Change rule".to_string(),
+                "and try B36/S23, HighLife.".to_string(),
+            ],
+            Lesson::Done => vec![
+                "That's the tour.  Everything else is in the".to_string(),
+                "palette under Ctrl+P.".to_string(),
+            ],
+        };
+        let skip = if self.tour.lesson() == Lesson::Done { "close" } else { "skip the tutorial" };
+        lines.push(format!("{:?} to {}", keys.tutorial, skip));
+        lines
+    }
+
+    /// Draws the lesson in a box at the top middle of the screen.
+    pub fn draw(&self, screen: &mut [u8], width: u32, height: u32, keys: &KeyBindings) {
+        let mut lines = vec![format!("Tutorial {} of {}", self.tour.number(), LESSONS.len())];
+        lines.extend(self.lines(keys));
+        let line = GLYPH_SIZE + 2;
+        let columns = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0) as u32;
+        let (w, h) = (columns * GLYPH_SIZE + 12, lines.len() as u32 * line + 10);
+        let (left, top) = ((width as i32 - w as i32) / 2, 24);
+        overlay::fill_rect(screen, width, height, (left, top, w, h), [0, 0, 40, 220]);
+        overlay::outline(screen, width, height, (left, top, w, h), [255, 220, 64, 255]);
+        for (i, text) in lines.iter().enumerate() {
+            let color = if i == 0 { [255, 220, 64] } else { [255, 255, 255] };
+            overlay::draw_text(screen, width, height, left + 6, top + 6 + (i as u32 * line) as i32, text, color);
+        }
+    }
+}
@@ -0,0 +1,30 @@
+//! Memory the process is using and the machine has, where the system says.
+
+/// The process's resident memory in bytes, on Linux.
+#[cfg(target_os = "linux")]
+pub fn resident() -> Option<u64> {
+    let statm = std::fs::read_to_string("/proc/self/statm").ok()?;
+    let pages: u64 = statm.split_whitespace().nth(1)?.parse().ok()?;
+    // SAFETY: sysconf only reads a system setting
+    let page = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+    Some(pages * page.max(0) as u64)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn resident() -> Option<u64> {
+    None
+}
+
+/// The machine's memory in bytes, on Linux.
+#[cfg(target_os = "linux")]
+pub fn physical() -> Option<u64> {
+    let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+    let line = meminfo.lines().find(|l| l.starts_with("MemTotal:"))?;
+    let kib: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kib * 1024)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn physical() -> Option<u64> {
+    None
+}
@@ -0,0 +1,152 @@
+//! Checking the ways of stepping a B/S universe against each other.  Two of
+//! them are run in lockstep from the same start and their grids compared
+//! every so many generations; when they differ, both are run again from the
+//! last generation they agreed on, one generation at a time, to find the
+//! first one they disagree on and the first cell, in reading order, they
+//! disagree about.
+//!
+//! The serial reference, [`ConwayState::next_state_serial`], is the plainest
+//! code and the one to trust; the rest are there for speed.
+
+use std::fmt;
+
+use rustlife_core::Grid;
+
+use crate::engine::{Boundary, CellState, ConwayState, DenseEngine, LifeEngine};
+
+/// The ways of stepping there are to compare.
+pub const ENGINES: &[&str] = &["reference", "dense", "parallel", "strips", "core"];
+
+/// One way of stepping.
+enum Engine {
+    /// [`ConwayState::next_state_serial`], with its scratch grid
+    Reference(ConwayState, ConwayState),
+    /// [`DenseEngine`], with its change lists
+    Dense(Box<DenseEngine>),
+    /// [`ConwayState::next_state`], rows in parallel
+    Parallel(ConwayState, ConwayState),
+    /// [`ConwayState::next_state_strips`], a strip of rows a thread
+    Strips(ConwayState, ConwayState),
+    /// The `no_std` core's [`Grid`]
+    Core(Grid),
+}
+
+impl Engine {
+    fn new(name: &str, start: &ConwayState) -> Result<Engine, String> {
+        Ok(match name {
+            "reference" => Engine::Reference(start.clone(), start.clone()),
+            "dense" => Engine::Dense(Box::new(DenseEngine::new(start.clone()))),
+            "parallel" => Engine::Parallel(start.clone(), start.clone()),
+            "strips" => Engine::Strips(start.clone(), start.clone()),
+            "core" => {
+                if start.custom_rule.is_some() || !start.walls.is_empty() || start.boundary != Boundary::Dead {
+                    return Err("the core engine has no plugin rules, walls or torus".to_string());
+                }
+                let mut grid = Grid::new(start.width, start.height);
+                grid.rule = start.rule;
+                for (i, &cell) in start.cells.iter().enumerate() {
+                    grid.set(i % start.width, i / start.width, cell);
+                }
+                Engine::Core(grid)
+            }
+            _ => return Err(format!("unknown engine '{}', expected one of: {}", name, ENGINES.join(", ")))
+        })
+    }
+
+    fn step(&mut self) {
+        match self {
+            Engine::Reference(state, scratch) => {
+                state.next_state_serial(scratch);
+                state.swap_state(scratch);
+            }
+            Engine::Dense(life) => life.step(),
+            Engine::Parallel(state, scratch) => {
+                state.next_state(scratch);
+                state.swap_state(scratch);
+            }
+            Engine::Strips(state, scratch) => {
+                state.next_state_strips(scratch);
+                state.swap_state(scratch);
+            }
+            Engine::Core(grid) => grid.step(),
+        }
+    }
+
+    fn cells(&self) -> &[CellState] {
+        match self {
+            Engine::Reference(state, _) | Engine::Parallel(state, _) | Engine::Strips(state, _) => &state.cells,
+            Engine::Dense(life) => &life.state().cells,
+            Engine::Core(grid) => grid.cells(),
+        }
+    }
+}
+
+/// Where two engines first disagreed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Divergence {
+    pub generation: u64,
+    pub x: usize,
+    pub y: usize,
+    /// The cell on the first engine and on the second
+    pub states: (CellState, CellState),
+    /// Cells that differ in that generation
+    pub cells: usize,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let state = |s: CellState| if s == CellState::Alive { "alive" } else { "dead" };
+        let differ = if self.cells == 1 { "cell differs" } else { "cells differ" };
+        write!(f, "generation {}, cell ({}, {}): {} on the first, {} on the second; {} {}",
+            self.generation, self.x, self.y, state(self.states.0), state(self.states.1), self.cells, differ)
+    }
+}
+
+/// Where `a` and `b` differ, if they do.
+fn compare(generation: u64, width: usize, a: &[CellState], b: &[CellState]) -> Option<Divergence> {
+    let first = a.iter().zip(b).position(|(p, q)| p != q)?;
+    Some(Divergence {
+        generation,
+        x: first % width,
+        y: first / width,
+        states: (a[first], b[first]),
+        cells: a.iter().zip(b).filter(|(p, q)| p != q).count(),
+    })
+}
+
+/// Runs engines `a` and `b` from `start` for `generations`, comparing them
+/// every `every` generations and after the last, and returns where they
+/// first disagree, or None if they never do.
+pub fn check(a: &str, b: &str, start: &ConwayState, generations: u64, every: u64) -> Result<Option<Divergence>, String> {
+    let every = every.max(1);
+    let (mut first, mut second) = (Engine::new(a, start)?, Engine::new(b, start)?);
+    // The last generation they were seen to agree on
+    let mut agreed = (0, start.clone());
+    if let Some(d) = compare(0, start.width, first.cells(), second.cells()) {
+        return Ok(Some(d));
+    }
+    for generation in 1..=generations {
+        first.step();
+        second.step();
+        if !generation.is_multiple_of(every) && generation != generations {
+            continue;
+        }
+        if compare(generation, start.width, first.cells(), second.cells()).is_none() {
+            agreed.0 = generation;
+            agreed.1.cells.copy_from_slice(first.cells());
+            continue;
+        }
+        // Back to where they agreed, a generation at a time
+        let (from, state) = agreed;
+        let (mut first, mut second) = (Engine::new(a, &state)?, Engine::new(b, &state)?);
+        for generation in from + 1..=generation {
+            first.step();
+            second.step();
+            if let Some(d) = compare(generation, start.width, first.cells(), second.cells()) {
+                return Ok(Some(d));
+            }
+        }
+        return Err(format!("{} and {} disagreed at generation {} but not when run again from generation {}", a, b, generation, from));
+    }
+    Ok(None)
+}
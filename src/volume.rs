@@ -0,0 +1,166 @@
+//! Space-time volumes: a window of the grid over a run of generations, with
+//! time as the third axis, saved for rendering or 3D printing elsewhere.
+//! The format follows the file's extension:
+//!
+//! - `.vox`, a MagicaVoxel model, one voxel per live cell, coloured from
+//!   deep blue for the oldest generation to white for the newest
+//! - `.obj`, a Wavefront point cloud, one vertex per live cell
+//!
+//! Either way time runs up the z axis, the first generation at the bottom.
+
+use std::collections::VecDeque;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use crate::engine::{CellState, LifeEngine};
+use crate::events::GenerationEvent;
+use crate::script::SharedLife;
+
+/// Longest side and most generations a volume holds, MagicaVoxel's limit.
+pub const MAX_SIZE: usize = 256;
+
+const OLDEST: [u8; 3] = [0x20, 0x40, 0xa0];
+const NEWEST: [u8; 3] = [0xff, 0xff, 0xff];
+
+/// Recent generations of a window of the grid, oldest first, each stored as
+/// the live cells inside the window in window-local coordinates.
+#[derive(Clone, Debug, Default)]
+pub struct Volume {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+    frames: VecDeque<Vec<(u16, u16)>>,
+}
+
+impl Volume {
+    /// Records the window at (x, y), cut down to [`MAX_SIZE`] a side.
+    pub fn new(x: usize, y: usize, width: usize, height: usize) -> Self {
+        Volume { x, y, width: width.min(MAX_SIZE), height: height.min(MAX_SIZE), frames: VecDeque::new() }
+    }
+
+    /// The middle of a `width` by `height` grid, as much of it as fits.
+    pub fn centred(width: usize, height: usize) -> Self {
+        let (w, h) = (width.min(MAX_SIZE), height.min(MAX_SIZE));
+        Volume::new((width - w) / 2, (height - h) / 2, w, h)
+    }
+
+    /// A volume of generations recorded elsewhere, oldest first.  Only the
+    /// newest [`MAX_SIZE`] are kept.
+    pub fn from_frames(width: usize, height: usize, frames: impl IntoIterator<Item = Vec<(u16, u16)>>) -> Self {
+        let mut volume = Volume::new(0, 0, width, height);
+        for frame in frames {
+            volume.push(frame);
+        }
+        volume
+    }
+
+    fn push(&mut self, frame: Vec<(u16, u16)>) {
+        if self.frames.len() == MAX_SIZE {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(frame);
+    }
+
+    /// Adds the window's live cells, dropping the oldest generation once
+    /// there are [`MAX_SIZE`].  Cells past the edge of the grid are dead.
+    pub fn record(&mut self, life: &dyn LifeEngine) {
+        let mut live = Vec::new();
+        for y in self.y..(self.y + self.height).min(life.height()) {
+            for x in self.x..(self.x + self.width).min(life.width()) {
+                if life.get(x, y) == CellState::Alive {
+                    live.push(((x - self.x) as u16, (y - self.y) as u16));
+                }
+            }
+        }
+        self.push(live);
+    }
+
+    /// Generations recorded.
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Every live cell as (x, y, z), with y flipped so the grid reads the
+    /// right way round from above and z the generation, oldest 0.
+    pub fn voxels(&self) -> impl Iterator<Item = (usize, usize, usize)> + '_ {
+        self.frames.iter().enumerate().flat_map(move |(z, frame)| {
+            frame.iter().map(move |&(x, y)| (x as usize, self.height - 1 - y as usize, z))
+        })
+    }
+
+    /// The volume as a MagicaVoxel file.
+    pub fn to_vox(&self) -> Vec<u8> {
+        fn chunk(out: &mut Vec<u8>, id: &[u8; 4], content: &[u8], children: &[u8]) {
+            out.extend_from_slice(id);
+            out.extend_from_slice(&(content.len() as u32).to_le_bytes());
+            out.extend_from_slice(&(children.len() as u32).to_le_bytes());
+            out.extend_from_slice(content);
+            out.extend_from_slice(children);
+        }
+
+        let depth = self.len().max(1);
+        let mut size = Vec::new();
+        for side in [self.width, self.height, depth] {
+            size.extend_from_slice(&(side.max(1) as u32).to_le_bytes());
+        }
+        let voxels: Vec<_> = self.voxels().collect();
+        let mut xyzi = (voxels.len() as u32).to_le_bytes().to_vec();
+        for (x, y, z) in voxels {
+            // Palette entries 1 to 255, spread over the generations
+            let colour = 1 + z * 254 / (depth - 1).max(1);
+            xyzi.extend_from_slice(&[x as u8, y as u8, z as u8, colour as u8]);
+        }
+        let mut rgba = Vec::with_capacity(1024);
+        for i in 0..256 {
+            let t = i as f64 / 254.0;
+            let channel = |c: usize| (OLDEST[c] as f64 + (NEWEST[c] as f64 - OLDEST[c] as f64) * t.min(1.0)) as u8;
+            rgba.extend_from_slice(&[channel(0), channel(1), channel(2), 0xff]);
+        }
+
+        let mut children = Vec::new();
+        chunk(&mut children, b"SIZE", &size, &[]);
+        chunk(&mut children, b"XYZI", &xyzi, &[]);
+        chunk(&mut children, b"RGBA", &rgba, &[]);
+        let mut out = b"VOX ".to_vec();
+        out.extend_from_slice(&150u32.to_le_bytes());
+        chunk(&mut out, b"MAIN", &[], &children);
+        out
+    }
+
+    /// The volume as a Wavefront OBJ point cloud.
+    pub fn to_obj(&self) -> String {
+        let mut out = format!("# space-time volume, {}x{} cells over {} generations\n", self.width, self.height, self.len());
+        for (x, y, z) in self.voxels() {
+            out.push_str(&format!("v {} {} {}\n", x, y, z));
+        }
+        out
+    }
+
+    /// Writes the volume to `path` in the format its extension names.
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        let extension = Path::new(path).extension().and_then(|e| e.to_str()).map(|e| e.to_ascii_lowercase());
+        let bytes = match extension.as_deref() {
+            Some("vox") => self.to_vox(),
+            Some("obj") => self.to_obj().into_bytes(),
+            _ => return Err(format!("{}: expected a .vox or .obj file", path))
+        };
+        fs::write(path, bytes).map_err(|e| format!("{}: {}", path, e))
+    }
+}
+
+pub type SharedVolume = Arc<Mutex<Volume>>;
+
+/// Event listener that records every generation of `life` into `volume`.
+pub fn recorder(volume: SharedVolume, life: SharedLife) -> impl FnMut(&GenerationEvent) + Send {
+    move |_| {
+        if let (Ok(mut v), Ok(l)) = (volume.lock(), life.read()) {
+            v.record(l.as_ref());
+        }
+    }
+}
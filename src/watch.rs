@@ -0,0 +1,208 @@
+//! Period detection, spaceship tracking, activity metrics, growth
+//! detection, the heatmap, cell ages, region statistics and timeline
+//! keyframes for the shown slot, run on the stepping thread and shown over
+//! the grid.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use rustlife::engine::BoundingBox;
+use rustlife::growth::GrowthDetector;
+use rustlife::period::{self, PeriodDetector};
+use rustlife::ships::{self, Ship};
+use rustlife::stats::{Activity, ActivityMeter, Ages, Heatmap, SharedRegions};
+use rustlife::timeline::SharedTimeline;
+use rustlife::{GenerationEvent, LifeEngine};
+
+use crate::slots::ActiveSlot;
+
+/// Runs `f` on the universe in the shown slot, read-locked, unless a lock
+/// is poisoned.
+fn shown<T>(active: &ActiveSlot, f: impl FnOnce(&dyn LifeEngine) -> T) -> Option<T> {
+    let life = Arc::clone(&active.read().ok()?.0);
+    let l = life.read().ok()?;
+    Some(f(l.as_ref()))
+}
+
+pub struct PeriodWatch {
+    /// Region watched, the whole grid if `None`
+    pub region: Option<BoundingBox>,
+    detector: PeriodDetector,
+    pub period: Option<u64>,
+}
+
+impl PeriodWatch {
+    pub fn new(region: Option<BoundingBox>) -> Self {
+        PeriodWatch { region, detector: PeriodDetector::default(), period: None }
+    }
+
+    /// Forgets what was seen, for when the universe is replaced.
+    pub fn restart(&mut self) {
+        self.detector.clear();
+        self.period = None;
+    }
+
+    pub fn status(&self) -> String {
+        match self.region {
+            Some(r) => format!("{}x{} at {},{}: {}", r.width(), r.height(), r.min_x, r.min_y, period::describe(self.period)),
+            None => period::describe(self.period)
+        }
+    }
+}
+
+/// `None` while detection is off.
+pub type SharedWatch = Arc<Mutex<Option<PeriodWatch>>>;
+
+/// Event listener that hashes the shown universe after every generation
+/// while a watch is set.
+pub fn periods(watch: SharedWatch, active: ActiveSlot) -> impl FnMut(&GenerationEvent) + Send {
+    move |event| {
+        let Ok(mut watch) = watch.lock() else { return };
+        let Some(w) = watch.as_mut() else { return };
+        shown(&active, |l| {
+            let hash = period::hash(l, w.region);
+            w.period = w.detector.observe(event.generation, hash);
+        });
+    }
+}
+
+/// `None` while tracking is off.
+pub type SharedShips = Arc<Mutex<Option<Vec<Ship>>>>;
+
+// Finding ships means running every small object, so not every generation
+const SHIP_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Event listener that looks for spaceships a few times a second while
+/// tracking is on.
+pub fn ships(found: SharedShips, active: ActiveSlot) -> impl FnMut(&GenerationEvent) + Send {
+    let mut last: Option<Instant> = None;
+    move |_| {
+        if last.is_some_and(|t| t.elapsed() < SHIP_INTERVAL) || found.lock().map_or(true, |f| f.is_none()) {
+            return;
+        }
+        last = Some(Instant::now());
+        let Some(ships) = shown(&active, ships::find) else { return };
+        if let Ok(mut f) = found.lock() {
+            // Switched off while searching
+            if f.is_some() {
+                *f = Some(ships);
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct ActivityWatch {
+    meter: ActivityMeter,
+    pub latest: Option<Activity>,
+}
+
+impl ActivityWatch {
+    /// Forgets what was seen, for when the universe is replaced.
+    pub fn restart(&mut self) {
+        self.meter.clear();
+        self.latest = None;
+    }
+
+    pub fn status(&self) -> String {
+        match self.latest {
+            Some(a) => format!("changed {}, entropy {:.2} bits, active {:.1}%",
+                a.changed, a.entropy, a.active_area as f64 * 100.0 / a.area.max(1) as f64),
+            None => "no activity measured yet".to_string()
+        }
+    }
+}
+
+/// `None` while the metrics are off.
+pub type SharedActivity = Arc<Mutex<Option<ActivityWatch>>>;
+
+/// Event listener that measures every generation while the metrics are on.
+pub fn activity(watch: SharedActivity, active: ActiveSlot) -> impl FnMut(&GenerationEvent) + Send {
+    move |event| {
+        let Ok(mut watch) = watch.lock() else { return };
+        let Some(w) = watch.as_mut() else { return };
+        shown(&active, |l| w.latest = Some(w.meter.measure(event, l)));
+    }
+}
+
+#[derive(Default)]
+pub struct GrowthWatch {
+    pub detector: GrowthDetector,
+}
+
+impl GrowthWatch {
+    /// Forgets what was seen, for when the universe is replaced.
+    pub fn restart(&mut self) {
+        self.detector.clear();
+    }
+
+    pub fn status(&self) -> String {
+        match self.detector.growth() {
+            Some(g) => format!("growth: {}", g),
+            None => "growth: watching".to_string()
+        }
+    }
+}
+
+/// `None` while growth detection is off.
+pub type SharedGrowth = Arc<Mutex<Option<GrowthWatch>>>;
+
+/// Event listener that samples the population and bounding box while
+/// growth detection is on.
+pub fn growth(watch: SharedGrowth, active: ActiveSlot) -> impl FnMut(&GenerationEvent) + Send {
+    move |event| {
+        let Ok(mut watch) = watch.lock() else { return };
+        let Some(w) = watch.as_mut() else { return };
+        shown(&active, |l| w.detector.observe(event, l));
+    }
+}
+
+/// `None` while the heatmap is not shown.
+pub type SharedHeatmap = Arc<Mutex<Option<Heatmap>>>;
+
+/// Event listener that adds every generation to the heatmap while it is
+/// shown.  Nothing is counted while it is hidden, so it starts from the
+/// generation it was shown at.
+pub fn heatmap(heatmap: SharedHeatmap, active: ActiveSlot) -> impl FnMut(&GenerationEvent) + Send {
+    move |_| {
+        let Ok(mut heatmap) = heatmap.lock() else { return };
+        let Some(h) = heatmap.as_mut() else { return };
+        shown(&active, |l| h.record(l));
+    }
+}
+
+/// `None` while the isometric view is not shown.
+pub type SharedAges = Arc<Mutex<Option<Ages>>>;
+
+/// Event listener that ages the live cells every generation while the
+/// isometric view is shown.
+pub fn ages(ages: SharedAges, active: ActiveSlot) -> impl FnMut(&GenerationEvent) + Send {
+    move |_| {
+        let Ok(mut ages) = ages.lock() else { return };
+        let Some(a) = ages.as_mut() else { return };
+        shown(&active, |l| a.record(l));
+    }
+}
+
+/// Event listener that samples every region each generation.
+pub fn regions(stats: SharedRegions, active: ActiveSlot) -> impl FnMut(&GenerationEvent) + Send {
+    move |event| {
+        let mut stats = match stats.lock() {
+            Ok(s) if !s.is_empty() => s,
+            _ => return
+        };
+        shown(&active, |l| stats.record(event.generation, l));
+    }
+}
+
+/// Event listener that keeps keyframes of the shown slot for the timeline.
+pub fn timeline(timeline: SharedTimeline, active: ActiveSlot) -> impl FnMut(&GenerationEvent) + Send {
+    move |event| {
+        // The universe first, the same order edits take them in
+        shown(&active, |l| {
+            if let Ok(mut t) = timeline.lock() {
+                t.record(event.generation, l);
+            }
+        });
+    }
+}
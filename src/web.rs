@@ -0,0 +1,109 @@
+//! Browser frontend.  There are no threads on wasm32, so the universe is
+//! stepped once per animation frame from the event loop instead of on a
+//! background thread.
+
+use std::rc::Rc;
+
+use pixels::{PixelsBuilder, SurfaceTexture};
+use winit::{
+    dpi::LogicalSize,
+    event::{Event, VirtualKeyCode},
+    event_loop::{ControlFlow, EventLoop},
+    platform::web::WindowExtWebSys,
+    window::WindowBuilder,
+};
+use winit_input_helper::WinitInputHelper;
+
+use rustlife::{LifeEngine, SimulationBuilder};
+
+use crate::render::{self, PixelMap, Theme, View};
+
+// Smaller than the desktop default; a single thread has to keep up with the display.
+const WIDTH: u32 = 512;
+const HEIGHT: u32 = 512;
+const GAME_WIDTH: usize = 512;
+const GAME_HEIGHT: usize = 512;
+
+pub fn start() {
+    std::panic::set_hook(Box::new(console_error_panic_hook::hook));
+    console_log::init_with_level(log::Level::Info).expect("error initializing logger");
+    wasm_bindgen_futures::spawn_local(run());
+}
+
+async fn run() {
+    let event_loop = EventLoop::new();
+    let mut input = WinitInputHelper::new();
+
+    let window = {
+        let size = LogicalSize::new(WIDTH as f64, HEIGHT as f64);
+        WindowBuilder::new()
+            .with_title("Conway's Game of Life")
+            .with_inner_size(size)
+            .with_min_inner_size(size)
+            .build(&event_loop)
+            .expect("WindowBuilder error")
+    };
+    let window = Rc::new(window);
+
+    // Attach the winit canvas to the page
+    web_sys::window()
+        .and_then(|win| win.document())
+        .and_then(|doc| doc.body())
+        .and_then(|body| body.append_child(&web_sys::Element::from(window.canvas())).ok())
+        .expect("couldn't append canvas to document body");
+
+    let mut pixels = {
+        let window_size = window.inner_size();
+        let surface_texture = SurfaceTexture::new(window_size.width, window_size.height, window.as_ref());
+        PixelsBuilder::new(WIDTH, HEIGHT, surface_texture)
+            .build_async()
+            .await
+            .expect("Pixels error")
+    };
+
+    let seed = rand::random();
+    let mut life = SimulationBuilder::new(GAME_WIDTH, GAME_HEIGHT).soup(0.5, seed).build().expect("dense backend");
+    window.set_title(&format!("Conway's Game of Life - seed {}", seed));
+    let theme = Theme::by_name("classic").expect("classic theme");
+    let mut paused = false;
+    let mut pixel_map = PixelMap::default();
+
+    event_loop.run(move |event, _, control_flow| {
+        if let Event::RedrawRequested(_) = event {
+            // Redraws are driven by requestAnimationFrame, so this steps once per frame
+            if !paused {
+                life.step();
+            }
+            pixel_map.update(WIDTH, HEIGHT, &View::whole((life.width(), life.height())));
+            render::draw(pixels.frame_mut(), life.as_ref(), &theme, &pixel_map);
+            if let Err(err) = pixels.render() {
+                log::error!("pixels.render: {}", err);
+                *control_flow = ControlFlow::Exit;
+                return;
+            }
+        }
+
+        if input.update(&event) {
+            if input.key_pressed(VirtualKeyCode::P) {
+                paused = !paused;
+            }
+            if input.key_pressed_os(VirtualKeyCode::Space) {
+                paused = true;
+                life.step();
+            }
+            if input.key_pressed(VirtualKeyCode::R) {
+                let seed = rand::random();
+                life.randomize(0.5, seed);
+                window.set_title(&format!("Conway's Game of Life - seed {}", seed));
+            }
+            if let Some(size) = input.window_resized() {
+                if let Err(err) = pixels.resize_surface(size.width, size.height) {
+                    log::error!("pixels.resize_surface: {}", err);
+                    *control_flow = ControlFlow::Exit;
+                    return;
+                }
+            }
+            window.request_redraw();
+        }
+    });
+}
@@ -0,0 +1,201 @@
+use rustlife::{apgcode, io, ConwayState, DenseEngine, Rule};
+
+fn identify(pattern: &str) -> Option<String> {
+    apgcode::identify(&io::parse_plaintext(pattern), Rule::conway())
+}
+
+/// A `width` by `height` universe with each pattern's corner at its (x, y).
+fn universe(width: usize, height: usize, patterns: &[(&str, usize, usize)]) -> DenseEngine {
+    let mut life = DenseEngine::new(ConwayState::empty(width, height));
+    for &(pattern, x, y) in patterns {
+        io::place(&mut life, &io::parse_plaintext(pattern), x, y);
+    }
+    life
+}
+
+#[test]
+fn identifies_common_objects() {
+    assert_eq!(identify("OO\nOO").as_deref(), Some("xs4_33"));
+    assert_eq!(identify("OOO").as_deref(), Some("xp2_7"));
+    assert_eq!(identify(".O\n..O\nOOO").as_deref(), Some("xq4_153"));
+    assert_eq!(identify(".OO.\nO..O\n.OO.").as_deref(), Some("xs6_696"));
+    assert_eq!(identify("O"), None);
+}
+
+#[test]
+fn decodes_what_it_encodes() {
+    let pattern = io::parse_plaintext("O......................................................O\n\n\n\n\n\nOO");
+    let code = apgcode::encode(&pattern);
+    let mut decoded = apgcode::decode(&code).unwrap();
+    let mut expected = pattern.clone();
+    decoded.sort();
+    expected.sort();
+    assert_eq!(decoded, expected);
+    assert_eq!(apgcode::decode("xq4_153").unwrap().len(), 5);
+    assert!(apgcode::decode("xs4_3!").is_err());
+}
+
+#[test]
+fn names_match_identified_objects() {
+    let patterns = [
+        ("OO\nOO", "block"),
+        (".OO\nO..O\n.O.O\n..O", "loaf"),
+        ("OO\nO.O\n.O", "boat"),
+        (".O\nO.O\n.O", "tub"),
+        ("OO\nO.O\n.OO", "ship"),
+        (".OO\nO..O\nO..O\n.OO", "pond"),
+        ("OO\nO.O\n.O.O\n..O", "long boat"),
+        (".O\nO.O\n.O.O\n..O", "barge"),
+        (".OO\nO..O\n.O..O\n..OO", "mango"),
+        ("OO\nO.O\n..O\n..OO", "eater 1"),
+        ("OO.O\nO.OO", "snake"),
+        (".OOO\nOOO", "toad"),
+        ("OO\nOO\n..OO\n..OO", "beacon"),
+        (".O..O\nO\nO...O\nOOOO", "lightweight spaceship"),
+    ];
+    for (pattern, name) in patterns {
+        assert_eq!(identify(pattern).as_deref().and_then(apgcode::name), Some(name), "{}", pattern);
+    }
+}
+
+#[test]
+fn finds_spaceships_and_their_speeds() {
+    use rustlife::ships;
+
+    let life = universe(64, 64, &[(".O\n..O\nOOO", 5, 5), (".O..O\nO\nO...O\nOOOO", 30, 30), ("OO\nOO", 50, 10)]);
+    let mut found: Vec<_> = ships::find(&life).iter().map(|s| (s.name(), s.speed(), s.velocity())).collect();
+    found.sort_by(|a, b| a.1.cmp(&b.1));
+    assert_eq!(found, [
+        (Some("lightweight spaceship"), "c/2 orthogonal".to_string(), (-0.5, 0.0)),
+        (Some("glider"), "c/4 diagonal".to_string(), (0.25, 0.25)),
+    ]);
+}
+
+#[test]
+fn speeds_are_written_the_usual_way() {
+    use rustlife::apgcode::Motion;
+    use rustlife::ships;
+
+    assert_eq!(ships::speed(Motion { period: 5, dx: 2, dy: 0 }), "2c/5 orthogonal");
+    assert_eq!(ships::speed(Motion { period: 48, dx: -12, dy: 12 }), "c/4 diagonal");
+    assert_eq!(ships::speed(Motion { period: 6, dx: 2, dy: 1 }), "c/3 oblique");
+}
+
+#[test]
+fn names_the_other_spaceships() {
+    let mwss = "...O\n.O...O\nO\nO....O\nOOOOO";
+    let hwss = "...OO\n.O....O\nO\nO.....O\nOOOOOO";
+    assert_eq!(identify(mwss).as_deref().and_then(apgcode::name), Some("middleweight spaceship"));
+    assert_eq!(identify(hwss).as_deref().and_then(apgcode::name), Some("heavyweight spaceship"));
+}
+
+#[test]
+fn finds_standalone_copies_in_every_orientation() {
+    use rustlife::{find, BoundingBox};
+
+    // Two gliders facing different ways, and one touching a block
+    let life = universe(40, 40, &[(".O\n..O\nOOO", 2, 2), ("OOO\n..O\n.O", 20, 5), (".O\n..O\nOOO\n...OO\n...OO", 10, 25)]);
+    let glider = apgcode::decode(apgcode::by_name("Glider").unwrap()).unwrap();
+    assert_eq!(find::find(&life, &glider), [
+        BoundingBox { min_x: 2, min_y: 2, max_x: 4, max_y: 4 },
+        BoundingBox { min_x: 20, min_y: 5, max_x: 22, max_y: 7 },
+    ]);
+}
+
+#[test]
+fn pi_heptomino_is_a_methuselah() {
+    use rustlife::methuselah::{self, Leaderboard};
+
+    let r = methuselah::run(0, io::parse_plaintext("OOO\nO.O\nO.O"), Rule::conway()).unwrap();
+    let blinker = methuselah::run(1, io::parse_plaintext("OOO"), Rule::conway()).unwrap();
+    assert_eq!((r.lifespan, r.final_population), (173, 55));
+    assert_eq!((blinker.lifespan, blinker.final_population), (1, 3));
+
+    let mut board = Leaderboard::new(1);
+    board.add(1, Some(blinker));
+    board.add(0, Some(r));
+    board.add(2, None);
+    assert_eq!((board.tried, board.top[0].seed, board.unsettled.clone()), (3, 0, vec![2]));
+}
+
+#[test]
+fn symmetric_soups_are_symmetric() {
+    use rustlife::census;
+    use rustlife::soup::{self, Symmetry};
+
+    assert_eq!(soup::generate(Symmetry::C1, census::SOUP_SIZE, 0.5, 7), census::soup(7, Symmetry::C1));
+    for symmetry in Symmetry::ALL {
+        assert_eq!(symmetry.to_string().parse::<Symmetry>(), Ok(symmetry));
+        let live = soup::generate(symmetry, 16, 0.5, 7);
+        let alive = |x: usize, y: usize| live.contains(&(x, y));
+        let turned = live.iter().all(|&(x, y)| alive(15 - x, 15 - y));
+        let mirrored = live.iter().all(|&(x, y)| alive(15 - x, y));
+        let quarter = live.iter().all(|&(x, y)| alive(15 - y, x));
+        let diagonal = live.iter().all(|&(x, y)| alive(y, x));
+        let expected = match symmetry {
+            Symmetry::C1 => [false, false, false, false],
+            Symmetry::C2 => [true, false, false, false],
+            Symmetry::C4 => [true, false, true, false],
+            Symmetry::D2 => [false, true, false, false],
+            Symmetry::D4 => [true, true, false, false],
+            Symmetry::D8 => [true, true, true, true],
+        };
+        assert_eq!([turned, mirrored, quarter, diagonal], expected, "{}", symmetry);
+    }
+}
+
+#[test]
+fn soup_layouts_shape_the_soup() {
+    use rustlife::soup::{self, Layout, Symmetry};
+
+    let density = |layout: &Layout, rows: std::ops::Range<usize>, columns: std::ops::Range<usize>| {
+        let live = soup::generate_layout(layout, Symmetry::C1, (64, 64), 1.0, 3);
+        live.iter().filter(|(x, y)| rows.contains(y) && columns.contains(x)).count()
+    };
+    let stripes: Layout = "stripes:8".parse().unwrap();
+    assert_eq!(density(&stripes, 0..8, 0..64), 8 * 64);
+    assert_eq!(density(&stripes, 8..16, 0..64), 0);
+    let gradient: Layout = "gradient".parse().unwrap();
+    assert!(density(&gradient, 0..64, 0..8) < density(&gradient, 0..64, 56..64));
+    assert_eq!("rings".parse(), Ok(Layout::Rings { width: 16 }));
+    assert!("spirals".parse::<Layout>().is_err());
+
+    let tiles = Layout::Tiles { pattern: "OO\nOO\n".to_string(), gap: 2 };
+    assert_eq!(soup::generate_layout(&tiles, Symmetry::C1, (10, 4), 0.5, 0).len(), 3 * 4);
+}
+
+#[test]
+fn library_patterns_are_what_they_are_called() {
+    use rustlife::library;
+
+    let patterns = library::builtin();
+    assert!(patterns.iter().all(|p| !p.cells.is_empty()));
+    let named: Vec<_> = patterns.iter().filter(|p| apgcode::by_name(&p.name).is_some()).collect();
+    assert!(named.len() >= 10);
+    for pattern in named {
+        let code = apgcode::identify(&pattern.cells, Rule::conway());
+        assert_eq!(code.as_deref().and_then(apgcode::name), Some(pattern.name.as_str()), "{}", pattern.name);
+    }
+}
+
+#[test]
+fn demo_playlist_is_all_built_in() {
+    use rustlife::{demo, library};
+
+    let shows = demo::playlist();
+    assert!(shows.len() >= 8);
+    assert!(shows.iter().all(|s| !s.pattern.cells.is_empty() && s.generations > 0 && !s.caption.is_empty()));
+    // The newer additions are what they claim to be
+    let copperhead = library::find("copperhead").unwrap();
+    assert!(apgcode::identify(&copperhead.cells, Rule::conway()).is_some_and(|c| c.starts_with("xq10_")));
+}
+
+#[test]
+fn finds_patterns_by_name_or_apgcode() {
+    use rustlife::library;
+
+    assert_eq!(library::find(" gosper Glider GUN").map(|p| p.cells.len()), Some(36));
+    assert_eq!(library::find("xq4_153").map(|p| p.cells), apgcode::decode("xq4_153").ok());
+    assert!(library::find("not a pattern").is_none());
+    assert_eq!(library::lifewiki_name("Gosper glider gun"), "gosperglidergun");
+}
@@ -0,0 +1,44 @@
+use rustlife::replay::Start;
+use rustlife::{io, Boundary, DenseEngine, Rule, SimulationBuilder};
+
+#[test]
+fn builder_makes_the_universe_asked_for() {
+    let glider = io::parse_plaintext(".O\n..O\nOOO");
+    let life = SimulationBuilder::new(16, 12).rule("B36/S23".parse().unwrap()).pattern(&glider).build().unwrap();
+    assert_eq!((life.width(), life.height(), life.population()), (16, 12, 5));
+    assert_eq!(life.bounding_box().map(|b| (b.min_x, b.min_y)), Some((6, 4)));
+
+    let soup = |seed| SimulationBuilder::new(32, 32).rule(Rule::conway()).soup(0.5, seed).build().unwrap();
+    assert_eq!(io::to_plaintext(soup(7).as_ref()), io::to_plaintext(soup(7).as_ref()));
+    assert!(SimulationBuilder::new(8, 8).backend("quantum").build().is_err());
+    assert_eq!(SimulationBuilder::new(8, 8).backend("sand").build().unwrap().name(), "sand");
+}
+
+#[test]
+fn gliders_come_round_a_torus() {
+    let glider = io::parse_plaintext(".O\n..O\nOOO");
+    let build = |boundary| SimulationBuilder::new(12, 10).boundary(boundary).pattern(&glider).build().unwrap();
+    let (mut torus, mut dead) = (build(Boundary::Torus), build(Boundary::Dead));
+    let start = io::to_plaintext(torus.as_ref());
+    // A cell a generation in four, so back where it started once it has
+    // gone round both ways
+    torus.step_n(4 * 60);
+    dead.step_n(4 * 60);
+    assert_eq!(io::to_plaintext(torus.as_ref()), start);
+    // Whereas a dead edge turns it into a block
+    assert_eq!(dead.population(), 4);
+
+    // Stepping only around the few changes agrees with stepping every cell
+    // as the glider goes through the corner
+    let corner = Start::Pattern { x: 60, y: 44, pattern: ".O\n..O\nOOO".to_string() };
+    let mut life = SimulationBuilder::new(64, 48).boundary(Boundary::Torus).start(corner).build().unwrap();
+    let mut reference = life.downcast_ref::<DenseEngine>().unwrap().state().clone();
+    for _ in 0..40 {
+        life.step();
+        reference = reference.step();
+        assert_eq!(io::to_plaintext(life.as_ref()), io::to_plaintext(&DenseEngine::new(reference.clone())));
+    }
+    assert_eq!(life.population(), 5);
+
+    assert!(SimulationBuilder::new(8, 8).backend("sand").boundary(Boundary::Torus).build().is_err());
+}
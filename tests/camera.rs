@@ -0,0 +1,44 @@
+use std::sync::{Arc, RwLock};
+
+use rustlife::camera::{CameraPath, Shot};
+use rustlife::script::{ScriptHost, SharedLife};
+use rustlife::{ConwayState, DenseEngine};
+
+#[test]
+fn passes_through_each_keyframe_and_holds_at_the_ends() {
+    let path = CameraPath::parse("# a fly-over\n0 10 10 1\n\n100 50 30 4  # closer\n200 80 80 1\n").unwrap();
+    assert_eq!(path.keyframes().len(), 3);
+    assert_eq!(path.at(100), Some(Shot { x: 50.0, y: 30.0, zoom: 4.0 }));
+    assert_eq!(path.at(0), Some(Shot { x: 10.0, y: 10.0, zoom: 1.0 }));
+    assert_eq!(path.at(500), Some(Shot { x: 80.0, y: 80.0, zoom: 1.0 }));
+    // Smoothly, with no jumps from one generation to the next
+    let shots: Vec<Shot> = (0..=200).filter_map(|g| path.at(g)).collect();
+    assert!(shots.windows(2).all(|w| (w[1].x - w[0].x).abs() < 1.0 && (w[1].y - w[0].y).abs() < 1.0));
+    assert!(CameraPath::new().at(0).is_none());
+}
+
+#[test]
+fn zooms_at_a_steady_rate() {
+    let path = CameraPath::parse("0 0 0 1\n100 0 0 16").unwrap();
+    let zoom = |g| path.at(g).unwrap().zoom;
+    assert!((zoom(50) - 4.0).abs() < 1e-9, "{}", zoom(50));
+}
+
+#[test]
+fn bad_paths_are_refused() {
+    assert!(CameraPath::parse("").is_err());
+    assert!(CameraPath::parse("0 1 2").unwrap_err().contains("line 1"));
+    assert!(CameraPath::parse("0 1 2 3\n10 1 2 0").unwrap_err().contains("line 2"));
+    assert!(CameraPath::parse("-5 1 2 3").is_err());
+}
+
+#[test]
+fn scripts_add_keyframes() {
+    let life: SharedLife = Arc::new(RwLock::new(Box::new(DenseEngine::new(ConwayState::empty(64, 64)))));
+    let mut host = ScriptHost::new(life, "camera(0, 32, 32, 1); camera(50, 10.5, 20, 2.5);").unwrap();
+    host.on_start().unwrap();
+    let camera = host.camera();
+    let path = camera.lock().unwrap();
+    assert_eq!(path.at(50), Some(Shot { x: 10.5, y: 20.0, zoom: 2.5 }));
+    assert_eq!(path.keyframes().len(), 2);
+}
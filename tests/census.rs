@@ -0,0 +1,50 @@
+use rustlife::census::{self, Census};
+use rustlife::soup::Symmetry;
+use rustlife::{io, BoundingBox, ConwayState, DenseEngine, Rule};
+
+/// A `width` by `height` universe with each pattern's corner at its (x, y).
+fn universe(width: usize, height: usize, patterns: &[(&str, usize, usize)]) -> DenseEngine {
+    let mut life = DenseEngine::new(ConwayState::empty(width, height));
+    for &(pattern, x, y) in patterns {
+        io::place(&mut life, &io::parse_plaintext(pattern), x, y);
+    }
+    life
+}
+
+#[test]
+fn census_counts_every_soup() {
+    let mut total = Census::new();
+    for seed in 0..2 {
+        total.add(census::search(seed, Symmetry::C1, Rule::conway()));
+    }
+    assert_eq!(total.soups, 2);
+    assert!(total.haul(Rule::conway(), Symmetry::C1).starts_with("@RULE b3s23\n@SYMMETRY C1\n"));
+    // Named as apgsearch does, centred between cells on a 16x16 soup
+    assert!(total.haul(Rule::conway(), Symmetry::D4).starts_with("@RULE b3s23\n@SYMMETRY D4_+4\n"));
+    assert_eq!(Symmetry::D8.apgsearch(17), "D8_1");
+}
+
+#[test]
+fn identifies_each_object_in_a_region() {
+    // A glider, a blinker on its side, a long boat turned round and a cell
+    let life = universe(48, 48, &[("..O\nO.O\n.OO", 30, 4), ("O\nO\nO", 4, 4), (".O\nO.O\n.O.O\n..OO", 4, 20), ("O", 40, 40)]);
+    let found: Vec<String> = census::identify_region(&life, None).iter().map(|o| o.to_string()).collect();
+    assert_eq!(found, [
+        "blinker (xp2_7) at 4,4",
+        "glider (xq4_153) at 30,4",
+        "long boat (xs7_25ac) at 4,20",
+        "unidentified object of 1 cell at 40,40",
+    ]);
+    let region = BoundingBox { min_x: 0, min_y: 0, max_x: 15, max_y: 30 };
+    let codes: Vec<String> = census::identify_region(&life, Some(region)).into_iter().map(|o| o.code).collect();
+    assert_eq!(codes, ["xp2_7", "xs7_25ac"]);
+    assert!(census::identify_region(&life, Some(BoundingBox { min_x: 60, min_y: 0, max_x: 70, max_y: 5 })).is_empty());
+}
+
+#[test]
+fn census_of_a_universe() {
+    let life = universe(32, 32, &[("OO\nOO", 2, 2), ("OO\nOO", 20, 2), ("OOO", 2, 20), ("O", 20, 20)]);
+    let taken = census::take(&life);
+    assert_eq!(taken.sorted(), [("xs4_33", 2), ("unidentified", 1), ("xp2_7", 1)]);
+    assert!(taken.report().starts_with("     2 block (xs4_33)\n"));
+}
@@ -0,0 +1,23 @@
+use rustlife::checkpoint::{self, Checkpoint};
+use rustlife::{io, SimulationBuilder};
+
+#[test]
+fn carries_on_from_the_latest() {
+    let dir = std::env::temp_dir().join(format!("rustlife-checkpoints-{}", std::process::id()));
+    let glider = io::parse_plaintext(".O\n..O\nOOO");
+    let mut life = SimulationBuilder::new(32, 32).pattern(&glider).build().unwrap();
+    for generation in 1..=5 {
+        life.step();
+        Checkpoint::of(generation, life.as_ref()).save(&dir).unwrap();
+    }
+    let saved = std::fs::read_dir(&dir).unwrap().count();
+    assert_eq!(saved, checkpoint::KEEP);
+
+    let latest = Checkpoint::latest(&dir).unwrap().unwrap();
+    assert_eq!(latest.generation(), 5);
+    let mut resumed = SimulationBuilder::new(32, 32).build().unwrap();
+    latest.restore(resumed.as_mut()).unwrap();
+    assert_eq!(io::to_plaintext(resumed.as_ref()), io::to_plaintext(life.as_ref()));
+    assert!(latest.restore(SimulationBuilder::new(16, 16).build().unwrap().as_mut()).is_err());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,36 @@
+use rustlife::evolve::{self, Evolution, Fitness};
+use rustlife::{io, Rule};
+
+fn genome(pattern: &str) -> u64 {
+    io::parse_plaintext(pattern).iter().fold(0, |g, &(x, y)| g | 1 << (y * evolve::SEED_BOX + x))
+}
+
+#[test]
+fn scores_follow_the_fitness() {
+    let glider = genome(".O\n..O\nOOO\n");
+    assert_eq!(evolve::pattern(glider), io::parse_plaintext(".O\n..O\nOOO\n"));
+    // A quarter of a cell each way a generation
+    let travelled = evolve::score(glider, Fitness::Distance, Rule::conway());
+    assert!((travelled - 64.0 * 2f64.sqrt()).abs() < 2.0, "{}", travelled);
+
+    let block = genome("OO\nOO\n");
+    assert_eq!(evolve::score(block, Fitness::Distance, Rule::conway()), 0.0);
+    assert_eq!(evolve::score(block, Fitness::Population, Rule::conway()), 4.0);
+    let blinker = evolve::score(genome("OOO\n"), Fitness::Lifespan, Rule::conway());
+    assert!(blinker <= 1.0, "{}", blinker);
+    assert_eq!(evolve::score(0, Fitness::Population, Rule::conway()), 0.0);
+}
+
+#[test]
+fn the_best_is_never_lost() {
+    let mut evolution = Evolution::new(8, Fitness::Distance, Rule::conway(), 7);
+    let mut best = evolution.best().score;
+    for _ in 0..3 {
+        evolution.step();
+        assert!(evolution.best().score >= best);
+        best = evolution.best().score;
+        assert_eq!(evolution.candidates.len(), 8);
+    }
+    assert_eq!(evolution.generation, 3);
+    assert_eq!("distance".parse::<Fitness>().unwrap(), Fitness::Distance);
+}
@@ -0,0 +1,31 @@
+use rustlife::explore::{self, Outcome, Ranking};
+use rustlife::Rule;
+
+#[test]
+fn random_rules_repeat_and_never_have_b0() {
+    for number in 0..100 {
+        let rule = explore::random_rule(number);
+        assert!(!rule.birth[0], "{}", rule);
+        assert_eq!(explore::random_rule(number), rule);
+    }
+}
+
+#[test]
+fn trials_tell_dead_full_and_busy_soups_apart() {
+    let died = explore::trial(1, "B/S".parse().unwrap());
+    assert_eq!((died.outcome, died.score), (Outcome::DiedOut, 0.0));
+    let exploded = explore::trial(1, "B1/S012345678".parse().unwrap());
+    assert_eq!(exploded.outcome, Outcome::Exploded);
+
+    let mut ranking = Ranking::new(1);
+    ranking.add(died);
+    ranking.add(exploded);
+    // Keeps churning without spreading far
+    let busy = explore::trial(1, "B3458/S47".parse().unwrap());
+    assert_eq!(busy.outcome, Outcome::Active, "{:?}", busy);
+    assert!(busy.score > 0.0);
+    ranking.add(busy.clone());
+    ranking.add(explore::trial(1, Rule::conway()));
+    assert_eq!((ranking.tried, ranking.died_out, ranking.exploded), (4, 1, 1));
+    assert_eq!(ranking.top.len(), 1);
+}
@@ -0,0 +1,122 @@
+//! Properties of the pattern parsers, checked with proptest on random
+//! patterns and on broken files, since patterns come from the internet.
+//! Failing cases are shrunk and saved under `proptest-regressions`; set
+//! `PROPTEST_CASES` for a longer run than the default.  `fuzz/` has a
+//! cargo-fuzz target for the same parsers.
+
+use std::collections::BTreeSet;
+
+use proptest::prelude::*;
+use proptest::sample::Index;
+use rustlife::{apgcode, io, Rule};
+
+const CORPUS: [&str; 11] = [
+    "x = 3, y = 3, rule = B3/S23\nbo$2bo$3o!",
+    "#N Gosper glider gun\nx = 36, y = 9\n24bo$22bobo$12b2o6b2o12b2o$11bo3bo4b2o12b2o$2o8bo5bo3b2o$2o8bo3bob2o4bobo$10bo5bo7bo$11bo3bo$12b2o!",
+    "!Name: glider\n.O\n..O\nOOO",
+    "xq4_153",
+    "xs4_33",
+    "xp2_7zy3123",
+    "B36/S23",
+    "23/3",
+    "#Life 1.05\n#R 23/3\n#P -1 -1\n.*\n..*\n***",
+    "#Life 1.06\n1 0\n2 1\n0 2\n1 2\n2 2",
+    "[M2] (rustlife)\n#R B3/S23\n.*$..*$***$\n4 1 0 0 0\n5 2 0 0 2",
+];
+
+/// Live cells in reading order, as the parsers give them back.
+fn pattern() -> impl Strategy<Value = Vec<(usize, usize)>> {
+    prop::collection::btree_set((0usize..120, 0usize..40), 0..400).prop_map(|cells: BTreeSet<_>| {
+        let mut live: Vec<_> = cells.into_iter().collect();
+        live.sort_by_key(|&(x, y)| (y, x));
+        live
+    })
+}
+
+/// Byte-level damage of the kind truncated downloads and hostile files have.
+fn damaged() -> impl Strategy<Value = String> {
+    let edit = (any::<Index>(), 0u8..6, any::<u8>(), 1usize..21);
+    (prop::sample::select(&CORPUS[..]), prop::collection::vec(edit, 1..6)).prop_map(|(seed, edits)| {
+        let mut bytes = seed.as_bytes().to_vec();
+        for (at, kind, byte, run) in edits {
+            let at = at.index(bytes.len() + 1);
+            match kind {
+                0 if at < bytes.len() => bytes[at] = byte,
+                1 if at < bytes.len() => { bytes.remove(at); }
+                2 => bytes.insert(at, b"0123456789bo$!.Oxyz_#= \n"[byte as usize % 24]),
+                // Runs long enough to overflow or exhaust memory
+                3 => bytes.splice(at..at, b"99999999999999999999".iter().copied().take(run)).for_each(drop),
+                4 => bytes.truncate(at),
+                _ => {
+                    let copy = bytes[at..].to_vec();
+                    bytes.extend(copy);
+                }
+            }
+        }
+        String::from_utf8_lossy(&bytes).into_owned()
+    })
+}
+
+fn parse_everything(input: &str) {
+    let _ = io::parse_pattern(input);
+    let _ = io::parse_rle(input);
+    io::parse_plaintext(input);
+    let _ = apgcode::decode(input);
+    let _ = input.parse::<Rule>();
+}
+
+proptest! {
+    #[test]
+    fn patterns_come_back_as_written(live in pattern()) {
+        let rle = io::cells_to_rle(&live);
+        prop_assert_eq!(io::parse_rle(&rle).unwrap(), live.clone(), "{}", rle);
+        prop_assert_eq!(io::cells_to_rle(&io::parse_rle(&rle).unwrap()), rle.clone());
+        prop_assert_eq!(io::parse_pattern(&rle).unwrap(), live.clone(), "{}", rle);
+
+        let plain = io::cells_to_plaintext(&live);
+        prop_assert_eq!(io::parse_plaintext(&plain), live.clone(), "{}", plain);
+        prop_assert_eq!(io::parse_pattern(&plain).unwrap(), live.clone(), "{}", plain);
+
+        // These keep no empty rows or columns at the top left
+        if !live.is_empty() {
+            let (left, top) = (live.iter().map(|c| c.0).min().unwrap(), live.iter().map(|c| c.1).min().unwrap());
+            let cornered: Vec<_> = live.iter().map(|&(x, y)| (x - left, y - top)).collect();
+            let rule = Rule::conway();
+            for written in [io::cells_to_life105(&live, Some(&rule)), io::cells_to_life106(&live), io::cells_to_macrocell(&live, Some(&rule))] {
+                prop_assert_eq!(io::parse_pattern(&written).unwrap(), cornered.clone(), "{}", written);
+            }
+        }
+    }
+
+    #[test]
+    fn damaged_patterns_never_panic(input in damaged()) {
+        parse_everything(&input);
+    }
+
+    #[test]
+    fn any_text_never_panics(input in "\\PC{0,64}") {
+        parse_everything(&input);
+    }
+}
+
+#[test]
+fn macrocell_bombs_are_refused() {
+    // Every level a square of four copies of the one below, down to a leaf
+    let tower = |leaf: &str, levels: usize| {
+        let branches: String = (4..4 + levels).enumerate().map(|(i, level)| format!("{} {} {} {} {}\n", level, i + 1, i + 1, i + 1, i + 1)).collect();
+        format!("[M2]\n{}\n{}", leaf, branches)
+    };
+    assert_eq!(io::parse_macrocell(&tower("$", 40)).unwrap(), []);
+    assert!(io::parse_macrocell(&tower("*$", 20)).unwrap_err().contains("more than"));
+    // Children must be a level down
+    assert!(io::parse_macrocell("[M2]\n*$\n9 1 1 1 1").is_err());
+    assert!(io::parse_macrocell("[M2]\n*$\n4 1 0 0 0\n6 2 0 0 0").is_err());
+}
+
+#[test]
+fn huge_runs_are_refused() {
+    assert!(io::parse_rle("18446744073709551615b2o!").is_err());
+    assert!(io::parse_rle("999999999o!").is_err());
+    assert!(io::parse_rle(&format!("{}$o!", io::MAX_EXTENT)).is_err());
+    assert_eq!(io::parse_rle(&format!("{}bo!", io::MAX_EXTENT - 1)).unwrap(), [(io::MAX_EXTENT - 1, 0)]);
+}
@@ -0,0 +1,626 @@
+use rustlife::{io, CellState, ConwayState};
+
+fn universe(width: usize, height: usize, pattern: &str, x: usize, y: usize) -> ConwayState {
+    let mut state = ConwayState::empty(width, height);
+    for (px, py) in io::parse_plaintext(pattern) {
+        state.set(x + px, y + py, CellState::Alive);
+    }
+    state
+}
+
+fn live_cells(state: &ConwayState) -> Vec<(usize, usize)> {
+    let mut live = Vec::new();
+    for y in 0..state.height {
+        for x in 0..state.width {
+            if state.get(x, y) == CellState::Alive {
+                live.push((x, y));
+            }
+        }
+    }
+    live
+}
+
+fn step_n(state: &ConwayState, n: usize) -> ConwayState {
+    let mut state = state.clone();
+    for _ in 0..n {
+        state = state.step();
+    }
+    state
+}
+
+#[test]
+fn blinker_has_period_two() {
+    let start = universe(5, 5, "OOO", 1, 2);
+    let one = start.step();
+    let two = one.step();
+
+    assert_eq!(live_cells(&one), vec![(2, 1), (2, 2), (2, 3)]);
+    assert_eq!(live_cells(&two), live_cells(&start));
+}
+
+#[test]
+fn block_is_still() {
+    let start = universe(4, 4, "OO\nOO", 1, 1);
+    assert_eq!(live_cells(&start.step()), live_cells(&start));
+}
+
+#[test]
+fn glider_moves_one_cell_diagonally_every_four_generations() {
+    let start = universe(16, 16, ".O\n..O\nOOO", 2, 2);
+    let moved: Vec<_> = live_cells(&start).into_iter().map(|(x, y)| (x + 1, y + 1)).collect();
+
+    assert_eq!(live_cells(&step_n(&start, 4)), moved);
+}
+
+#[test]
+fn r_pentomino_stabilises_at_116() {
+    // Big enough that the six gliders it throws off never reach the edge
+    let start = universe(640, 640, ".OO\nOO\n.O", 319, 319);
+    assert_eq!(step_n(&start, 1103).population(), 116);
+}
+
+#[test]
+fn parallel_step_matches_serial_step() {
+    let mut start = universe(97, 61, "", 0, 0);
+    for (i, c) in start.cells.iter_mut().enumerate() {
+        if (i * 7919) % 13 < 5 {
+            *c = CellState::Alive;
+        }
+    }
+    let mut parallel = ConwayState::empty(97, 61);
+    start.next_state(&mut parallel);
+    // Four strips that don't divide the rows evenly
+    let mut strips = ConwayState::empty(97, 61);
+    let pool = rayon::ThreadPoolBuilder::new().num_threads(4).build().unwrap();
+    pool.install(|| start.next_state_strips(&mut strips));
+
+    assert_eq!(parallel.cells, start.step().cells);
+    assert_eq!(strips.cells, parallel.cells);
+}
+
+#[test]
+fn stepping_only_the_changes_matches_stepping_everything() {
+    use rustlife::{DenseEngine, LifeEngine, Rule};
+
+    // Sparse enough that most steps only look at the changed cells
+    let start = universe(64, 64, ".OO\nOO\n.O", 30, 30);
+    let mut life = DenseEngine::new(start.clone());
+    let mut reference = start;
+    for generation in 0..300 {
+        match generation {
+            100 => {
+                life.set(5, 5, CellState::Alive);
+                life.set(6, 5, CellState::Alive);
+                life.set(7, 5, CellState::Alive);
+                reference.cells[5 * 64 + 5..5 * 64 + 8].fill(CellState::Alive);
+            }
+            150 => {
+                life.set_wall(40, 40, Some(CellState::Alive));
+                reference.set_wall(40, 40, Some(CellState::Alive));
+            }
+            200 => {
+                let highlife: Rule = "B36/S23".parse().unwrap();
+                life.set_rule(highlife);
+                reference.rule = highlife;
+            }
+            _ => {}
+        }
+        let next = reference.step();
+        let changed = reference.cells.iter().zip(&next.cells).filter(|(a, b)| a != b).count();
+        life.step();
+        reference = next;
+        assert_eq!(life.state().cells, reference.cells, "generation {}", generation + 1);
+        assert_eq!((life.population(), life.changed_cells()), (reference.population(), changed), "generation {}", generation + 1);
+        assert_eq!(life.bounding_box(), reference.bounding_box(), "generation {}", generation + 1);
+    }
+}
+
+#[test]
+fn generations_match_repeated_steps() {
+    let start = universe(16, 16, ".O\n..O\nOOO", 1, 1);
+    let boards: Vec<ConwayState> = start.generations().take(9).collect();
+
+    for (n, board) in boards.iter().enumerate() {
+        assert_eq!(board.cells, step_n(&start, n).cells, "generation {}", n);
+    }
+    assert_eq!(start.step_n(8).cells, boards[8].cells);
+}
+
+#[test]
+fn blinker_events_count_births_and_deaths() {
+    use rustlife::stats::PopulationHistory;
+    use rustlife::{DenseEngine, GenerationEvent, LifeEngine};
+
+    let mut life = DenseEngine::new(universe(5, 5, "OOO", 1, 2));
+    let mut history = PopulationHistory::new(2);
+    for generation in 1..=3 {
+        life.step();
+        history.record((&GenerationEvent::from_engine(generation, &life)).into());
+    }
+    let samples: Vec<_> = history.iter().map(|s| (s.generation, s.population, s.births, s.deaths)).collect();
+    assert_eq!(samples, [(2, 3, 2, 2), (3, 3, 2, 2)]);
+    assert!(history.to_csv().starts_with("generation,population,births,deaths\n2,3,2,2\n"));
+}
+
+#[test]
+fn detector_finds_blinker_period() {
+    use rustlife::period::{self, PeriodDetector};
+    use rustlife::{DenseEngine, LifeEngine};
+
+    let mut life = DenseEngine::new(universe(8, 8, "OOO", 2, 3));
+    let mut detector = PeriodDetector::default();
+    let found: Vec<_> = (0..4)
+        .map(|generation| {
+            let period = detector.observe(generation, period::hash(&life, None));
+            life.step();
+            period
+        })
+        .collect();
+    assert_eq!(found, [None, None, Some(2), Some(2)]);
+}
+
+#[test]
+fn activity_settles_with_the_blinker() {
+    use rustlife::stats::{self, ActivityMeter};
+    use rustlife::{DenseEngine, GenerationEvent, LifeEngine};
+
+    let mut life = DenseEngine::new(universe(16, 16, "OOO", 2, 3));
+    let mut meter = ActivityMeter::new();
+    let mut areas = Vec::new();
+    for generation in 1..=3 {
+        life.step();
+        areas.push(meter.measure(&GenerationEvent::from_engine(generation, &life), &life).active_area);
+    }
+    // Only the tile holding the blinker ever changes
+    assert_eq!(areas, [64, 64, 64]);
+    assert_eq!(stats::entropy(&DenseEngine::new(universe(16, 16, "", 0, 0))), 0.0);
+    assert!(stats::entropy(&life) > 0.0);
+}
+
+#[test]
+fn stop_conditions_fire_once() {
+    use rustlife::stop::{StopChecker, StopConditions, StopReason};
+    use rustlife::{DenseEngine, GenerationEvent, LifeEngine};
+
+    let run = |pattern: &str, conditions: StopConditions| {
+        let mut life = DenseEngine::new(universe(8, 8, pattern, 2, 3));
+        let mut checker = StopChecker::new(conditions);
+        (1..=6).filter_map(|generation| {
+            life.step();
+            checker.check(&GenerationEvent::from_engine(generation, &life), &life).map(|r| (generation, r))
+        }).collect::<Vec<_>>()
+    };
+    let all = StopConditions { extinction: true, repeat: true, generation: Some(5) };
+    assert_eq!(run("OOO", all), [(3, StopReason::Repeating(2)), (5, StopReason::Reached(5))]);
+    assert_eq!(run("OO", all), [(1, StopReason::Extinct), (5, StopReason::Reached(5))]);
+    assert_eq!(run("OOO", StopConditions::default()), []);
+}
+
+#[test]
+fn heatmap_counts_time_alive() {
+    use rustlife::stats::Heatmap;
+    use rustlife::{DenseEngine, LifeEngine};
+
+    let mut life = DenseEngine::new(universe(5, 5, "OOO", 1, 2));
+    let mut heatmap = Heatmap::new();
+    for _ in 0..4 {
+        heatmap.record(&life);
+        life.step();
+    }
+    assert_eq!(heatmap.generations, 4);
+    assert_eq!((heatmap.get(2, 2), heatmap.get(1, 2), heatmap.get(2, 1), heatmap.get(0, 0)), (1.0, 0.5, 0.5, 0.0));
+}
+
+#[test]
+fn ages_count_generations_alive_in_a_row() {
+    use rustlife::stats::Ages;
+    use rustlife::{DenseEngine, LifeEngine};
+
+    let mut life = DenseEngine::new(universe(5, 5, "OOO", 1, 2));
+    let mut ages = Ages::new();
+    for _ in 0..3 {
+        ages.record(&life);
+        life.step();
+    }
+    // The blinker's middle never dies; its ends come and go
+    assert_eq!((ages.get(2, 2), ages.get(1, 2), ages.get(2, 1), ages.get(0, 0)), (3, 1, 0, 0));
+    ages.clear();
+    assert_eq!(ages.get(2, 2), 0);
+}
+
+#[test]
+fn bounding_box_follows_steps_and_edits() {
+    use rustlife::{BoundingBox, DenseEngine, LifeEngine};
+
+    let mut life = DenseEngine::new(universe(16, 16, ".O\n..O\nOOO", 1, 1));
+    assert_eq!(life.bounding_box(), Some(BoundingBox { min_x: 1, min_y: 1, max_x: 3, max_y: 3 }));
+    life.step_n(4);
+    assert_eq!(life.bounding_box(), Some(BoundingBox { min_x: 2, min_y: 2, max_x: 4, max_y: 4 }));
+    life.set(10, 12, CellState::Alive);
+    assert_eq!(life.bounding_box(), Some(BoundingBox { min_x: 2, min_y: 2, max_x: 10, max_y: 12 }));
+    life.set(10, 12, CellState::Dead);
+    assert_eq!(life.bounding_box(), Some(BoundingBox { min_x: 2, min_y: 2, max_x: 4, max_y: 4 }));
+    life.clear();
+    assert_eq!(life.bounding_box(), None);
+}
+
+#[test]
+fn regions_are_tracked_separately() {
+    use rustlife::stats::{Region, RegionStats};
+    use rustlife::{DenseEngine, LifeEngine};
+
+    let mut life = DenseEngine::new(universe(16, 8, "OOO", 1, 2));
+    life.set(10, 2, CellState::Alive);
+    life.set(11, 2, CellState::Alive);
+    life.set(10, 3, CellState::Alive);
+    life.set(11, 3, CellState::Alive);
+    let mut regions = RegionStats::new(8);
+    regions.add(Region { name: "blinker".to_string(), x: 0, y: 0, width: 5, height: 5 });
+    regions.add(Region { name: "block".to_string(), x: 8, y: 0, width: 8, height: 8 });
+    for generation in 0..2 {
+        regions.record(generation, &life);
+        life.step();
+    }
+    assert_eq!(regions.latest("blinker").map(|s| (s.population, s.changed)), Some((3, 4)));
+    assert_eq!(regions.latest("block").map(|s| (s.population, s.changed)), Some((4, 0)));
+    assert!(regions.to_csv().ends_with("1,blinker,3,4\n1,block,4,0\n"));
+    assert!(regions.remove("block") && !regions.remove("block"));
+}
+
+#[test]
+fn noise_flips_the_expected_share_of_cells() {
+    use rand::SeedableRng;
+    use rustlife::{noise, DenseEngine, LifeEngine};
+
+    let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(1);
+    let mut life = DenseEngine::new(ConwayState::empty(100, 100));
+    assert_eq!(noise::inject(&mut life, 0.0, &mut rng), 0);
+    assert_eq!(noise::inject(&mut life, 1.0, &mut rng), 10_000);
+    assert_eq!(life.population(), 10_000);
+    let flipped = noise::inject(&mut life, 0.01, &mut rng);
+    assert!((50..150).contains(&flipped), "{} flipped", flipped);
+    assert_eq!(life.population(), 10_000 - flipped);
+    // Too small for 1 - p to be told from 1
+    assert_eq!(noise::inject(&mut life, 1e-18, &mut rng), 0);
+    assert_eq!(noise::inject(&mut life, f64::MIN_POSITIVE, &mut rng), 0);
+}
+
+#[test]
+fn walls_hold_their_state() {
+    use rustlife::{DenseEngine, LifeEngine};
+
+    // A hole stops half of a blinker from turning, and a wall outlasts
+    // clearing and painting until it is removed
+    let mut life = DenseEngine::new(universe(8, 8, "OOO", 2, 4));
+    assert!(life.set_wall(3, 3, Some(CellState::Dead)));
+    life.step();
+    assert_eq!(life.get(3, 3), CellState::Dead);
+    assert_eq!(life.population(), 2);
+    assert!(life.set_wall(0, 0, Some(CellState::Alive)));
+    life.clear();
+    life.set(0, 0, CellState::Dead);
+    assert_eq!((life.get(0, 0), life.population()), (CellState::Alive, 1));
+    life.set_wall(0, 0, None);
+    life.step();
+    assert_eq!(life.population(), 0);
+}
+
+#[test]
+fn predators_eat_prey_and_starve_without_it() {
+    use rustlife::species::{Species, SpeciesEngine};
+    use rustlife::LifeEngine;
+
+    let mut life = SpeciesEngine::new(8, 8);
+    for (x, y) in [(2, 2), (3, 2), (2, 3), (3, 3)] {
+        life.set(x, y, CellState::Alive);
+    }
+    life.set_species(1, 1, Species::Predator);
+    assert_eq!(life.populations(), [4, 1]);
+
+    // The prey cell touching the predator is eaten, the predator has too
+    // few neighbours to survive, and two prey are born beside it
+    life.step();
+    assert_eq!(life.species(2, 2), Some(Species::Predator));
+    assert_eq!(life.species(1, 1), None);
+    assert_eq!(life.populations(), [5, 1]);
+    assert_eq!(life.population(), 6);
+
+    let mut alone = SpeciesEngine::new(8, 8);
+    for (x, y) in [(2, 2), (3, 2), (2, 3), (3, 3)] {
+        alone.set_species(x, y, Species::Predator);
+    }
+    alone.step();
+    assert_eq!(alone.populations(), [0, 0]);
+}
+
+#[test]
+fn reaction_grows_from_a_seed_unless_killed() {
+    use rustlife::reaction::ReactionEngine;
+    use rustlife::LifeEngine;
+
+    let run = |kill: f64| {
+        let mut life = ReactionEngine::new(64, 64);
+        life.set_rates(0.055, kill);
+        for y in 29..35 {
+            for x in 29..35 {
+                life.set(x, y, CellState::Alive);
+            }
+        }
+        assert_eq!(life.population(), 36);
+        life.step_n(500);
+        assert!((0..64).all(|x| (0.0..=1.0).contains(&life.level(x, 32))));
+        life.population()
+    };
+    assert!(run(0.062) > 36);
+    assert_eq!(run(0.1), 0);
+}
+
+#[test]
+fn sand_falls_and_piles_up() {
+    use rustlife::sand::{Element, SandEngine};
+    use rustlife::LifeEngine;
+
+    let mut life = SandEngine::new(16, 16);
+    for x in 4..12 {
+        life.set_element(x, 0, Element::Sand);
+        life.set_element(x, 1, Element::Sand);
+    }
+    life.set_element(8, 8, Element::Fire);
+    life.set_element(1, 15, Element::Wall);
+    life.step_n(200);
+
+    // Everything has landed on the floor, none lost, and the fire is out
+    let counts = life.counts();
+    assert_eq!(counts[Element::Sand as usize], 16);
+    assert_eq!(counts[Element::Fire as usize], 0);
+    assert_eq!(life.element(1, 15), Element::Wall);
+    for x in 0..16 {
+        for y in 0..15 {
+            if life.element(x, y) == Element::Sand {
+                assert_eq!(life.element(x, y + 1), Element::Sand, "sand floating at {}, {}", x, y);
+            }
+        }
+    }
+}
+
+#[test]
+fn forest_grows_and_burns() {
+    use rustlife::forest::ForestEngine;
+    use rustlife::LifeEngine;
+
+    // Without lightning an empty forest only grows
+    let mut life = ForestEngine::new(64, 64);
+    life.set_chances(0.05, 0.0);
+    life.step_n(100);
+    let grown = life.stats();
+    assert!(grown.trees > 64 * 64 * 9 / 10, "only {} trees", grown.trees);
+    assert_eq!((grown.burning, grown.strikes), (0, 0));
+
+    // Then a strike sets it alight and fire spreads through it
+    life.set_chances(0.0, 0.001);
+    life.step_n(20);
+    let burnt = life.stats();
+    assert!(burnt.strikes > 0);
+    assert!(burnt.trees + burnt.burning < grown.trees / 2, "{} trees left", burnt.trees);
+
+    // The same seed runs the same way
+    let run = |seed| {
+        let mut life = ForestEngine::new(32, 32);
+        life.randomize(0.5, seed);
+        life.step_n(50);
+        (life.stats().trees, life.population())
+    };
+    assert_eq!(run(7), run(7));
+}
+
+#[test]
+fn cyclic_states_are_eaten_by_their_successors() {
+    use rustlife::cyclic::CyclicEngine;
+    use rustlife::{CellState, LifeEngine};
+
+    // A cell with its successor next to it moves on: with three states
+    // state 1 spreads into 0 and stops there
+    let mut life = CyclicEngine::new(3, 1);
+    life.set_params(3, 1);
+    life.set(0, 0, CellState::Alive);
+    life.step();
+    assert_eq!((life.get(1, 0), life.get(2, 0)), (CellState::Alive, CellState::Dead));
+    life.step_n(5);
+    assert_eq!(life.population(), 3);
+
+    // With two, state 1 wraps round to 0 as it spreads
+    let mut life = CyclicEngine::new(3, 1);
+    life.set_params(2, 1);
+    life.set(0, 0, CellState::Alive);
+    life.step();
+    assert_eq!((life.get(0, 0), life.get(1, 0), life.get(2, 0)), (CellState::Dead, CellState::Alive, CellState::Dead));
+
+    // From a random soup it settles into waves that keep most cells moving
+    let mut life = CyclicEngine::new(64, 64);
+    life.set_params(8, 1);
+    life.randomize(1.0, 3);
+    life.step_n(300);
+    assert!(life.changed_cells() > 64 * 64 / 2, "only {} cells changed", life.changed_cells());
+}
+
+#[test]
+fn ising_orders_when_cold_and_follows_the_field() {
+    use rustlife::ising::IsingEngine;
+    use rustlife::LifeEngine;
+
+    // Cold, a random start coarsens into domains, lowering the energy
+    let mut life = IsingEngine::new(64, 64);
+    life.set_params(1.0, 0.0);
+    life.randomize(0.5, 11);
+    let start = life.stats().energy;
+    life.step_n(100);
+    let cold = life.stats();
+    assert!(cold.energy < start - 1.0, "energy only fell from {} to {}", start, cold.energy);
+
+    // A strong field turns every spin its way
+    life.set_params(1.0, -5.0);
+    life.step_n(5);
+    assert_eq!(life.population(), 0);
+    assert_eq!(life.stats().magnetization, -1.0);
+
+    // Hot, it stays disordered
+    life.set_params(100.0, 0.0);
+    life.step_n(50);
+    assert!(life.stats().magnetization.abs() < 0.1);
+}
+
+#[test]
+fn lattice_gases_keep_their_particles() {
+    use rustlife::gas::{GasEngine, GasModel};
+    use rustlife::{CellState, LifeEngine};
+
+    // Two HPP particles meeting head on leave at right angles
+    let mut life = GasEngine::new(8, 8);
+    life.set(3, 3, CellState::Alive);
+    life.step();
+    assert_eq!(life.stats().particles, 4);
+    assert_eq!([(4, 3), (2, 3), (3, 2), (3, 4)].map(|(x, y)| life.get(x, y)), [CellState::Alive; 4]);
+
+    // A random gas keeps its particles, bouncing them off the edges
+    for model in GasModel::ALL {
+        let mut life = GasEngine::new(48, 32);
+        life.set_model(model);
+        life.randomize(0.3, 5);
+        let start = life.stats().particles;
+        life.step_n(200);
+        let after = life.stats();
+        assert_eq!(after.particles, start, "{} lost particles", model);
+        assert_eq!(after.model, model);
+    }
+}
+
+#[test]
+fn rule_110_tapes_send_out_gliders() {
+    use rustlife::elementary::{self, ElementaryEngine};
+    use rustlife::{CellState, LifeEngine};
+
+    // Each generation is a new row: rule 90 grows a Sierpinski triangle
+    let mut life = ElementaryEngine::new(9, 4);
+    life.set_code(90);
+    life.set(4, 0, CellState::Alive);
+    life.step_n(2);
+    let row = |life: &ElementaryEngine, y| (0..9).map(|x| if life.get(x, y) == CellState::Alive { 'O' } else { '.' }).collect::<String>();
+    assert_eq!([row(&life, 1), row(&life, 2)], ["...O.O...", "..O...O.."]);
+    // Once full the history scrolls up
+    life.step_n(2);
+    assert_eq!(row(&life, 0), "...O.O...");
+
+    let highlighted = |life: &ElementaryEngine| (0..life.height())
+        .flat_map(|y| (0..life.width()).map(move |x| (x, y)))
+        .filter(|&(x, y)| life.color(x, y).is_some())
+        .count();
+
+    // Plain ether stays plain, while a tape breaks it up into gliders
+    let mut life = ElementaryEngine::new(14 * 12, 60);
+    elementary::load_tape(&mut life, "").unwrap();
+    life.step_n(40);
+    assert_eq!(highlighted(&life), 0);
+    elementary::load_tape(&mut life, "1101").unwrap();
+    life.step_n(40);
+    assert!(highlighted(&life) > 0);
+    assert!(elementary::load_tape(&mut life, "12").is_err());
+}
+
+#[test]
+fn volumes_stack_generations_upwards() {
+    use rustlife::volume::Volume;
+    use rustlife::{DenseEngine, LifeEngine};
+
+    let mut life = DenseEngine::new(universe(5, 5, "OOO", 1, 2));
+    let mut volume = Volume::centred(5, 5);
+    for _ in 0..2 {
+        volume.record(&life);
+        life.step();
+    }
+    // Flat then upright, with y counted from the bottom
+    let mut voxels: Vec<_> = volume.voxels().collect();
+    voxels.sort();
+    assert_eq!(voxels, [(1, 2, 0), (2, 1, 1), (2, 2, 0), (2, 2, 1), (2, 3, 1), (3, 2, 0)]);
+    assert_eq!(volume.to_obj().lines().filter(|l| l.starts_with("v ")).count(), 6);
+
+    let vox = volume.to_vox();
+    assert_eq!(&vox[..4], b"VOX ");
+    let size = vox.windows(4).position(|w| w == b"SIZE").unwrap();
+    let dims: Vec<u32> = vox[size + 12..size + 24].chunks(4).map(|c| u32::from_le_bytes(c.try_into().unwrap())).collect();
+    assert_eq!(dims, [5, 5, 2]);
+    let xyzi = vox.windows(4).position(|w| w == b"XYZI").unwrap();
+    assert_eq!(u32::from_le_bytes(vox[xyzi + 12..xyzi + 16].try_into().unwrap()), 6);
+}
+
+#[test]
+fn timeline_goes_back_to_any_generation() {
+    use rustlife::timeline::{Timeline, MAX_KEYFRAMES};
+    use rustlife::{DenseEngine, LifeEngine};
+
+    let start = universe(64, 64, ".OO\nOO\n.O", 30, 30);
+    let mut life = DenseEngine::new(start.clone());
+    let mut timeline = Timeline::new(4);
+    let mut states = vec![start];
+    timeline.record(0, &life);
+    for generation in 1..=400 {
+        life.step();
+        // A glider dropped in part way through is kept with the run
+        if generation == 150 {
+            for (x, y) in io::parse_plaintext(".O\n..O\nOOO") {
+                life.set(x + 2, y + 2, CellState::Alive);
+            }
+            timeline.edited(generation);
+        }
+        timeline.record(generation, &life);
+        states.push(life.state().clone());
+    }
+    assert!(timeline.generations().count() <= MAX_KEYFRAMES && timeline.interval() > 4);
+
+    for generation in [0, 1, 77, 149, 150, 151, 299, 400, 120, 3] {
+        assert_eq!(timeline.seek(&mut life, generation), Some(generation));
+        assert_eq!(life.state().cells, states[generation as usize].cells, "generation {}", generation);
+    }
+}
+
+#[test]
+fn timeline_goes_back_across_a_rule_change() {
+    use rustlife::timeline::Timeline;
+    use rustlife::SimulationBuilder;
+
+    let mut life = SimulationBuilder::new(48, 48).soup(0.4, 5).build().unwrap();
+    let mut timeline = Timeline::new(10);
+    let mut states = vec![io::to_plaintext(life.as_ref())];
+    timeline.record(0, life.as_ref());
+    for generation in 1..=60 {
+        life.step();
+        if generation == 30 {
+            life.set_rule("B36/S23".parse().unwrap());
+            timeline.edited(generation);
+        }
+        timeline.record(generation, life.as_ref());
+        states.push(io::to_plaintext(life.as_ref()));
+    }
+    // Stepped on from the keyframe at 20 under the rule it had then
+    assert_eq!(timeline.seek(life.as_mut(), 25), Some(25));
+    assert_eq!(io::to_plaintext(life.as_ref()), states[25]);
+    assert_eq!(life.rule(), rustlife::Rule::conway());
+    assert_eq!(timeline.seek(life.as_mut(), 45), Some(45));
+    assert_eq!(io::to_plaintext(life.as_ref()), states[45]);
+    assert_eq!(life.rule().to_string(), "B36/S23");
+}
+
+#[test]
+fn diff_shows_births_and_deaths_until_the_period() {
+    use rustlife::diff::{Change, Reference};
+    use rustlife::{DenseEngine, LifeEngine};
+
+    let mut life = DenseEngine::new(universe(5, 5, "OOO", 1, 2));
+    let reference = Reference::of(&life, 0);
+    life.step();
+    assert_eq!(reference.count(&life), (2, 2));
+    assert_eq!(reference.change(2, 1, life.get(2, 1)), Change::Born);
+    assert_eq!(reference.change(1, 2, life.get(1, 2)), Change::Died);
+    assert_eq!(reference.change(2, 2, life.get(2, 2)), Change::Alive);
+    life.step();
+    assert_eq!(reference.count(&life), (0, 0));
+}
@@ -0,0 +1,16 @@
+use rustlife::{io, CellState, ConwayState};
+use rustlife_core::Grid;
+
+#[test]
+fn core_grid_steps_like_the_engine() {
+    let mut start = ConwayState::empty(24, 20);
+    let mut grid = Grid::new(24, 20);
+    for (x, y) in io::parse_plaintext("OOO\nO.O\nO.O") {
+        start.set(10 + x, 8 + y, CellState::Alive);
+        grid.set(10 + x, 8 + y, CellState::Alive);
+    }
+    for state in start.generations().skip(1).take(40) {
+        grid.step();
+        assert_eq!(grid.cells(), &state.cells[..]);
+    }
+}
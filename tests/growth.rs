@@ -0,0 +1,66 @@
+use rustlife::growth::{Growth, GrowthDetector, MIN_GENERATIONS, WINDOW};
+use rustlife::{library, BoundingBox, CellState, ConwayState, DenseEngine, GenerationEvent, LifeEngine};
+
+fn classify(population: impl Fn(u64) -> usize, extent: impl Fn(u64) -> usize) -> Vec<Option<Growth>> {
+    let mut detector = GrowthDetector::new();
+    // Long enough for the samples to be thinned out a few times
+    (1..=4000).map(|g| {
+        let side = extent(g);
+        detector.record(g, population(g), Some(BoundingBox { min_x: 0, min_y: 0, max_x: side, max_y: side }));
+        detector.growth()
+    }).collect()
+}
+
+#[test]
+fn growth_is_told_apart() {
+    let last = |found: Vec<Option<Growth>>| found.last().copied().flatten();
+    assert_eq!(last(classify(|_| 40, |_| 10)), Some(Growth::Bounded));
+    assert_eq!(last(classify(|g| 40 + (g % 2) as usize, |g| 10 + g as usize / 4)), Some(Growth::Spreading));
+    assert_eq!(last(classify(|g| 100 + g as usize / 6, |g| 40 + g as usize / 4)), Some(Growth::Linear));
+    assert_eq!(last(classify(|g| (g * g / 200) as usize, |g| g as usize / 2)), Some(Growth::Quadratic));
+    // Nothing said until it has watched for long enough
+    let found = classify(|g| g as usize, |_| 10);
+    assert!(found[..MIN_GENERATIONS as usize - 1].iter().all(Option::is_none));
+}
+
+#[test]
+fn growth_is_judged_by_the_latest_generations() {
+    let bounds = |side: u64| Some(BoundingBox { min_x: 0, min_y: 0, max_x: side as usize, max_y: side as usize });
+    // A breeder that was already well under way when the watch started
+    let mut detector = GrowthDetector::new();
+    for g in 20_000..20_000 + MIN_GENERATIONS + 8 {
+        detector.record(g, (g * g / 200) as usize, bounds(g / 2));
+    }
+    assert_eq!(detector.growth(), Some(Growth::Quadratic));
+
+    // A gun for a long while, then a breeder, then nothing more
+    let population = |g: u64| match g {
+        ..10_000 => g / 6,
+        10_000..20_000 => 10_000 / 6 + (g - 10_000).pow(2) / 200,
+        _ => 10_000 / 6 + 10_000 * 10_000 / 200
+    };
+    let mut detector = GrowthDetector::new();
+    let mut seen = Vec::new();
+    for g in 1..=30_000 {
+        detector.record(g, population(g) as usize, bounds(100));
+        if [9_999, 10_000 + WINDOW, 30_000].contains(&g) {
+            seen.push(detector.growth());
+        }
+    }
+    assert_eq!(seen, [Some(Growth::Linear), Some(Growth::Quadratic), Some(Growth::Bounded)]);
+}
+
+#[test]
+fn the_gosper_gun_grows_linearly() {
+    let mut state = ConwayState::empty(200, 200);
+    for (x, y) in library::find("Gosper glider gun").unwrap().cells {
+        state.set(x + 2, y + 2, CellState::Alive);
+    }
+    let mut life = DenseEngine::new(state);
+    let mut detector = GrowthDetector::new();
+    for generation in 1..=400 {
+        life.step();
+        detector.observe(&GenerationEvent::from_engine(generation, &life), &life);
+    }
+    assert_eq!(detector.growth(), Some(Growth::Linear));
+}
@@ -0,0 +1,91 @@
+use std::sync::Arc;
+
+use rustlife::inspect::{changes, explain, inspect};
+use rustlife::{io, CellState, ConwayState, DenseEngine, LifeEngine, NeighborhoodRule, Rule};
+
+fn universe(width: usize, height: usize, pattern: &str, x: usize, y: usize) -> DenseEngine {
+    let mut state = ConwayState::empty(width, height);
+    for (px, py) in io::parse_plaintext(pattern) {
+        state.set(x + px, y + py, CellState::Alive);
+    }
+    DenseEngine::new(state)
+}
+
+#[test]
+fn inspector_names_the_rule_clause() {
+    let life = universe(5, 5, "OOO", 1, 2);
+    let clause = |x, y| {
+        let cell = inspect(&life, x, y);
+        (cell.state, cell.neighbors, cell.next)
+    };
+    assert_eq!(clause(2, 2), (CellState::Alive, 2, Some((CellState::Alive, "S2".to_string()))));
+    assert_eq!(clause(1, 2), (CellState::Alive, 1, Some((CellState::Dead, "not S1".to_string()))));
+    assert_eq!(clause(2, 1), (CellState::Dead, 3, Some((CellState::Alive, "B3".to_string()))));
+    assert_eq!(clause(0, 0), (CellState::Dead, 0, Some((CellState::Dead, "not B0".to_string()))));
+}
+
+#[test]
+fn explain_mode_says_why_and_finds_the_changes() {
+    let life = universe(5, 5, "OOO", 1, 2);
+    let said = |x, y| explain(&inspect(&life, x, y), &Rule::conway());
+    assert_eq!(said(2, 2), "alive with 2 live neighbours: S2 is in B3/S23, so it survives");
+    assert_eq!(said(1, 2), "alive with 1 live neighbour: no S1 in B3/S23, so it dies of loneliness");
+    assert_eq!(said(2, 1), "dead with 3 live neighbours: B3 is in B3/S23, so it is born");
+    assert_eq!(said(1, 1), "dead with 2 live neighbours: no B2 in B3/S23, so it stays dead");
+    assert_eq!(changes(&life), [(2, 1), (1, 2), (3, 2), (2, 3)]);
+    // Stepping makes exactly those changes
+    let mut next = universe(5, 5, "OOO", 1, 2);
+    next.step();
+    for (x, y) in changes(&life) {
+        assert_ne!(next.get(x, y), life.get(x, y));
+    }
+    assert_eq!(next.population(), 3);
+}
+
+/// Every cell flips, whatever its neighbours.
+struct Flip;
+
+impl NeighborhoodRule for Flip {
+    fn name(&self) -> &str {
+        "flip"
+    }
+
+    fn next(&self, cell_state: CellState, _neighbors: u8) -> CellState {
+        if cell_state == CellState::Alive { CellState::Dead } else { CellState::Alive }
+    }
+}
+
+#[test]
+fn plugin_rules_decide_the_next_state() {
+    let mut life = universe(5, 5, "OOO", 1, 2);
+    assert!(life.set_custom_rule(Some(Arc::new(Flip))));
+
+    // The blinker's middle would survive under B3/S23, but the plugin
+    // kills it
+    let cell = inspect(&life, 2, 2);
+    assert_eq!(cell.next, Some((CellState::Dead, "plugin rule".to_string())));
+    assert!(cell.plugin);
+    assert_eq!(explain(&cell, &life.rule()), "alive with 2 live neighbours; a plugin rule decides, and makes it dead");
+
+    // Every cell changes, far from the live ones too
+    assert_eq!(changes(&life).len(), 25);
+    assert!(changes(&life).contains(&(4, 4)));
+    life.step();
+    assert_eq!(life.population(), 22);
+}
+
+#[test]
+fn b0_births_are_found_away_from_the_live_cells() {
+    let mut life = universe(9, 9, "OOO", 1, 1);
+    life.set_rule("B0/S8".parse::<Rule>().unwrap());
+    let cell = inspect(&life, 8, 8);
+    assert_eq!(cell.next, Some((CellState::Alive, "B0".to_string())));
+
+    let found = changes(&life);
+    assert!(found.contains(&(8, 8)));
+    let before = io::live_cells(&life);
+    life.step();
+    let after = io::live_cells(&life);
+    let flipped = (0..9).flat_map(|y| (0..9).map(move |x| (x, y))).filter(|c| before.contains(c) != after.contains(c)).count();
+    assert_eq!(found.len(), flipped);
+}
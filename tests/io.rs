@@ -0,0 +1,156 @@
+use rustlife::io;
+
+const GLIDER: &str = ".O\n..O\nOOO\n";
+
+#[test]
+fn parse_pattern_tells_formats_apart() {
+    let plain = io::parse_pattern(GLIDER).unwrap();
+    assert_eq!(plain, io::parse_plaintext(GLIDER));
+    assert_eq!(io::parse_pattern("!Name: glider\n.O\n..O\nOOO").unwrap(), plain);
+    assert_eq!(io::parse_pattern("x = 3, y = 3, rule = B3/S23\nbo$2bo$3o!").unwrap(), plain);
+    assert_eq!(io::parse_pattern("bo$2bo$3o!").unwrap(), plain);
+}
+
+#[test]
+fn rle_round_trips() {
+    let live = io::parse_plaintext("O....O\n\n\n..OOO\n");
+    let rle = io::cells_to_rle(&live);
+    assert_eq!(rle, "x = 6, y = 4\no4bo3$2b3o!\n");
+    assert_eq!(io::parse_rle(&rle).unwrap(), live);
+}
+
+#[test]
+fn every_format_round_trips() {
+    use rustlife::io::Format;
+    use rustlife::Rule;
+
+    // A glider and, far off, a block, so macrocell has empty squares
+    let mut live = io::parse_plaintext(GLIDER);
+    live.extend([(40, 30), (41, 30), (40, 31), (41, 31)]);
+    live.sort_unstable_by_key(|&(x, y)| (y, x));
+    let highlife: Rule = "B36/S23".parse().unwrap();
+    for format in [Format::Plaintext, Format::Rle, Format::Life105, Format::Life106, Format::Macrocell] {
+        let text = format.write(&live, Some(&highlife));
+        let mut read = io::parse_pattern(&text).unwrap();
+        read.sort_unstable_by_key(|&(x, y)| (y, x));
+        assert_eq!(read, live, "{}", format);
+        let rule = if format == Format::Life106 { None } else { Some(highlife) };
+        assert_eq!(io::pattern_rule(&text), rule, "{}", format);
+    }
+    assert_eq!(Format::from_path("gun.MC"), Some(Format::Macrocell));
+}
+
+#[test]
+fn other_formats_parse() {
+    let glider = io::parse_plaintext(GLIDER);
+    assert_eq!(io::parse_pattern("#Life 1.05\n#N\n#P -1 -1\n.*\n..*\n***\n").unwrap(), glider);
+    assert_eq!(io::parse_pattern("#Life 1.06\n0 -1\n1 0\n-1 1\n0 1\n1 1\n").unwrap(), glider);
+    assert_eq!(io::parse_pattern("[M2] (golly 4.2)\n#R B3/S23\n.*$..*$***$\n4 1 0 0 0\n").unwrap(), glider);
+    assert_eq!(io::pattern_rule("#N Glider\n#R -1 -1\nx = 3, y = 3, rule = B3/S23:T10,10\nbo$2bo$3o!"), Some(rustlife::Rule::conway()));
+    assert_eq!(io::trim(&[(3, 4), (5, 4)]), vec![(0, 0), (2, 0)]);
+}
+
+#[test]
+fn universes_serialize_packed() {
+    use rustlife::{CellState, ConwayState, Rule};
+
+    let mut state = ConwayState::empty(40, 30);
+    state.rule = "B36/S23".parse().unwrap();
+    for (x, y) in io::parse_plaintext(GLIDER) {
+        state.set(x + 10, y + 20, CellState::Alive);
+    }
+    state.set_wall(0, 0, Some(CellState::Alive));
+    let json = serde_json::to_string(&state).unwrap();
+    // A byte per eight cells at most, far less with the empty rows shortened
+    assert!(json.len() < 40 * 30 / 8, "{}", json);
+    assert!(json.contains(r#""rule":"B36/S23""#));
+
+    let back: ConwayState = serde_json::from_str(&json).unwrap();
+    assert_eq!((back.width, back.height, back.rule), (40, 30, state.rule));
+    assert_eq!(back.cells, state.cells);
+    assert_eq!(back.wall(0, 0), Some(CellState::Alive));
+    assert!(serde_json::from_str::<Rule>(r#""B9/S23""#).is_err());
+}
+
+#[test]
+fn svg_joins_runs_of_squares() {
+    use rustlife::svg::{self, Shape, Style};
+
+    let live = io::parse_plaintext(GLIDER).into_iter().map(|(x, y)| (x + 50, y + 7)).collect::<Vec<_>>();
+    let squares = svg::write(&live, &Style::default());
+    assert!(squares.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="50" height="50""#), "{}", squares);
+    // The bottom row of three is one rectangle
+    assert_eq!(squares.matches("<rect x=").count(), 3);
+    assert!(squares.contains(r#"<rect x="10" y="30" width="30" height="10"/>"#));
+
+    let style = Style { shape: "circle".parse().unwrap(), background: None, margin: 0, ..Style::default() };
+    let circles = svg::write(&live, &style);
+    assert_eq!((circles.matches("<circle").count(), circles.contains("100%")), (5, false));
+    assert!("hexagon".parse::<Shape>().is_err());
+}
+
+#[test]
+fn text_draws_the_window_asked_for() {
+    use rustlife::text::{self, Glyphs};
+    use rustlife::{BoundingBox, CellState, ConwayState, DenseEngine, LifeEngine};
+
+    let mut state = DenseEngine::new(ConwayState::empty(10, 10));
+    for (x, y) in io::parse_plaintext(GLIDER) {
+        state.set(x + 1, y + 1, CellState::Alive);
+    }
+    let window = BoundingBox { min_x: 1, min_y: 1, max_x: 3, max_y: 3 };
+    assert_eq!(text::render(&state, window, Glyphs::Ascii), [".O.", "..O", "OOO"]);
+    assert_eq!(text::render(&state, window, Glyphs::Blocks), [" ▀▄", "▀▀▀"]);
+    assert_eq!(text::render(&state, window, Glyphs::Braille), ["⠬⠆"]);
+    // Past the edge of the grid is dead
+    let corner = BoundingBox { min_x: 8, min_y: 8, max_x: 11, max_y: 11 };
+    assert_eq!(text::render(&state, corner, Glyphs::Ascii), ["....", "....", "....", "...."]);
+    assert_eq!("braille".parse::<Glyphs>(), Ok(Glyphs::Braille));
+}
+
+#[test]
+fn printer_writes_every_nth_generation() {
+    use std::sync::{Arc, Mutex, RwLock};
+
+    use rustlife::text::{self, Glyphs};
+    use rustlife::{BoundingBox, ConwayState, DenseEngine, GenerationEvent, LifeEngine};
+
+    #[derive(Clone, Default)]
+    struct Sink(Arc<Mutex<Vec<u8>>>);
+    impl std::io::Write for Sink {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let life: Box<dyn LifeEngine> = Box::new(DenseEngine::new(ConwayState::empty(4, 4)));
+    let sink = Sink::default();
+    let window = BoundingBox { min_x: 0, min_y: 0, max_x: 1, max_y: 0 };
+    let mut print = text::printer(Arc::new(RwLock::new(life)), 2, Some(window), Glyphs::Ascii, sink.clone());
+    for generation in 1..=4 {
+        print(&GenerationEvent { generation, population: 0, changed: 0, births: 0, species: None });
+    }
+    let out = String::from_utf8(sink.0.lock().unwrap().clone()).unwrap();
+    assert_eq!(out, "# generation 2, population 0\n..\n# generation 4, population 0\n..\n");
+}
+
+#[test]
+fn export_trims_and_turns_to_one_orientation() {
+    use rustlife::io::Format;
+    use rustlife::{apgcode, Rule};
+
+    let glider: Vec<_> = io::parse_plaintext(GLIDER).into_iter().map(|(x, y)| (x + 10, y + 5)).collect();
+    let rle = io::export(&glider, Format::Rle, Some(&Rule::conway()), false);
+    assert_eq!(rle, "#C Population: 5\nx = 3, y = 3, rule = B3/S23\nbo$2bo$3o!\n");
+    assert_eq!(io::parse_pattern(&rle).unwrap(), io::parse_plaintext(GLIDER));
+
+    let canonical = io::export(&glider, Format::Plaintext, None, true);
+    for turned in apgcode::orientations(&glider) {
+        assert_eq!(io::export(&turned, Format::Plaintext, None, true), canonical);
+    }
+    assert!(canonical.starts_with("!Population: 5\n"));
+}
@@ -0,0 +1,43 @@
+use rustlife::ising::IsingEngine;
+use rustlife::{Anchor, CellState, LifeEngine};
+
+/// Magnetization and energy worked out from every spin.
+fn measured(life: &IsingEngine, field: f64) -> (f64, f64) {
+    let (w, h) = (life.width(), life.height());
+    let s = |x, y| if life.get(x, y) == CellState::Alive { 1.0 } else { -1.0 };
+    let (mut total, mut bonds) = (0.0, 0.0);
+    for y in 0..h {
+        for x in 0..w {
+            total += s(x, y);
+            if x + 1 < w { bonds += s(x, y) * s(x + 1, y); }
+            if y + 1 < h { bonds += s(x, y) * s(x, y + 1); }
+        }
+    }
+    let cells = (w * h) as f64;
+    (total / cells, -(bonds + field * total) / cells)
+}
+
+#[test]
+fn stats_kept_up_to_date_match_the_spins() {
+    let mut life = IsingEngine::new(20, 12);
+    life.set_params(1.5, 0.3);
+    let check = |life: &IsingEngine| {
+        let stats = life.stats();
+        let (magnetization, energy) = measured(life, 0.3);
+        assert!((stats.magnetization - magnetization).abs() < 1e-12, "{} against {}", stats.magnetization, magnetization);
+        assert!((stats.energy - energy).abs() < 1e-12, "{} against {}", stats.energy, energy);
+    };
+    check(&life);
+    life.randomize(0.5, 3);
+    check(&life);
+    for generation in 0..5 {
+        life.step();
+        life.set(generation, 3, CellState::Alive);
+        life.set(19, generation, CellState::Dead);
+        check(&life);
+    }
+    life.resize(9, 30, Anchor::Center);
+    check(&life);
+    life.step();
+    check(&life);
+}
@@ -0,0 +1,32 @@
+use rustlife::{lexicon, library};
+
+#[test]
+fn entries_are_read_with_their_patterns() {
+    let text = "The Life Lexicon, release 1\n\n\
+                :blinker: (p2) The smallest {oscillator}.\n  See also {toad}.\n\t***\n\n\
+                :toad: (p2) Another one.\n\t.OOO\n\tOOO.\n\n\
+                -----------------------------------------\n\
+                Bibliography, not an entry\n";
+    let entries = lexicon::parse(text);
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].term, "blinker");
+    assert_eq!(entries[0].definition, "(p2) The smallest oscillator. See also toad.");
+    assert_eq!(entries[0].pattern, Some(vec![(0, 0), (1, 0), (2, 0)]));
+    assert_eq!(entries[1].definition, "(p2) Another one.");
+    assert_eq!(entries[1].pattern.as_ref().map(Vec::len), Some(6));
+}
+
+#[test]
+fn search_puts_terms_first() {
+    let entries = lexicon::builtin();
+    let found: Vec<&str> = lexicon::search(&entries, "GLIDER").iter().map(|e| e.term.as_str()).collect();
+    assert_eq!(found[0], "glider");
+    assert_eq!(found[1], "Gosper glider gun");
+    // Then those that only mention gliders
+    assert!(found[2..].contains(&"breeder"));
+    assert_eq!(lexicon::search(&entries, "").len(), entries.len());
+    assert!(lexicon::search(&entries, "no such thing").is_empty());
+
+    let glider = entries.iter().find(|e| e.term == "glider").unwrap();
+    assert_eq!(glider.pattern, library::find("glider").map(|p| p.cells));
+}
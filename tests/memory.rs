@@ -0,0 +1,20 @@
+use rustlife::memory::Estimate;
+use rustlife::SimulationBuilder;
+
+#[test]
+fn estimates_add_up() {
+    let e = Estimate::new("cyclic", 100, 50).unwrap();
+    assert_eq!((e.grid, e.scratch, e.history, e.snapshots), (5000, 5000, 0, 0));
+    let e = e.keyframes(4).snapshots(2);
+    assert_eq!((e.history, e.snapshots), (4 * 625, 10000));
+    assert_eq!(e.total(), 22500);
+    assert!(Estimate::new("nonsense", 10, 10).is_none());
+}
+
+#[test]
+fn refuses_grids_over_the_limit() {
+    // Refused before anything is allocated, so this doesn't take 8 GiB
+    let err = SimulationBuilder::new(65536, 65536).memory_limit(1 << 30).build().err().unwrap();
+    assert!(err.contains("more than the 1.0 GiB allowed"), "{}", err);
+    assert!(SimulationBuilder::new(64, 64).memory_limit(1 << 30).build().is_ok());
+}
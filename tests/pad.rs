@@ -0,0 +1,41 @@
+use rustlife::pad::{Action, Axis, Button, Input, Motion, Sticks};
+
+#[test]
+fn presses_become_actions_and_axes_are_kept() {
+    let mut sticks = Sticks::default();
+    assert_eq!(sticks.apply(Input::Pressed(Button::South)), Some(Action::TogglePause));
+    assert_eq!(sticks.apply(Input::Pressed(Button::East)), Some(Action::Step));
+    assert_eq!(sticks.apply(Input::Pressed(Button::DPadRight)), Some(Action::NextPattern));
+    assert_eq!(sticks.apply(Input::Pressed(Button::DPadLeft)), Some(Action::PreviousPattern));
+
+    // Up on the stick is up the screen, so towards smaller y
+    assert_eq!(sticks.apply(Input::Moved(Axis::LeftStickX, 0.5)), None);
+    assert_eq!(sticks.apply(Input::Moved(Axis::LeftStickY, 1.0)), None);
+    sticks.apply(Input::Moved(Axis::RightTrigger, 0.75));
+    sticks.apply(Input::Moved(Axis::LeftTrigger, 2.0));
+    assert_eq!(sticks, Sticks { x: 0.5, y: -1.0, zoom_in: 0.75, zoom_out: 1.0 });
+
+    // Unplugging lets go of everything
+    sticks.apply(Input::Disconnected);
+    assert_eq!(sticks, Sticks::default());
+}
+
+#[test]
+fn sticks_steer_past_the_dead_zone() {
+    let at_rest = Sticks { x: 0.1, y: -0.15, zoom_in: 0.05, zoom_out: 0.0 };
+    assert_eq!(at_rest.motion(0.05), None);
+
+    // A full push pans a view width a second
+    let right = Sticks { x: 1.0, ..Sticks::default() };
+    assert_eq!(right.motion(0.05), Some(Motion { pan: (0.05, 0.0), zoom: 1.0 }));
+
+    // A fully pulled trigger zooms four times closer a second, and the
+    // other trigger undoes it
+    let zoom = |sticks: Sticks| sticks.motion(0.1).unwrap().zoom;
+    assert!((zoom(Sticks { zoom_in: 1.0, ..Sticks::default() }) - 4f64.powf(0.1)).abs() < 1e-12);
+    assert!((zoom(Sticks { zoom_out: 1.0, ..Sticks::default() }) - 4f64.powf(-0.1)).abs() < 1e-12);
+    assert_eq!(Sticks { x: 1.0, zoom_in: 1.0, zoom_out: 1.0, ..Sticks::default() }.motion(0.1).unwrap().zoom, 1.0);
+
+    // A late frame moves no further than a tenth of a second's worth
+    assert_eq!(right.motion(3.0), right.motion(0.1));
+}
@@ -0,0 +1,50 @@
+use rustlife::period::{Cycle, CycleDetector};
+use rustlife::{io, CellState, ConwayState, DenseEngine, GenerationEvent, LifeEngine};
+
+fn universe(width: usize, height: usize, pattern: &str, x: usize, y: usize) -> DenseEngine {
+    let mut state = ConwayState::empty(width, height);
+    for (px, py) in io::parse_plaintext(pattern) {
+        state.set(x + px, y + py, CellState::Alive);
+    }
+    DenseEngine::new(state)
+}
+
+#[test]
+fn cycle_detector_finds_the_exact_period() {
+    let run = |every: u64| {
+        let mut life = universe(8, 8, "OOO", 2, 3);
+        let mut detector = CycleDetector::new(every);
+        (1..=12).filter_map(|generation| {
+            life.step();
+            detector.observe(&GenerationEvent::from_engine(generation, &life), &life).map(|c| (generation, c))
+        }).collect::<Vec<_>>()
+    };
+    assert_eq!(run(1), [(3, Cycle { period: 2, start: 1 })]);
+    // Samples at 4 and 8 only show the period divides 4
+    assert_eq!(run(4), [(10, Cycle { period: 2, start: 4 })]);
+    assert_eq!(Cycle { period: 2, start: 1 }.to_string(), "universe entered a cycle of period 2 at generation 1");
+}
+
+#[test]
+fn cycle_detector_samples_again_after_an_edit() {
+    const PULSAR: &str = "..OOO...OOO..\n.............\nO....O.O....O\nO....O.O....O\nO....O.O....O\n..OOO...OOO..\n.............\n..OOO...OOO..\nO....O.O....O\nO....O.O....O\nO....O.O....O\n.............\n..OOO...OOO..";
+    let mut life = universe(32, 32, "OOO", 2, 3);
+    let mut detector = CycleDetector::new(2);
+    let mut found = Vec::new();
+    for generation in 1..=40 {
+        // The blinker repeats between the samples at 2 and 4, then is swapped
+        // for a pulsar, whose period 3 doesn't divide that gap
+        if generation == 5 {
+            life.clear();
+            for (x, y) in io::parse_plaintext(PULSAR) {
+                life.set(10 + x, 10 + y, CellState::Alive);
+            }
+        }
+        life.step();
+        if let Some(cycle) = detector.observe(&GenerationEvent::from_engine(generation, &life), &life) {
+            found.push((generation, cycle.period));
+        }
+    }
+    assert_eq!(found.len(), 1, "{:?}", found);
+    assert_eq!(found[0].1, 3);
+}
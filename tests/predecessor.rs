@@ -0,0 +1,55 @@
+use rustlife::{io, predecessor, CellState, ConwayState, Rule};
+
+/// The pattern's box in one step of `parent`, as plaintext.
+fn stepped(parent: &[(usize, usize)], (w, h): (usize, usize)) -> Vec<(usize, usize)> {
+    let mut state = ConwayState::empty(w + 2, h + 2);
+    for &(x, y) in parent {
+        state.set(x, y, CellState::Alive);
+    }
+    let next = state.step_n(1);
+    let mut live = Vec::new();
+    for y in 0..h {
+        for x in 0..w {
+            if next.get(x + 1, y + 1) == CellState::Alive {
+                live.push((x, y));
+            }
+        }
+    }
+    live
+}
+
+#[test]
+fn finds_parents_that_step_to_the_pattern() {
+    for pattern in ["OOO", ".O\n..O\nOOO", "OO\nOO", "O..O\n.OO.\nO..O"] {
+        let live = io::parse_plaintext(pattern);
+        let parent = predecessor::search(&live, io::extent(&live), Rule::conway()).unwrap().expect("a parent");
+        let mut got = stepped(&parent, io::extent(&live));
+        let mut want = live.clone();
+        got.sort();
+        want.sort();
+        assert_eq!(got, want, "{}", pattern);
+    }
+}
+
+#[test]
+fn keeps_dead_edges_of_the_box() {
+    // A lone cell with dead cells round it, then a full 8 by 8 box
+    let live = io::parse_plaintext("...\n.O.\n...");
+    let parent = predecessor::search(&live, (3, 3), Rule::conway()).unwrap().expect("a parent");
+    assert_eq!(stepped(&parent, (3, 3)), [(1, 1)]);
+    let random = io::parse_plaintext("O.OO.O.O\nOOO.OOO.\n.OOOOO.O\nOO.O.OOO\n.OOOOOO.\nOOO.OOOO\nO.OOOO.O\n.OO.OOO.");
+    let parent = predecessor::search(&random, (8, 8), Rule::conway()).unwrap().expect("a parent");
+    let mut got = stepped(&parent, (8, 8));
+    let mut want = random.clone();
+    got.sort();
+    want.sort();
+    assert_eq!(got, want);
+}
+
+#[test]
+fn reports_patterns_with_no_parent() {
+    // Under B/S everything dies, so nothing leads to a live cell
+    let none: Rule = "B/S".parse().unwrap();
+    assert_eq!(predecessor::search(&io::parse_plaintext("O"), (1, 1), none).unwrap(), None);
+    assert!(predecessor::search(&io::parse_plaintext("OOOOOOOOO"), (9, 1), Rule::conway()).is_err());
+}
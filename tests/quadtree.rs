@@ -0,0 +1,61 @@
+use rustlife::quadtree::Quadtree;
+use rustlife::{io, BoundingBox, CellState, DenseEngine, LifeEngine, SimulationBuilder};
+
+fn live(life: &dyn LifeEngine) -> Vec<(usize, usize)> {
+    io::live_cells(life)
+}
+
+fn region(min_x: usize, min_y: usize, max_x: usize, max_y: usize) -> BoundingBox {
+    BoundingBox { min_x, min_y, max_x, max_y }
+}
+
+#[test]
+fn answers_as_looking_at_every_cell_would() {
+    // Sizes that aren't a whole number of tiles, so squares are cut off
+    let life = SimulationBuilder::new(77, 45).soup(0.05, 3).build().unwrap();
+    let tree = Quadtree::of(life.as_ref());
+    let cells = live(life.as_ref());
+    assert_eq!(tree.population(), cells.len());
+    assert_eq!(tree.bounding_box(), life.bounding_box());
+
+    for (i, r) in [region(0, 0, 76, 44), region(3, 5, 9, 6), region(13, 0, 60, 44), region(70, 40, 200, 200), region(8, 8, 15, 15)].into_iter().enumerate() {
+        let inside: Vec<_> = cells.iter().copied().filter(|&(x, y)| x >= r.min_x && x <= r.max_x && y >= r.min_y && y <= r.max_y).collect();
+        assert_eq!(tree.population_in(r), inside.len(), "region {}", i);
+        assert_eq!(tree.is_empty(r), inside.is_empty(), "region {}", i);
+        let mut reading = inside.clone();
+        reading.sort_by_key(|&(x, y)| (y, x));
+        assert_eq!(tree.live_cells_in(r), reading, "region {}", i);
+    }
+
+    let squared = |(ax, ay): (usize, usize), (bx, by): (usize, usize)| ax.abs_diff(bx).pow(2) + ay.abs_diff(by).pow(2);
+    for point in [(0, 0), (38, 22), (76, 44), (500, 3)] {
+        let nearest = tree.nearest(point.0, point.1).unwrap();
+        let best = cells.iter().map(|&c| squared(c, point)).min().unwrap();
+        assert_eq!(squared(nearest, point), best, "from {:?}", point);
+    }
+    assert_eq!(Quadtree::new(77, 45).nearest(10, 10), None);
+    assert!(Quadtree::new(0, 0).is_empty(region(0, 0, 10, 10)));
+}
+
+#[test]
+fn the_dense_backend_keeps_its_index_up_to_date() {
+    let glider = io::parse_plaintext(".O\n..O\nOOO");
+    // A lone glider steps through its changes, a soup through the whole grid
+    for mut life in [
+        SimulationBuilder::new(64, 40).pattern(&glider).build().unwrap(),
+        SimulationBuilder::new(64, 40).soup(0.4, 7).build().unwrap(),
+    ] {
+        for generation in 0..30 {
+            if generation == 10 {
+                life.set(63, 39, CellState::Alive);
+                life.set(0, 0, CellState::Alive);
+            }
+            if generation == 20 {
+                life.resize(50, 50, rustlife::Anchor::Center);
+            }
+            let index = life.downcast_ref::<DenseEngine>().expect("the dense backend keeps an index").quadtree();
+            assert_eq!(*index, Quadtree::of(life.as_ref()), "generation {}", generation);
+            life.step();
+        }
+    }
+}
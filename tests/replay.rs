@@ -0,0 +1,105 @@
+use rustlife::noise::Noise;
+use rustlife::replay::{Edit, Player, Recorder, Settings, Start};
+use rustlife::{io, SimulationBuilder};
+
+#[test]
+fn randomizing_mid_run_keeps_later_edits_in_order() {
+    let start = Start::Pattern { x: 10, y: 10, pattern: ".O\n..O\nOOO".to_string() };
+    let mut life = SimulationBuilder::new(48, 48).start(start.clone()).build().unwrap();
+    let mut recorder = Recorder::new(life.as_ref(), start);
+    let randomize = Edit::Randomize { density: 0.3, seed: 9, symmetry: Default::default(), size: 0, layout: Default::default() };
+    let paint = Edit::Paste { x: 2, y: 2, pattern: "OO\nOO".to_string() };
+
+    // As the window does: the soup starts the count again from 0, and a
+    // block is put down five generations into it
+    let mut generation = 0;
+    for _ in 0..20 {
+        life.step();
+        generation += 1;
+    }
+    recorder.jump(generation, 0);
+    randomize.apply(life.as_mut()).unwrap();
+    recorder.record(0, randomize);
+    for generation in 0..10 {
+        if generation == 5 {
+            paint.apply(life.as_mut()).unwrap();
+            recorder.record(generation, paint.clone());
+        }
+        life.step();
+    }
+
+    let replay = recorder.replay();
+    let mut played = replay.initial_universe().unwrap();
+    let mut player = Player::new(replay);
+    for generation in 1..=30 {
+        player.apply_due(generation - 1, played.as_mut()).unwrap();
+        played.step();
+    }
+    assert!(player.finished());
+    assert_eq!(io::to_plaintext(played.as_ref()), io::to_plaintext(life.as_ref()));
+}
+
+#[test]
+fn noise_is_replayed_from_its_seed() {
+    let start = Start::Pattern { x: 0, y: 0, pattern: "OOO".to_string() };
+    let mut life = SimulationBuilder::new(32, 32).start(start.clone()).build().unwrap();
+    let mut recorder = Recorder::new(life.as_ref(), start);
+    let mut noise = Noise::new(0.01, 4);
+    recorder.record(3, Edit::Noise { probability: 0.01, seed: 4 });
+    for generation in 0..20 {
+        life.step();
+        if generation >= 3 {
+            noise.inject(life.as_mut());
+        }
+    }
+
+    let replay = recorder.replay();
+    let mut played = replay.initial_universe().unwrap();
+    let mut player = Player::new(replay);
+    for generation in 1..=20 {
+        player.apply_due(generation - 1, played.as_mut()).unwrap();
+        played.step();
+        player.inject_noise(played.as_mut());
+    }
+    assert!(life.population() > 3);
+    assert_eq!(io::to_plaintext(played.as_ref()), io::to_plaintext(life.as_ref()));
+}
+
+#[test]
+fn going_back_along_the_timeline_is_replayed() {
+    use rustlife::timeline::Timeline;
+
+    let start = Start::Soup { density: 0.4, seed: 3, symmetry: Default::default(), size: 0, layout: Default::default() };
+    let mut life = SimulationBuilder::new(40, 40).start(start.clone()).build().unwrap();
+    let mut recorder = Recorder::new(life.as_ref(), start);
+    let mut timeline = Timeline::new(10);
+    timeline.record(0, life.as_ref());
+    for generation in 1..=40 {
+        life.step();
+        timeline.record(generation, life.as_ref());
+    }
+
+    // As the window does: back to 20, then a block dropped in at 25
+    let reached = timeline.seek(life.as_mut(), 20).unwrap();
+    recorder.jump(40, reached);
+    let cells = io::cells_to_rle(&io::live_cells(life.as_ref()));
+    recorder.record(reached, Edit::Seek { generation: reached, cells, settings: Settings::of(life.as_ref()) });
+    let block = Edit::Paste { x: 1, y: 1, pattern: "OO\nOO".to_string() };
+    for generation in reached..reached + 10 {
+        if generation == 25 {
+            block.apply(life.as_mut()).unwrap();
+            recorder.record(generation, block.clone());
+        }
+        life.step();
+    }
+
+    let replay = recorder.replay();
+    let mut played = replay.initial_universe().unwrap();
+    let mut player = Player::new(replay);
+    for generation in 1..=50 {
+        player.apply_due(generation - 1, played.as_mut()).unwrap();
+        played.step();
+    }
+    assert!(player.finished());
+    assert_eq!(io::to_plaintext(played.as_ref()), io::to_plaintext(life.as_ref()));
+}
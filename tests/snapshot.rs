@@ -0,0 +1,43 @@
+use rustlife::snapshot::Snapshot;
+use rustlife::{io, SimulationBuilder};
+
+#[test]
+fn round_trips_cells_and_rule() {
+    let glider = io::parse_plaintext(".O\n..O\nOOO");
+    let mut life = SimulationBuilder::new(37, 21).rule("B36/S23".parse().unwrap()).pattern(&glider).build().unwrap();
+    life.step();
+    let snapshot = Snapshot::of(1, life.as_ref());
+    let read = Snapshot::from_bytes(&snapshot.to_bytes()).unwrap();
+    assert_eq!(read, snapshot);
+    assert_eq!((read.generation, read.width, read.height), (1, 37, 21));
+
+    let mut restored = SimulationBuilder::new(37, 21).build().unwrap();
+    read.restore(restored.as_mut()).unwrap();
+    assert_eq!(io::to_plaintext(restored.as_ref()), io::to_plaintext(life.as_ref()));
+    assert_eq!(restored.rule(), life.rule());
+    assert!(read.restore(SimulationBuilder::new(36, 21).build().unwrap().as_mut()).is_err());
+}
+
+#[test]
+fn sparse_grids_take_little_room() {
+    let glider = io::parse_plaintext(".O\n..O\nOOO");
+    let life = SimulationBuilder::new(2048, 2048).pattern(&glider).build().unwrap();
+    let snapshot = Snapshot::of(0, life.as_ref());
+    assert!(snapshot.size() < 2048 * 2048 / 8 / 100, "{} bytes", snapshot.size());
+    assert_eq!(snapshot.alive().unwrap().iter().filter(|&&a| a).count(), 5);
+}
+
+#[test]
+fn refuses_what_is_not_a_snapshot() {
+    let life = SimulationBuilder::new(8, 8).build().unwrap();
+    let bytes = Snapshot::of(0, life.as_ref()).to_bytes();
+    assert!(Snapshot::is_snapshot(&bytes));
+    assert!(Snapshot::from_bytes(b"#Life 1.06\n0 0\n").is_err());
+    assert!(Snapshot::from_bytes(&bytes[..12]).unwrap_err().contains("cut short"));
+    let mut newer = bytes.clone();
+    newer[6] = 9;
+    assert!(Snapshot::from_bytes(&newer).unwrap_err().contains("version"));
+    // A header whose cells have been cut off is only found out unpacking
+    let cut = Snapshot::from_bytes(&bytes[..bytes.len() - 4]).unwrap();
+    assert!(cut.alive().is_err());
+}
@@ -0,0 +1,30 @@
+use rustlife::stop::{StopChecker, StopConditions, StopReason};
+use rustlife::{io, ConwayState, DenseEngine, GenerationEvent, LifeEngine};
+
+#[test]
+fn restarting_forgets_the_universe_before() {
+    let place = |life: &mut DenseEngine, pattern: &str, x, y| {
+        life.clear();
+        io::place(life, &io::parse_plaintext(pattern), x, y);
+    };
+    let run = |restart: bool| {
+        let mut life = DenseEngine::new(ConwayState::empty(16, 16));
+        let mut checker = StopChecker::new(StopConditions { extinction: true, repeat: true, generation: None });
+        place(&mut life, "O\nO\nO", 5, 4);
+        let mut found = vec![checker.check(&GenerationEvent::from_engine(1, &life), &life)];
+        // Loaded over it the other way round, so the next generation looks
+        // like the last one before the load
+        place(&mut life, "OOO", 4, 5);
+        if restart {
+            checker.restart();
+        }
+        for generation in 2..=4 {
+            life.step();
+            found.push(checker.check(&GenerationEvent::from_engine(generation, &life), &life));
+        }
+        found
+    };
+    assert_eq!(run(true), [None, None, None, Some(StopReason::Repeating(2))]);
+    // Otherwise the load looks like the universe standing still
+    assert_eq!(run(false)[1], Some(StopReason::Repeating(1)));
+}
@@ -0,0 +1,64 @@
+use std::time::{Duration, Instant};
+
+use rustlife::timing::{Bound, Breakdown, Phase, Sampler, Thread};
+
+fn ms(n: u64) -> Duration {
+    Duration::from_millis(n)
+}
+
+#[test]
+fn shares_are_of_each_threads_second() {
+    // Stepping: 300 + 100 + 200 ms, drawing: 100 + 50 ms, over a second
+    // that really took two
+    let phases = [(ms(600), 3), (ms(200), 3), (ms(400), 4), (ms(200), 2), (ms(100), 0)];
+    let b = Breakdown::of(&phases, Duration::from_secs(2));
+    let names: Vec<_> = b.rows.iter().map(|r| r.name).collect();
+    assert_eq!(names, ["neighbours", "swap", "publish", "pixel fill", "present"]);
+    assert_eq!(b.rows[0].thread, Thread::Stepping);
+    assert_eq!(b.rows[3].thread, Thread::Drawing);
+    assert!((b.rows[0].share - 0.3).abs() < 1e-9);
+    assert!((b.rows[0].each - 200.0).abs() < 1e-9);
+    // Nothing counted takes nothing each, whatever the time
+    assert_eq!(b.rows[4].each, 0.0);
+    assert!((b.stepping - 0.6).abs() < 1e-9);
+    assert!((b.drawing - 0.15).abs() < 1e-9);
+    assert_eq!(b.bound(), Bound::Simulation);
+
+    let second = Duration::from_secs(1);
+    let drawing = [(ms(100), 1), (ms(0), 1), (ms(0), 1), (ms(500), 1), (ms(300), 1)];
+    assert_eq!(Breakdown::of(&drawing, second).bound(), Bound::Render);
+    let idle = [(ms(300), 1), (ms(0), 1), (ms(0), 1), (ms(400), 1), (ms(0), 1)];
+    assert_eq!(Breakdown::of(&idle, second).bound(), Bound::Neither);
+}
+
+#[test]
+fn the_overlay_redraws_every_second_while_on() {
+    let mut sampler = Sampler::default();
+    let timings = sampler.timings();
+    let later = |s: u64| Instant::now() + Duration::from_secs(s);
+
+    // Off, nothing is timed and there is nothing new to draw
+    timings.time(Phase::Step, || ());
+    assert!(!sampler.sample(later(2)));
+    assert!(sampler.shown().is_none());
+
+    sampler.toggle();
+    assert!(sampler.is_on());
+    timings.add(Phase::Step, ms(250));
+    timings.add(Phase::Present, ms(100));
+    // Not until a whole second has gone by
+    assert!(!sampler.sample(Instant::now()));
+    assert!(sampler.sample(later(1)));
+    let shown = sampler.shown().unwrap();
+    assert_eq!(shown.rows[0].count, 1);
+    assert_eq!(shown.rows[4].count, 1);
+
+    // Each second is a new breakdown, started over
+    assert!(sampler.sample(later(3)));
+    assert_eq!(sampler.shown().unwrap().rows[0].count, 0);
+
+    sampler.toggle();
+    assert!(!sampler.is_on());
+    assert!(sampler.shown().is_none());
+    assert!(!sampler.sample(later(5)));
+}
@@ -0,0 +1,47 @@
+use rustlife::tour::{glider_near, Act, Lesson, Tour, LESSONS};
+use rustlife::{io, CellState, ConwayState, DenseEngine};
+
+fn universe(cells: &str, at: (usize, usize)) -> DenseEngine {
+    let mut state = ConwayState::empty(20, 20);
+    for (x, y) in io::parse_plaintext(cells) {
+        state.set(at.0 + x, at.1 + y, CellState::Alive);
+    }
+    DenseEngine::new(state)
+}
+
+#[test]
+fn lessons_go_in_order_and_only_on_what_they_ask() {
+    let empty = universe("", (0, 0));
+    let mut tour = Tour::default();
+    assert_eq!((tour.lesson(), tour.number()), (Lesson::Pause, 1));
+    // Stepping before pausing doesn't count
+    tour.advance(&[Act::Stepped, Act::ChangedRule], &empty);
+    assert_eq!(tour.lesson(), Lesson::Pause);
+    tour.advance(&[Act::Paused], &empty);
+    tour.advance(&[Act::Stepped], &empty);
+    assert_eq!(tour.lesson(), Lesson::Glider);
+
+    // A cell on its own isn't a glider, but finishing one is
+    tour.advance(&[Act::Painted(5, 5)], &universe("O", (5, 5)));
+    assert_eq!(tour.lesson(), Lesson::Glider);
+    tour.advance(&[Act::Painted(7, 7)], &universe(".O\n..O\nOOO", (5, 5)));
+    assert_eq!(tour.lesson(), Lesson::Library);
+
+    tour.advance(&[Act::Placed], &empty);
+    tour.advance(&[Act::ChangedRule], &empty);
+    assert_eq!((tour.lesson(), tour.number()), (Lesson::Done, LESSONS.len()));
+    // And it stays done
+    tour.advance(&[Act::Paused], &empty);
+    assert_eq!((tour.lesson(), tour.number()), (Lesson::Done, LESSONS.len()));
+}
+
+#[test]
+fn gliders_are_found_whichever_way_they_face() {
+    // Turned, mirrored, in either phase and right up against the corner
+    for cells in [".O\n..O\nOOO", "O.O\n.OO\n.O", "OOO\nO\n.O", "O.O\nOO\n.O"] {
+        assert!(glider_near(&universe(cells, (0, 0)), 1, 1), "{:?}", cells);
+    }
+    // Too far from the painted cell, or not a glider at all
+    assert!(!glider_near(&universe(".O\n..O\nOOO", (12, 12)), 1, 1));
+    assert!(!glider_near(&universe("OOO", (5, 5)), 6, 5));
+}
@@ -0,0 +1,35 @@
+use rustlife::soup::{self, Symmetry};
+use rustlife::verify::{self, Divergence, ENGINES};
+use rustlife::{CellState, ConwayState};
+
+fn soup_state(rule: &str) -> ConwayState {
+    let mut state = ConwayState::empty(48, 40);
+    state.rule = rule.parse().unwrap();
+    for (x, y) in soup::generate(Symmetry::C1, 32, 0.5, 7) {
+        state.set(x + 8, y + 4, CellState::Alive);
+    }
+    state
+}
+
+#[test]
+fn every_engine_agrees_with_the_reference() {
+    for rule in ["B3/S23", "B36/S23", "B2/S"] {
+        let start = soup_state(rule);
+        for engine in ENGINES {
+            assert_eq!(verify::check(engine, "reference", &start, 60, 1), Ok(None), "{} on {}", engine, rule);
+        }
+        assert_eq!(verify::check("dense", "core", &start, 60, 16), Ok(None), "{}", rule);
+    }
+}
+
+#[test]
+fn engines_that_cant_be_compared_are_refused() {
+    let mut start = soup_state("B3/S23");
+    assert!(verify::check("hashlife", "reference", &start, 10, 1).unwrap_err().contains("unknown engine"));
+    start.set_wall(0, 0, Some(CellState::Alive));
+    assert!(verify::check("core", "reference", &start, 10, 1).is_err());
+    assert_eq!(verify::check("dense", "reference", &start, 10, 1), Ok(None));
+
+    let d = Divergence { generation: 12, x: 3, y: 4, states: (CellState::Alive, CellState::Dead), cells: 1 };
+    assert_eq!(d.to_string(), "generation 12, cell (3, 4): alive on the first, dead on the second; 1 cell differs");
+}